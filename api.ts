@@ -25,7 +25,7 @@ export class AuctionFactory extends Client {
 
   createAuction = (name: string, end: number, admin: Address = this.agent.address) => {
     if (!name) throw new Error('Pass auction name')
-    return this.execute({ create_auction: { admin, name, end_block: end } })
+    return this.execute({ create_auction: { config: { version: 1, admin, name, end_block: end } } })
   }
 
   listAuctions = (start: number = 0, limit: number = 10) =>
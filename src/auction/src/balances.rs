@@ -0,0 +1,38 @@
+//! The balance of every bidder, stored as a raw little-endian `u128` under
+//! the canonical address. Balances are read and written by every bid, so
+//! this avoids the index lookup and serialization overhead of the generic
+//! storage types. The order in which bidders first bid is kept separately.
+
+use std::convert::TryInto;
+
+use fadroma::cosmwasm_std::{Storage, CanonicalAddr, Uint128, StdError, StdResult};
+use shared::versioned_key;
+
+const PREFIX: &[u8] = versioned_key!(b"balances");
+
+#[inline]
+fn key(bidder: &CanonicalAddr) -> Vec<u8> {
+    [PREFIX, bidder.as_slice()].concat()
+}
+
+/// Returns `None` if the address has never placed a bid.
+pub fn get(storage: &dyn Storage, bidder: &CanonicalAddr) -> StdResult<Option<Uint128>> {
+    let Some(bytes) = storage.get(&key(bidder)) else {
+        return Ok(None);
+    };
+
+    let bytes: [u8; 16] = bytes.try_into()
+        .map_err(|_| StdError::generic_err("Stored balance is not 16 bytes long."))?;
+
+    Ok(Some(Uint128::new(u128::from_le_bytes(bytes))))
+}
+
+#[inline]
+pub fn get_or_default(storage: &dyn Storage, bidder: &CanonicalAddr) -> StdResult<Uint128> {
+    Ok(get(storage, bidder)?.unwrap_or_default())
+}
+
+#[inline]
+pub fn set(storage: &mut dyn Storage, bidder: &CanonicalAddr, amount: Uint128) {
+    storage.set(&key(bidder), &amount.u128().to_le_bytes());
+}
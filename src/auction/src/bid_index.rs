@@ -0,0 +1,86 @@
+//! Every bidder's balance sorted by amount in descending order, so that the
+//! highest (rank 0), second highest (rank 1) etc. bids can be looked up
+//! without scanning all bidders. The rank of each bidder is stored separately
+//! so an update only has to move the entry past the bids it overtakes.
+
+use fadroma::{
+    storage::{iterable::IterableStorage, ItemSpace, StaticKey, TypedKey},
+    cosmwasm_std::{Storage, CanonicalAddr, Uint128, StdResult},
+    bin_serde::{FadromaSerialize, FadromaDeserialize}
+};
+use shared::{versioned_namespace, versioned_key};
+
+#[derive(FadromaSerialize, FadromaDeserialize, Clone, PartialEq, Debug)]
+pub struct RankedBid {
+    pub bidder: CanonicalAddr,
+    pub amount: Uint128
+}
+
+versioned_namespace!(RanksNs, b"bid_ranks");
+const RANKS: ItemSpace<u64, RanksNs, TypedKey<CanonicalAddr>> = ItemSpace::new();
+
+#[inline]
+fn sorted() -> IterableStorage<RankedBid, StaticKey> {
+    IterableStorage::new(StaticKey(versioned_key!(b"sorted_bids")))
+}
+
+/// Returns the bid at the given rank where 0 is the highest.
+#[inline]
+pub fn get(storage: &dyn Storage, rank: u64) -> StdResult<Option<RankedBid>> {
+    sorted().get(storage, rank)
+}
+
+/// Returns the rank of the bidder or `None` if they never placed a bid.
+#[inline]
+pub fn rank(storage: &dyn Storage, bidder: &CanonicalAddr) -> StdResult<Option<u64>> {
+    RANKS.load(storage, bidder)
+}
+
+/// Sets the balance of the bidder and moves them to their new rank.
+/// Bids that are equal keep their relative order, so the bidder
+/// that reached an amount first stays ahead.
+pub fn update(storage: &mut dyn Storage, bidder: &CanonicalAddr, amount: Uint128) -> StdResult<()> {
+    let mut sorted = sorted();
+
+    let entry = RankedBid { bidder: bidder.clone(), amount };
+    let mut rank = match RANKS.load(storage, bidder)? {
+        Some(rank) => rank,
+        None => sorted.push(storage, &entry)?
+    };
+
+    // Move up while the bid is higher than the one above it...
+    while rank > 0 {
+        let above = sorted.get_or_error(storage, rank - 1)?;
+        if above.amount >= amount {
+            break;
+        }
+
+        place(storage, &mut sorted, rank, &above)?;
+        rank -= 1;
+    }
+
+    // ...or down while it is lower than the one below it.
+    let len = sorted.len(storage)?;
+    while rank + 1 < len {
+        let below = sorted.get_or_error(storage, rank + 1)?;
+        if below.amount <= amount {
+            break;
+        }
+
+        place(storage, &mut sorted, rank, &below)?;
+        rank += 1;
+    }
+
+    place(storage, &mut sorted, rank, &entry)
+}
+
+#[inline]
+fn place(
+    storage: &mut dyn Storage,
+    sorted: &mut IterableStorage<RankedBid, StaticKey>,
+    rank: u64,
+    entry: &RankedBid
+) -> StdResult<()> {
+    sorted.set(storage, rank, entry)?;
+    RANKS.save(storage, &entry.bidder, &rank)
+}
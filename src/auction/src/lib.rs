@@ -1,249 +1,3628 @@
+mod balances;
+mod bid_index;
+mod raffle;
+
+use fadroma::cosmwasm_std::{Response, DepsMut, Env};
+use shared::{
+    layout, version, Migrate, MigrateMsg,
+    errors::ContractError,
+    events::{self, actions}
+};
+
+// The DSL turns every trait implementation in the contract module into messages
+// before any `#[cfg]` in it is evaluated, so the implementations that authenticate
+// with viewing keys are given separately and only added to the module with the
+// `vk` feature, which leaves them out of the messages entirely otherwise.
+#[cfg(feature = "vk")]
+macro_rules! contract {
+    (
+        #[$attr:meta]
+        pub mod $name:ident { $($items:tt)* }
+        viewing_keys { $($keys:tt)* }
+    ) => {
+        #[$attr]
+        pub mod $name { $($items)* $($keys)* }
+    };
+}
+
+#[cfg(not(feature = "vk"))]
+macro_rules! contract {
+    (
+        #[$attr:meta]
+        pub mod $name:ident { $($items:tt)* }
+        viewing_keys { $($keys:tt)* }
+    ) => {
+        #[$attr]
+        pub mod $name { $($items)* }
+    };
+}
+
+contract! {
+
 #[fadroma::dsl::contract]
 pub mod auction {
     use fadroma::{
         dsl::*,
         core::*,
-        scrt::vk::{auth::{self, VkAuth}, ViewingKey},
+        scrt::permit::Permit,
         killswitch::{self, Killswitch, ContractStatus},
         admin::{self, Admin, Mode},
-        storage::{SingleItem, TypedKey, map::InsertOnlyMap},
+        storage::{
+            SingleItem, ItemSpace, TypedKey, TypedKey2, StaticKey,
+            iterable::IterableStorage
+        },
         cosmwasm_std::{
-            self, Response, StdError, Uint128, BankMsg,
-            Addr, CanonicalAddr, StdResult, to_binary, coin
+            self, Response, StdError, Uint128, Decimal, BankMsg, WasmMsg, WasmQuery,
+            QueryRequest, ContractInfoResponse, Reply, SubMsg, SubMsgResult, CosmosMsg,
+            Event, Storage, Deps, DepsMut, Env, BlockInfo, Addr, Binary, CanonicalAddr,
+            Coin, StdResult, OverflowError, to_binary, from_binary, coin
+        },
+        bin_serde::{FadromaSerialize, FadromaDeserialize},
+        schemars
+    };
+    use shared::{
+        versioned_namespace, versioned_key, layout, version::{self, ContractVersion},
+        Auction, AuctionConfig, AuctionPermission, AuctionState, BidEntry, BidRank,
+        BidReceipt, BidResult, BidSimulation, CoSeller, CoSellers, Config, CreatedAt,
+        Delivery, ExtensionProposal, ExtensionVoteConfig, FeeConfig, PendingApproval,
+        ProceedsResult, ProxyBid, RaffleConfig, RetractResult, SellerAction,
+        SettlementHookMsg, Denom, Invariants, Metrics, Pagination, PaginatedResponse,
+        SaleInfo, SaleStatus, StatusChange, StatusInfo, TimeRemaining,
+        MAX_BPS, MAX_DELIVERY_LEN, MAX_MEMO_LEN, MAX_REFUND_BATCH, MAX_AUTO_REFUNDS,
+        MAX_SETTLEMENT_HOOKS,
+        errors::{
+            ContractError, AuthError, ClaimError, CoSellerError, ExtensionError, HookError, LifecycleError,
+            OracleError, PaginationError, RaffleError, RetractionError, SelectionError, TokenError,
+            DepositError
         },
-        schemars,
-        namespace
+        events::{self, actions, statuses}, snip20, snip721, oracle, client::FactoryClient
     };
-    use shared::{Auction, Pagination, PaginatedResponse, SaleInfo, SaleStatus};
+    use crate::{balances, bid_index, raffle};
+    #[cfg(feature = "vk")]
+    use {
+        fadroma::scrt::vk::{auth::{self, VkAuth}, ViewingKey},
+        shared::{Claim, keys::ViewingKeys}
+    };
+
+    versioned_namespace!(InfoNs, b"info");
+    const INFO: SingleItem<SaleInfo, InfoNs> = SingleItem::new();
+
+    versioned_namespace!(HighestBidNs, b"highest_bid");
+    const HIGHEST_BID: SingleItem<CanonicalAddr, HighestBidNs> = SingleItem::new();
+
+    // Bidder addresses in the order in which they placed their first bid.
+    // Their balances are kept in the balances module.
+    #[inline]
+    fn bidder_addresses() -> IterableStorage<CanonicalAddr, StaticKey> {
+        IterableStorage::new(StaticKey(versioned_key!(b"bidder_addresses")))
+    }
+
+    versioned_namespace!(SettingsNs, b"settings");
+    const SETTINGS: SingleItem<Settings, SettingsNs> = SingleItem::new();
+
+    // The optional parts of the Config with their defaults applied.
+    #[derive(FadromaSerialize, FadromaDeserialize)]
+    struct Settings {
+        guard_queries: bool,
+        start_block: u64,
+        denom: Denom,
+        reserve: Uint128,
+        min_increment: Uint128,
+        beneficiary: Option<CanonicalAddr>,
+        receipt_nft: Option<ContractLink<CanonicalAddr>>,
+        tradable_claims: bool,
+        raffle: Option<RaffleSettings>,
+        manual_winner: bool,
+        dispute_blocks: u64,
+        arbiter: Option<CanonicalAddr>,
+        early_retraction_penalty: Option<u16>,
+        bid_cooldown: u64,
+        oracle: Option<OracleSettings>,
+        usd_reserve: Option<Decimal>,
+        usd_min_increment: Option<Decimal>,
+        show_highest_bidder: bool,
+        hide_current_highest: bool,
+        public_active_bids: bool,
+        extension_vote: Option<ExtensionVoteSettings>,
+        exclude_contracts: bool,
+        bid_token: Option<ContractLink<CanonicalAddr>>,
+        seller_deposit: Uint128,
+        factory: Option<ContractLink<CanonicalAddr>>
+    }
+
+    // The reserve and minimum increment as converted from USD at the last bid.
+    versioned_namespace!(ConvertedNs, b"converted");
+    const CONVERTED: SingleItem<Converted, ConvertedNs> = SingleItem::new();
+
+    #[derive(FadromaSerialize, FadromaDeserialize)]
+    struct Converted {
+        reserve: Uint128,
+        min_increment: Uint128
+    }
+
+    #[derive(FadromaSerialize, FadromaDeserialize)]
+    struct ExtensionVoteSettings {
+        threshold: u16,
+        max_blocks: u64
+    }
+
+    #[derive(FadromaSerialize, FadromaDeserialize)]
+    struct OracleSettings {
+        contract: ContractLink<CanonicalAddr>,
+        max_age: u64
+    }
+
+    #[derive(FadromaSerialize, FadromaDeserialize)]
+    struct RaffleSettings {
+        ticket_price: Uint128,
+        refund_rate: Decimal
+    }
+
+    // The id of each bidder's claim, i.e. their index in bidder_addresses().
+    // Only stored if claims are tradable.
+    versioned_namespace!(ClaimIdsNs, b"claim_ids");
+    const CLAIM_IDS: ItemSpace<u64, ClaimIdsNs, TypedKey<CanonicalAddr>> = ItemSpace::new();
+
+    // Claims which have been transferred. The rest are owned by the original bidder.
+    versioned_namespace!(ClaimOwnersNs, b"claim_owners");
+    const CLAIM_OWNERS: ItemSpace<CanonicalAddr, ClaimOwnersNs, TypedKey<u64>> = ItemSpace::new();
+
+    // Bidders that allow others to bid on their behalf.
+    versioned_namespace!(AcceptsBidsForNs, b"accepts_bids_for");
+    const ACCEPTS_BIDS_FOR: ItemSpace<bool, AcceptsBidsForNs, TypedKey<CanonicalAddr>> = ItemSpace::new();
+
+    // Whether each bidder wants their refund sent when the proceeds are claimed.
+    versioned_namespace!(AutoRefundNs, b"auto_refund");
+    const AUTO_REFUND: ItemSpace<bool, AutoRefundNs, TypedKey<CanonicalAddr>> = ItemSpace::new();
+
+    // How many bidders have auto refund enabled, at most MAX_AUTO_REFUNDS.
+    versioned_namespace!(AutoRefundsNs, b"auto_refunds");
+    const AUTO_REFUNDS: SingleItem<u64, AutoRefundsNs> = SingleItem::new();
+
+    // The part of each bidder's proxy deposit that hasn't been bid yet.
+    versioned_namespace!(ProxyUnspentNs, b"proxy_unspent");
+    const PROXY_UNSPENT: ItemSpace<Uint128, ProxyUnspentNs, TypedKey<CanonicalAddr>> = ItemSpace::new();
+
+    // The sum of the funds that were bid minus the ones paid out.
+    versioned_namespace!(TotalEscrowedNs, b"total_escrowed");
+    const TOTAL_ESCROWED: SingleItem<Uint128, TotalEscrowedNs> = SingleItem::new();
+
+    // Every bid and proxy deposit, not only the first one of each bidder.
+    // Also the id of the receipt of the last one.
+    versioned_namespace!(NumBidsNs, b"num_bids");
+    const NUM_BIDS: SingleItem<u64, NumBidsNs> = SingleItem::new();
+
+    versioned_namespace!(BidReceiptsNs, b"bid_receipts");
+    const BID_RECEIPTS: ItemSpace<BidReceipt, BidReceiptsNs, TypedKey<u64>> = ItemSpace::new();
+
+    versioned_namespace!(StatusChangedNs, b"status_changed");
+    const STATUS_CHANGED: SingleItem<StatusChange, StatusChangedNs> = SingleItem::new();
+
+    versioned_namespace!(CreatedAtNs, b"created_at");
+    const CREATED_AT: SingleItem<CreatedAt, CreatedAtNs> = SingleItem::new();
+
+    versioned_namespace!(MetricsNs, b"metrics");
+    const METRICS: SingleItem<Metrics, MetricsNs> = SingleItem::new();
+
+    // The index in bidder_addresses() that the next refund_batch call starts from.
+    versioned_namespace!(RefundCursorNs, b"refund_cursor");
+    const REFUND_CURSOR: SingleItem<u64, RefundCursorNs> = SingleItem::new();
+
+    // Set if the sale was cancelled during the dispute window.
+    versioned_namespace!(CancelledNs, b"cancelled");
+    const CANCELLED: SingleItem<bool, CancelledNs> = SingleItem::new();
+
+    versioned_namespace!(SettledNs, b"settled");
+    const SETTLED: SingleItem<bool, SettledNs> = SingleItem::new();
+
+    // Kept apart from the SaleInfo so that the registry of the factory keeps its layout.
+    versioned_namespace!(EndTimeNs, b"end_time");
+    const END_TIME: SingleItem<u64, EndTimeNs> = SingleItem::new();
+
+    // Proposals to extend the sale. Their id is their index.
+    #[inline]
+    fn extension_proposals() -> IterableStorage<ExtensionProposal, StaticKey> {
+        IterableStorage::new(StaticKey(versioned_key!(b"extension_proposals")))
+    }
+
+    // Whether a bidder has voted for an extension proposal.
+    versioned_namespace!(ExtensionVotesNs, b"extension_votes");
+    const EXTENSION_VOTES: ItemSpace<bool, ExtensionVotesNs, TypedKey2<u64, CanonicalAddr>> = ItemSpace::new();
+
+    // The votes of each bidder on proposals that may not have been applied yet,
+    // so that they can be taken back if the bidder retracts early.
+    versioned_namespace!(CastVotesNs, b"cast_votes");
+    const CAST_VOTES: ItemSpace<Vec<CastVote>, CastVotesNs, TypedKey<CanonicalAddr>> = ItemSpace::new();
+
+    #[derive(FadromaSerialize, FadromaDeserialize)]
+    struct CastVote {
+        proposal: u64,
+        weight: Uint128
+    }
+
+    // The number of blocks that applied proposals have extended the sale by.
+    versioned_namespace!(ExtendedBlocksNs, b"extended_blocks");
+    const EXTENDED_BLOCKS: SingleItem<u64, ExtendedBlocksNs> = SingleItem::new();
+
+    // The block at which each bidder last placed a bid.
+    // Only stored if the auction has a bid cooldown.
+    versioned_namespace!(LastBidNs, b"last_bid");
+    const LAST_BID: ItemSpace<u64, LastBidNs, TypedKey<CanonicalAddr>> = ItemSpace::new();
+
+    // Deducted from bids retracted before the end and added to the proceeds at settlement.
+    versioned_namespace!(PenaltiesNs, b"penalties");
+    const PENALTIES: SingleItem<Uint128, PenaltiesNs> = SingleItem::new();
+
+    // What each address has been paid and can send to itself with claim. Apart from
+    // auto refunds, which refund_batch sends right away, and what close sweeps up,
+    // funds only leave the contract through claim, so a failed transfer of a payout
+    // reverts nothing but the claim.
+    versioned_namespace!(PayoutsNs, b"payouts");
+    const PAYOUTS: ItemSpace<Uint128, PayoutsNs, TypedKey<CanonicalAddr>> = ItemSpace::new();
+
+    // The sum of all payouts, so that close knows what of the balance belongs to no one.
+    versioned_namespace!(UnclaimedNs, b"unclaimed");
+    const UNCLAIMED: SingleItem<Uint128, UnclaimedNs> = SingleItem::new();
+
+    // Set by close. Only claim is still accepted afterwards.
+    versioned_namespace!(ClosedNs, b"closed");
+    const CLOSED: SingleItem<bool, ClosedNs> = SingleItem::new();
+
+    // What the beneficiary receives after the fee, set at settlement.
+    versioned_namespace!(ProceedsNs, b"proceeds");
+    const PROCEEDS: SingleItem<Proceeds, ProceedsNs> = SingleItem::new();
+
+    #[derive(FadromaSerialize, FadromaDeserialize, Default)]
+    struct Proceeds {
+        total: Uint128,
+        claimed: Uint128
+    }
+
+    impl Proceeds {
+        #[inline]
+        fn remaining(&self) -> Uint128 {
+            self.total - self.claimed
+        }
+    }
+
+    versioned_namespace!(AuditorNs, b"auditor");
+    const AUDITOR: SingleItem<CanonicalAddr, AuditorNs> = SingleItem::new();
+
+    // Only stored until the seller deposit is returned or set aside for the bidders.
+    versioned_namespace!(DepositNs, b"deposit");
+    const DEPOSIT: SingleItem<Uint128, DepositNs> = SingleItem::new();
+
+    // The seller deposit of a cancelled sale and the total escrowed
+    // at that point, which the share of each bidder is relative to.
+    versioned_namespace!(CompensationNs, b"compensation");
+    const COMPENSATION: SingleItem<Compensation, CompensationNs> = SingleItem::new();
+
+    #[derive(FadromaSerialize, FadromaDeserialize)]
+    struct Compensation {
+        deposit: Uint128,
+        escrowed: Uint128
+    }
+
+    versioned_namespace!(PauserNs, b"pauser");
+    const PAUSER: SingleItem<CanonicalAddr, PauserNs> = SingleItem::new();
+
+    // Only stored while an ownership transfer waits to be accepted.
+    versioned_namespace!(PendingOwnerNs, b"pending_owner");
+    const PENDING_OWNER: SingleItem<CanonicalAddr, PendingOwnerNs> = SingleItem::new();
+
+    // Contracts that are notified when the sale is settled.
+    #[inline]
+    fn settlement_hooks() -> IterableStorage<ContractLink<CanonicalAddr>, StaticKey> {
+        IterableStorage::new(StaticKey(versioned_key!(b"settlement_hooks")))
+    }
+
+    // Callbacks to settlement hooks are the only submessages that are replied to.
+    const SETTLEMENT_HOOK_REPLY_ID: u64 = 0;
+
+    // Only stored if the auction was created with a payload for the winner.
+    versioned_namespace!(WinnerPayloadNs, b"winner_payload");
+    const WINNER_PAYLOAD: SingleItem<Binary, WinnerPayloadNs> = SingleItem::new();
+
+    // Attached by bidders to their bid, keyed by the bidder.
+    versioned_namespace!(MemosNs, b"memos");
+    const MEMOS: ItemSpace<String, MemosNs, TypedKey<CanonicalAddr>> = ItemSpace::new();
+
+    // Sent by the winner and the admin to each other after settlement.
+    versioned_namespace!(DeliveryDetailsNs, b"delivery_details");
+    const DELIVERY_DETAILS: SingleItem<String, DeliveryDetailsNs> = SingleItem::new();
+
+    versioned_namespace!(FulfillmentProofNs, b"fulfillment_proof");
+    const FULFILLMENT_PROOF: SingleItem<String, FulfillmentProofNs> = SingleItem::new();
+
+    // Only stored in auctions with co-sellers.
+    versioned_namespace!(CoSellersNs, b"co_sellers");
+    const CO_SELLERS: SingleItem<CoSellers<CanonicalAddr>, CoSellersNs> = SingleItem::new();
+
+    // Actions approved by fewer co-sellers than the threshold, in the order of their first approval.
+    versioned_namespace!(ApprovalsNs, b"approvals");
+    const APPROVALS: SingleItem<Vec<Approval>, ApprovalsNs> = SingleItem::new();
+
+    #[derive(FadromaSerialize, FadromaDeserialize)]
+    struct Approval {
+        // The JSON encoding of the SellerAction, which equal actions share.
+        action: Binary,
+        approvers: Vec<CanonicalAddr>
+    }
+
+    // Only stored in auctions with manual winner selection.
+    versioned_namespace!(SelectedWinnerNs, b"selected_winner");
+    const SELECTED_WINNER: SingleItem<CanonicalAddr, SelectedWinnerNs> = SingleItem::new();
+
+    fn count(
+        storage: &mut dyn Storage,
+        counter: impl FnOnce(&mut Metrics) -> &mut u64
+    ) -> StdResult<()> {
+        let mut metrics = METRICS.load_or_default(storage)?;
+
+        let counter = counter(&mut metrics);
+        *counter = counter.saturating_add(1);
+
+        METRICS.save(storage, &metrics)
+    }
+
+    fn escrow(
+        storage: &mut dyn Storage,
+        update: impl FnOnce(Uint128) -> Result<Uint128, OverflowError>
+    ) -> StdResult<()> {
+        let total = update(TOTAL_ESCROWED.load_or_default(storage)?)?;
+
+        TOTAL_ESCROWED.save(storage, &total)
+    }
+
+    // The highest bid is paid out to the beneficiary unless it is below the reserve price.
+    fn is_winning_bid(
+        storage: &dyn Storage,
+        bidder: &CanonicalAddr,
+        balance: Uint128,
+        reserve: Uint128
+    ) -> StdResult<bool> {
+        if CANCELLED.load_or_default(storage)? {
+            return Ok(false);
+        }
+
+        Ok(HIGHEST_BID.load(storage)?.as_ref() == Some(bidder) && balance >= reserve)
+    }
+
+    #[inline]
+    fn unspent(storage: &dyn Storage, bidder: &CanonicalAddr) -> StdResult<Uint128> {
+        Ok(PROXY_UNSPENT.load(storage, bidder)?.unwrap_or_default())
+    }
+
+    // Zeroes the balance and the unspent proxy deposit of the bidder and returns
+    // them so that they can be refunded. The winner only gets back the deposit.
+    // Bidders of a cancelled sale also get their share of the seller deposit,
+    // rounded down, so any remainder stays in the contract.
+    fn take_refund(
+        storage: &mut dyn Storage,
+        bidder: &CanonicalAddr,
+        reserve: Uint128
+    ) -> Result<Uint128, ContractError> {
+        let balance = balances::get_or_default(storage, bidder)?;
+        let unspent = unspent(storage, bidder)?;
+
+        let refund = if is_winning_bid(storage, bidder, balance, reserve)? {
+            if unspent.is_zero() {
+                return Err(LifecycleError::WinnerCannotRetract.into());
+            }
+
+            unspent
+        } else {
+            set_bid(storage, bidder, Uint128::zero())?;
+
+            balance + unspent
+        };
+
+        PROXY_UNSPENT.remove(storage, bidder);
+        escrow(storage, |total| total.checked_sub(refund))?;
+
+        if let Some(compensation) = COMPENSATION.load(storage)? {
+            let share = compensation.deposit.multiply_ratio(refund, compensation.escrowed);
+
+            return Ok(refund + share);
+        }
+
+        Ok(refund)
+    }
+
+    // Like take_refund but before the end, so that the highest bid can be retracted
+    // too. Returns the refund and the penalty, which is only deducted from the bid.
+    fn take_early_refund(
+        storage: &mut dyn Storage,
+        settings: &Settings,
+        bidder: &CanonicalAddr,
+        penalty_bps: u16
+    ) -> StdResult<(Uint128, Uint128)> {
+        let balance = balances::get_or_default(storage, bidder)?;
+        let unspent = unspent(storage, bidder)?;
+        let penalty = balance.multiply_ratio(penalty_bps, MAX_BPS);
+
+        set_bid(storage, bidder, Uint128::zero())?;
+        PROXY_UNSPENT.remove(storage, bidder);
+        escrow(storage, |total| total.checked_sub(balance + unspent))?;
+        withdraw_votes(storage, bidder)?;
+
+        if !penalty.is_zero() {
+            let penalties = PENALTIES.load_or_default(storage)?;
+            PENALTIES.save(storage, &(penalties + penalty))?;
+        }
+
+        // The next bid takes the first place and the runner-up may respond to it.
+        resolve_proxy_bids(storage, settings)?;
+
+        Ok((balance + unspent - penalty, penalty))
+    }
+
+    // Same as take_refund but without changing anything.
+    fn refundable(
+        storage: &dyn Storage,
+        bidder: &CanonicalAddr,
+        reserve: Uint128
+    ) -> StdResult<Uint128> {
+        let balance = balances::get_or_default(storage, bidder)?;
+        let unspent = unspent(storage, bidder)?;
+
+        if is_winning_bid(storage, bidder, balance, reserve)? {
+            Ok(unspent)
+        } else {
+            Ok(balance + unspent)
+        }
+    }
+
+    fn set_auto_refund_of(
+        storage: &mut dyn Storage,
+        bidder: &CanonicalAddr,
+        enabled: bool
+    ) -> Result<(), ContractError> {
+        let was_enabled = AUTO_REFUND.load(storage, bidder)?.unwrap_or_default();
+        if was_enabled == enabled {
+            return Ok(());
+        }
+
+        let count = AUTO_REFUNDS.load_or_default(storage)?;
+
+        if enabled {
+            let balance = balances::get_or_default(storage, bidder)?;
+            if (balance + unspent(storage, bidder)?).is_zero() {
+                return Err(LifecycleError::NoActiveBid.into());
+            }
+
+            if count >= MAX_AUTO_REFUNDS {
+                return Err(LifecycleError::AutoRefundsFull(MAX_AUTO_REFUNDS).into());
+            }
+
+            AUTO_REFUNDS.save(storage, &(count + 1))?;
+        } else {
+            AUTO_REFUNDS.save(storage, &count.saturating_sub(1))?;
+        }
+
+        AUTO_REFUND.save(storage, bidder, &enabled)?;
+
+        Ok(())
+    }
+
+    // Adds the amount to the payouts that the recipient can claim.
+    fn credit(storage: &mut dyn Storage, recipient: &CanonicalAddr, amount: Uint128) -> StdResult<()> {
+        if amount.is_zero() {
+            return Ok(());
+        }
+
+        let payout = PAYOUTS.load(storage, recipient)?.unwrap_or_default();
+        let unclaimed = UNCLAIMED.load_or_default(storage)?;
+
+        UNCLAIMED.save(storage, &unclaimed.checked_add(amount)?)?;
+        PAYOUTS.save(storage, recipient, &payout.checked_add(amount)?)
+    }
+
+    // The message which sends the amount to the recipient in the currency of the sale.
+    fn payout_msg(
+        deps: Deps,
+        settings: &Settings,
+        recipient: &Addr,
+        amount: Uint128
+    ) -> StdResult<CosmosMsg> {
+        match &settings.bid_token {
+            Some(token) => {
+                let token = token.clone().humanize(deps.api)?;
+
+                Ok(WasmMsg::Execute {
+                    contract_addr: token.address.into_string(),
+                    code_hash: token.code_hash,
+                    msg: to_binary(&snip20::ExecuteMsg::Transfer {
+                        recipient: recipient.to_string(),
+                        amount,
+                        memo: None
+                    })?,
+                    funds: vec![]
+                }.into())
+            }
+            None => Ok(BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: vec![coin(amount.u128(), settings.denom.clone())]
+            }.into())
+        }
+    }
+
+    // Until the admin has selected the winner of an auction with manual
+    // winner selection, it isn't known which of the bids can be refunded.
+    // If even the highest bid is below the reserve there is no one to select.
+    fn assert_winner_known(storage: &dyn Storage, settings: &Settings) -> Result<(), ContractError> {
+        if !settings.manual_winner ||
+            CANCELLED.load_or_default(storage)? ||
+            SELECTED_WINNER.load(storage)?.is_some()
+        {
+            return Ok(());
+        }
+
+        let Some(highest) = HIGHEST_BID.load(storage)? else {
+            return Ok(());
+        };
+
+        if balances::get_or_default(storage, &highest)? < settings.reserve {
+            return Ok(());
+        }
+
+        Err(SelectionError::NotSelected.into())
+    }
+
+    // Loads the settings with the USD denominated reserve and minimum increment
+    // converted at the current price. They must not be saved back afterwards.
+    // Only needed while bids can be placed, after that see ended_settings.
+    fn load_settings(deps: Deps, env: &Env) -> Result<Settings, ContractError> {
+        let mut settings = SETTINGS.load_or_error(deps.storage)?;
+
+        let Some(oracle) = &settings.oracle else {
+            return Ok(settings);
+        };
+
+        if settings.usd_reserve.is_none() && settings.usd_min_increment.is_none() {
+            return Ok(settings);
+        }
+
+        let price: oracle::PriceResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: deps.api.addr_humanize(&oracle.contract.address)?.into_string(),
+            code_hash: oracle.contract.code_hash.clone(),
+            msg: to_binary(&oracle::QueryMsg::Price { denom: settings.denom.to_string() })?
+        }))?;
+
+        if env.block.time.seconds().saturating_sub(price.updated_at) > oracle.max_age {
+            return Err(OracleError::Stale {
+                updated_at: price.updated_at,
+                max_age: oracle.max_age
+            }.into());
+        }
+
+        if price.rate.is_zero() {
+            return Err(OracleError::ZeroPrice(settings.denom.to_string()).into());
+        }
+
+        // Rounded up so that the USD amount is always met.
+        let convert = |usd: Decimal| usd.checked_div(price.rate)
+            .map(|x| Uint128::one() * x.ceil())
+            .map_err(|err| StdError::generic_err(err.to_string()));
+
+        if let Some(usd) = settings.usd_reserve {
+            settings.reserve = settings.reserve.max(convert(usd)?);
+        }
+
+        if let Some(usd) = settings.usd_min_increment {
+            settings.min_increment = settings.min_increment.max(convert(usd)?);
+        }
+
+        Ok(settings)
+    }
+
+    // Loads the settings with the reserve and minimum increment that were in effect
+    // at the last bid. Used once the sale has finished, when no more bids can change
+    // them, so that settling and refunding never depend on the oracle.
+    fn ended_settings(storage: &dyn Storage) -> StdResult<Settings> {
+        let mut settings = SETTINGS.load_or_error(storage)?;
+
+        if let Some(converted) = CONVERTED.load(storage)? {
+            settings.reserve = converted.reserve;
+            settings.min_increment = converted.min_increment;
+        }
+
+        Ok(settings)
+    }
+
+    // Records the converted reserve and minimum increment for ended_settings.
+    fn save_converted(storage: &mut dyn Storage, settings: &Settings) -> StdResult<()> {
+        if settings.oracle.is_none() {
+            return Ok(());
+        }
+
+        CONVERTED.save(storage, &Converted {
+            reserve: settings.reserve,
+            min_increment: settings.min_increment
+        })
+    }
+
+    // Loads the settings of an auction that has ended and has tradable claims.
+    fn claim_settings(deps: Deps, env: &Env) -> Result<Settings, ContractError> {
+        let settings = ended_settings(deps.storage)?;
+        if !settings.tradable_claims {
+            return Err(ClaimError::NotTradable.into());
+        }
+
+        if !is_finished(deps.storage, &INFO.load_or_error(deps.storage)?, &env.block)? {
+            return Err(LifecycleError::NotFinished.into());
+        }
+
+        assert_winner_known(deps.storage, &settings)?;
+
+        Ok(settings)
+    }
+
+    // The sale ends at the end block or the end time, whichever is reached first.
+    fn is_finished(storage: &dyn Storage, info: &SaleInfo, block: &BlockInfo) -> StdResult<bool> {
+        if info.is_finished(block.height) {
+            return Ok(true);
+        }
+
+        Ok(END_TIME.load(storage)?.is_some_and(|x| block.time.seconds() > x))
+    }
+
+    // Takes the votes of the bidder back from the proposals which haven't been applied.
+    // The bidder can vote for them again after bidding anew.
+    fn withdraw_votes(storage: &mut dyn Storage, bidder: &CanonicalAddr) -> StdResult<()> {
+        let Some(votes) = CAST_VOTES.load(storage, bidder)? else {
+            return Ok(());
+        };
+
+        CAST_VOTES.remove(storage, bidder);
+        let mut proposals = extension_proposals();
+
+        for vote in votes {
+            let mut proposal = proposals.get_or_error(storage, vote.proposal)?;
+            if proposal.applied.is_some() {
+                continue;
+            }
+
+            proposal.votes = proposal.votes.checked_sub(vote.weight)?;
+            proposals.set(storage, vote.proposal, &proposal)?;
+            EXTENSION_VOTES.remove(storage, (&vote.proposal, bidder));
+        }
+
+        Ok(())
+    }
+
+    // Adds the current bid and unspent proxy deposit of the voter to the votes of the
+    // proposal, the same funds that the threshold is taken from, and extends the sale
+    // by the proposed blocks, at most the ones left, once they reach the threshold.
+    fn vote_for_extension(
+        deps: DepsMut,
+        block: &BlockInfo,
+        voter: &CanonicalAddr,
+        id: u64,
+        resp: Response
+    ) -> Result<Response, ContractError> {
+        let Some(vote) = SETTINGS.load_or_error(deps.storage)?.extension_vote else {
+            return Err(ExtensionError::NotEnabled.into());
+        };
+
+        let mut info = INFO.load_or_error(deps.storage)?;
+        if is_finished(deps.storage, &info, block)? {
+            return Err(LifecycleError::Finished.into());
+        }
+
+        let weight = balances::get_or_default(deps.storage, voter)? + unspent(deps.storage, voter)?;
+        if weight.is_zero() {
+            return Err(ExtensionError::NoBid.into());
+        }
+
+        let mut proposals = extension_proposals();
+        let Some(mut proposal) = proposals.get(deps.storage, id)? else {
+            return Err(ExtensionError::NotFound(id).into());
+        };
+
+        if proposal.applied.is_some() {
+            return Err(ExtensionError::AlreadyApplied(id).into());
+        }
+
+        if EXTENSION_VOTES.load(deps.storage, (&id, voter))?.unwrap_or_default() {
+            return Err(ExtensionError::AlreadyVoted(id).into());
+        }
+
+        EXTENSION_VOTES.save(deps.storage, (&id, voter), &true)?;
+        proposal.votes += weight;
+
+        let mut votes = CAST_VOTES.load(deps.storage, voter)?.unwrap_or_default();
+        votes.push(CastVote { proposal: id, weight });
+        CAST_VOTES.save(deps.storage, voter, &votes)?;
+
+        let mut resp = resp.add_attribute(events::PROPOSAL, id.to_string());
+        let total = TOTAL_ESCROWED.load_or_default(deps.storage)?;
+
+        if proposal.votes.full_mul(MAX_BPS) >= total.full_mul(vote.threshold) {
+            let extended = EXTENDED_BLOCKS.load_or_default(deps.storage)?;
+            let blocks = proposal.blocks.min(vote.max_blocks - extended);
+
+            info.end_block += blocks;
+            INFO.save(deps.storage, &info)?;
+            EXTENDED_BLOCKS.save(deps.storage, &(extended + blocks))?;
+            proposal.applied = Some(blocks);
+
+            resp = resp.add_attribute(events::END_BLOCK, info.end_block.to_string());
+            resp = report_end_block(deps.as_ref(), info.end_block, resp)?;
+        }
+
+        proposals.set(deps.storage, id, &proposal)?;
+
+        Ok(resp)
+    }
+
+    // Lets the factory know that the end block changed
+    // so that it can keep its index of end blocks in order.
+    fn report_end_block(
+        deps: Deps,
+        end_block: u64,
+        resp: Response
+    ) -> Result<Response, ContractError> {
+        let Some(factory) = SETTINGS.load_or_error(deps.storage)?.factory else {
+            return Ok(resp);
+        };
+
+        let factory = FactoryClient(factory.humanize(deps.api)?);
+
+        Ok(resp.add_message(factory.end_block_changed(end_block)?))
+    }
+
+    // Loads the settings of an auction whose proceeds can be claimed.
+    fn settlement_settings(deps: Deps, env: &Env) -> Result<(SaleInfo, Settings), ContractError> {
+        let sale_info = INFO.load_or_error(deps.storage)?;
+        if !is_finished(deps.storage, &sale_info, &env.block)? {
+            return Err(LifecycleError::NotFinished.into());
+        }
+
+        if CANCELLED.load_or_default(deps.storage)? {
+            return Err(LifecycleError::Cancelled.into());
+        }
+
+        let settings = ended_settings(deps.storage)?;
+
+        // Sales that ended at their end time are already finished before the end block.
+        let unlocked_at = sale_info.end_block.saturating_add(settings.dispute_blocks);
+        if settings.dispute_blocks > 0 && unlocked_at >= env.block.height {
+            return Err(LifecycleError::ProceedsLocked(unlocked_at).into());
+        }
+
+        assert_winner_known(deps.storage, &settings)?;
+
+        Ok((sale_info, settings))
+    }
+
+    // Pays out the given amount of the proceeds or everything that is left, settling the
+    // sale first if needed. The amount is split among the recipients by their share in
+    // basis points, with what is lost to rounding going to the first one.
+    fn pay_proceeds(
+        mut deps: DepsMut,
+        env: &Env,
+        amount: Option<Uint128>,
+        recipients: &[(CanonicalAddr, u16)],
+        mut resp: Response
+    ) -> Result<Response, ContractError> {
+        let (sale_info, settings) = settlement_settings(deps.as_ref(), env)?;
+        count(deps.storage, |x| &mut x.claims)?;
+
+        let mut result = ProceedsResult {
+            winner: None,
+            proceeds: Uint128::zero(),
+            fee: Uint128::zero(),
+            remaining: Uint128::zero()
+        };
+
+        // The first claim settles the sale, unless that has already
+        // happened through settle, and the rest pay out what is left.
+        if !SETTLED.load_or_default(deps.storage)? {
+            let (settled, fee) = settle_sale(deps.branch(), env, sale_info, &settings, resp)?;
+            resp = settled;
+            result.fee = fee;
+        } else if PROCEEDS.load_or_default(deps.storage)?.remaining().is_zero() {
+            return Err(LifecycleError::AlreadySettled.into());
+        }
+
+        let mut proceeds = PROCEEDS.load_or_default(deps.storage)?;
+        let remaining = proceeds.remaining();
+
+        let amount = amount.unwrap_or(remaining);
+        if amount > remaining {
+            return Err(LifecycleError::ProceedsExceeded(remaining).into());
+        }
+
+        if !amount.is_zero() {
+            proceeds.claimed += amount;
+            PROCEEDS.save(deps.storage, &proceeds)?;
+
+            let parts = recipients.iter()
+                .skip(1)
+                .map(|(_, share)| amount.multiply_ratio(*share, MAX_BPS))
+                .collect::<Vec<_>>();
+            let first = amount - parts.iter().copied().sum::<Uint128>();
+
+            for ((recipient, _), part) in recipients.iter().zip(std::iter::once(first).chain(parts)) {
+                if !part.is_zero() {
+                    credit(deps.storage, recipient, part)?;
+                }
+
+                resp = resp.add_attribute(events::RECIPIENT, recipient.clone().humanize(deps.api)?);
+            }
+        }
+
+        result.winner = HIGHEST_BID.load(deps.storage)?
+            .map(|x| x.humanize(deps.api))
+            .transpose()?;
+        result.proceeds = amount;
+        result.remaining = remaining - amount;
+
+        Ok(resp.set_data(to_binary(&result)?))
+    }
+
+    // Cancels the sale once the sender is known to be allowed to.
+    fn cancel(
+        deps: DepsMut,
+        env: &Env,
+        settings: &Settings,
+        reason: String,
+        resp: Response
+    ) -> Result<Response, ContractError> {
+        if reason.trim().is_empty() {
+            return Err(StdError::generic_err("A reason for cancelling the sale is required.").into());
+        }
+
+        // The window starts once the sale is finished, which
+        // may be before the end block if it has an end time.
+        let sale_info = INFO.load_or_error(deps.storage)?;
+        if settings.dispute_blocks == 0 ||
+            !is_finished(deps.storage, &sale_info, &env.block)? ||
+            env.block.height > sale_info.end_block.saturating_add(settings.dispute_blocks)
+        {
+            return Err(LifecycleError::NotInDisputeWindow.into());
+        }
+
+        if CANCELLED.load_or_default(deps.storage)? {
+            return Err(LifecycleError::Cancelled.into());
+        }
+
+        CANCELLED.save(deps.storage, &true)?;
+
+        let mut resp = resp.add_attribute(events::REASON, reason);
+
+        // Split among the bidders as they take their refunds.
+        if let Some(deposit) = DEPOSIT.load(deps.storage)? {
+            DEPOSIT.remove(deps.storage);
+
+            let escrowed = TOTAL_ESCROWED.load_or_default(deps.storage)?;
+            if escrowed.is_zero() {
+                return_deposit(deps.storage, deposit)?;
+            } else {
+                COMPENSATION.save(deps.storage, &Compensation { deposit, escrowed })?;
+            }
+
+            resp = resp.add_attribute(events::DEPOSIT, deposit);
+        }
+
+        Ok(resp)
+    }
+
+    // Moves the end block of a sale that hasn't finished yet, as approved by the co-sellers.
+    fn extend(
+        deps: DepsMut,
+        env: &Env,
+        blocks: u64,
+        resp: Response
+    ) -> Result<Response, ContractError> {
+        if blocks == 0 {
+            return Err(CoSellerError::NoBlocks.into());
+        }
+
+        if END_TIME.load(deps.storage)?.is_some() {
+            return Err(CoSellerError::EndTime.into());
+        }
+
+        let mut sale_info = INFO.load_or_error(deps.storage)?;
+        if is_finished(deps.storage, &sale_info, &env.block)? {
+            return Err(LifecycleError::Finished.into());
+        }
+
+        sale_info.end_block = sale_info.end_block.saturating_add(blocks);
+        INFO.save(deps.storage, &sale_info)?;
+
+        let resp = resp
+            .add_attribute(events::ACTION, actions::EXTEND)
+            .add_attribute(events::BLOCKS, blocks.to_string())
+            .add_attribute(events::END_BLOCK, sale_info.end_block.to_string());
+
+        report_end_block(deps.as_ref(), sale_info.end_block, resp)
+    }
+
+    // Draws the raffle, pays the fee, mints the receipt, notifies the settlement hooks
+    // and pays the refunds that bidders opted into. The proceeds are kept to be claimed.
+    // Returns the response with the messages added and the fee that was paid.
+    fn settle_sale(
+        deps: DepsMut,
+        env: &Env,
+        sale_info: SaleInfo,
+        settings: &Settings,
+        mut resp: Response
+    ) -> Result<(Response, Uint128), ContractError> {
+        let mut pot = Uint128::zero();
+        let mut paid_fee = Uint128::zero();
+        let mut price = Uint128::zero();
+
+        if settings.raffle.is_some() {
+            // The winner of the draw takes the place of the highest bidder.
+            if let Some(winner) = raffle::draw(deps.storage, env)? {
+                HIGHEST_BID.save(deps.storage, &winner)?;
+            }
+
+            pot = raffle::take_pot(deps.storage)?;
+        }
+
+        // Penalties of early retractions go to the seller even if no one won.
+        let mut proceeds = PENALTIES.load_or_default(deps.storage)?;
+
+        let winner = match HIGHEST_BID.load(deps.storage)? {
+            // No one won, so the highest bid is left to be refunded like the others.
+            Some(addr) if balances::get_or_default(deps.storage, &addr)? < settings.reserve => {
+                HIGHEST_BID.remove(deps.storage);
+
+                None
+            }
+            highest => highest
+        };
+
+        if let Some(addr) = winner {
+            let balance = balances::get_or_default(deps.storage, &addr)?;
+
+            balances::set(deps.storage, &addr, Uint128::zero());
+            bid_index::update(deps.storage, &addr, Uint128::zero())?;
+            escrow(deps.storage, |total| total.checked_sub(balance))?;
+
+            price = balance + pot;
+            proceeds += price;
+
+            let winner = addr.humanize(deps.api)?;
+
+            if let Some(nft) = settings.receipt_nft.clone() {
+                let nft = nft.humanize(deps.api)?;
+
+                resp = resp.add_message(WasmMsg::Execute {
+                    contract_addr: nft.address.into_string(),
+                    code_hash: nft.code_hash,
+                    msg: to_binary(&snip721::ExecuteMsg::MintNft {
+                        // There is only one winner per auction.
+                        token_id: Some(env.contract.address.to_string()),
+                        owner: Some(winner.to_string()),
+                        public_metadata: Some(snip721::Metadata {
+                            token_uri: None,
+                            extension: Some(snip721::Extension {
+                                name: Some(sale_info.name),
+                                description: sale_info.metadata
+                            })
+                        }),
+                        memo: None
+                    })?,
+                    funds: vec![]
+                });
+            }
+        }
+        // Otherwise no one made any bids on this sale or none met the reserve.
+
+        // The fee is looked up now rather than at creation
+        // so that the factory can change it at any time.
+        if let Some(factory) = settings.factory.clone() {
+            let factory = FactoryClient(factory.humanize(deps.api)?);
+            let fee: Option<FeeConfig<Addr>> = deps.querier.query(&factory.fee_config()?)?;
+
+            if let Some(fee) = fee {
+                let amount = proceeds * fee.rate;
+                proceeds -= amount;
+                paid_fee = amount;
+
+                if !amount.is_zero() {
+                    credit(deps.storage, &fee.recipient.as_str().canonize(deps.api)?, amount)?;
+
+                    resp = resp
+                        .add_attribute(events::FEE, amount)
+                        .add_attribute(events::FEE_RECIPIENT, fee.recipient);
+                }
+            }
+        }
+
+        PROCEEDS.save(deps.storage, &Proceeds { total: proceeds, claimed: Uint128::zero() })?;
+        SETTLED.save(deps.storage, &true)?;
+
+        if let Some(deposit) = DEPOSIT.load(deps.storage)? {
+            DEPOSIT.remove(deps.storage);
+            return_deposit(deps.storage, deposit)?;
+
+            resp = resp.add_attribute(events::DEPOSIT, deposit);
+        }
+
+        let callback = to_binary(&SettlementHookMsg::AuctionSettled {
+            auction: ContractLink {
+                address: env.contract.address.clone(),
+                code_hash: env.contract.code_hash.clone()
+            },
+            winner: HIGHEST_BID.load(deps.storage)?
+                .map(|x| x.humanize(deps.api))
+                .transpose()?,
+            price,
+            denom: match &settings.bid_token {
+                Some(token) => token.address.clone().humanize(deps.api)?.into_string(),
+                None => settings.denom.as_str().into()
+            }
+        })?;
+
+        let mut hooks = settlement_hooks().iter(deps.storage)?
+            .map(|x| x?.humanize(deps.api))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        // The factory is told like a hook so that it can rank the sale.
+        // Unlike hooks, it can't be removed by the admin.
+        hooks.extend(settings.factory.clone().humanize(deps.api)?);
+
+        for hook in hooks {
+            resp = resp.add_submessage(SubMsg::reply_always(
+                WasmMsg::Execute {
+                    contract_addr: hook.address.into_string(),
+                    code_hash: hook.code_hash,
+                    msg: callback.clone(),
+                    funds: vec![]
+                },
+                SETTLEMENT_HOOK_REPLY_ID
+            ));
+        }
+
+        Ok((resp, paid_fee))
+    }
+
+    // The deposit goes back to whoever is the admin at the time.
+    fn return_deposit(storage: &mut dyn Storage, deposit: Uint128) -> StdResult<()> {
+        let admin = admin::STORE.load(storage)?
+            .ok_or_else(|| StdError::generic_err("Admin not set."))?;
+
+        credit(storage, &admin, deposit)
+    }
+
+    // Returns the original bidder and the current owner of the claim.
+    fn load_claim(
+        storage: &dyn Storage,
+        id: u64
+    ) -> Result<(CanonicalAddr, CanonicalAddr), ContractError> {
+        let bidder = bidder_addresses().get(storage, id)?
+            .ok_or(ClaimError::NotFound(id))?;
+        let owner = CLAIM_OWNERS.load(storage, &id)?
+            .unwrap_or_else(|| bidder.clone());
+
+        Ok((bidder, owner))
+    }
+
+    // The amount that the bidder has to add to their balance to take the first
+    // place. Ties are won by whoever reached the amount first, so the current
+    // highest bid has to be exceeded.
+    fn min_bid_to_win_of(
+        deps: Deps,
+        env: &Env,
+        bidder: &CanonicalAddr
+    ) -> Result<Uint128, ContractError> {
+        let storage = deps.storage;
+        if is_finished(storage, &INFO.load_or_error(storage)?, &env.block)? {
+            return Err(LifecycleError::Finished.into());
+        }
+
+        let settings = load_settings(deps, env)?;
+        if settings.raffle.is_some() {
+            return Err(RaffleError::Unsupported("The min_bid_to_win query").into());
+        }
+
+        let balance = balances::get_or_default(storage, bidder)?;
+
+        let target = match bid_index::get(storage, 0)? {
+            Some(highest) if highest.bidder != *bidder => {
+                highest.amount.checked_add(bid_step(&settings)).map_err(StdError::from)?
+            }
+            // Either no one has bid or the bidder is already the highest.
+            _ => Uint128::one()
+        };
+
+        Ok(target.max(settings.reserve).saturating_sub(balance))
+    }
+
+    // Fails if the bid can't be placed right now, without storing anything.
+    // Returns the part of the amount that is added to the balance and the new balance.
+    fn validate_bid(
+        storage: &dyn Storage,
+        env: &Env,
+        settings: &Settings,
+        bidder: &CanonicalAddr,
+        amount: Uint128
+    ) -> Result<(Uint128, Uint128), ContractError> {
+        let sale_info = INFO.load_or_error(storage)?;
+        if is_finished(storage, &sale_info, &env.block)? {
+            return Err(LifecycleError::Finished.into());
+        }
+
+        if settings.start_block > env.block.height {
+            return Err(LifecycleError::NotStarted.into());
+        }
+
+        // Would count as a bid and lock the config without escrowing anything.
+        if amount.is_zero() {
+            return Err(LifecycleError::ZeroBid.into());
+        }
+
+        assert_cooled_down(storage, env, settings, bidder)?;
+
+        // Only the refundable part of a raffle bid is kept as the balance of the bidder.
+        let refundable = match &settings.raffle {
+            Some(raffle) => {
+                if !(amount % raffle.ticket_price).is_zero() {
+                    return Err(RaffleError::InvalidBid {
+                        amount,
+                        ticket_price: raffle.ticket_price
+                    }.into());
+                }
+
+                amount * raffle.refund_rate
+            }
+            None => amount
+        };
+
+        let balance = balances::get_or_default(storage, bidder)? + refundable;
+
+        if let Some(highest) = bid_index::get(storage, 0)?.filter(|x| x.bidder != *bidder) {
+            if !settings.min_increment.is_zero() &&
+                balance < highest.amount + settings.min_increment
+            {
+                return Err(below_min_increment(settings));
+            }
+        }
+
+        Ok((refundable, balance))
+    }
+
+    // The amount of the denomination that was sent. Auctions that
+    // take bids in a token don't accept native coins at all.
+    fn sent_amount(settings: &Settings, funds: Vec<Coin>) -> Result<Uint128, ContractError> {
+        if settings.bid_token.is_some() {
+            return Err(TokenError::TokenOnly.into());
+        }
+
+        Ok(funds.into_iter()
+            .find(|x| settings.denom == x.denom)
+            .map(|x| x.amount)
+            .unwrap_or_default())
+    }
+
+    // Adds the amount to the balance of the bidder and returns the new balance.
+    fn place_bid(
+        storage: &mut dyn Storage,
+        env: &Env,
+        settings: &Settings,
+        bidder: &CanonicalAddr,
+        amount: Uint128
+    ) -> Result<BidResult, ContractError> {
+        let (refundable, balance) = validate_bid(storage, env, settings, bidder, amount)?;
+        start_cooldown(storage, env, settings, bidder)?;
+        save_converted(storage, settings)?;
+        count(storage, |x| &mut x.bids)?;
+
+        if let Some(raffle) = &settings.raffle {
+            let tickets = u64::try_from((amount / raffle.ticket_price).u128())
+                .map_err(|_| StdError::generic_err("Too many tickets."))?;
+
+            let entropy = [
+                bidder.as_slice(),
+                &amount.to_be_bytes(),
+                &env.block.height.to_be_bytes(),
+                &env.block.time.nanos().to_be_bytes()
+            ].concat();
+
+            raffle::buy(storage, bidder, tickets, amount - refundable, &entropy)?;
+        }
+
+        if balances::get(storage, bidder)?.is_none() {
+            register_bidder(storage, bidder, settings)?;
+        }
+
+        set_bid(storage, bidder, balance)?;
+        escrow(storage, |total| total.checked_add(refundable))?;
+        let receipt = issue_receipt(storage, &env.block)?;
+
+        resolve_proxy_bids(storage, settings)?;
+
+        Ok(BidResult {
+            balance: balances::get_or_default(storage, bidder)?,
+            receipt
+        })
+    }
+
+    // Whether the bidder would have the highest bid after raising it to the balance,
+    // once resolve_proxy_bids has raised the bids of both from their proxy deposits.
+    fn would_be_highest(
+        storage: &dyn Storage,
+        settings: &Settings,
+        bidder: &CanonicalAddr,
+        balance: Uint128
+    ) -> StdResult<bool> {
+        // Either no one else has bid or the bidder is already the highest.
+        let Some(top) = bid_index::get(storage, 0)?.filter(|x| x.bidder != *bidder) else {
+            return Ok(!balance.is_zero());
+        };
+
+        let step = bid_step(settings);
+        let top_max = top.amount.checked_add(unspent(storage, &top.bidder)?)?;
+        let own_max = balance.checked_add(unspent(storage, bidder)?)?;
+
+        // Equal bids keep their order, so the current highest stays ahead.
+        if balance > top.amount {
+            Ok(top_max < balance.checked_add(step)? || own_max >= top_max)
+        } else {
+            Ok(own_max > balance && own_max >= top.amount.checked_add(step)? && own_max > top_max)
+        }
+    }
+
+    // Only contracts have contract info on chain, so any address
+    // that the query succeeds for is a contract.
+    fn assert_not_contract(deps: Deps, settings: &Settings, address: &Addr) -> Result<(), ContractError> {
+        if !settings.exclude_contracts {
+            return Ok(());
+        }
+
+        let query = QueryRequest::Wasm(WasmQuery::ContractInfo {
+            contract_addr: address.to_string()
+        });
+
+        if deps.querier.query::<ContractInfoResponse>(&query).is_ok() {
+            return Err(AuthError::ContractBidder(address.to_string()).into());
+        }
+
+        Ok(())
+    }
+
+    fn status_name<A>(status: &ContractStatus<A>) -> &'static str {
+        match status {
+            ContractStatus::Operational => statuses::OPERATIONAL,
+            ContractStatus::Paused { .. } => statuses::PAUSED,
+            ContractStatus::Migrating { .. } => statuses::MIGRATING
+        }
+    }
+
+    // Whoever owns the auction once it is settled or cancelled keeps it.
+    fn assert_transferable(storage: &dyn Storage) -> Result<(), ContractError> {
+        if SETTLED.load_or_default(storage)? {
+            return Err(LifecycleError::AlreadySettled.into());
+        }
+
+        if CANCELLED.load_or_default(storage)? {
+            return Err(LifecycleError::Cancelled.into());
+        }
+
+        Ok(())
+    }
+
+    fn simulate_bid_of(
+        deps: Deps,
+        env: &Env,
+        bidder: &CanonicalAddr,
+        amount: Uint128
+    ) -> Result<BidSimulation, ContractError> {
+        let settings = load_settings(deps, env)?;
+
+        let fee_rate = match settings.factory.clone() {
+            Some(factory) => {
+                let factory = FactoryClient(factory.humanize(deps.api)?);
+                let fee: Option<FeeConfig<Addr>> = deps.querier.query(&factory.fee_config()?)?;
+
+                fee.map(|x| x.rate).unwrap_or_default()
+            }
+            None => Decimal::zero()
+        };
+
+        let mut simulation = BidSimulation {
+            accepted: false,
+            error: None,
+            would_be_highest: false,
+            balance: balances::get_or_default(deps.storage, bidder)?,
+            reserve: settings.reserve,
+            min_increment: settings.min_increment,
+            fee_rate
+        };
+
+        let validation = assert_not_contract(deps, &settings, &bidder.humanize(deps.api)?)
+            .and_then(|_| validate_bid(deps.storage, env, &settings, bidder, amount));
+
+        match validation {
+            Ok((_, balance)) => {
+                simulation.accepted = true;
+                simulation.balance = balance;
+                // The winner of a raffle is drawn instead.
+                simulation.would_be_highest = settings.raffle.is_none() &&
+                    would_be_highest(deps.storage, &settings, bidder, balance)?;
+            }
+            Err(err) => simulation.error = Some(err.to_string())
+        }
+
+        Ok(simulation)
+    }
+
+    // Counts the bid and returns the id of its receipt. Ids start at 1.
+    fn issue_receipt(storage: &mut dyn Storage, block: &BlockInfo) -> StdResult<u64> {
+        let receipt = NUM_BIDS.load_or_default(storage)? + 1;
+
+        NUM_BIDS.save(storage, &receipt)?;
+        BID_RECEIPTS.save(storage, &receipt, &BidReceipt {
+            height: block.height,
+            time: block.time.seconds()
+        })?;
+
+        Ok(receipt)
+    }
+
+    // Fails if the bidder is still cooling down from their last bid and records this one.
+    // Bids raised from a proxy deposit don't count as they aren't placed by the bidder.
+    fn start_cooldown(
+        storage: &mut dyn Storage,
+        env: &Env,
+        settings: &Settings,
+        bidder: &CanonicalAddr
+    ) -> Result<(), ContractError> {
+        if settings.bid_cooldown == 0 {
+            return Ok(());
+        }
+
+        assert_cooled_down(storage, env, settings, bidder)?;
+        LAST_BID.save(storage, bidder, &env.block.height)?;
+
+        Ok(())
+    }
+
+    fn assert_cooled_down(
+        storage: &dyn Storage,
+        env: &Env,
+        settings: &Settings,
+        bidder: &CanonicalAddr
+    ) -> Result<(), ContractError> {
+        if let Some(last) = LAST_BID.load(storage, bidder)? {
+            let until = last.saturating_add(settings.bid_cooldown);
+            if env.block.height < until {
+                return Err(LifecycleError::BidCooldown(until).into());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn below_min_increment(settings: &Settings) -> ContractError {
+        StdError::generic_err(format!(
+            "Bid must exceed the current highest by at least {}{}.",
+            settings.min_increment,
+            settings.denom
+        )).into()
+    }
+
+    // The smallest amount by which a bid can take the first place.
+    #[inline]
+    fn bid_step(settings: &Settings) -> Uint128 {
+        settings.min_increment.max(Uint128::one())
+    }
+
+    fn register_bidder(
+        storage: &mut dyn Storage,
+        bidder: &CanonicalAddr,
+        settings: &Settings
+    ) -> StdResult<()> {
+        let id = bidder_addresses().push(storage, bidder)?;
+
+        if settings.tradable_claims {
+            CLAIM_IDS.save(storage, bidder, &id)?;
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn set_bid(storage: &mut dyn Storage, bidder: &CanonicalAddr, amount: Uint128) -> StdResult<()> {
+        balances::set(storage, bidder, amount);
+        bid_index::update(storage, bidder, amount)
+    }
+
+    // Moves funds from the unspent proxy deposit of the bidder into their bid.
+    fn raise_bid(
+        storage: &mut dyn Storage,
+        bidder: &CanonicalAddr,
+        from: Uint128,
+        to: Uint128
+    ) -> StdResult<()> {
+        if to <= from {
+            return Ok(());
+        }
+
+        let unspent = unspent(storage, bidder)?.checked_sub(to - from)?;
+        PROXY_UNSPENT.save(storage, bidder, &unspent)?;
+
+        set_bid(storage, bidder, to)
+    }
+
+    // Lets the runner-up respond to being outbid using their proxy deposit. If the
+    // highest bidder has a deposit too, the one with the higher maximum ends up
+    // ahead by the minimum step while the other one bids their whole maximum.
+    // Equal maximums are won by the highest bidder.
+    fn resolve_proxy_bids(storage: &mut dyn Storage, settings: &Settings) -> StdResult<()> {
+        if let (Some(top), Some(second)) = (bid_index::get(storage, 0)?, bid_index::get(storage, 1)?) {
+            let step = bid_step(settings);
+            let top_max = top.amount.checked_add(unspent(storage, &top.bidder)?)?;
+            let second_max = second.amount.checked_add(unspent(storage, &second.bidder)?)?;
+
+            if second_max > second.amount && second_max >= top.amount.checked_add(step)? {
+                // The highest bid is raised first so that it stays ahead on equal amounts.
+                if top_max >= second_max {
+                    let amount = top_max.min(second_max.saturating_add(step));
+                    raise_bid(storage, &top.bidder, top.amount, amount)?;
+                    raise_bid(storage, &second.bidder, second.amount, second_max)?;
+                } else {
+                    let amount = second_max.min(top_max.saturating_add(step));
+                    raise_bid(storage, &top.bidder, top.amount, top_max)?;
+                    raise_bid(storage, &second.bidder, second.amount, amount)?;
+                }
+            }
+        }
+
+        // Only bids retracted before the end can leave no one with a balance.
+        match bid_index::get(storage, 0)?.filter(|x| !x.amount.is_zero()) {
+            Some(top) => HIGHEST_BID.save(storage, &top.bidder)?,
+            None => HIGHEST_BID.remove(storage)
+        }
+
+        Ok(())
+    }
+
+    fn proxy_bid_of(storage: &dyn Storage, bidder: &CanonicalAddr) -> Result<ProxyBid, ContractError> {
+        let active = balances::get_or_default(storage, bidder)?;
+
+        Ok(ProxyBid {
+            active,
+            committed: active.checked_add(unspent(storage, bidder)?).map_err(StdError::from)?
+        })
+    }
+
+    // Bidders whose balance was refunded are still in the index but
+    // are sorted below everyone else, so they are reported as unranked.
+    fn rank_of(storage: &dyn Storage, bidder: &CanonicalAddr) -> Result<BidRank, ContractError> {
+        let balance = balances::get_or_default(storage, bidder)?;
+        let highest = bid_index::get(storage, 0)?
+            .map(|x| x.amount)
+            .unwrap_or_default();
+
+        let rank = if balance.is_zero() {
+            None
+        } else {
+            bid_index::rank(storage, bidder)?
+        };
+
+        Ok(BidRank {
+            rank,
+            behind_highest: highest.saturating_sub(balance)
+        })
+    }
+
+    fn active_bids_page(
+        storage: &dyn Storage,
+        pagination: Pagination
+    ) -> Result<PaginatedResponse<Uint128>, ContractError> {
+        let addresses = bidder_addresses().iter(storage)?;
+        let len = addresses.len();
+
+        let iterator = addresses
+            .skip(pagination.start as usize)
+            .take(pagination.limit() as usize);
+
+        Ok(PaginatedResponse {
+            total: len,
+            entries: iterator
+                .map(|address| balances::get_or_default(storage, &address?))
+                .collect::<StdResult<Vec<Uint128>>>()?
+        })
+    }
+
+    fn bid_entries_page(
+        deps: Deps,
+        pagination: Pagination
+    ) -> Result<PaginatedResponse<BidEntry>, ContractError> {
+        let addresses = bidder_addresses().iter(deps.storage)?;
+        let len = addresses.len();
+
+        let iterator = addresses
+            .skip(pagination.start as usize)
+            .take(pagination.limit() as usize);
+
+        Ok(PaginatedResponse {
+            total: len,
+            entries: iterator
+                .map(|address| {
+                    let address = address?;
+
+                    Ok(BidEntry {
+                        amount: balances::get_or_default(deps.storage, &address)?,
+                        address: address.humanize(deps.api)?
+                    })
+                })
+                .collect::<StdResult<Vec<BidEntry>>>()?
+        })
+    }
+
+    // The raffle draw and manual selection both save the winner as the highest bidder.
+    fn winner_payload_of(
+        storage: &dyn Storage,
+        address: &CanonicalAddr
+    ) -> Result<Option<Binary>, ContractError> {
+        if !SETTLED.load_or_default(storage)? {
+            return Err(LifecycleError::NotSettled.into());
+        }
+
+        if HIGHEST_BID.load(storage)?.as_ref() != Some(address) {
+            return Err(AuthError::Unauthorized.into());
+        }
+
+        Ok(WINNER_PAYLOAD.load(storage)?)
+    }
+
+    fn assert_text_len(what: &str, text: &str, max: usize) -> StdResult<()> {
+        if text.len() > max {
+            return Err(StdError::generic_err(format!(
+                "{} is {} bytes long but the maximum is {}.",
+                what,
+                text.len(),
+                max
+            )));
+        }
+
+        Ok(())
+    }
+
+    // Only the winner and the admin of a settled sale take part in the delivery.
+    fn assert_delivery_party(deps: Deps, address: &CanonicalAddr) -> Result<(), ContractError> {
+        if !SETTLED.load_or_default(deps.storage)? {
+            return Err(LifecycleError::NotSettled.into());
+        }
+
+        let is_winner = HIGHEST_BID.load(deps.storage)?.as_ref() == Some(address);
+        let is_admin = admin::STORE.load(deps.storage)?.as_ref() == Some(address);
+
+        if !is_winner && !is_admin {
+            return Err(AuthError::Unauthorized.into());
+        }
+
+        Ok(())
+    }
+
+    fn delivery_of(deps: Deps, address: &CanonicalAddr) -> Result<Delivery, ContractError> {
+        assert_delivery_party(deps, address)?;
+
+        Ok(Delivery {
+            details: DELIVERY_DETAILS.load(deps.storage)?,
+            fulfillment_proof: FULFILLMENT_PROOF.load(deps.storage)?
+        })
+    }
+
+    // The bidder can always view their memo, the admin only once there
+    // is someone to deliver to. Not even the auditor can view it.
+    fn memo_of(
+        deps: Deps,
+        bidder: &CanonicalAddr,
+        viewer: &CanonicalAddr
+    ) -> Result<Option<String>, ContractError> {
+        if viewer != bidder {
+            let admin = admin::STORE.load(deps.storage)?;
+            if admin.as_ref() != Some(viewer) {
+                return Err(AuthError::Unauthorized.into());
+            }
+
+            if !SETTLED.load_or_default(deps.storage)? {
+                return Err(LifecycleError::NotSettled.into());
+            }
+        }
+
+        Ok(MEMOS.load(deps.storage, bidder)?)
+    }
+
+    // Fails if the auction was configured to guard queries
+    // and the contract is currently not operational.
+    fn assert_queries_allowed(deps: Deps) -> Result<(), ContractError> {
+        if SETTINGS.load_or_error(deps.storage)?.guard_queries &&
+            !killswitch::is_operational(deps)?
+        {
+            return Err(LifecycleError::Paused.into());
+        }
+
+        Ok(())
+    }
+
+    fn is_auditor(storage: &dyn Storage, address: &CanonicalAddr) -> StdResult<bool> {
+        Ok(AUDITOR.load(storage)?.as_ref() == Some(address))
+    }
+
+    // Accepts either the viewing key of the given address or the one of the auditor.
+    #[cfg(feature = "vk")]
+    fn authenticate_viewer(
+        storage: &dyn Storage,
+        key: &ViewingKey,
+        address: &CanonicalAddr
+    ) -> Result<(), ContractError> {
+        if auth::authenticate(storage, key, address).is_ok() {
+            return Ok(());
+        }
+
+        authenticate_auditor(storage, key)
+    }
+
+    #[cfg(feature = "vk")]
+    fn authenticate_auditor(
+        storage: &dyn Storage,
+        key: &ViewingKey
+    ) -> Result<(), ContractError> {
+        match AUDITOR.load(storage)? {
+            Some(auditor) => auth::authenticate(storage, key, &auditor)
+                .map_err(|_| AuthError::Unauthorized.into()),
+            None => Err(AuthError::Unauthorized.into())
+        }
+    }
+
+    impl Contract {
+        // This runs before executing any messages.
+        #[execute_guard]
+        pub fn guard(msg: &ExecuteMsg) -> Result<(), StdError> {
+            let operational = killswitch::assert_is_operational(deps.as_ref());
+    
+            // Only allow the killswitch module messages so that we can resume the
+            // the contract if it was paused for example.
+            // However, if the contract has been set to the "migrating" status,
+            // Even the admin cannot reverse that anymore.
+            // A closed sale still pays out whatever hasn't been claimed yet.
+            match operational {
+                Err(_) if matches!(msg, ExecuteMsg::Claim { }) &&
+                    CLOSED.load_or_default(deps.storage)? => Ok(()),
+                Err(err) if !matches!(msg, ExecuteMsg::SetStatus { .. }) => Err(err),
+                _ => Ok(())
+            }
+        }
+
+        #[reply]
+        pub fn reply(reply: Reply) -> Result<Response, StdError> {
+            if reply.id != SETTLEMENT_HOOK_REPLY_ID {
+                return Err(StdError::generic_err("Unexpected reply id."));
+            }
+
+            let resp = match reply.result {
+                SubMsgResult::Ok(_) => Response::default(),
+                SubMsgResult::Err(error) => Response::default().add_event(
+                    Event::new(events::SETTLEMENT_HOOK_FAILED)
+                        .add_attribute(events::ERROR, error)
+                )
+            };
+
+            Ok(resp)
+        }
+    }
+
+    impl Auction for Contract {
+        type Error = ContractError;
+
+        #[init(entry_wasm)]
+        fn new(config: Config) -> Result<Response, <Self as Auction>::Error> {
+            config.assert_supported()?;
+
+            if config.end_block <= env.block.height {
+                return Err(LifecycleError::EndBlockPassed.into());
+            }
+
+            if config.start_block.is_some_and(|x| x >= config.end_block) {
+                return Err(LifecycleError::StartAfterEnd.into());
+            }
+
+            if config.end_time.is_some_and(|x| x <= env.block.time.seconds()) {
+                return Err(LifecycleError::EndTimePassed.into());
+            }
+
+            SaleInfo::validate_name(&config.name)?;
+
+            if let Some(metadata) = &config.metadata {
+                SaleInfo::validate_metadata(metadata)?;
+            }
+
+            if let Some(raffle) = &config.raffle {
+                if raffle.ticket_price.is_zero() {
+                    return Err(RaffleError::ZeroTicketPrice.into());
+                }
+
+                if raffle.refund_rate.is_some_and(|x| x > Decimal::one()) {
+                    return Err(RaffleError::RefundRateTooHigh.into());
+                }
+
+                // These rely on the highest bid winning the sale.
+                if config.reserve.is_some() {
+                    return Err(RaffleError::Unsupported("A reserve price").into());
+                }
+
+                if config.min_increment.is_some() {
+                    return Err(RaffleError::Unsupported("A minimum increment").into());
+                }
+
+                if config.tradable_claims == Some(true) {
+                    return Err(RaffleError::Unsupported("Tradable claims").into());
+                }
+
+                if config.manual_winner == Some(true) {
+                    return Err(RaffleError::Unsupported("Manual winner selection").into());
+                }
+
+                // Cancelling would leave the pot without an owner.
+                if config.dispute_blocks.is_some_and(|x| x > 0) {
+                    return Err(RaffleError::Unsupported("A dispute window").into());
+                }
+
+                if config.usd_reserve.is_some() || config.usd_min_increment.is_some() {
+                    return Err(RaffleError::Unsupported("USD denominated amounts").into());
+                }
+
+                if config.early_retraction_penalty.is_some() {
+                    return Err(RaffleError::Unsupported("Early retraction").into());
+                }
+            }
+
+            if let Some(bps) = config.early_retraction_penalty.filter(|x| *x > MAX_BPS) {
+                return Err(RetractionError::PenaltyTooHigh(bps).into());
+            }
+
+            if config.oracle.is_none() &&
+                (config.usd_reserve.is_some() || config.usd_min_increment.is_some())
+            {
+                return Err(OracleError::Missing.into());
+            }
+
+            // Prices are only looked up for the native denomination.
+            if config.bid_token.is_some() && config.oracle.is_some() {
+                return Err(TokenError::WithOracle.into());
+            }
+
+            // Payouts are made in the token, so the deposit couldn't be paid back.
+            let seller_deposit = config.seller_deposit.unwrap_or_default();
+            if !seller_deposit.is_zero() && config.bid_token.is_some() {
+                return Err(DepositError::WithToken.into());
+            }
+
+            if let Some(co_sellers) = &config.co_sellers {
+                co_sellers.validate()?;
+
+                if config.beneficiary.is_some() {
+                    return Err(CoSellerError::WithBeneficiary.into());
+                }
+            }
+
+            if let Some(vote) = &config.extension_vote {
+                if vote.threshold == 0 || vote.threshold > MAX_BPS {
+                    return Err(ExtensionError::InvalidThreshold.into());
+                }
+
+                if config.end_time.is_some() {
+                    return Err(ExtensionError::WithEndTime.into());
+                }
+            }
+
+            let denom = config.denom();
+
+            let sent = info.funds.iter()
+                .find(|x| denom == x.denom)
+                .map(|x| x.amount)
+                .unwrap_or_default();
+
+            if sent != seller_deposit {
+                return Err(DepositError::Mismatch(seller_deposit).into());
+            }
+
+            if !seller_deposit.is_zero() {
+                DEPOSIT.save(deps.storage, &seller_deposit)?;
+            }
+
+            if let Some(end_time) = config.end_time {
+                END_TIME.save(deps.storage, &end_time)?;
+            }
+
+            if let Some(payload) = &config.winner_payload {
+                WINNER_PAYLOAD.save(deps.storage, payload)?;
+            }
+
+            if let Some(co_sellers) = &config.co_sellers {
+                CO_SELLERS.save(deps.storage, &CoSellers {
+                    sellers: co_sellers.sellers.iter()
+                        .map(|x| Ok::<_, StdError>(CoSeller {
+                            address: x.address.as_str().canonize(deps.api)?,
+                            share: x.share
+                        }))
+                        .collect::<StdResult<_>>()?,
+                    threshold: co_sellers.threshold
+                })?;
+            }
+
+            admin::init(deps.branch(), config.admin.as_deref(), &info)?;
+            layout::save(deps.storage);
+            version::save(deps.storage, version::AUCTION_CONTRACT_NAME)?;
+            CREATED_AT.save(deps.storage, &CreatedAt {
+                height: env.block.height,
+                time: env.block.time.seconds()
+            })?;
+            INFO.save(deps.storage, &SaleInfo {
+                name: config.name,
+                end_block: config.end_block,
+                metadata: config.metadata
+            })?;
+            SETTINGS.save(deps.storage, &Settings {
+                guard_queries: config.guard_queries.unwrap_or_default(),
+                start_block: config.start_block.unwrap_or(env.block.height),
+                denom,
+                reserve: config.reserve.unwrap_or_default(),
+                min_increment: config.min_increment.unwrap_or_default(),
+                beneficiary: config.beneficiary
+                    .map(|x| x.as_str().canonize(deps.api))
+                    .transpose()?,
+                receipt_nft: config.receipt_nft
+                    .map(|x| Ok::<_, StdError>(ContractLink {
+                        address: x.address.as_str().canonize(deps.api)?,
+                        code_hash: x.code_hash
+                    }))
+                    .transpose()?,
+                tradable_claims: config.tradable_claims.unwrap_or_default(),
+                raffle: config.raffle.map(|x| RaffleSettings {
+                    ticket_price: x.ticket_price,
+                    refund_rate: x.refund_rate.unwrap_or_default()
+                }),
+                manual_winner: config.manual_winner.unwrap_or_default(),
+                dispute_blocks: config.dispute_blocks.unwrap_or_default(),
+                arbiter: config.arbiter
+                    .map(|x| x.as_str().canonize(deps.api))
+                    .transpose()?,
+                early_retraction_penalty: config.early_retraction_penalty,
+                bid_cooldown: config.bid_cooldown.unwrap_or_default(),
+                oracle: config.oracle
+                    .map(|x| Ok::<_, StdError>(OracleSettings {
+                        contract: ContractLink {
+                            address: x.address.as_str().canonize(deps.api)?,
+                            code_hash: x.code_hash
+                        },
+                        max_age: x.max_age
+                    }))
+                    .transpose()?,
+                usd_reserve: config.usd_reserve,
+                usd_min_increment: config.usd_min_increment,
+                show_highest_bidder: config.show_highest_bidder.unwrap_or_default(),
+                hide_current_highest: config.hide_current_highest.unwrap_or_default(),
+                public_active_bids: config.public_active_bids.unwrap_or_default(),
+                extension_vote: config.extension_vote.map(|x| ExtensionVoteSettings {
+                    threshold: x.threshold,
+                    max_blocks: x.max_blocks
+                }),
+                exclude_contracts: config.exclude_contracts.unwrap_or_default(),
+                seller_deposit,
+                bid_token: config.bid_token
+                    .map(|x| Ok::<_, StdError>(ContractLink {
+                        address: x.address.as_str().canonize(deps.api)?,
+                        code_hash: x.code_hash
+                    }))
+                    .transpose()?,
+                factory: config.factory
+                    .map(|x| Ok::<_, StdError>(ContractLink {
+                        address: x.address.as_str().canonize(deps.api)?,
+                        code_hash: x.code_hash
+                    }))
+                    .transpose()?
+            })?;
+    
+            // Lets the factory verify what it has instantiated.
+            Ok(Response::default()
+                .set_data(to_binary(&ContractLink {
+                    address: env.contract.address,
+                    code_hash: env.contract.code_hash
+                })?)
+            )
+        }
+    
+        #[execute]
+        fn bid(
+            auto_refund: Option<bool>,
+            memo: Option<String>
+        ) -> Result<Response, <Self as Auction>::Error> {
+            let sender = info.sender.as_str().canonize(deps.api)?;
+            let settings = load_settings(deps.as_ref(), &env)?;
+            assert_not_contract(deps.as_ref(), &settings, &info.sender)?;
+
+            if let Some(memo) = &memo {
+                assert_text_len("Memo", memo, MAX_MEMO_LEN)?;
+            }
+
+            let amount = sent_amount(&settings, info.funds)?;
+            let result = place_bid(deps.storage, &env, &settings, &sender, amount)?;
+
+            if let Some(enabled) = auto_refund {
+                set_auto_refund_of(deps.storage, &sender, enabled)?;
+            }
+
+            if let Some(memo) = memo {
+                MEMOS.save(deps.storage, &sender, &memo)?;
+            }
+
+            Ok(Response::default()
+                .add_attribute(events::ACTION, actions::BID)
+                .add_attribute(events::BIDDER, info.sender)
+                .add_attribute(events::RECEIPT, result.receipt.to_string())
+                .set_data(to_binary(&result)?)
+            )
+        }
+    
+        #[execute]
+        fn bid_for(beneficiary: String) -> Result<Response, <Self as Auction>::Error> {
+            let beneficiary = deps.api.addr_validate(&beneficiary)?;
+            let bidder = beneficiary.as_str().canonize(deps.api)?;
+
+            if !ACCEPTS_BIDS_FOR.load(deps.storage, &bidder)?.unwrap_or_default() {
+                return Err(AuthError::BidsForNotAccepted(beneficiary.into_string()).into());
+            }
+
+            // Neither the bidder nor whoever pays for the bid can be a contract.
+            let settings = load_settings(deps.as_ref(), &env)?;
+            assert_not_contract(deps.as_ref(), &settings, &beneficiary)?;
+            assert_not_contract(deps.as_ref(), &settings, &info.sender)?;
+
+            let amount = sent_amount(&settings, info.funds)?;
+            let result = place_bid(deps.storage, &env, &settings, &bidder, amount)?;
+
+            Ok(Response::default()
+                .add_attribute(events::ACTION, actions::BID_FOR)
+                .add_attribute(events::BIDDER, beneficiary)
+                .add_attribute(events::PAYER, info.sender)
+                .add_attribute(events::RECEIPT, result.receipt.to_string())
+                .set_data(to_binary(&result)?)
+            )
+        }
+
+        #[execute]
+        fn proxy_bid() -> Result<Response, <Self as Auction>::Error> {
+            let sale_info = INFO.load_or_error(deps.storage)?;
+            if is_finished(deps.storage, &sale_info, &env.block)? {
+                return Err(LifecycleError::Finished.into());
+            }
+
+            let settings = load_settings(deps.as_ref(), &env)?;
+            if settings.start_block > env.block.height {
+                return Err(LifecycleError::NotStarted.into());
+            }
+
+            if settings.raffle.is_some() {
+                return Err(RaffleError::Unsupported("Proxy bidding").into());
+            }
+
+            assert_not_contract(deps.as_ref(), &settings, &info.sender)?;
+
+            let amount = sent_amount(&settings, info.funds)?;
+
+            if amount.is_zero() {
+                return Err(StdError::generic_err("A proxy bid requires a deposit.").into());
+            }
+
+            let sender = info.sender.as_str().canonize(deps.api)?;
+            start_cooldown(deps.storage, &env, &settings, &sender)?;
+            save_converted(deps.storage, &settings)?;
+            count(deps.storage, |x| &mut x.bids)?;
+
+            let balance = match balances::get(deps.storage, &sender)? {
+                Some(balance) => balance,
+                None => {
+                    register_bidder(deps.storage, &sender, &settings)?;
+                    set_bid(deps.storage, &sender, Uint128::zero())?;
+
+                    Uint128::zero()
+                }
+            };
+
+            let max = proxy_bid_of(deps.storage, &sender)?.committed + amount;
+            PROXY_UNSPENT.save(deps.storage, &sender, &(max - balance))?;
+            escrow(deps.storage, |total| total.checked_add(amount))?;
+            let receipt = issue_receipt(deps.storage, &env.block)?;
+
+            // Start with the lowest bid that takes the first place and
+            // leave the rest to the highest bidder's deposit, if any.
+            let opening = match bid_index::get(deps.storage, 0)? {
+                Some(highest) if highest.bidder != sender && !highest.amount.is_zero() => {
+                    let opening = highest.amount.checked_add(bid_step(&settings))
+                        .map_err(StdError::from)?;
+
+                    if max < opening {
+                        return Err(below_min_increment(&settings));
+                    }
+
+                    opening.max(settings.reserve).min(max)
+                }
+                _ => max.min(balance.max(settings.reserve).max(Uint128::one()))
+            };
+
+            raise_bid(deps.storage, &sender, balance, opening)?;
+            resolve_proxy_bids(deps.storage, &settings)?;
+
+            let balance = balances::get_or_default(deps.storage, &sender)?;
+
+            Ok(Response::default()
+                .add_attribute(events::ACTION, actions::PROXY_BID)
+                .add_attribute(events::BIDDER, info.sender)
+                .add_attribute(events::RECEIPT, receipt.to_string())
+                .set_data(to_binary(&BidResult { balance, receipt })?)
+            )
+        }
+
+        #[execute]
+        fn bid_from_allowance(amount: Uint128) -> Result<Response, <Self as Auction>::Error> {
+            let settings = load_settings(deps.as_ref(), &env)?;
+            let Some(token) = settings.bid_token.clone() else {
+                return Err(TokenError::NotEnabled.into());
+            };
+
+            if amount.is_zero() {
+                return Err(LifecycleError::ZeroBid.into());
+            }
+
+            assert_not_contract(deps.as_ref(), &settings, &info.sender)?;
+
+            let sender = info.sender.as_str().canonize(deps.api)?;
+            let result = place_bid(deps.storage, &env, &settings, &sender, amount)?;
+
+            // The bid is only kept if the token accepts the transfer.
+            let token = token.humanize(deps.api)?;
+
+            Ok(Response::default()
+                .add_message(WasmMsg::Execute {
+                    contract_addr: token.address.into_string(),
+                    code_hash: token.code_hash,
+                    msg: to_binary(&snip20::ExecuteMsg::TransferFrom {
+                        owner: info.sender.to_string(),
+                        recipient: env.contract.address.to_string(),
+                        amount,
+                        memo: None
+                    })?,
+                    funds: vec![]
+                })
+                .add_attribute(events::ACTION, actions::BID_FROM_ALLOWANCE)
+                .add_attribute(events::BIDDER, info.sender)
+                .add_attribute(events::RECEIPT, result.receipt.to_string())
+                .set_data(to_binary(&result)?)
+            )
+        }
+
+        #[execute]
+        fn set_auto_refund(enabled: bool) -> Result<Response, <Self as Auction>::Error> {
+            let sender = info.sender.as_str().canonize(deps.api)?;
+            set_auto_refund_of(deps.storage, &sender, enabled)?;
+
+            Ok(Response::default()
+                .add_attribute(events::ACTION, actions::SET_AUTO_REFUND)
+                .add_attribute(events::ENABLED, enabled.to_string())
+            )
+        }
+
+        #[execute]
+        fn accept_bids_for(accept: bool) -> Result<Response, <Self as Auction>::Error> {
+            let sender = info.sender.as_str().canonize(deps.api)?;
+
+            if accept {
+                ACCEPTS_BIDS_FOR.save(deps.storage, &sender, &true)?;
+            } else {
+                ACCEPTS_BIDS_FOR.remove(deps.storage, &sender);
+            }
+
+            Ok(Response::default()
+                .add_attribute(events::ACTION, actions::ACCEPT_BIDS_FOR)
+                .add_attribute(events::ACCEPT, accept.to_string())
+            )
+        }
+
+        #[execute]
+        fn retract_bid() -> Result<Response, <Self as Auction>::Error> {
+            let sale_info = INFO.load_or_error(deps.storage)?;
+            let sender = info.sender.as_str().canonize(deps.api)?;
+            count(deps.storage, |x| &mut x.retractions)?;
+
+            let (balance, penalty) = if is_finished(deps.storage, &sale_info, &env.block)? {
+                let settings = ended_settings(deps.storage)?;
+                if settings.raffle.is_some() && !SETTLED.load_or_default(deps.storage)? {
+                    return Err(RaffleError::NotDrawn.into());
+                }
+
+                assert_winner_known(deps.storage, &settings)?;
+
+                // A transferred claim can only be redeemed by its new owner.
+                if let Some(id) = CLAIM_IDS.load(deps.storage, &sender)? {
+                    if load_claim(deps.storage, id)?.1 != sender {
+                        return Err(ClaimError::Transferred.into());
+                    }
+                }
+
+                let balance = take_refund(deps.storage, &sender, settings.reserve)?;
+
+                (balance, Uint128::zero())
+            } else {
+                // The reserve doesn't apply yet so the oracle isn't needed.
+                let settings = SETTINGS.load_or_error(deps.storage)?;
+                let Some(bps) = settings.early_retraction_penalty else {
+                    return Err(LifecycleError::NotFinished.into());
+                };
+
+                take_early_refund(deps.storage, &settings, &sender, bps)?
+            };
+
+            if balance.is_zero() && penalty.is_zero() {
+                return Err(LifecycleError::NothingToRefund.into());
+            }
+
+            credit(deps.storage, &sender, balance)?;
+
+            let mut resp = Response::default()
+                .add_attribute(events::ACTION, actions::RETRACT_BID)
+                .add_attribute(events::BIDDER, info.sender)
+                .add_attribute(events::REFUND, balance);
+
+            if !penalty.is_zero() {
+                resp = resp.add_attribute(events::PENALTY, penalty);
+            }
+
+            Ok(resp.set_data(to_binary(&RetractResult { refunded: balance, penalty })?))
+        }
+
+        #[execute]
+        #[admin::require_admin]
+        fn claim_proceeds(amount: Option<Uint128>) -> Result<Response, <Self as Auction>::Error> {
+            if CO_SELLERS.load(deps.storage)?.is_some() {
+                return Err(CoSellerError::ApprovalRequired.into());
+            }
+
+            let settings = SETTINGS.load_or_error(deps.storage)?;
+            let recipient = match settings.beneficiary {
+                Some(beneficiary) => beneficiary,
+                None => info.sender.as_str().canonize(deps.api)?
+            };
+
+            let resp = Response::default()
+                .add_attribute(events::ACTION, actions::CLAIM_PROCEEDS);
+
+            pay_proceeds(deps, &env, amount, &[(recipient, MAX_BPS)], resp)
+        }
+
+        #[execute]
+        fn claim() -> Result<Response, <Self as Auction>::Error> {
+            let sender = info.sender.as_str().canonize(deps.api)?;
+
+            let amount = PAYOUTS.load(deps.storage, &sender)?.unwrap_or_default();
+            if amount.is_zero() {
+                return Err(LifecycleError::NothingToClaim.into());
+            }
+
+            PAYOUTS.remove(deps.storage, &sender);
+            let unclaimed = UNCLAIMED.load_or_default(deps.storage)?;
+            UNCLAIMED.save(deps.storage, &unclaimed.saturating_sub(amount))?;
+            count(deps.storage, |x| &mut x.claims)?;
+
+            let settings = SETTINGS.load_or_error(deps.storage)?;
+
+            let resp = Response::default()
+                .add_message(payout_msg(deps.as_ref(), &settings, &info.sender, amount)?);
+
+            Ok(resp
+                .add_attribute(events::ACTION, actions::CLAIM)
+                .add_attribute(events::RECIPIENT, info.sender)
+                .add_attribute(events::AMOUNT, amount)
+            )
+        }
+
+        #[execute]
+        fn settle() -> Result<Response, <Self as Auction>::Error> {
+            if SETTLED.load_or_default(deps.storage)? {
+                return Err(LifecycleError::AlreadySettled.into());
+            }
+
+            let (sale_info, settings) = settlement_settings(deps.as_ref(), &env)?;
+
+            let resp = Response::default()
+                .add_attribute(events::ACTION, actions::SETTLE);
+            let (resp, _) = settle_sale(deps.branch(), &env, sale_info, &settings, resp)?;
+
+            Ok(resp)
+        }
+
+        #[execute]
+        fn propose_extension(blocks: u64) -> Result<Response, <Self as Auction>::Error> {
+            let Some(vote) = SETTINGS.load_or_error(deps.storage)?.extension_vote else {
+                return Err(ExtensionError::NotEnabled.into());
+            };
+
+            let left = vote.max_blocks - EXTENDED_BLOCKS.load_or_default(deps.storage)?;
+            if blocks == 0 || blocks > left {
+                return Err(ExtensionError::InvalidBlocks { blocks, left }.into());
+            }
+
+            let id = extension_proposals().push(deps.storage, &ExtensionProposal {
+                blocks,
+                votes: Uint128::zero(),
+                applied: None
+            })?;
+
+            let sender = info.sender.as_str().canonize(deps.api)?;
+            let resp = Response::default()
+                .add_attribute(events::ACTION, actions::PROPOSE_EXTENSION)
+                .add_attribute(events::BLOCKS, blocks.to_string())
+                .set_data(to_binary(&id)?);
+
+            vote_for_extension(deps, &env.block, &sender, id, resp)
+        }
+
+        #[execute]
+        fn vote_extension(proposal: u64) -> Result<Response, <Self as Auction>::Error> {
+            let sender = info.sender.as_str().canonize(deps.api)?;
+            let resp = Response::default()
+                .add_attribute(events::ACTION, actions::VOTE_EXTENSION);
+
+            vote_for_extension(deps, &env.block, &sender, proposal, resp)
+        }
+
+        #[execute]
+        #[admin::require_admin]
+        fn update_config(
+            name: Option<String>,
+            metadata: Option<String>,
+            reserve: Option<Uint128>,
+            end_block: Option<u64>
+        ) -> Result<Response, <Self as Auction>::Error> {
+            // Bids retracted early don't unlock it again.
+            if NUM_BIDS.load_or_default(deps.storage)? > 0 {
+                return Err(LifecycleError::ConfigLocked.into());
+            }
+
+            let mut sale_info = INFO.load_or_error(deps.storage)?;
+
+            // A sale that ended without bids can't be started again.
+            if is_finished(deps.storage, &sale_info, &env.block)? {
+                return Err(LifecycleError::Finished.into());
+            }
+
+            if SETTLED.load_or_default(deps.storage)? {
+                return Err(LifecycleError::AlreadySettled.into());
+            }
+
+            if CANCELLED.load_or_default(deps.storage)? {
+                return Err(LifecycleError::Cancelled.into());
+            }
+
+            if let Some(name) = name {
+                SaleInfo::validate_name(&name)?;
+                sale_info.name = name;
+            }
+
+            if let Some(metadata) = metadata {
+                SaleInfo::validate_metadata(&metadata)?;
+                sale_info.metadata = Some(metadata);
+            }
+
+            if let Some(end_block) = end_block {
+                if end_block <= env.block.height {
+                    return Err(LifecycleError::EndBlockPassed.into());
+                }
+
+                if SETTINGS.load_or_error(deps.storage)?.start_block >= end_block {
+                    return Err(LifecycleError::StartAfterEnd.into());
+                }
+
+                sale_info.end_block = end_block;
+            }
+
+            INFO.save(deps.storage, &sale_info)?;
+
+            let mut resp = Response::default().add_attribute(events::ACTION, actions::UPDATE_CONFIG);
+
+            if end_block.is_some() {
+                resp = report_end_block(deps.as_ref(), sale_info.end_block, resp)?;
+            }
+
+            if let Some(reserve) = reserve {
+                let mut settings = SETTINGS.load_or_error(deps.storage)?;
+                if settings.raffle.is_some() {
+                    return Err(RaffleError::Unsupported("A reserve price").into());
+                }
+
+                settings.reserve = reserve;
+
+                SETTINGS.save(deps.storage, &settings)?;
+            }
+
+            Ok(resp)
+        }
+
+        #[execute]
+        #[admin::require_admin]
+        fn set_auditor(address: Option<String>) -> Result<Response, <Self as Auction>::Error> {
+            if let Some(address) = address {
+                AUDITOR.save(deps.storage, &address.as_str().canonize(deps.api)?)?;
+            } else {
+                AUDITOR.remove(deps.storage);
+            }
+
+            Ok(Response::default().add_attribute(events::ACTION, actions::SET_AUDITOR))
+        }
+
+        #[execute]
+        #[admin::require_admin]
+        fn set_pauser(address: Option<String>) -> Result<Response, <Self as Auction>::Error> {
+            if let Some(address) = address {
+                PAUSER.save(deps.storage, &address.as_str().canonize(deps.api)?)?;
+            } else {
+                PAUSER.remove(deps.storage);
+            }
+
+            Ok(Response::default().add_attribute(events::ACTION, actions::SET_PAUSER))
+        }
+
+        #[execute]
+        #[admin::require_admin]
+        fn transfer_ownership(new_owner: Option<String>) -> Result<Response, <Self as Auction>::Error> {
+            assert_transferable(deps.storage)?;
+
+            let mut resp = Response::default()
+                .add_attribute(events::ACTION, actions::TRANSFER_OWNERSHIP)
+                .add_attribute(events::PREVIOUS_OWNER, info.sender.as_str());
+
+            if let Some(new_owner) = new_owner {
+                let new_owner = deps.api.addr_validate(&new_owner)?;
+                PENDING_OWNER.save(deps.storage, &new_owner.as_str().canonize(deps.api)?)?;
+
+                resp = resp.add_attribute(events::NEW_OWNER, new_owner);
+            } else {
+                PENDING_OWNER.remove(deps.storage);
+            }
+
+            Ok(resp)
+        }
+
+        #[execute]
+        fn accept_ownership() -> Result<Response, <Self as Auction>::Error> {
+            assert_transferable(deps.storage)?;
+
+            let pending = PENDING_OWNER.load(deps.storage)?
+                .ok_or(AuthError::NoPendingOwner)?;
+            if pending != info.sender.as_str().canonize(deps.api)? {
+                return Err(AuthError::Unauthorized.into());
+            }
+
+            let previous = admin::STORE.load_humanize(deps.as_ref())?;
+
+            admin::STORE.save(deps.storage, &pending)?;
+            PENDING_OWNER.remove(deps.storage);
+
+            let mut resp = Response::default()
+                .add_attribute(events::ACTION, actions::ACCEPT_OWNERSHIP);
+
+            if let Some(previous) = previous {
+                resp = resp.add_attribute(events::PREVIOUS_OWNER, previous);
+            }
+
+            Ok(resp.add_attribute(events::NEW_OWNER, info.sender))
+        }
+
+        #[execute]
+        #[admin::require_admin]
+        fn export_bidders(pagination: Pagination) -> Result<Response, <Self as Auction>::Error> {
+            let page = bid_entries_page(deps.as_ref(), pagination)?;
+
+            Ok(Response::default()
+                .add_attribute(events::ACTION, actions::EXPORT_BIDDERS)
+                .set_data(to_binary(&page)?)
+            )
+        }
+
+        #[execute]
+        fn revoke_permit(name: String) -> Result<Response, <Self as Auction>::Error> {
+            Permit::<AuctionPermission>::revoke(deps.storage, &info.sender, &name);
+            count(deps.storage, |x| &mut x.key_operations)?;
+
+            Ok(Response::default())
+        }
+    
+        #[execute]
+        fn transfer_claim(
+            claim_id: u64,
+            recipient: String
+        ) -> Result<Response, <Self as Auction>::Error> {
+            let settings = claim_settings(deps.as_ref(), &env)?;
+            let (bidder, owner) = load_claim(deps.storage, claim_id)?;
+
+            if owner != info.sender.as_str().canonize(deps.api)? {
+                return Err(AuthError::Unauthorized.into());
+            }
+
+            let balance = balances::get_or_default(deps.storage, &bidder)?;
+            if balance.is_zero() {
+                return Err(ClaimError::Empty(claim_id).into());
+            }
+
+            if is_winning_bid(deps.storage, &bidder, balance, settings.reserve)? {
+                return Err(LifecycleError::WinnerCannotRetract.into());
+            }
+
+            let recipient = deps.api.addr_validate(&recipient)?;
+            CLAIM_OWNERS.save(deps.storage, &claim_id, &recipient.as_str().canonize(deps.api)?)?;
+
+            Ok(Response::default()
+                .add_attribute(events::ACTION, actions::TRANSFER_CLAIM)
+                .add_attribute(events::CLAIM_ID, claim_id.to_string())
+                .add_attribute(events::RECIPIENT, recipient)
+            )
+        }
+
+        #[execute]
+        #[admin::require_admin]
+        fn select_winner(address: String) -> Result<Response, <Self as Auction>::Error> {
+            let settings = ended_settings(deps.storage)?;
+            if !settings.manual_winner {
+                return Err(SelectionError::NotManual.into());
+            }
+
+            if !is_finished(deps.storage, &INFO.load_or_error(deps.storage)?, &env.block)? {
+                return Err(LifecycleError::NotFinished.into());
+            }
+
+            if SELECTED_WINNER.load(deps.storage)?.is_some() {
+                return Err(SelectionError::AlreadySelected.into());
+            }
+
+            let winner = deps.api.addr_validate(&address)?;
+            let canonical = winner.as_str().canonize(deps.api)?;
+
+            let balance = balances::get_or_default(deps.storage, &canonical)?;
+            if balance.is_zero() {
+                return Err(SelectionError::NoBid(address).into());
+            }
+
+            if balance < settings.reserve {
+                return Err(LifecycleError::ReserveNotMet.into());
+            }
+
+            // The selected bidder takes the place of the highest one.
+            SELECTED_WINNER.save(deps.storage, &canonical)?;
+            HIGHEST_BID.save(deps.storage, &canonical)?;
+
+            Ok(Response::default()
+                .add_attribute(events::ACTION, actions::SELECT_WINNER)
+                .add_attribute(events::WINNER, winner)
+            )
+        }
+
+        #[execute]
+        fn cancel_sale(reason: String) -> Result<Response, <Self as Auction>::Error> {
+            let settings = SETTINGS.load_or_error(deps.storage)?;
+
+            let sender = info.sender.as_str().canonize(deps.api)?;
+            if settings.arbiter.as_ref() != Some(&sender) {
+                if admin::assert(deps.as_ref(), &info).is_err() {
+                    return Err(AuthError::Unauthorized.into());
+                }
+
+                if CO_SELLERS.load(deps.storage)?.is_some() {
+                    return Err(CoSellerError::ApprovalRequired.into());
+                }
+            }
+
+            let resp = Response::default()
+                .add_attribute(events::ACTION, actions::CANCEL_SALE);
+
+            cancel(deps, &env, &settings, reason, resp)
+        }
+
+        #[execute]
+        fn approve_action(action: SellerAction) -> Result<Response, <Self as Auction>::Error> {
+            let Some(co_sellers) = CO_SELLERS.load(deps.storage)? else {
+                return Err(CoSellerError::NotEnabled.into());
+            };
+
+            let sender = info.sender.as_str().canonize(deps.api)?;
+            if !co_sellers.sellers.iter().any(|x| x.address == sender) {
+                return Err(AuthError::Unauthorized.into());
+            }
+
+            let key = to_binary(&action)?;
+            let mut approvals = APPROVALS.load_or_default(deps.storage)?;
+
+            let index = match approvals.iter().position(|x| x.action == key) {
+                Some(index) => index,
+                None => {
+                    approvals.push(Approval { action: key, approvers: vec![] });
+                    approvals.len() - 1
+                }
+            };
+
+            if approvals[index].approvers.contains(&sender) {
+                return Err(CoSellerError::AlreadyApproved.into());
+            }
+
+            approvals[index].approvers.push(sender);
+            let count = approvals[index].approvers.len();
+
+            let resp = Response::default()
+                .add_attribute(events::ACTION, actions::APPROVE_ACTION)
+                .add_attribute(events::APPROVALS, count.to_string());
+
+            if count < usize::from(co_sellers.threshold) {
+                APPROVALS.save(deps.storage, &approvals)?;
+
+                return Ok(resp);
+            }
+
+            approvals.remove(index);
+            APPROVALS.save(deps.storage, &approvals)?;
+
+            match action {
+                SellerAction::ClaimProceeds { amount } => {
+                    let recipients = co_sellers.sellers.into_iter()
+                        .map(|x| (x.address, x.share))
+                        .collect::<Vec<_>>();
+                    let resp = resp.add_attribute(events::ACTION, actions::CLAIM_PROCEEDS);
+
+                    pay_proceeds(deps, &env, amount, &recipients, resp)
+                }
+                SellerAction::CancelSale { reason } => {
+                    let settings = SETTINGS.load_or_error(deps.storage)?;
+                    let resp = resp.add_attribute(events::ACTION, actions::CANCEL_SALE);
+
+                    cancel(deps, &env, &settings, reason, resp)
+                }
+                SellerAction::Extend { blocks } => extend(deps, &env, blocks, resp)
+            }
+        }
+
+        #[execute]
+        fn send_delivery_details(details: String) -> Result<Response, <Self as Auction>::Error> {
+            assert_text_len("Delivery details", &details, MAX_DELIVERY_LEN)?;
+
+            if !SETTLED.load_or_default(deps.storage)? {
+                return Err(LifecycleError::NotSettled.into());
+            }
+
+            let sender = info.sender.as_str().canonize(deps.api)?;
+            if HIGHEST_BID.load(deps.storage)? != Some(sender) {
+                return Err(AuthError::Unauthorized.into());
+            }
+
+            DELIVERY_DETAILS.save(deps.storage, &details)?;
+
+            Ok(Response::default().add_attribute(events::ACTION, actions::SEND_DELIVERY_DETAILS))
+        }
+
+        #[execute]
+        #[admin::require_admin]
+        fn send_fulfillment_proof(proof: String) -> Result<Response, <Self as Auction>::Error> {
+            assert_text_len("Fulfillment proof", &proof, MAX_DELIVERY_LEN)?;
+
+            if !SETTLED.load_or_default(deps.storage)? {
+                return Err(LifecycleError::NotSettled.into());
+            }
+
+            FULFILLMENT_PROOF.save(deps.storage, &proof)?;
+
+            Ok(Response::default().add_attribute(events::ACTION, actions::SEND_FULFILLMENT_PROOF))
+        }
+
+        #[execute]
+        #[admin::require_admin]
+        fn close() -> Result<Response, <Self as Auction>::Error> {
+            let settled = SETTLED.load_or_default(deps.storage)?;
+            if !settled && !CANCELLED.load_or_default(deps.storage)? {
+                return Err(LifecycleError::NotSettled.into());
+            }
+
+            if !PROCEEDS.load_or_default(deps.storage)?.remaining().is_zero() {
+                return Err(LifecycleError::ProceedsUnclaimed.into());
+            }
+
+            if !TOTAL_ESCROWED.load_or_default(deps.storage)?.is_zero() {
+                return Err(LifecycleError::RefundsPending.into());
+            }
+
+            let settings = SETTINGS.load_or_error(deps.storage)?;
+
+            let mut resp = Response::default()
+                .add_attribute(events::ACTION, actions::CLOSE);
+
+            // Token balances can't be queried without a viewing key, so only
+            // whatever is left of the native denom is swept.
+            if settings.bid_token.is_none() {
+                let balance = deps.querier.query_balance(&env.contract.address, settings.denom.as_str())?;
+                let dust = balance.amount.saturating_sub(UNCLAIMED.load_or_default(deps.storage)?);
+
+                if !dust.is_zero() {
+                    let recipient = match settings.beneficiary {
+                        Some(beneficiary) => beneficiary.humanize(deps.api)?,
+                        None => info.sender.clone()
+                    };
+
+                    resp = resp
+                        .add_message(BankMsg::Send {
+                            to_address: recipient.to_string(),
+                            amount: vec![coin(dust.u128(), settings.denom.as_str())]
+                        })
+                        .add_attribute(events::RECIPIENT, recipient)
+                        .add_attribute(events::AMOUNT, dust);
+                }
+            }
+
+            killswitch::STORE.canonize_and_save(deps.branch(), ContractStatus::Migrating {
+                reason: "Closed".into(),
+                new_address: None
+            })?;
+            STATUS_CHANGED.save(deps.storage, &StatusChange {
+                height: env.block.height,
+                time: env.block.time.seconds()
+            })?;
+            CLOSED.save(deps.storage, &true)?;
+
+            if let Some(factory) = settings.factory {
+                let factory = FactoryClient(factory.humanize(deps.api)?);
+                resp = resp.add_message(factory.auction_closed(settled)?);
+            }
+
+            Ok(resp)
+        }
+
+        #[execute]
+        fn redeem_claim(claim_id: u64) -> Result<Response, <Self as Auction>::Error> {
+            let settings = claim_settings(deps.as_ref(), &env)?;
+            count(deps.storage, |x| &mut x.claims)?;
+            let (bidder, owner) = load_claim(deps.storage, claim_id)?;
+
+            if owner != info.sender.as_str().canonize(deps.api)? {
+                return Err(AuthError::Unauthorized.into());
+            }
+
+            let balance = take_refund(deps.storage, &bidder, settings.reserve)?;
+            if balance.is_zero() {
+                return Err(ClaimError::Empty(claim_id).into());
+            }
+
+            credit(deps.storage, &owner, balance)?;
+
+            Ok(Response::default()
+                .add_attribute(events::ACTION, actions::REDEEM_CLAIM)
+                .add_attribute(events::CLAIM_ID, claim_id.to_string())
+                .add_attribute(events::RECIPIENT, info.sender)
+            )
+        }
+
+        #[execute]
+        fn refund_batch(limit: u8) -> Result<Response, <Self as Auction>::Error> {
+            if limit == 0 || limit > MAX_REFUND_BATCH {
+                return Err(PaginationError::InvalidBatchLimit { limit }.into());
+            }
+
+            let sale_info = INFO.load_or_error(deps.storage)?;
+            if !is_finished(deps.storage, &sale_info, &env.block)? {
+                return Err(LifecycleError::NotFinished.into());
+            }
+
+            let settings = ended_settings(deps.storage)?;
+            if settings.raffle.is_some() && !SETTLED.load_or_default(deps.storage)? {
+                return Err(RaffleError::NotDrawn.into());
+            }
+
+            assert_winner_known(deps.storage, &settings)?;
+
+            let addresses = bidder_addresses();
+            let len = addresses.len(deps.storage)?;
+
+            let start = REFUND_CURSOR.load_or_default(deps.storage)?;
+            let end = len.min(start + limit as u64);
+
+            let mut resp = Response::default();
+            let mut refunded = 0u64;
+
+            for id in start..end {
+                let (bidder, owner) = load_claim(deps.storage, id)?;
+
+                if refundable(deps.storage, &bidder, settings.reserve)?.is_zero() {
+                    continue;
+                }
+
+                let balance = take_refund(deps.storage, &bidder, settings.reserve)?;
+                refunded += 1;
+
+                // Bidders that enabled auto refund are paid right away unless they
+                // have transferred their claim, which is refunded to its current owner.
+                if owner == bidder && AUTO_REFUND.load(deps.storage, &bidder)?.unwrap_or_default() {
+                    let recipient = bidder.humanize(deps.api)?;
+                    resp = resp.add_message(payout_msg(deps.as_ref(), &settings, &recipient, balance)?);
+                } else {
+                    credit(deps.storage, &owner, balance)?;
+                }
+            }
+
+            REFUND_CURSOR.save(deps.storage, &end)?;
+
+            Ok(resp
+                .add_attribute(events::ACTION, actions::REFUND_BATCH)
+                .add_attribute(events::REFUNDED, refunded.to_string())
+                .add_attribute(events::REMAINING, (len - end).to_string())
+            )
+        }
+
+        #[execute]
+        #[admin::require_admin]
+        fn add_settlement_hook(
+            contract: ContractLink<String>
+        ) -> Result<Response, <Self as Auction>::Error> {
+            if SETTLED.load_or_default(deps.storage)? {
+                return Err(LifecycleError::AlreadySettled.into());
+            }
+
+            let address = contract.address.as_str().canonize(deps.api)?;
+
+            let mut hooks = settlement_hooks();
+            for hook in hooks.iter(deps.storage)? {
+                if hook?.address == address {
+                    return Err(HookError::AlreadyAdded(contract.address).into());
+                }
+            }
+
+            if hooks.len(deps.storage)? >= MAX_SETTLEMENT_HOOKS {
+                return Err(HookError::TooMany.into());
+            }
+
+            hooks.push(deps.storage, &ContractLink { address, code_hash: contract.code_hash })?;
+
+            Ok(Response::default()
+                .add_attribute(events::ACTION, actions::ADD_SETTLEMENT_HOOK)
+                .add_attribute(events::HOOK, contract.address)
+            )
+        }
+
+        #[execute]
+        #[admin::require_admin]
+        fn remove_settlement_hook(address: String) -> Result<Response, <Self as Auction>::Error> {
+            let canonical = address.as_str().canonize(deps.api)?;
+
+            let mut hooks = settlement_hooks();
+            let index = hooks.iter(deps.storage)?
+                .position(|x| x.is_ok_and(|x| x.address == canonical));
+
+            let Some(index) = index else {
+                return Err(HookError::NotFound(address).into());
+            };
+
+            hooks.swap_remove(deps.storage, index as u64)?;
+
+            Ok(Response::default()
+                .add_attribute(events::ACTION, actions::REMOVE_SETTLEMENT_HOOK)
+                .add_attribute(events::HOOK, address)
+            )
+        }
+
+        #[query]
+        fn view_bid_with_permit(
+            permit: Permit<AuctionPermission>
+        ) -> Result<Uint128, <Self as Auction>::Error> {
+            assert_queries_allowed(deps)?;
+
+            let address = permit.validate(
+                deps,
+                env.contract.address.as_str(),
+                None,
+                &[AuctionPermission::ViewBid]
+            )?;
+            let address = address.as_str().canonize(deps.api)?;
+
+            Ok(balances::get_or_default(deps.storage, &address)?)
+        }
+
+        #[query]
+        fn view_bid_of_with_permit(
+            bidder: String,
+            permit: Permit<AuctionPermission>
+        ) -> Result<Uint128, <Self as Auction>::Error> {
+            assert_queries_allowed(deps)?;
+
+            let viewer = permit.validate(
+                deps,
+                env.contract.address.as_str(),
+                None,
+                &[AuctionPermission::ViewBid]
+            )?;
+            let viewer = viewer.as_str().canonize(deps.api)?;
+            let bidder = bidder.as_str().canonize(deps.api)?;
+
+            if viewer != bidder && !is_auditor(deps.storage, &viewer)? {
+                return Err(AuthError::Unauthorized.into());
+            }
+
+            Ok(balances::get_or_default(deps.storage, &bidder)?)
+        }
+    
+        #[query]
+        fn min_bid_to_win_with_permit(
+            permit: Permit<AuctionPermission>
+        ) -> Result<Uint128, <Self as Auction>::Error> {
+            assert_queries_allowed(deps)?;
+
+            let address = permit.validate(
+                deps,
+                env.contract.address.as_str(),
+                None,
+                &[AuctionPermission::ViewBid]
+            )?;
+            let address = address.as_str().canonize(deps.api)?;
+
+            min_bid_to_win_of(deps, &env, &address)
+        }
+
+        #[query]
+        fn simulate_bid(
+            permit: Permit<AuctionPermission>,
+            amount: Uint128
+        ) -> Result<BidSimulation, <Self as Auction>::Error> {
+            assert_queries_allowed(deps)?;
+
+            let address = permit.validate(
+                deps,
+                env.contract.address.as_str(),
+                None,
+                &[AuctionPermission::ViewBid]
+            )?;
+            let address = address.as_str().canonize(deps.api)?;
+
+            simulate_bid_of(deps, &env, &address, amount)
+        }
+
+        #[query]
+        fn view_payout_with_permit(
+            permit: Permit<AuctionPermission>
+        ) -> Result<Uint128, <Self as Auction>::Error> {
+            assert_queries_allowed(deps)?;
+
+            let address = permit.validate(
+                deps,
+                env.contract.address.as_str(),
+                None,
+                &[AuctionPermission::ViewBid]
+            )?;
+            let address = address.as_str().canonize(deps.api)?;
+
+            Ok(PAYOUTS.load(deps.storage, &address)?.unwrap_or_default())
+        }
+
+        #[query]
+        fn view_proxy_bid_with_permit(
+            permit: Permit<AuctionPermission>
+        ) -> Result<ProxyBid, <Self as Auction>::Error> {
+            assert_queries_allowed(deps)?;
+
+            let address = permit.validate(
+                deps,
+                env.contract.address.as_str(),
+                None,
+                &[AuctionPermission::ViewBid]
+            )?;
+            let address = address.as_str().canonize(deps.api)?;
+
+            proxy_bid_of(deps.storage, &address)
+        }
+
+        #[query]
+        fn my_rank_with_permit(
+            permit: Permit<AuctionPermission>
+        ) -> Result<BidRank, <Self as Auction>::Error> {
+            assert_queries_allowed(deps)?;
+
+            let address = permit.validate(
+                deps,
+                env.contract.address.as_str(),
+                None,
+                &[AuctionPermission::ViewBid]
+            )?;
+            let address = address.as_str().canonize(deps.api)?;
+
+            rank_of(deps.storage, &address)
+        }
+
+        #[query]
+        fn winner_payload_with_permit(
+            permit: Permit<AuctionPermission>
+        ) -> Result<Option<Binary>, <Self as Auction>::Error> {
+            assert_queries_allowed(deps)?;
+
+            let address = permit.validate(
+                deps,
+                env.contract.address.as_str(),
+                None,
+                &[AuctionPermission::ViewPayload]
+            )?;
+            let address = address.as_str().canonize(deps.api)?;
+
+            winner_payload_of(deps.storage, &address)
+        }
+
+        #[query]
+        fn delivery_with_permit(
+            permit: Permit<AuctionPermission>
+        ) -> Result<Delivery, <Self as Auction>::Error> {
+            assert_queries_allowed(deps)?;
+
+            let address = permit.validate(
+                deps,
+                env.contract.address.as_str(),
+                None,
+                &[AuctionPermission::ViewDelivery]
+            )?;
+            let address = address.as_str().canonize(deps.api)?;
+
+            delivery_of(deps, &address)
+        }
+
+        #[query]
+        fn view_memo_with_permit(
+            bidder: String,
+            permit: Permit<AuctionPermission>
+        ) -> Result<Option<String>, <Self as Auction>::Error> {
+            assert_queries_allowed(deps)?;
+
+            let viewer = permit.validate(
+                deps,
+                env.contract.address.as_str(),
+                None,
+                &[AuctionPermission::ViewMemo]
+            )?;
+            let viewer = viewer.as_str().canonize(deps.api)?;
+
+            memo_of(deps, &bidder.as_str().canonize(deps.api)?, &viewer)
+        }
+
+        #[query]
+        fn active_bids(
+            pagination: Pagination
+        ) -> Result<PaginatedResponse<Uint128>, <Self as Auction>::Error> {
+            assert_queries_allowed(deps)?;
+
+            if !SETTINGS.load_or_error(deps.storage)?.public_active_bids {
+                return Err(AuthError::Unauthorized.into());
+            }
+
+            active_bids_page(deps.storage, pagination)
+        }
+
+        #[query]
+        fn active_bids_with_permit(
+            permit: Permit<AuctionPermission>,
+            pagination: Pagination
+        ) -> Result<PaginatedResponse<Uint128>, <Self as Auction>::Error> {
+            assert_queries_allowed(deps)?;
+
+            let address = permit.validate(
+                deps,
+                env.contract.address.as_str(),
+                None,
+                &[AuctionPermission::ViewActiveBids]
+            )?;
+            let address = address.as_str().canonize(deps.api)?;
+
+            if admin::STORE.load(deps.storage)?.as_ref() != Some(&address) &&
+                !is_auditor(deps.storage, &address)?
+            {
+                return Err(AuthError::Unauthorized.into());
+            }
+
+            active_bids_page(deps.storage, pagination)
+        }
+
+        #[query]
+        fn audit_bids_with_permit(
+            permit: Permit<AuctionPermission>,
+            pagination: Pagination
+        ) -> Result<PaginatedResponse<BidEntry>, <Self as Auction>::Error> {
+            assert_queries_allowed(deps)?;
+
+            let address = permit.validate(
+                deps,
+                env.contract.address.as_str(),
+                None,
+                &[AuctionPermission::ViewActiveBids]
+            )?;
+            let address = address.as_str().canonize(deps.api)?;
+
+            if !is_auditor(deps.storage, &address)? {
+                return Err(AuthError::Unauthorized.into());
+            }
+
+            bid_entries_page(deps, pagination)
+        }
+    
+        #[query]
+        fn auditor() -> Result<Option<Addr>, <Self as Auction>::Error> {
+            Ok(AUDITOR.load_humanize(deps)?)
+        }
+
+        #[query]
+        fn pauser() -> Result<Option<Addr>, <Self as Auction>::Error> {
+            Ok(PAUSER.load_humanize(deps)?)
+        }
+
+        #[query]
+        fn pending_owner() -> Result<Option<Addr>, <Self as Auction>::Error> {
+            Ok(PENDING_OWNER.load_humanize(deps)?)
+        }
 
-    namespace!(InfoNs, b"info");
-    const INFO: SingleItem<SaleInfo, InfoNs> = SingleItem::new();
+        #[query]
+        fn selected_winner() -> Result<Option<Addr>, <Self as Auction>::Error> {
+            Ok(SELECTED_WINNER.load_humanize(deps)?)
+        }
 
-    namespace!(HighestBidNs, b"highest_bid");
-    const HIGHEST_BID: SingleItem<CanonicalAddr, HighestBidNs> = SingleItem::new();
+        #[query]
+        fn early_retraction_penalty() -> Result<Option<u16>, <Self as Auction>::Error> {
+            Ok(SETTINGS.load_or_error(deps.storage)?.early_retraction_penalty)
+        }
 
-    namespace!(BiddersNs, b"bidders");
-    #[inline]
-    fn bidders() -> InsertOnlyMap<
-        TypedKey<'static, CanonicalAddr>,
-        Uint128,
-        BiddersNs
-    > {
-        InsertOnlyMap::new()
-    }
+        #[query]
+        fn settlement_hooks() -> Result<Vec<ContractLink<Addr>>, <Self as Auction>::Error> {
+            settlement_hooks().iter(deps.storage)?
+                .map(|x| Ok(x?.humanize(deps.api)?))
+                .collect()
+        }
 
-    impl Contract {
-        // This runs before executing any messages.
-        #[execute_guard]
-        pub fn guard(msg: &ExecuteMsg) -> Result<(), StdError> {
-            let operational = killswitch::assert_is_operational(deps.as_ref());
-    
-            // Only allow the killswitch module messages so that we can resume the
-            // the contract if it was paused for example.
-            // However, if the contract has been set to the "migrating" status,
-            // Even the admin cannot reverse that anymore.
-            if operational.is_err() && !matches!(msg, ExecuteMsg::SetStatus { .. }) {
-                Err(operational.unwrap_err())
-            } else {
-                Ok(())
+        #[query]
+        fn sale_status() -> Result<SaleStatus, <Self as Auction>::Error> {
+            let info = INFO.load_or_error(deps.storage)?;
+            let settings = SETTINGS.load_or_error(deps.storage)?;
+
+            let is_finished = is_finished(deps.storage, &info, &env.block)?;
+            let bidder_count = bidder_addresses().len(deps.storage)?;
+            let num_bids = NUM_BIDS.load_or_default(deps.storage)?;
+            let created_at = CREATED_AT.load(deps.storage)?;
+
+            if settings.hide_current_highest && !is_finished {
+                return Ok(SaleStatus {
+                    current_highest: None,
+                    highest_bidder: None,
+                    bidder_count,
+                    num_bids,
+                    is_finished,
+                    info,
+                    created_at
+                });
             }
+
+            let highest_bid = HIGHEST_BID.load(deps.storage)?;
+
+            let current_highest = if let Some(addr) = &highest_bid {
+                balances::get_or_default(deps.storage, addr)?
+            } else {
+                Uint128::zero()
+            };
+
+            let highest_bidder = if settings.show_highest_bidder {
+                highest_bid.map(|x| x.humanize(deps.api)).transpose()?
+            } else {
+                None
+            };
+
+            Ok(SaleStatus {
+                current_highest: Some(current_highest),
+                highest_bidder,
+                bidder_count,
+                num_bids,
+                is_finished,
+                info,
+                created_at
+            })
         }
-    }
 
-    impl Auction for Contract {
-        type Error = StdError;
+        #[query]
+        fn config() -> Result<AuctionConfig, <Self as Auction>::Error> {
+            let settings = SETTINGS.load_or_error(deps.storage)?;
 
-        #[init(entry_wasm)]
-        fn new(
-            admin: Option<String>,
-            name: String,
-            end_block: u64
-        ) -> Result<Response, <Self as Auction>::Error> {
-            if end_block <= env.block.height {
-                return Err(StdError::generic_err("End block has already passed."));
+            let beneficiary = match settings.beneficiary {
+                Some(beneficiary) => beneficiary.humanize(deps.api)?,
+                None => admin::STORE.load_humanize(deps)?
+                    .ok_or_else(|| StdError::generic_err("Admin not set."))?
+            };
+
+            let factory = settings.factory.humanize(deps.api)?;
+            let fee = match &factory {
+                Some(factory) => deps.querier.query(&FactoryClient(factory.clone()).fee_config()?)?,
+                None => None
+            };
+
+            Ok(AuctionConfig {
+                created_at: CREATED_AT.load(deps.storage)?,
+                end_time: END_TIME.load(deps.storage)?,
+                start_block: settings.start_block,
+                guard_queries: settings.guard_queries,
+                denom: settings.denom,
+                reserve: settings.reserve,
+                min_increment: settings.min_increment,
+                beneficiary,
+                receipt_nft: settings.receipt_nft.humanize(deps.api)?,
+                tradable_claims: settings.tradable_claims,
+                raffle: settings.raffle.map(|x| RaffleConfig {
+                    ticket_price: x.ticket_price,
+                    refund_rate: Some(x.refund_rate)
+                }),
+                manual_winner: settings.manual_winner,
+                dispute_blocks: settings.dispute_blocks,
+                arbiter: settings.arbiter.humanize(deps.api)?,
+                early_retraction_penalty: settings.early_retraction_penalty,
+                bid_cooldown: settings.bid_cooldown,
+                oracle: settings.oracle
+                    .map(|x| Ok::<_, StdError>(oracle::PriceOracle {
+                        address: x.contract.address.humanize(deps.api)?.into_string(),
+                        code_hash: x.contract.code_hash,
+                        max_age: x.max_age
+                    }))
+                    .transpose()?,
+                usd_reserve: settings.usd_reserve,
+                usd_min_increment: settings.usd_min_increment,
+                show_highest_bidder: settings.show_highest_bidder,
+                hide_current_highest: settings.hide_current_highest,
+                public_active_bids: settings.public_active_bids,
+                extension_vote: settings.extension_vote.map(|x| ExtensionVoteConfig {
+                    threshold: x.threshold,
+                    max_blocks: x.max_blocks
+                }),
+                exclude_contracts: settings.exclude_contracts,
+                bid_token: settings.bid_token.humanize(deps.api)?,
+                seller_deposit: settings.seller_deposit,
+                factory,
+                fee,
+                co_sellers: CO_SELLERS.load(deps.storage)?.humanize(deps.api)?
+            })
+        }
+
+        #[query]
+        fn approvals() -> Result<Vec<PendingApproval>, <Self as Auction>::Error> {
+            APPROVALS.load_or_default(deps.storage)?
+                .into_iter()
+                .map(|x| Ok(PendingApproval {
+                    action: from_binary(&x.action)?,
+                    approvers: x.approvers.humanize(deps.api)?
+                }))
+                .collect()
+        }
+
+        #[query]
+        fn factory() -> Result<Option<ContractLink<Addr>>, <Self as Auction>::Error> {
+            let settings = SETTINGS.load_or_error(deps.storage)?;
+
+            Ok(settings.factory.humanize(deps.api)?)
+        }
+
+        #[query]
+        fn bid_receipt(receipt: u64) -> Result<Option<BidReceipt>, <Self as Auction>::Error> {
+            Ok(BID_RECEIPTS.load(deps.storage, &receipt)?)
+        }
+
+        #[query]
+        fn time_remaining() -> Result<TimeRemaining, <Self as Auction>::Error> {
+            let info = INFO.load_or_error(deps.storage)?;
+            let end_time = END_TIME.load(deps.storage)?;
+
+            if is_finished(deps.storage, &info, &env.block)? {
+                return Ok(TimeRemaining {
+                    blocks: 0,
+                    seconds: end_time.map(|_| 0)
+                });
             }
 
-            admin::init(deps.branch(), admin.as_deref(), &info)?;
-            INFO.save(deps.storage, &SaleInfo { name, end_block })?;
-    
-            Ok(Response::default()
-                .set_data(to_binary(&env.contract.address)?)
-            )
+            Ok(TimeRemaining {
+                blocks: info.end_block - env.block.height,
+                seconds: end_time.map(|x| x - env.block.time.seconds())
+            })
         }
-    
-        #[execute]
-        fn bid() -> Result<Response, <Self as Auction>::Error> {
-            let sale_info = INFO.load_or_error(deps.storage)?;
-            if sale_info.end_block < env.block.height {
-                return Err(StdError::generic_err("Sale has finished."));
+
+        #[query]
+        fn extension_proposal(proposal: u64) -> Result<ExtensionProposal, <Self as Auction>::Error> {
+            extension_proposals()
+                .get(deps.storage, proposal)?
+                .ok_or_else(|| ExtensionError::NotFound(proposal).into())
+        }
+
+        #[query]
+        fn state() -> Result<AuctionState, <Self as Auction>::Error> {
+            // Closing migrates the contract but the outcome of the sale still stands.
+            if CLOSED.load_or_default(deps.storage)? {
+                return if SETTLED.load_or_default(deps.storage)? {
+                    Ok(AuctionState::Settled)
+                } else {
+                    Ok(AuctionState::Cancelled)
+                };
             }
 
-            let sender = info.sender.canonize(deps.api)?;
+            match killswitch::STORE.load_or_default(deps.storage)? {
+                ContractStatus::Migrating { .. } => return Ok(AuctionState::Cancelled),
+                ContractStatus::Paused { .. } => return Ok(AuctionState::Paused),
+                ContractStatus::Operational => { }
+            }
 
-            let mut bidders = bidders();
-            let mut balance = bidders.get_or_default(deps.storage, &sender)?;
-            balance += info.funds.into_iter()
-                .find(|x| x.denom == "uscrt")
-                .map(|x| x.amount)
-                .unwrap_or_default();
+            if SETTLED.load_or_default(deps.storage)? {
+                return Ok(AuctionState::Settled);
+            }
 
-            bidders.insert(deps.storage, &sender, &balance)?;
+            if CANCELLED.load_or_default(deps.storage)? {
+                return Ok(AuctionState::Cancelled);
+            }
 
-            if let Some(addr) = HIGHEST_BID.load(deps.storage)? {
-                if addr != sender {
-                    let current_highest = bidders.get_or_error(deps.storage, &addr)?;
+            let info = INFO.load_or_error(deps.storage)?;
+            let settings = SETTINGS.load_or_error(deps.storage)?;
 
-                    if balance > current_highest {
-                        HIGHEST_BID.save(deps.storage, &sender)?;
-                    }
-                }
+            let state = if settings.start_block > env.block.height {
+                AuctionState::NotStarted
+            } else if is_finished(deps.storage, &info, &env.block)? {
+                AuctionState::Ended
             } else {
-                // This is the first bid.
-                HIGHEST_BID.save(deps.storage, &sender)?;
+                AuctionState::Active
             };
 
-            Ok(Response::default())
+            Ok(state)
         }
-    
+
+        #[query]
+        fn status_info() -> Result<StatusInfo, <Self as Auction>::Error> {
+            Ok(StatusInfo {
+                status: killswitch::STORE.load_humanize_or_default(deps)?,
+                changed_at: STATUS_CHANGED.load(deps.storage)?
+            })
+        }
+
+        #[query]
+        fn metrics() -> Result<Metrics, <Self as Auction>::Error> {
+            Ok(METRICS.load_or_default(deps.storage)?)
+        }
+
+        #[query]
+        fn version() -> Result<ContractVersion, <Self as Auction>::Error> {
+            Ok(version::load(deps.storage)?
+                .ok_or_else(|| StdError::generic_err("Contract version not recorded."))?)
+        }
+
+        #[query]
+        fn invariants() -> Result<Invariants, <Self as Auction>::Error> {
+            let total_escrowed = TOTAL_ESCROWED.load_or_default(deps.storage)?;
+
+            let mut outstanding_bids = Uint128::zero();
+            for address in bidder_addresses().iter(deps.storage)? {
+                let amount = proxy_bid_of(deps.storage, &address?)?.committed;
+                outstanding_bids = outstanding_bids.checked_add(amount).map_err(StdError::from)?;
+            }
+
+            Ok(Invariants {
+                total_escrowed,
+                outstanding_bids,
+                holds: total_escrowed == outstanding_bids
+            })
+        }
+    }
+
+    impl Killswitch for Contract {
+        type Error = StdError;
+
+        /// Pausing or migrating requires a reason so that users can see why.
         #[execute]
-        fn retract_bid() -> Result<Response, <Self as Auction>::Error> {
-            let sale_info = INFO.load_or_error(deps.storage)?;
-            if sale_info.end_block > env.block.height {
-                return Err(StdError::generic_err("Sale hasn't finished yet."));
+        fn set_status(
+            status: ContractStatus<Addr>,
+        ) -> Result<Response, <Self as Killswitch>::Error> {
+            match &status {
+                ContractStatus::Paused { reason } |
+                ContractStatus::Migrating { reason, .. } if reason.trim().is_empty() =>
+                    return Err(StdError::generic_err("A reason for the status change is required.")),
+                _ => { }
             }
 
+            let previous = killswitch::STORE.load_or_default(deps.storage)?;
+
             let sender = info.sender.as_str().canonize(deps.api)?;
-            let highest_bidder = HIGHEST_BID.load_or_error(deps.storage)?;
+            let is_pauser = PAUSER.load(deps.storage)?.is_some_and(|x| x == sender);
+
+            if is_pauser && admin::assert(deps.as_ref(), &info).is_err() {
+                // Anything other than halting the contract is up to the admin.
+                if !matches!(status, ContractStatus::Paused { .. }) {
+                    return Err(StdError::generic_err("Unauthorized"));
+                }
 
-            if highest_bidder == sender {
-                return Err(StdError::generic_err("You have won the sale and cannot retract your bid."));
+                killswitch::assert_can_set_status(deps.as_ref(), &status)?;
+                killswitch::STORE.canonize_and_save(deps.branch(), status.clone())?;
+            } else {
+                killswitch::set_status(deps.branch(), info.clone(), status.clone())?;
             }
 
-            let mut bidders = bidders();
+            let mut resp = Response::default()
+                .add_attribute(events::ACTION, actions::SET_STATUS)
+                .add_attribute(events::STATUS, status.to_string())
+                .add_attribute(events::PREVIOUS_STATUS, status_name(&previous))
+                .add_attribute(events::NEW_STATUS, status_name(&status))
+                .add_attribute(events::ACTOR, info.sender);
 
-            let balance = bidders.get_or_default(deps.storage, &sender)?;
-            bidders.insert(deps.storage, &sender, &Uint128::zero())?;
+            match status {
+                ContractStatus::Paused { reason } |
+                ContractStatus::Migrating { reason, .. } =>
+                    resp = resp.add_attribute(events::REASON, reason),
+                ContractStatus::Operational => { }
+            }
 
-            let send_msg = if balance > Uint128::zero() {
-                vec![BankMsg::Send {
-                    to_address: info.sender.into_string(),
-                    amount: vec![coin(balance.u128(), "uscrt")]
-                }]
-            } else {
-                vec![]
-            };
+            STATUS_CHANGED.save(deps.storage, &StatusChange {
+                height: env.block.height,
+                time: env.block.time.seconds()
+            })?;
 
-            Ok(Response::default().add_messages(send_msg))
+            Ok(resp)
+        }
+    
+        #[query]
+        fn status() -> Result<ContractStatus<Addr>, <Self as Killswitch>::Error> {
+            <killswitch::DefaultImpl as Killswitch>::status(deps, env)
         }
+    }
 
+    #[auto_impl(admin::DefaultImpl)]
+    impl Admin for Contract {
         #[execute]
-        #[admin::require_admin]
-        fn claim_proceeds() -> Result<Response, <Self as Auction>::Error> {
-            let sale_info = INFO.load_or_error(deps.storage)?;
-            if sale_info.end_block > env.block.height {
-                return Err(StdError::generic_err("Sale hasn't finished yet."));
-            }
+        fn change_admin(mode: Option<Mode>) -> Result<Response, Self::Error> { }
+    
+        #[query]
+        fn admin() -> Result<Option<Addr>, Self::Error> { }
+    }
+}
 
-            let send_msg = if let Some(addr) = HIGHEST_BID.load(deps.storage)? {
-                let mut bidders = bidders();
+// Added to the module above with the `vk` feature, see `contract!`.
+viewing_keys {
+    impl ViewingKeys for Contract {
+        type Error = ContractError;
 
-                let balance = bidders.get_or_default(deps.storage, &addr)?;
-                bidders.insert(deps.storage, &addr, &Uint128::zero())?;
+        #[execute]
+        fn bid_with_key(
+            key: String,
+            _padding: Option<String>
+        ) -> Result<Response, <Self as ViewingKeys>::Error> {
+            let sender = info.sender.as_str().canonize(deps.api)?;
+            let resp = Self::bid(deps.branch(), env, info, None, None)?;
 
-                vec![BankMsg::Send {
-                    to_address: info.sender.into_string(),
-                    amount: vec![coin(balance.u128(), "uscrt")]
-                }]
-            } else {
-                // No one made any bids on this sale
-                vec![]
-            };
+            auth::STORE.save(deps.storage, &sender, &ViewingKey::from(key).to_hashed())?;
+            count(deps.storage, |x| &mut x.key_operations)?;
 
-            Ok(Response::default().add_messages(send_msg))
+            Ok(resp)
         }
-    
+
+        #[execute]
+        fn revoke_all_keys() -> Result<Response, <Self as ViewingKeys>::Error> {
+            let sender = info.sender.canonize(deps.api)?;
+            auth::STORE.remove(deps.storage, &sender);
+            count(deps.storage, |x| &mut x.key_operations)?;
+
+            Ok(Response::default())
+        }
+
         #[query]
         fn view_bid(
             address: String,
             key: String
-        ) -> Result<Uint128, <Self as Auction>::Error> {
+        ) -> Result<Uint128, <Self as ViewingKeys>::Error> {
+            assert_queries_allowed(deps)?;
+
             let address = address.as_str().canonize(deps.api)?;
-            auth::authenticate(deps.storage, &ViewingKey::from(key), &address)?;
+            authenticate_viewer(deps.storage, &ViewingKey::from(key), &address)?;
 
-            bidders().get_or_default(deps.storage, &address)
+            Ok(balances::get_or_default(deps.storage, &address)?)
         }
-    
+
         #[query]
-        fn active_bids(
-            pagination: Pagination
-        ) -> Result<PaginatedResponse<Uint128>, <Self as Auction>::Error> {
-            let bidders = bidders().values(deps.storage)?;
-            let len = bidders.len();
-
-            let limit = pagination.limit.min(Pagination::LIMIT);
-            let iterator = bidders
-                .skip(pagination.start as usize)
-                .take(limit as usize);
-
-            Ok(PaginatedResponse {
-                total: len as u64,
-                entries: iterator
-                    .into_iter()
-                    .collect::<StdResult<Vec<Uint128>>>()?
-            })
+        fn min_bid_to_win(
+            address: String,
+            key: String
+        ) -> Result<Uint128, <Self as ViewingKeys>::Error> {
+            assert_queries_allowed(deps)?;
+
+            let address = address.as_str().canonize(deps.api)?;
+            authenticate_viewer(deps.storage, &ViewingKey::from(key), &address)?;
+
+            min_bid_to_win_of(deps, &env, &address)
         }
-    
+
         #[query]
-        fn sale_status() -> Result<SaleStatus, <Self as Auction>::Error> {
-            let info = INFO.load_or_error(deps.storage)?;
+        fn view_payout(
+            address: String,
+            key: String
+        ) -> Result<Uint128, <Self as ViewingKeys>::Error> {
+            assert_queries_allowed(deps)?;
 
-            let current_highest = if let Some(addr) = HIGHEST_BID.load(deps.storage)? {
-                bidders().get_or_error(deps.storage, &addr)?
-            } else {
-                Uint128::zero()
+            let address = address.as_str().canonize(deps.api)?;
+            authenticate_viewer(deps.storage, &ViewingKey::from(key), &address)?;
+
+            Ok(PAYOUTS.load(deps.storage, &address)?.unwrap_or_default())
+        }
+
+        #[query]
+        fn view_proxy_bid(
+            address: String,
+            key: String
+        ) -> Result<ProxyBid, <Self as ViewingKeys>::Error> {
+            assert_queries_allowed(deps)?;
+
+            let address = address.as_str().canonize(deps.api)?;
+            authenticate_viewer(deps.storage, &ViewingKey::from(key), &address)?;
+
+            proxy_bid_of(deps.storage, &address)
+        }
+
+        #[query]
+        fn my_rank(
+            address: String,
+            key: String
+        ) -> Result<BidRank, <Self as ViewingKeys>::Error> {
+            assert_queries_allowed(deps)?;
+
+            let address = address.as_str().canonize(deps.api)?;
+            authenticate_viewer(deps.storage, &ViewingKey::from(key), &address)?;
+
+            rank_of(deps.storage, &address)
+        }
+
+        #[query]
+        fn winner_payload(
+            address: String,
+            key: String
+        ) -> Result<Option<Binary>, <Self as ViewingKeys>::Error> {
+            assert_queries_allowed(deps)?;
+
+            // Not even the auditor can view the payload.
+            let address = address.as_str().canonize(deps.api)?;
+            auth::authenticate(deps.storage, &ViewingKey::from(key), &address)
+                .map_err(|_| AuthError::Unauthorized)?;
+
+            winner_payload_of(deps.storage, &address)
+        }
+
+        #[query]
+        fn delivery(
+            address: String,
+            key: String
+        ) -> Result<Delivery, <Self as ViewingKeys>::Error> {
+            assert_queries_allowed(deps)?;
+
+            // Not even the auditor can view it.
+            let address = address.as_str().canonize(deps.api)?;
+            auth::authenticate(deps.storage, &ViewingKey::from(key), &address)
+                .map_err(|_| AuthError::Unauthorized)?;
+
+            delivery_of(deps, &address)
+        }
+
+        #[query]
+        fn view_memo(
+            bidder: String,
+            viewer: String,
+            key: String
+        ) -> Result<Option<String>, <Self as ViewingKeys>::Error> {
+            assert_queries_allowed(deps)?;
+
+            let viewer = viewer.as_str().canonize(deps.api)?;
+            auth::authenticate(deps.storage, &ViewingKey::from(key), &viewer)
+                .map_err(|_| AuthError::Unauthorized)?;
+
+            memo_of(deps, &bidder.as_str().canonize(deps.api)?, &viewer)
+        }
+
+        #[query]
+        fn view_claim(
+            address: String,
+            key: String,
+            claim_id: Option<u64>
+        ) -> Result<Claim, <Self as ViewingKeys>::Error> {
+            assert_queries_allowed(deps)?;
+
+            if !SETTINGS.load_or_error(deps.storage)?.tradable_claims {
+                return Err(ClaimError::NotTradable.into());
+            }
+
+            let address = address.as_str().canonize(deps.api)?;
+            authenticate_viewer(deps.storage, &ViewingKey::from(key), &address)?;
+
+            let id = match claim_id {
+                Some(id) => id,
+                None => CLAIM_IDS.load(deps.storage, &address)?
+                    .ok_or(ClaimError::NoBid)?
             };
 
-            Ok(SaleStatus {
-                current_highest,
-                is_finished: info.end_block < env.block.height,
-                info
+            let (bidder, owner) = load_claim(deps.storage, id)?;
+
+            // Both the original bidder and the current owner can view the claim.
+            if address != bidder && address != owner {
+                return Err(AuthError::Unauthorized.into());
+            }
+
+            Ok(Claim {
+                id,
+                amount: balances::get_or_default(deps.storage, &bidder)?,
+                owner: owner.humanize(deps.api)?
             })
         }
+
+        #[query]
+        fn audit_bids(
+            key: String,
+            pagination: Pagination
+        ) -> Result<PaginatedResponse<BidEntry>, <Self as ViewingKeys>::Error> {
+            assert_queries_allowed(deps)?;
+            authenticate_auditor(deps.storage, &ViewingKey::from(key))?;
+
+            bid_entries_page(deps, pagination)
+        }
     }
 
-    #[auto_impl(auth::DefaultImpl)]
     impl VkAuth for Contract {
+        type Error = StdError;
+
         #[execute]
         fn create_viewing_key(
             entropy: String,
             padding: Option<String>
-        ) -> Result<Response, Self::Error> { }
+        ) -> Result<Response, Self::Error> {
+            count(deps.storage, |x| &mut x.key_operations)?;
+
+            <auth::DefaultImpl as VkAuth>::create_viewing_key(deps, env, info, entropy, padding)
+        }
 
         #[execute]
         fn set_viewing_key(
             key: String,
             padding: Option<String>
-        ) -> Result<Response, Self::Error> { }
+        ) -> Result<Response, Self::Error> {
+            count(deps.storage, |x| &mut x.key_operations)?;
+
+            <auth::DefaultImpl as VkAuth>::set_viewing_key(deps, env, info, key, padding)
+        }
     }
+}
 
+}
 
-    #[auto_impl(killswitch::DefaultImpl)]
-    impl Killswitch for Contract {
-        #[execute]
-        fn set_status(
-            status: ContractStatus<Addr>,
-        ) -> Result<Response, <Self as Killswitch>::Error> { }
-    
-        #[query]
-        fn status() -> Result<ContractStatus<Addr>, <Self as Killswitch>::Error> { }
+// Implemented outside of the contract module, where the
+// DSL expects every method of an implemented trait to be a message.
+impl Migrate for auction::Contract {
+    type Error = ContractError;
+
+    fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+        // See layout::LEGACY_AUCTION for why the first release can't be migrated.
+        let from = layout::migrate(deps.storage, None)?;
+        let release = version::migrate(deps.storage, version::AUCTION_CONTRACT_NAME)?;
+
+        let mut resp = Response::default()
+            .add_attribute(events::ACTION, actions::MIGRATE)
+            .add_attribute(events::LAYOUT_VERSION, from.to_string());
+
+        if let Some(release) = release {
+            resp = resp.add_attribute(events::CONTRACT_VERSION, release.version);
+        }
+
+        Ok(resp)
     }
+}
 
-    #[auto_impl(admin::DefaultImpl)]
-    impl Admin for Contract {
-        #[execute]
-        fn change_admin(mode: Option<Mode>) -> Result<Response, Self::Error> { }
-    
-        #[query]
-        fn admin() -> Result<Option<Addr>, Self::Error> { }
+#[cfg(target_arch = "wasm32")]
+mod wasm_migrate {
+    use fadroma::cosmwasm_std::do_migrate;
+    use shared::Migrate;
+
+    #[no_mangle]
+    extern "C" fn migrate(env_ptr: u32, msg_ptr: u32) -> u32 {
+        do_migrate(&<super::auction::Contract as Migrate>::migrate, env_ptr, msg_ptr)
     }
 }
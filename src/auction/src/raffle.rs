@@ -0,0 +1,105 @@
+//! Tickets of auctions that run as a raffle. Every bid buys a range of
+//! consecutive tickets so the winner can be found by looking up which
+//! range the drawn ticket falls into.
+//!
+//! The version of cosmwasm that fadroma is built with doesn't expose the
+//! randomness of the block, so the draw is seeded by hashing together the
+//! entropy of every bid. Contract storage is encrypted on Secret Network,
+//! which means that the seed can't be known in advance by any bidder.
+
+use fadroma::{
+    storage::{iterable::IterableStorage, SingleItem, StaticKey},
+    cosmwasm_std::{Storage, Env, CanonicalAddr, Uint128, StdResult},
+    bin_serde::{FadromaSerialize, FadromaDeserialize},
+    crypto::{Prng, sha_256}
+};
+use shared::{versioned_namespace, versioned_key};
+
+// The tickets from the end of the previous range up to (excluding) `end`.
+#[derive(FadromaSerialize, FadromaDeserialize)]
+struct TicketRange {
+    owner: CanonicalAddr,
+    end: u64
+}
+
+#[inline]
+fn ranges() -> IterableStorage<TicketRange, StaticKey> {
+    IterableStorage::new(StaticKey(versioned_key!(b"ticket_ranges")))
+}
+
+versioned_namespace!(SeedNs, b"raffle_seed");
+const SEED: SingleItem<[u8; 32], SeedNs> = SingleItem::new();
+
+// The parts of the bids that are not refunded to the losers.
+versioned_namespace!(PotNs, b"raffle_pot");
+const POT: SingleItem<Uint128, PotNs> = SingleItem::new();
+
+/// Returns the number of tickets sold so far.
+pub fn total_tickets(storage: &dyn Storage) -> StdResult<u64> {
+    let ranges = ranges();
+
+    match ranges.len(storage)? {
+        0 => Ok(0),
+        len => Ok(ranges.get_or_error(storage, len - 1)?.end)
+    }
+}
+
+/// Assigns the next `tickets` tickets to the owner and adds
+/// the non-refundable part of their bid to the pot.
+pub fn buy(
+    storage: &mut dyn Storage,
+    owner: &CanonicalAddr,
+    tickets: u64,
+    non_refundable: Uint128,
+    entropy: &[u8]
+) -> StdResult<()> {
+    let end = total_tickets(storage)? + tickets;
+    ranges().push(storage, &TicketRange { owner: owner.clone(), end })?;
+
+    let pot = POT.load_or_default(storage)?.checked_add(non_refundable)?;
+    POT.save(storage, &pot)?;
+
+    let seed = SEED.load_or_default(storage)?;
+    SEED.save(storage, &sha_256(&[&seed[..], entropy].concat()))
+}
+
+/// Picks a ticket at random and returns its owner
+/// or `None` if no tickets have been sold.
+pub fn draw(storage: &dyn Storage, env: &Env) -> StdResult<Option<CanonicalAddr>> {
+    let total = total_tickets(storage)?;
+    if total == 0 {
+        return Ok(None);
+    }
+
+    let entropy = [
+        env.block.height.to_be_bytes(),
+        env.block.time.nanos().to_be_bytes()
+    ].concat();
+
+    let seed = SEED.load_or_default(storage)?;
+    let ticket = Prng::new(&seed, &entropy).next_u64() % total;
+
+    // Find the first range that ends after the ticket.
+    let ranges = ranges();
+    let (mut low, mut high) = (0, ranges.len(storage)?);
+
+    while low < high {
+        let mid = low + (high - low) / 2;
+
+        if ranges.get_or_error(storage, mid)?.end > ticket {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+
+    Ok(Some(ranges.get_or_error(storage, low)?.owner))
+}
+
+/// Empties the pot and returns what was in it.
+pub fn take_pot(storage: &mut dyn Storage) -> StdResult<Uint128> {
+    let pot = POT.load_or_default(storage)?;
+    POT.remove(storage);
+
+    Ok(pot)
+}
@@ -4,130 +4,1616 @@ pub mod factory {
         dsl::*,
         core::*,
         schemars,
+        admin::{self, Admin, Mode},
         cosmwasm_std::{
-            self, Response, StdError, SubMsg, WasmMsg, Binary,
-            Reply, CanonicalAddr, Addr, StdResult, to_binary, from_binary
+            self, Response, StdError, SubMsg, WasmMsg, BankMsg, Event, Env, Deps, DepsMut,
+            Reply, SubMsgResult, CanonicalAddr, Addr, Binary, Coin, StdResult, Uint128,
+            Storage, to_binary, from_binary
+        },
+        storage::{
+            iterable::IterableStorage, ItemSpace,
+            SingleItem, StaticKey, TypedKey, FixedSegmentSizeKey
         },
-        storage::{iterable::IterableStorage, SingleItem, StaticKey},
         bin_serde::{FadromaSerialize, FadromaDeserialize},
+        scrt::permit::Permit,
         namespace
     };
     use shared::{
-        InstantiateMsg as AuctionInitMsg, SaleInfo,
-        Pagination, PaginatedResponse
+        versioned_namespace, versioned_key, layout, version::{self, ContractVersion},
+        InstantiateMsg as AuctionInitMsg, AuctionEnd, AuctionState, Config, CurrencyPolicy,
+        FeeConfig, MigrateMsg, SaleInfo, SaleStatus, SubscriberMsg, FactoryPermission,
+        Pagination, PaginatedResponse, validate_tags,
+        client::AuctionClient,
+        errors::{ContractError, AuthError, CurrencyError, LifecycleError, ReplyError},
+        events::{self, actions}
     };
     use serde::{Serialize, Deserialize};
 
-    namespace!(ContractNs, b"contract");
+    versioned_namespace!(ContractNs, b"contract");
     const AUCTION_CONTRACT: SingleItem<
         ContractCode,
         ContractNs
     > = SingleItem::new();
 
-    #[derive(Serialize, Deserialize, FadromaSerialize, FadromaDeserialize, Canonize, Debug)]
+    versioned_namespace!(NextReplyIdNs, b"next_reply_id");
+    const NEXT_REPLY_ID: SingleItem<u64, NextReplyIdNs> = SingleItem::new();
+
+    versioned_namespace!(FeeConfigNs, b"fee_config");
+    const FEE_CONFIG: SingleItem<FeeConfig<CanonicalAddr>, FeeConfigNs> = SingleItem::new();
+
+    // Any currency is allowed while there is none.
+    versioned_namespace!(CurrencyPolicyNs, b"currency_policy");
+    const CURRENCY_POLICY: SingleItem<CurrencyPolicy<CanonicalAddr>, CurrencyPolicyNs> = SingleItem::new();
+
+    /// The maximum number of contracts that can subscribe to the factory.
+    pub const MAX_SUBSCRIBERS: u64 = 10;
+
+    /// The maximum number of auctions that a single `process_ended` call settles
+    /// and that a single `archive_auctions` call archives.
+    pub const MAX_PROCESS_BATCH: u8 = 10;
+
+    // Paid to the caller of process_ended for every auction that it settles.
+    versioned_namespace!(KeeperRewardNs, b"keeper_reward");
+    const KEEPER_REWARD: SingleItem<Coin, KeeperRewardNs> = SingleItem::new();
+
+    versioned_namespace!(DefaultAuctionAdminNs, b"default_auction_admin");
+    const DEFAULT_AUCTION_ADMIN: SingleItem<CanonicalAddr, DefaultAuctionAdminNs> = SingleItem::new();
+
+    // The most auctions that a creator can have which aren't known to be settled.
+    versioned_namespace!(CreatorLimitNs, b"creator_limit");
+    const CREATOR_LIMIT: SingleItem<u64, CreatorLimitNs> = SingleItem::new();
+
+    // How many auctions each creator has which aren't known to be settled or
+    // cancelled, including those still being instantiated.
+    versioned_namespace!(ActiveCountsNs, b"active_counts");
+    const ACTIVE_COUNTS: ItemSpace<u64, ActiveCountsNs, TypedKey<CanonicalAddr>> = ItemSpace::new();
+
+    // The position in by_end_block() that the next process_ended call starts from.
+    versioned_namespace!(ProcessCursorNs, b"process_cursor");
+    const PROCESS_CURSOR: SingleItem<u64, ProcessCursorNs> = SingleItem::new();
+
+    // The position in deferred() of each auction in it, keyed by its index in auctions().
+    versioned_namespace!(DeferredPositionsNs, b"deferred_positions");
+    const DEFERRED_POSITIONS: ItemSpace<u64, DeferredPositionsNs, TypedKey<u64>> = ItemSpace::new();
+
+    // Auctions known to be settled, keyed by their index in auctions().
+    versioned_namespace!(SettledNs, b"settled");
+    const SETTLED: ItemSpace<bool, SettledNs, TypedKey<u64>> = ItemSpace::new();
+
+    // Settle messages sent by process_ended, keyed by the id of their reply.
+    // They share the ids with auctions which are being instantiated.
+    versioned_namespace!(PendingSettlementsNs, b"pending_settlements");
+    const PENDING_SETTLEMENTS: ItemSpace<
+        PendingSettlement,
+        PendingSettlementsNs,
+        TypedKey<u64>
+    > = ItemSpace::new();
+
+    // Callbacks to subscribers all use this id since their replies only
+    // report failures. Auctions use ids counting up from 0.
+    const SUBSCRIBER_REPLY_ID: u64 = u64::MAX;
+
+    // Auctions which are being instantiated, keyed by the id of the reply
+    // that will confirm their address. They are moved to auctions() once that
+    // happens so that the registry never contains entries without an address.
+    versioned_namespace!(PendingNs, b"pending");
+    const PENDING: ItemSpace<
+        PendingAuction,
+        PendingNs,
+        TypedKey<u64>
+    > = ItemSpace::new();
+
+    // The position in listed() of each auction that hasn't been archived,
+    // keyed by its index in auctions().
+    versioned_namespace!(ListedPositionsNs, b"listed_positions");
+    const LISTED_POSITIONS: ItemSpace<u64, ListedPositionsNs, TypedKey<u64>> = ItemSpace::new();
+
+    // What each auction was created with, keyed by its index in auctions(),
+    // so that it can be cloned. The config is kept in its JSON encoding.
+    versioned_namespace!(OriginsNs, b"origins");
+    const ORIGINS: ItemSpace<AuctionOrigin<CanonicalAddr>, OriginsNs, TypedKey<u64>> = ItemSpace::new();
+
+    // The index in auctions() of each auction, keyed by its address,
+    // so that callbacks from auctions can be attributed to their entry.
+    versioned_namespace!(IndicesNs, b"indices");
+    const INDICES: ItemSpace<u64, IndicesNs, TypedKey<CanonicalAddr>> = ItemSpace::new();
+
+    // Auctions which have been closed, keyed by their index in auctions().
+    versioned_namespace!(ClosedNs, b"closed");
+    const CLOSED: ItemSpace<bool, ClosedNs, TypedKey<u64>> = ItemSpace::new();
+
+    /// The maximum number of sales that are ranked by `top_sales` for each denom.
+    pub const MAX_TOP_SALES: u8 = 10;
+
+    // Keyed by the denom since prices in different ones can't be compared.
+    // Highest price first, ties are kept in the order of settlement.
+    versioned_namespace!(TopSalesNs, b"top_sales");
+    const TOP_SALES: ItemSpace<Vec<RankedSale>, TopSalesNs, TypedKey<String>> = ItemSpace::new();
+
+    versioned_namespace!(VolumeStatsNs, b"volume_stats");
+    const VOLUME_STATS: SingleItem<VolumeStats, VolumeStatsNs> = SingleItem::new();
+
+    #[derive(Serialize, Deserialize, FadromaSerialize, FadromaDeserialize, Canonize, schemars::JsonSchema, PartialEq, Debug)]
     #[serde(rename_all = "snake_case")]
     pub struct AuctionEntry<A> {
         pub contract: ContractLink<A>,
         pub info: SaleInfo
     }
 
+    // Position of an auction in auctions(), ordered by end block.
+    #[derive(FadromaSerialize, FadromaDeserialize)]
+    struct EndingEntry {
+        end_block: u64,
+        index: u64
+    }
+
+    // The entries of the first release, see layout::LEGACY_FACTORY.
+    namespace!(LegacyContractNs, b"contract");
+    const LEGACY_CONTRACT: SingleItem<ContractCode, LegacyContractNs> = SingleItem::new();
+
+    #[derive(FadromaSerialize, FadromaDeserialize)]
+    struct LegacyAuctionEntry {
+        contract: ContractLink<CanonicalAddr>,
+        info: LegacySaleInfo
+    }
+
+    // From before sales had metadata.
+    #[derive(FadromaSerialize, FadromaDeserialize)]
+    struct LegacySaleInfo {
+        name: String,
+        end_block: u64
+    }
+
+    #[derive(FadromaSerialize, FadromaDeserialize)]
+    struct PendingAuction {
+        code_hash: String,
+        info: SaleInfo,
+        origin: AuctionOrigin<CanonicalAddr>,
+        // Sent along with the instantiation, e.g. the seller deposit.
+        funds: Vec<Coin>
+    }
+
+    #[derive(FadromaSerialize, FadromaDeserialize)]
+    struct RankedSale {
+        index: u64,
+        price: Uint128
+    }
+
+    /// One of the sales with the highest final price in a denom. The price
+    /// is the winning bid before fees.
+    #[derive(Serialize, Deserialize, schemars::JsonSchema, PartialEq, Debug)]
+    #[serde(rename_all = "snake_case")]
+    pub struct TopSale {
+        pub index: u64,
+        pub auction: AuctionEntry<Addr>,
+        pub price: Uint128
+    }
+
+    /// What the auctions in the registry were sold for, as reported by them when
+    /// they were settled. Settlements without a winning bid aren't counted.
+    #[derive(
+        Serialize, Deserialize, FadromaSerialize, FadromaDeserialize,
+        schemars::JsonSchema, Default, PartialEq, Debug
+    )]
+    #[serde(rename_all = "snake_case")]
+    pub struct VolumeStats {
+        pub sales: u64,
+        /// Amounts in different denoms can't be added up so they are kept
+        /// apart, in the order in which the denoms were first sold in.
+        pub by_denom: Vec<DenomVolume>
+    }
+
+    #[derive(
+        Serialize, Deserialize, FadromaSerialize, FadromaDeserialize,
+        schemars::JsonSchema, PartialEq, Debug
+    )]
+    #[serde(rename_all = "snake_case")]
+    pub struct DenomVolume {
+        /// The native denom or the address of the bid token.
+        pub denom: String,
+        pub sales: u64,
+        /// The sum of the prices before fees.
+        pub total: Uint128
+    }
+
+    #[derive(FadromaSerialize, FadromaDeserialize)]
+    struct PendingSettlement {
+        index: u64,
+        keeper: CanonicalAddr,
+        rewarded: bool
+    }
+
+    /// What an auction was created with.
+    #[derive(Serialize, Deserialize, FadromaSerialize, FadromaDeserialize, Canonize, schemars::JsonSchema, Debug)]
+    #[serde(rename_all = "snake_case")]
+    pub struct AuctionOrigin<A> {
+        pub creator: A,
+        /// The JSON encoding of the config, as given by the creator.
+        pub config: Binary,
+        pub tags: Vec<String>
+    }
+
+    /// A registry entry as exported by `export_registry`. Fields are only ever
+    /// added to it so that existing backups and mirrors remain readable.
+    #[derive(Serialize, Deserialize, schemars::JsonSchema, Debug)]
+    #[serde(rename_all = "snake_case")]
+    pub struct RegistryEntry {
+        pub index: u64,
+        pub contract: ContractLink<Addr>,
+        pub info: SaleInfo,
+        /// `None` for auctions created before the origin was recorded.
+        pub origin: Option<AuctionOrigin<Addr>>
+    }
+
+    #[allow(clippy::new_ret_no_self)]
     impl Contract {
+        /// Any `initial_auctions` are created right away in the same transaction.
         #[init(entry_wasm)]
-        pub fn new(auction: ContractCode) -> Result<Response, StdError> {
+        pub fn new(
+            admin: Option<String>,
+            auction: ContractCode,
+            initial_auctions: Option<Vec<Config>>
+        ) -> Result<Response, ContractError> {
+            admin::init(deps.branch(), admin.as_deref(), &info)?;
             AUCTION_CONTRACT.save(deps.storage, &auction)?;
+            layout::save(deps.storage);
+            version::save(deps.storage, version::FACTORY_CONTRACT_NAME)?;
 
-            Ok(Response::default())
+            let creator = info.sender.as_str().canonize(deps.api)?;
+            let mut resp = Response::default();
+
+            // The funds can't be split between them, so these can't have a seller deposit.
+            for config in initial_auctions.unwrap_or_default() {
+                resp = resp.add_submessage(
+                    instantiate_auction(deps.branch(), &env, &auction, config, vec![], creator.clone(), vec![])?
+                );
+            }
+
+            Ok(resp)
         }
 
+        /// The auction can be listed under up to [`shared::MAX_TAGS`] tags.
+        /// If `end` is given, it replaces the end block of the config.
         #[execute]
         pub fn create_auction(
-            admin: Option<String>,
-            name: String,
-            end_block: u64
-        ) -> Result<Response, StdError> {
+            mut config: Config,
+            tags: Option<Vec<String>>,
+            end: Option<AuctionEnd>
+        ) -> Result<Response, ContractError> {
+            let tags = tags.unwrap_or_default();
+            validate_tags(&tags)?;
+
+            if let Some(end) = end {
+                config.end_block = end.to_height(env.block.height)?;
+            }
+
+            let creator = info.sender.as_str().canonize(deps.api)?;
             let auction = AUCTION_CONTRACT.load_or_error(deps.storage)?;
-            auctions().push(
-                deps.storage,
-                &AuctionEntry {
-                    contract: ContractLink {
-                        address: CanonicalAddr(Binary::default()),
-                        code_hash: auction.code_hash.clone()
-                    },
-                    info: SaleInfo {
-                        name: name.clone(),
-                        end_block
+            let msg = instantiate_auction(deps.branch(), &env, &auction, config, tags, creator, info.funds)?;
+
+            Ok(Response::default()
+                .add_submessage(msg)
+                .add_attribute(events::ACTION, actions::CREATE_AUCTION)
+            )
+        }
+
+        /// Creates an auction with the same config and tags as the settled auction at the
+        /// given index in the registry, apart from the end block. It starts right away
+        /// and has no end time.
+        /// Only the address that created the original auction can clone it.
+        #[execute]
+        pub fn clone_auction(index: u64, new_end_block: u64) -> Result<Response, ContractError> {
+            // Auctions created before configs were kept can't be cloned.
+            let Some(origin) = ORIGINS.load(deps.storage, &index)? else {
+                return Err(StdError::generic_err(format!("Auction {} can't be cloned.", index)).into());
+            };
+
+            let creator = info.sender.as_str().canonize(deps.api)?;
+            if origin.creator != creator {
+                return Err(AuthError::Unauthorized.into());
+            }
+
+            let entry = auctions().get_or_error(deps.storage, index)?;
+            let client = AuctionClient(entry.contract.humanize(deps.api)?);
+
+            let state: AuctionState = deps.querier.query(&client.state()?)?;
+            if state != AuctionState::Settled {
+                return Err(LifecycleError::NotSettled.into());
+            }
+
+            let mut config: Config = from_binary(&origin.config)?;
+            config.end_block = new_end_block;
+            config.end_time = None;
+            config.start_block = None;
+
+            let auction = AUCTION_CONTRACT.load_or_error(deps.storage)?;
+            let msg = instantiate_auction(deps.branch(), &env, &auction, config, origin.tags, creator, info.funds)?;
+
+            Ok(Response::default()
+                .add_submessage(msg)
+                .add_attribute(events::ACTION, actions::CLONE_AUCTION)
+            )
+        }
+
+        /// Registers the calling contract to receive a [`SubscriberMsg`]
+        /// whenever an auction is created. A subscriber that fails to
+        /// handle it doesn't prevent the auction from being created.
+        #[execute]
+        pub fn subscribe(code_hash: String) -> Result<Response, ContractError> {
+            let address = info.sender.as_str().canonize(deps.api)?;
+
+            let mut subscribers = subscribers();
+            for subscriber in subscribers.iter(deps.storage)? {
+                if subscriber?.address == address {
+                    return Err(StdError::generic_err("Already subscribed.").into());
+                }
+            }
+
+            if subscribers.len(deps.storage)? >= MAX_SUBSCRIBERS {
+                return Err(StdError::generic_err(format!(
+                    "The maximum of {} subscribers has been reached.",
+                    MAX_SUBSCRIBERS
+                )).into());
+            }
+
+            subscribers.push(deps.storage, &ContractLink { address, code_hash })?;
+
+            Ok(Response::default()
+                .add_attribute(events::ACTION, actions::SUBSCRIBE)
+                .add_attribute(events::SUBSCRIBER, info.sender)
+            )
+        }
+
+        #[execute]
+        #[admin::require_admin]
+        pub fn remove_subscriber(address: String) -> Result<Response, ContractError> {
+            let canonical = address.as_str().canonize(deps.api)?;
+
+            let mut subscribers = subscribers();
+            let index = subscribers.iter(deps.storage)?
+                .position(|x| x.is_ok_and(|x| x.address == canonical));
+
+            let Some(index) = index else {
+                return Err(StdError::generic_err("Not a subscriber.").into());
+            };
+
+            subscribers.swap_remove(deps.storage, index as u64)?;
+
+            Ok(Response::default()
+                .add_attribute(events::ACTION, actions::REMOVE_SUBSCRIBER)
+                .add_attribute(events::SUBSCRIBER, address)
+            )
+        }
+
+        /// Applies to every auction that is settled from now on,
+        /// including the ones which were created before.
+        #[execute]
+        #[admin::require_admin]
+        pub fn set_fee_config(fee: Option<FeeConfig<String>>) -> Result<Response, ContractError> {
+            if let Some(fee) = fee {
+                fee.validate()?;
+
+                FEE_CONFIG.save(deps.storage, &FeeConfig {
+                    rate: fee.rate,
+                    recipient: fee.recipient.as_str().canonize(deps.api)?
+                })?;
+            } else {
+                FEE_CONFIG.remove(deps.storage);
+            }
+
+            Ok(Response::default().add_attribute(events::ACTION, actions::SET_FEE_CONFIG))
+        }
+
+        /// Restricts the currencies of the auctions that are created from now on,
+        /// including clones. Existing auctions keep theirs. `None` allows any.
+        #[execute]
+        #[admin::require_admin]
+        pub fn set_currency_policy(policy: Option<CurrencyPolicy<String>>) -> Result<Response, ContractError> {
+            if let Some(policy) = policy {
+                policy.validate()?;
+
+                let tokens = policy.tokens.into_iter()
+                    .map(|x| Ok(ContractLink {
+                        address: x.address.as_str().canonize(deps.api)?,
+                        code_hash: x.code_hash
+                    }))
+                    .collect::<StdResult<Vec<_>>>()?;
+
+                CURRENCY_POLICY.save(deps.storage, &CurrencyPolicy { denoms: policy.denoms, tokens })?;
+            } else {
+                CURRENCY_POLICY.remove(deps.storage);
+            }
+
+            Ok(Response::default().add_attribute(events::ACTION, actions::SET_CURRENCY_POLICY))
+        }
+
+        /// Caps how many auctions a single creator can have which aren't known to be
+        /// settled or cancelled. Settled auctions are known to be once they report
+        /// it but cancelled ones only once `process_ended` has reached them, so
+        /// creators may have to wait for it to free up a slot.
+        /// Applies to auctions created from now on and `None` removes the cap.
+        #[execute]
+        #[admin::require_admin]
+        pub fn set_creator_limit(limit: Option<u64>) -> Result<Response, ContractError> {
+            match limit {
+                Some(limit) => CREATOR_LIMIT.save(deps.storage, &limit)?,
+                None => CREATOR_LIMIT.remove(deps.storage)
+            }
+
+            Ok(Response::default().add_attribute(events::ACTION, actions::SET_CREATOR_LIMIT))
+        }
+
+        /// Becomes the admin of the auctions created from now on, including clones,
+        /// whose config doesn't name one. It then also receives their proceeds unless
+        /// a beneficiary is set. Without it, the factory itself is their admin.
+        #[execute]
+        #[admin::require_admin]
+        pub fn set_default_auction_admin(admin: Option<String>) -> Result<Response, ContractError> {
+            match admin {
+                Some(admin) => DEFAULT_AUCTION_ADMIN.save(deps.storage, &admin.as_str().canonize(deps.api)?)?,
+                None => DEFAULT_AUCTION_ADMIN.remove(deps.storage)
+            }
+
+            Ok(Response::default().add_attribute(events::ACTION, actions::SET_DEFAULT_AUCTION_ADMIN))
+        }
+
+        /// Archives the auctions at the given indices in the registry which are settled
+        /// or cancelled and skips the rest, for auctions which `process_ended` can't
+        /// archive, e.g. because it has already passed them. At most
+        /// [`MAX_PROCESS_BATCH`] indices can be given.
+        #[execute]
+        #[admin::require_admin]
+        pub fn archive_auctions(indices: Vec<u64>) -> Result<Response, ContractError> {
+            if indices.is_empty() || indices.len() > MAX_PROCESS_BATCH as usize {
+                return Err(StdError::generic_err(format!(
+                    "Between 1 and {} auctions can be archived at once.",
+                    MAX_PROCESS_BATCH
+                )).into());
+            }
+
+            let mut archived = 0u8;
+
+            for index in indices {
+                if LISTED_POSITIONS.load(deps.storage, &index)?.is_none() {
+                    continue;
+                }
+
+                let entry = auctions().get_or_error(deps.storage, index)?;
+                let client = AuctionClient(entry.contract.humanize(deps.api)?);
+
+                let state: AuctionState = deps.querier.query(&client.state()?)?;
+                match state {
+                    AuctionState::Settled => finish(deps.storage, env.block.height, index, true)?,
+                    AuctionState::Cancelled => finish(deps.storage, env.block.height, index, false)?,
+                    _ => continue
+                }
+
+                archived += 1;
+            }
+
+            Ok(Response::default()
+                .add_attribute(events::ACTION, actions::ARCHIVE_AUCTIONS)
+                .add_attribute(events::ARCHIVED, archived.to_string())
+            )
+        }
+
+        /// Settles up to `limit` auctions which have ended, in the order of their end
+        /// block as kept in the registry, and pays the caller the keeper reward for
+        /// each one that was settled, as long as the factory holds enough funds.
+        /// There is no reward for auctions that nobody bid in or for the caller's own.
+        /// Auctions that can't be settled when they are reached, e.g. because they
+        /// are paused or in their dispute window, are skipped and have to be settled
+        /// through the auction itself. They are still listed by `list_unsettled`.
+        #[execute]
+        pub fn process_ended(limit: u8) -> Result<Response, ContractError> {
+            if limit == 0 || limit > MAX_PROCESS_BATCH {
+                return Err(StdError::generic_err(format!(
+                    "Limit must be between 1 and {}.",
+                    MAX_PROCESS_BATCH
+                )).into());
+            }
+
+            let keeper = info.sender.as_str().canonize(deps.api)?;
+
+            let start = PROCESS_CURSOR.load_or_default(deps.storage)?;
+            let end = lower_bound(deps.storage, env.block.height)?.max(start);
+            let mut cursor = start;
+            let mut processed = 0u8;
+
+            let mut resp = Response::default();
+
+            while cursor < end && processed < limit {
+                let index = by_end_block().get_or_error(deps.storage, cursor)?.index;
+                cursor += 1;
+
+                if SETTLED.load(deps.storage, &index)?.unwrap_or_default() {
+                    continue;
+                }
+
+                let entry = auctions().get_or_error(deps.storage, index)?;
+                let client = AuctionClient(entry.contract.humanize(deps.api)?);
+
+                // Auctions of the first release can't tell their state since they
+                // weren't migrated with the factory, see layout::LEGACY_AUCTION.
+                let Ok(state) = deps.querier.query::<AuctionState>(&client.state()?) else {
+                    defer(deps.storage, index)?;
+                    continue;
+                };
+
+                match state {
+                    AuctionState::Ended => { }
+                    // Settled by its admin in the meantime.
+                    AuctionState::Settled => {
+                        finish(deps.storage, env.block.height, index, true)?;
+                        continue;
+                    }
+                    // Will never be settled so it is archived right away.
+                    AuctionState::Cancelled => {
+                        finish(deps.storage, env.block.height, index, false)?;
+                        continue;
+                    }
+                    _ => {
+                        defer(deps.storage, index)?;
+                        continue;
                     }
                 }
-            )?;
 
-            let label = format!(
-                "Auction: {}, started at: {}, ending at {}",
-                name,
-                env.block.height,
-                env.block.height + end_block
-            );
-        
-            let msg = SubMsg::reply_on_success(
-                WasmMsg::Instantiate {
-                    code_id: auction.id,
-                    code_hash: auction.code_hash,
-                    msg: to_binary(&AuctionInitMsg { admin, name, end_block })?,
-                    funds: vec![],
-                    label
-                },
-                0
-            );
-        
-            Ok(Response::default().add_submessage(msg))
+                // Settling an empty auction or one's own takes no work worth paying for.
+                let status: SaleStatus = deps.querier.query(&client.sale_status()?)?;
+                let creator = ORIGINS.load(deps.storage, &index)?.map(|x| x.creator);
+                let rewarded = status.current_highest.is_some_and(|x| !x.is_zero()) &&
+                    creator.as_ref() != Some(&keeper);
+
+                let reply_id = NEXT_REPLY_ID.load_or_default(deps.storage)?;
+                NEXT_REPLY_ID.save(deps.storage, &(reply_id + 1))?;
+
+                PENDING_SETTLEMENTS.save(
+                    deps.storage,
+                    &reply_id,
+                    &PendingSettlement { index, keeper: keeper.clone(), rewarded }
+                )?;
+
+                resp = resp.add_submessage(SubMsg::reply_always(client.settle()?, reply_id));
+                processed += 1;
+            }
+
+            // Finishing auctions above may have moved it further already.
+            let cursor = cursor.max(PROCESS_CURSOR.load_or_default(deps.storage)?);
+            PROCESS_CURSOR.save(deps.storage, &cursor)?;
+
+            Ok(resp
+                .add_attribute(events::ACTION, actions::PROCESS_ENDED)
+                .add_attribute(events::PROCESSED, processed.to_string())
+                .add_attribute(events::REMAINING, (end - cursor).to_string())
+            )
+        }
+
+        /// Funds for the reward have to be sent to the factory separately.
+        #[execute]
+        #[admin::require_admin]
+        pub fn set_keeper_reward(reward: Option<Coin>) -> Result<Response, ContractError> {
+            match reward {
+                Some(reward) => KEEPER_REWARD.save(deps.storage, &reward)?,
+                None => KEEPER_REWARD.remove(deps.storage)
+            }
+
+            Ok(Response::default().add_attribute(events::ACTION, actions::SET_KEEPER_REWARD))
+        }
+
+        /// Adds an auction that was instantiated outside of the factory to the registry.
+        /// It has to have been instantiated from the code that the factory creates
+        /// auctions from and can't have finished. Since it wasn't created through the
+        /// factory, it can't be cloned and doesn't count against the creator limit.
+        #[execute]
+        #[admin::require_admin]
+        pub fn register_existing(contract: ContractLink<String>) -> Result<Response, ContractError> {
+            let auction = AUCTION_CONTRACT.load_or_error(deps.storage)?;
+            if contract.code_hash != auction.code_hash {
+                return Err(StdError::generic_err(format!(
+                    "Expected an auction with code hash {} but got {}.",
+                    auction.code_hash,
+                    contract.code_hash
+                )).into());
+            }
+
+            let contract = ContractLink {
+                address: deps.api.addr_validate(&contract.address)?,
+                code_hash: contract.code_hash
+            };
+
+            let canonical = contract.clone().canonize(deps.api)?;
+            if INDICES.load(deps.storage, &canonical.address)?.is_some() {
+                return Err(StdError::generic_err(format!(
+                    "Auction {} is already registered.",
+                    contract.address
+                )).into());
+            }
+
+            // Queries are checked against the code hash of the contract,
+            // so this also fails if the given one isn't actually its own.
+            let status: SaleStatus = deps.querier.query(&AuctionClient(contract.clone()).sale_status()?)?;
+            if status.is_finished {
+                return Err(LifecycleError::Finished.into());
+            }
+
+            register(deps.storage, canonical, status.info, &[])?;
+
+            Ok(Response::default()
+                .add_attribute(events::ACTION, actions::REGISTER_EXISTING)
+                .add_attribute(events::AUCTION_ADDRESS, contract.address)
+            )
+        }
+
+        /// Sent by an auction when it is closed. Marks its entry as closed and
+        /// archives it. Auctions which aren't in the registry are rejected.
+        #[execute]
+        pub fn auction_closed(settled: bool) -> Result<Response, ContractError> {
+            let sender = info.sender.as_str().canonize(deps.api)?;
+            let Some(index) = INDICES.load(deps.storage, &sender)? else {
+                return Err(AuthError::Unauthorized.into());
+            };
+
+            CLOSED.save(deps.storage, &index, &true)?;
+            finish(deps.storage, env.block.height, index, settled)?;
+
+            Ok(Response::default()
+                .add_attribute(events::ACTION, actions::AUCTION_CLOSED)
+                .add_attribute(events::AUCTION_ADDRESS, info.sender)
+            )
+        }
+
+        /// Sent by an auction when its end block changes. Moves it among the auctions
+        /// ordered by end block so that `list_ending_soon`, `list_unsettled` and
+        /// `process_ended` go by the new one. Auctions which aren't in the registry
+        /// are rejected.
+        #[execute]
+        pub fn end_block_changed(end_block: u64) -> Result<Response, ContractError> {
+            let sender = info.sender.as_str().canonize(deps.api)?;
+            let Some(index) = INDICES.load(deps.storage, &sender)? else {
+                return Err(AuthError::Unauthorized.into());
+            };
+
+            let resp = Response::default()
+                .add_attribute(events::ACTION, actions::END_BLOCK_CHANGED)
+                .add_attribute(events::AUCTION_ADDRESS, info.sender)
+                .add_attribute(events::END_BLOCK, end_block.to_string());
+
+            // Archived auctions are no longer listed by end block.
+            if LISTED_POSITIONS.load(deps.storage, &index)?.is_none() {
+                return Ok(resp);
+            }
+
+            let mut auctions = auctions();
+            let mut entry = auctions.get_or_error(deps.storage, index)?;
+
+            move_by_end_block(deps.storage, index, entry.info.end_block, end_block)?;
+
+            entry.info.end_block = end_block;
+            auctions.set(deps.storage, index, &entry)?;
+
+            Ok(resp)
+        }
+
+        /// The settlement callback that auctions in the registry send to the
+        /// factory. Marks the auction as settled and archives it. Sales with a
+        /// price are ranked for `top_sales` and added to the `volume_stats`.
+        /// Auctions which aren't in the registry are rejected.
+        #[execute]
+        pub fn auction_settled(
+            auction: ContractLink<Addr>,
+            winner: Option<Addr>,
+            price: Uint128,
+            denom: String
+        ) -> Result<Response, ContractError> {
+            // The winner isn't kept since an auction can hide it.
+            let _ = winner;
+
+            if auction.address != info.sender {
+                return Err(AuthError::Unauthorized.into());
+            }
+
+            let sender = info.sender.as_str().canonize(deps.api)?;
+            let Some(index) = INDICES.load(deps.storage, &sender)? else {
+                return Err(AuthError::Unauthorized.into());
+            };
+
+            finish(deps.storage, env.block.height, index, true)?;
+
+            if !price.is_zero() {
+                let mut top = TOP_SALES.load(deps.storage, &denom)?.unwrap_or_default();
+                let position = top.iter()
+                    .position(|x| x.price < price)
+                    .unwrap_or(top.len());
+
+                if position < MAX_TOP_SALES as usize {
+                    top.insert(position, RankedSale { index, price });
+                    top.truncate(MAX_TOP_SALES as usize);
+
+                    TOP_SALES.save(deps.storage, &denom, &top)?;
+                }
+
+                let mut stats = VOLUME_STATS.load_or_default(deps.storage)?;
+                stats.sales += 1;
+
+                match stats.by_denom.iter_mut().find(|x| x.denom == denom) {
+                    Some(volume) => {
+                        volume.sales += 1;
+                        volume.total += price;
+                    }
+                    None => stats.by_denom.push(DenomVolume { denom, sales: 1, total: price })
+                }
+
+                VOLUME_STATS.save(deps.storage, &stats)?;
+            }
+
+            Ok(Response::default()
+                .add_attribute(events::ACTION, actions::AUCTION_SETTLED)
+                .add_attribute(events::AUCTION_ADDRESS, info.sender)
+            )
         }
 
         #[reply]
-        pub fn reply(reply: Reply) -> Result<Response, StdError> {
-            if reply.id != 0 {
-                return Err(StdError::generic_err("Unexpected reply id."));
+        pub fn reply(reply: Reply) -> Result<Response, ContractError> {
+            if reply.id == SUBSCRIBER_REPLY_ID {
+                let resp = match reply.result {
+                    SubMsgResult::Ok(_) => Response::default(),
+                    SubMsgResult::Err(error) => Response::default().add_event(
+                        Event::new(events::SUBSCRIBER_CALLBACK_FAILED)
+                            .add_attribute(events::ERROR, error)
+                    )
+                };
+
+                return Ok(resp);
             }
 
-            let resp = reply.result.unwrap();
-            let address: Addr = from_binary(resp.data.as_ref().unwrap())?;
+            if let Some(pending) = PENDING_SETTLEMENTS.load(deps.storage, &reply.id)? {
+                PENDING_SETTLEMENTS.remove(deps.storage, &reply.id);
 
-            let auctions = auctions();
+                let auction = auctions().get_or_error(deps.storage, pending.index)?
+                    .contract
+                    .address
+                    .humanize(deps.api)?;
+
+                if let SubMsgResult::Err(error) = reply.result {
+                    defer(deps.storage, pending.index)?;
+
+                    // Reported rather than returned so that the other settlements aren't reverted.
+                    return Ok(Response::default().add_event(
+                        Event::new(events::AUCTION_SETTLEMENT_FAILED)
+                            .add_attribute(events::AUCTION_ADDRESS, auction)
+                            .add_attribute(events::ERROR, error)
+                    ));
+                }
+
+                finish(deps.storage, env.block.height, pending.index, true)?;
+
+                let mut resp = Response::default().add_event(
+                    Event::new(events::AUCTION_SETTLED)
+                        .add_attribute(events::AUCTION_ADDRESS, auction)
+                );
+
+                let reward = if pending.rewarded {
+                    KEEPER_REWARD.load(deps.storage)?
+                } else {
+                    None
+                };
+
+                if let Some(reward) = reward {
+                    let balance = deps.querier.query_balance(&env.contract.address, &reward.denom)?;
+
+                    if balance.amount >= reward.amount && !reward.amount.is_zero() {
+                        resp = resp.add_message(BankMsg::Send {
+                            to_address: pending.keeper.humanize(deps.api)?.into_string(),
+                            amount: vec![reward]
+                        });
+                    }
+                }
+
+                return Ok(resp);
+            }
+
+            let Some(pending) = PENDING.load(deps.storage, &reply.id)? else {
+                return Err(StdError::generic_err("Unexpected reply id.").into());
+            };
+
+            PENDING.remove(deps.storage, &reply.id);
+
+            let resp = match reply.result {
+                SubMsgResult::Ok(resp) => resp,
+                SubMsgResult::Err(error) => {
+                    decrement_active(deps.storage, &pending.origin.creator)?;
 
-            let index = auctions.len(deps.storage)? - 1;
-            auctions.update(deps.storage, index, |mut entry| {
-                entry.contract.address = address.canonize(deps.api)?;
+                    // Reported rather than returned so that the failure isn't reverted.
+                    let mut resp = Response::default().add_event(
+                        Event::new(events::AUCTION_INSTANTIATION_FAILED)
+                            .add_attribute(events::AUCTION_NAME, pending.info.name)
+                            .add_attribute(events::ERROR, ReplyError::InstantiateFailed(error).to_string())
+                    );
+
+                    // The funds stay with the factory when the instantiation fails.
+                    if !pending.funds.is_empty() {
+                        resp = resp.add_message(BankMsg::Send {
+                            to_address: pending.origin.creator.humanize(deps.api)?.into_string(),
+                            amount: pending.funds
+                        });
+                    }
+
+                    return Ok(resp);
+                }
+            };
 
-                Ok(entry)
+            let Some(data) = resp.data else {
+                return Err(ReplyError::MissingReplyData.into());
+            };
+
+            let child: ContractLink<Addr> = from_binary(&data)?;
+
+            // Failing here reverts the instantiation as well.
+            if child.code_hash != pending.code_hash {
+                return Err(StdError::generic_err(format!(
+                    "Expected an auction with code hash {} but got {}.",
+                    pending.code_hash,
+                    child.code_hash
+                )).into());
+            }
+
+            let event = Event::new(events::AUCTION_CREATED)
+                .add_attribute(events::AUCTION_NAME, &pending.info.name)
+                .add_attribute(events::AUCTION_ADDRESS, &child.address);
+
+            let callback = to_binary(&SubscriberMsg::AuctionCreated {
+                auction: child.clone(),
+                info: SaleInfo {
+                    name: pending.info.name.clone(),
+                    end_block: pending.info.end_block,
+                    metadata: pending.info.metadata.clone()
+                }
             })?;
 
-            Ok(Response::default())
+            let mut resp = Response::default();
+            for subscriber in subscribers().iter(deps.storage)? {
+                let subscriber = subscriber?.humanize(deps.api)?;
+
+                resp = resp.add_submessage(SubMsg::reply_always(
+                    WasmMsg::Execute {
+                        contract_addr: subscriber.address.into_string(),
+                        code_hash: subscriber.code_hash,
+                        msg: callback.clone(),
+                        funds: vec![]
+                    },
+                    SUBSCRIBER_REPLY_ID
+                ));
+            }
+
+            let index = register(
+                deps.storage,
+                child.canonize(deps.api)?,
+                pending.info,
+                &pending.origin.tags
+            )?;
+
+            ORIGINS.save(deps.storage, &index, &pending.origin)?;
+
+            Ok(resp.add_event(event))
         }
 
+        /// Auctions which haven't been archived, in the order in which they were
+        /// created, apart from those which took the place of an archived one.
         #[query]
         pub fn list_auctions(
             pagination: Pagination
-        ) -> Result<PaginatedResponse<AuctionEntry<Addr>>, StdError> {
-            let limit = pagination.limit.min(Pagination::LIMIT);
+        ) -> Result<PaginatedResponse<AuctionEntry<Addr>>, ContractError> {
+            list_indices(deps, &listed(), pagination)
+        }
+
+        /// Auctions which were archived once they were settled or
+        /// cancelled, in the order in which they were archived.
+        #[query]
+        pub fn list_archive(
+            pagination: Pagination
+        ) -> Result<PaginatedResponse<AuctionEntry<Addr>>, ContractError> {
+            list_indices(deps, &archive(), pagination)
+        }
+
+        /// Auctions which are still running and end before the given block,
+        /// ordered by their end block. Auctions report changes to their end block
+        /// to the factory, apart from those of the first release.
+        #[query]
+        pub fn list_ending_soon(
+            before_block: u64,
+            pagination: Pagination
+        ) -> Result<PaginatedResponse<AuctionEntry<Addr>>, ContractError> {
+            let start = lower_bound(deps.storage, env.block.height)?;
+            let end = lower_bound(deps.storage, before_block)?.max(start);
+
+            let auctions = auctions();
+            let by_end_block = by_end_block();
+            let entries = (start..end)
+                .skip(pagination.start as usize)
+                .take(pagination.limit() as usize)
+                .map(|i| {
+                    let entry = by_end_block.get_or_error(deps.storage, i)?;
+
+                    auctions.get_or_error(deps.storage, entry.index)?.humanize(deps.api)
+                })
+                .collect::<StdResult<Vec<AuctionEntry<Addr>>>>()?;
+
+            Ok(PaginatedResponse {
+                total: end - start,
+                entries
+            })
+        }
+
+        /// Auctions which have ended but aren't known to be settled. Those that
+        /// `process_ended` skipped come first, followed by those it has yet to reach
+        /// in the order of their end block. The latter are counted in `total` until
+        /// `process_ended` reaches them even if they were settled out of that order,
+        /// so a page can have fewer entries than requested. Cancelled auctions are
+        /// only known not to need settling once `process_ended` has reached them.
+        #[query]
+        pub fn list_unsettled(
+            pagination: Pagination
+        ) -> Result<PaginatedResponse<AuctionEntry<Addr>>, ContractError> {
+            let deferred = deferred();
+            let skipped = deferred.len(deps.storage)?;
+
+            let cursor = PROCESS_CURSOR.load_or_default(deps.storage)?;
+            let end = lower_bound(deps.storage, env.block.height)?.max(cursor);
+            let total = skipped + (end - cursor);
+
+            let auctions = auctions();
+            let by_end_block = by_end_block();
+            let mut entries = vec![];
+
+            for i in (0..total).skip(pagination.start as usize).take(pagination.limit() as usize) {
+                let index = if i < skipped {
+                    deferred.get_or_error(deps.storage, i)?
+                } else {
+                    by_end_block.get_or_error(deps.storage, cursor + (i - skipped))?.index
+                };
+
+                // Settled through the auction itself.
+                if LISTED_POSITIONS.load(deps.storage, &index)?.is_none() {
+                    continue;
+                }
+
+                entries.push(auctions.get_or_error(deps.storage, index)?.humanize(deps.api)?);
+            }
+
+            Ok(PaginatedResponse { total, entries })
+        }
+
+        /// The whole registry, in the order in which the auctions were
+        /// created, e.g. to back it up. Requires a permit of the admin.
+        #[query]
+        pub fn export_registry(
+            permit: Permit<FactoryPermission>,
+            pagination: Pagination
+        ) -> Result<PaginatedResponse<RegistryEntry>, ContractError> {
+            let address = permit.validate(
+                deps,
+                env.contract.address.as_str(),
+                None,
+                &[FactoryPermission::ExportRegistry]
+            )?;
+            let address = address.as_str().canonize(deps.api)?;
+
+            if admin::STORE.load(deps.storage)? != Some(address) {
+                return Err(AuthError::Unauthorized.into());
+            }
 
             let auctions = auctions();
             let iterator = auctions
                 .iter(deps.storage)?
+                .enumerate()
                 .skip(pagination.start as usize)
-                .take(limit as usize);
+                .take(pagination.limit() as usize);
 
             Ok(PaginatedResponse {
                 total: auctions.len(deps.storage)?,
-                entries: iterator.into_iter()
-                    .map(|x| x?.humanize(deps.api))
+                entries: iterator
+                    .map(|(index, entry)| {
+                        let entry = entry?.humanize(deps.api)?;
+                        let index = index as u64;
+
+                        Ok(RegistryEntry {
+                            index,
+                            contract: entry.contract,
+                            info: entry.info,
+                            origin: ORIGINS.load(deps.storage, &index)?
+                                .map(|x| x.humanize(deps.api))
+                                .transpose()?
+                        })
+                    })
+                    .collect::<StdResult<Vec<RegistryEntry>>>()?
+            })
+        }
+
+        /// The code that new auctions are instantiated from.
+        #[query]
+        pub fn auction_code() -> Result<ContractCode, ContractError> {
+            Ok(AUCTION_CONTRACT.load_or_error(deps.storage)?)
+        }
+
+        /// The name and release of the code that the factory is running.
+        #[query]
+        pub fn version() -> Result<ContractVersion, ContractError> {
+            Ok(version::load(deps.storage)?
+                .ok_or_else(|| StdError::generic_err("Contract version not recorded."))?)
+        }
+
+        /// Whether the auction at the given index in the registry has been closed.
+        #[query]
+        pub fn is_closed(index: u64) -> Result<bool, ContractError> {
+            Ok(CLOSED.load(deps.storage, &index)?.unwrap_or_default())
+        }
+
+        /// The settled sales in the given denom with the highest prices, highest
+        /// first. The denom is the native one or the address of the bid token.
+        /// Archived auctions are included.
+        #[query]
+        pub fn top_sales(denom: String, limit: u8) -> Result<Vec<TopSale>, ContractError> {
+            if limit == 0 || limit > MAX_TOP_SALES {
+                return Err(StdError::generic_err(format!(
+                    "Limit must be between 1 and {}.",
+                    MAX_TOP_SALES
+                )).into());
+            }
+
+            let auctions = auctions();
+
+            TOP_SALES.load(deps.storage, &denom)?
+                .unwrap_or_default()
+                .into_iter()
+                .take(limit as usize)
+                .map(|x| Ok(TopSale {
+                    index: x.index,
+                    auction: auctions.get_or_error(deps.storage, x.index)?.humanize(deps.api)?,
+                    price: x.price
+                }))
+                .collect()
+        }
+
+        #[query]
+        pub fn volume_stats() -> Result<VolumeStats, ContractError> {
+            Ok(VOLUME_STATS.load_or_default(deps.storage)?)
+        }
+
+        #[query]
+        pub fn currency_policy() -> Result<Option<CurrencyPolicy<Addr>>, ContractError> {
+            Ok(CURRENCY_POLICY.load_humanize(deps)?)
+        }
+
+        #[query]
+        pub fn fee_config() -> Result<Option<FeeConfig<Addr>>, ContractError> {
+            Ok(FEE_CONFIG.load_humanize(deps)?)
+        }
+
+        #[query]
+        pub fn keeper_reward() -> Result<Option<Coin>, ContractError> {
+            Ok(KEEPER_REWARD.load(deps.storage)?)
+        }
+
+        #[query]
+        pub fn default_auction_admin() -> Result<Option<Addr>, ContractError> {
+            Ok(DEFAULT_AUCTION_ADMIN.load_humanize(deps)?)
+        }
+
+        #[query]
+        pub fn creator_limit() -> Result<Option<u64>, ContractError> {
+            Ok(CREATOR_LIMIT.load(deps.storage)?)
+        }
+
+        /// How many auctions of the creator count against the creator limit.
+        #[query]
+        pub fn active_auctions(creator: String) -> Result<u64, ContractError> {
+            let creator = creator.as_str().canonize(deps.api)?;
+
+            Ok(ACTIVE_COUNTS.load(deps.storage, &creator)?.unwrap_or_default())
+        }
+
+        #[query]
+        pub fn subscribers() -> Result<Vec<ContractLink<Addr>>, ContractError> {
+            subscribers()
+                .iter(deps.storage)?
+                .map(|x| Ok(x?.humanize(deps.api)?))
+                .collect()
+        }
+
+        #[query]
+        pub fn list_auctions_by_tag(
+            tag: String,
+            pagination: Pagination
+        ) -> Result<PaginatedResponse<AuctionEntry<Addr>>, ContractError> {
+            let auctions = auctions();
+            let tagged = tagged(&tag);
+            let iterator = tagged
+                .iter(deps.storage)?
+                .skip(pagination.start as usize)
+                .take(pagination.limit() as usize);
+
+            Ok(PaginatedResponse {
+                total: tagged.len(deps.storage)?,
+                entries: iterator
+                    .map(|index| auctions.get_or_error(deps.storage, index?)?.humanize(deps.api))
                     .collect::<StdResult<Vec<AuctionEntry<Addr>>>>()?
             })
         }
     }
 
+    /// Brings the storage of a deployed factory up to the current layout,
+    /// as detected from the layout version that it stores. The first release
+    /// had no admin, so the one given with the message is set if there is none.
+    pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+        let from = layout::migrate(deps.storage, Some(convert_legacy))?;
+        let release = version::migrate(deps.storage, version::FACTORY_CONTRACT_NAME)?;
+
+        if let Some(admin) = msg.admin {
+            if admin::STORE.load(deps.storage)?.is_none() {
+                admin::STORE.save(deps.storage, &admin.as_str().canonize(deps.api)?)?;
+            }
+        }
+
+        let mut resp = Response::default()
+            .add_attribute(events::ACTION, actions::MIGRATE)
+            .add_attribute(events::LAYOUT_VERSION, from.to_string());
+
+        if let Some(release) = release {
+            resp = resp.add_attribute(events::CONTRACT_VERSION, release.version);
+        }
+
+        Ok(resp)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    mod wasm_migrate {
+        use super::cosmwasm_std::do_migrate;
+
+        #[no_mangle]
+        extern "C" fn migrate(env_ptr: u32, msg_ptr: u32) -> u32 {
+            do_migrate(&super::migrate, env_ptr, msg_ptr)
+        }
+    }
+
+    // Validates the config and records a pending entry
+    // for the auction that the returned message instantiates.
+    fn instantiate_auction(
+        deps: DepsMut,
+        env: &Env,
+        auction: &ContractCode,
+        mut config: Config,
+        tags: Vec<String>,
+        creator: CanonicalAddr,
+        funds: Vec<Coin>
+    ) -> Result<SubMsg, ContractError> {
+        config.assert_supported()?;
+        SaleInfo::validate_name(&config.name)?;
+
+        if let Some(metadata) = &config.metadata {
+            SaleInfo::validate_metadata(metadata)?;
+        }
+
+        let active = ACTIVE_COUNTS.load(deps.storage, &creator)?.unwrap_or_default();
+        if let Some(limit) = CREATOR_LIMIT.load(deps.storage)? {
+            if active >= limit {
+                return Err(LifecycleError::TooManyActive(limit).into());
+            }
+        }
+
+        ACTIVE_COUNTS.save(deps.storage, &creator, &(active + 1))?;
+
+        let reply_id = NEXT_REPLY_ID.load_or_default(deps.storage)?;
+        NEXT_REPLY_ID.save(deps.storage, &(reply_id + 1))?;
+
+        PENDING.save(
+            deps.storage,
+            &reply_id,
+            &PendingAuction {
+                code_hash: auction.code_hash.clone(),
+                info: SaleInfo {
+                    name: config.name.clone(),
+                    end_block: config.end_block,
+                    metadata: config.metadata.clone()
+                },
+                origin: AuctionOrigin {
+                    creator,
+                    config: to_binary(&config)?,
+                    tags
+                },
+                funds: funds.clone()
+            }
+        )?;
+
+        // The origin keeps the config as given so that clones get the current defaults.
+        apply_currency_policy(deps.as_ref(), &mut config)?;
+
+        if config.admin.is_none() {
+            config.admin = DEFAULT_AUCTION_ADMIN.load_humanize(deps.as_ref())?.map(Addr::into_string);
+        }
+
+        config.factory = Some(ContractLink {
+            address: env.contract.address.to_string(),
+            code_hash: env.contract.code_hash.clone()
+        });
+
+        let label = format!(
+            "Auction: {}, started at: {}, ending at {}",
+            config.name,
+            env.block.height,
+            config.end_block
+        );
+
+        Ok(SubMsg::reply_always(
+            WasmMsg::Instantiate {
+                code_id: auction.id,
+                code_hash: auction.code_hash.clone(),
+                msg: to_binary(&AuctionInitMsg { config })?,
+                funds,
+                label
+            },
+            reply_id
+        ))
+    }
+
+    // Sets the currency that the auction will take bids in, as the policy allows.
+    fn apply_currency_policy(deps: Deps, config: &mut Config) -> Result<(), ContractError> {
+        let Some(policy) = CURRENCY_POLICY.load(deps.storage)? else {
+            return Ok(());
+        };
+
+        if let Some(token) = &mut config.bid_token {
+            let address = token.address.as_str().canonize(deps.api)?;
+            let Some(allowed) = policy.tokens.iter().find(|x| x.address == address) else {
+                return Err(CurrencyError::TokenNotAllowed(token.address.clone()).into());
+            };
+
+            token.code_hash = allowed.code_hash.clone();
+
+            return Ok(());
+        }
+
+        let denom = match &config.denom {
+            Some(denom) => denom.clone(),
+            None => policy.denoms.first().cloned().ok_or(CurrencyError::TokenRequired)?
+        };
+
+        if !policy.denoms.contains(&denom) {
+            return Err(CurrencyError::DenomNotAllowed(denom.as_str().into()).into());
+        }
+
+        config.denom = Some(denom);
+
+        Ok(())
+    }
+
+    // Adds the auction to the registry and lists it.
+    fn register(
+        storage: &mut dyn Storage,
+        contract: ContractLink<CanonicalAddr>,
+        info: SaleInfo,
+        tags: &[String]
+    ) -> StdResult<u64> {
+        let end_block = info.end_block;
+        let address = contract.address.clone();
+        let index = auctions().push(storage, &AuctionEntry { contract, info })?;
+
+        INDICES.save(storage, &address, &index)?;
+        insert_by_end_block(storage, EndingEntry { end_block, index })?;
+
+        let position = listed().push(storage, &index)?;
+        LISTED_POSITIONS.save(storage, &index, &position)?;
+
+        for tag in tags {
+            tagged(tag).push(storage, &index)?;
+        }
+
+        Ok(index)
+    }
+
+    // Moves the registry of the first release to the current layout and builds the
+    // indexes that it didn't have. It didn't record who created the auctions or with
+    // what config, so like those added by register_existing, they can't be cloned
+    // and don't count against the creator limit.
+    fn convert_legacy(storage: &mut dyn Storage) -> StdResult<()> {
+        if let Some(auction) = LEGACY_CONTRACT.load(storage)? {
+            AUCTION_CONTRACT.save(storage, &auction)?;
+            LEGACY_CONTRACT.remove(storage);
+        }
+
+        let mut legacy = legacy_auctions();
+        let entries = legacy.iter(storage)?.collect::<StdResult<Vec<_>>>()?;
+        let mut ending = Vec::with_capacity(entries.len());
+
+        for entry in &entries {
+            let end_block = entry.info.end_block;
+            let index = auctions().push(storage, &AuctionEntry {
+                contract: entry.contract.clone(),
+                info: SaleInfo {
+                    name: entry.info.name.clone(),
+                    end_block,
+                    metadata: None
+                }
+            })?;
+
+            INDICES.save(storage, &entry.contract.address, &index)?;
+
+            let position = listed().push(storage, &index)?;
+            LISTED_POSITIONS.save(storage, &index, &position)?;
+
+            ending.push(EndingEntry { end_block, index });
+        }
+
+        // Pushed in order so that none of them have to be moved. The sort is
+        // stable, so those ending at the same block stay in the order of creation.
+        ending.sort_by_key(|x| x.end_block);
+
+        let mut by_end_block = by_end_block();
+        for entry in &ending {
+            by_end_block.push(storage, entry)?;
+        }
+
+        for _ in &entries {
+            legacy.pop(storage)?;
+        }
+
+        Ok(())
+    }
+
+    // Records that the auction has been settled or cancelled, frees up the
+    // slot of its creator and moves it to the archive. Only the first call
+    // for an auction does the latter two since it is no longer listed after.
+    fn finish(storage: &mut dyn Storage, height: u64, index: u64, settled: bool) -> StdResult<()> {
+        if settled {
+            SETTLED.save(storage, &index, &true)?;
+        }
+
+        let Some(position) = LISTED_POSITIONS.load(storage, &index)? else {
+            return Ok(());
+        };
+
+        undefer(storage, index)?;
+
+        // Auctions created before origins were kept were never counted.
+        if let Some(origin) = ORIGINS.load(storage, &index)? {
+            decrement_active(storage, &origin.creator)?;
+        }
+
+        // The last listed auction takes the place of the archived one.
+        if let Some(moved) = listed().swap_remove(storage, position)? {
+            LISTED_POSITIONS.save(storage, &moved, &position)?;
+        }
+
+        LISTED_POSITIONS.remove(storage, &index);
+        archive().push(storage, &index)?;
+
+        skip_finished(storage, height)
+    }
+
+    // Moves PROCESS_CURSOR past the ended auctions at its front which are
+    // already archived, so that list_unsettled doesn't count them anymore.
+    fn skip_finished(storage: &mut dyn Storage, height: u64) -> StdResult<()> {
+        let by_end_block = by_end_block();
+        let end = lower_bound(storage, height)?;
+        let mut cursor = PROCESS_CURSOR.load_or_default(storage)?;
+
+        while cursor < end {
+            let index = by_end_block.get_or_error(storage, cursor)?.index;
+            if LISTED_POSITIONS.load(storage, &index)?.is_some() {
+                break;
+            }
+
+            cursor += 1;
+        }
+
+        PROCESS_CURSOR.save(storage, &cursor)
+    }
+
+    // Remembers an auction that process_ended went past without settling it.
+    fn defer(storage: &mut dyn Storage, index: u64) -> StdResult<()> {
+        if DEFERRED_POSITIONS.load(storage, &index)?.is_some() {
+            return Ok(());
+        }
+
+        let position = deferred().push(storage, &index)?;
+        DEFERRED_POSITIONS.save(storage, &index, &position)
+    }
+
+    fn undefer(storage: &mut dyn Storage, index: u64) -> StdResult<()> {
+        let Some(position) = DEFERRED_POSITIONS.load(storage, &index)? else {
+            return Ok(());
+        };
+
+        // The last deferred auction takes the place of the removed one.
+        if let Some(moved) = deferred().swap_remove(storage, position)? {
+            DEFERRED_POSITIONS.save(storage, &moved, &position)?;
+        }
+
+        DEFERRED_POSITIONS.remove(storage, &index);
+
+        Ok(())
+    }
+
+    fn list_indices(
+        deps: Deps,
+        indices: &IterableStorage<u64, StaticKey>,
+        pagination: Pagination
+    ) -> Result<PaginatedResponse<AuctionEntry<Addr>>, ContractError> {
+        let auctions = auctions();
+        let iterator = indices
+            .iter(deps.storage)?
+            .skip(pagination.start as usize)
+            .take(pagination.limit() as usize);
+
+        Ok(PaginatedResponse {
+            total: indices.len(deps.storage)?,
+            entries: iterator
+                .map(|index| auctions.get_or_error(deps.storage, index?)?.humanize(deps.api))
+                .collect::<StdResult<Vec<AuctionEntry<Addr>>>>()?
+        })
+    }
+
+    fn decrement_active(storage: &mut dyn Storage, creator: &CanonicalAddr) -> StdResult<()> {
+        let active = ACTIVE_COUNTS.load(storage, creator)?.unwrap_or_default();
+
+        if active <= 1 {
+            ACTIVE_COUNTS.remove(storage, creator);
+
+            Ok(())
+        } else {
+            ACTIVE_COUNTS.save(storage, creator, &(active - 1))
+        }
+    }
+
+    // Every auction that was created, so that indices never change.
+    // Only those in listed() are returned by list_auctions.
     #[inline]
     fn auctions() -> IterableStorage<
         AuctionEntry<CanonicalAddr>,
         StaticKey
     > {
+        IterableStorage::new(StaticKey(versioned_key!(b"auctions")))
+    }
+
+    // The registry of the first release.
+    #[inline]
+    fn legacy_auctions() -> IterableStorage<LegacyAuctionEntry, StaticKey> {
         IterableStorage::new(StaticKey(b"auctions"))
     }
+
+    // Indices into auctions() of the auctions which haven't been archived.
+    #[inline]
+    fn listed() -> IterableStorage<u64, StaticKey> {
+        IterableStorage::new(StaticKey(versioned_key!(b"listed")))
+    }
+
+    // Indices into auctions() of the auctions which process_ended went past
+    // without settling them and that haven't been archived since.
+    #[inline]
+    fn deferred() -> IterableStorage<u64, StaticKey> {
+        IterableStorage::new(StaticKey(versioned_key!(b"deferred")))
+    }
+
+    // Indices into auctions() of the archived auctions.
+    #[inline]
+    fn archive() -> IterableStorage<u64, StaticKey> {
+        IterableStorage::new(StaticKey(versioned_key!(b"archive")))
+    }
+
+    #[inline]
+    fn subscribers() -> IterableStorage<ContractLink<CanonicalAddr>, StaticKey> {
+        IterableStorage::new(StaticKey(versioned_key!(b"subscribers")))
+    }
+
+    #[inline]
+    fn by_end_block() -> IterableStorage<EndingEntry, StaticKey> {
+        IterableStorage::new(StaticKey(versioned_key!(b"by_end_block")))
+    }
+
+    // Auctions are usually created in order of their end block
+    // so new entries rarely have to move far from the back.
+    fn insert_by_end_block(storage: &mut dyn Storage, entry: EndingEntry) -> StdResult<()> {
+        let mut by_end_block = by_end_block();
+        let mut pos = by_end_block.push(storage, &entry)?;
+
+        while pos > 0 {
+            let prev = by_end_block.get_or_error(storage, pos - 1)?;
+            if prev.end_block <= entry.end_block {
+                break;
+            }
+
+            by_end_block.set(storage, pos, &prev)?;
+            pos -= 1;
+        }
+
+        by_end_block.set(storage, pos, &entry)
+    }
+
+    // Moves the auction to its place for the new end block, shifting those in between by
+    // one. If it ends up ahead of PROCESS_CURSOR it is no longer deferred, since
+    // process_ended will reach it again, and if it ends up behind it, it is deferred.
+    fn move_by_end_block(
+        storage: &mut dyn Storage,
+        index: u64,
+        from_block: u64,
+        to_block: u64
+    ) -> StdResult<()> {
+        let mut by_end_block = by_end_block();
+        let len = by_end_block.len(storage)?;
+
+        let mut pos = lower_bound(storage, from_block)?;
+        while by_end_block.get_or_error(storage, pos)?.index != index {
+            pos += 1;
+        }
+
+        let mut cursor = PROCESS_CURSOR.load_or_default(storage)?;
+        if pos < cursor {
+            cursor -= 1;
+        }
+
+        while pos + 1 < len {
+            let next = by_end_block.get_or_error(storage, pos + 1)?;
+            if next.end_block > to_block {
+                break;
+            }
+
+            by_end_block.set(storage, pos, &next)?;
+            pos += 1;
+        }
+
+        while pos > 0 {
+            let prev = by_end_block.get_or_error(storage, pos - 1)?;
+            if prev.end_block <= to_block {
+                break;
+            }
+
+            by_end_block.set(storage, pos, &prev)?;
+            pos -= 1;
+        }
+
+        by_end_block.set(storage, pos, &EndingEntry { end_block: to_block, index })?;
+
+        if pos < cursor {
+            cursor += 1;
+            defer(storage, index)?;
+        } else {
+            undefer(storage, index)?;
+        }
+
+        PROCESS_CURSOR.save(storage, &cursor)
+    }
+
+    // The position of the first auction in by_end_block() that ends at or after the block.
+    fn lower_bound(storage: &dyn Storage, block: u64) -> StdResult<u64> {
+        let by_end_block = by_end_block();
+
+        let mut low = 0;
+        let mut high = by_end_block.len(storage)?;
+
+        while low < high {
+            let mid = low + (high - low) / 2;
+
+            if by_end_block.get_or_error(storage, mid)?.end_block < block {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+
+        Ok(low)
+    }
+
+    // Indexes into auctions() of the auctions listed under the tag.
+    #[inline]
+    fn tagged(tag: &str) -> IterableStorage<u64, FixedSegmentSizeKey<'_, 2>> {
+        IterableStorage::new(FixedSegmentSizeKey::new([versioned_key!(b"tagged"), tag.as_bytes()]))
+    }
+
+    #[auto_impl(admin::DefaultImpl)]
+    impl Admin for Contract {
+        #[execute]
+        fn change_admin(mode: Option<Mode>) -> Result<Response, Self::Error> { }
+
+        #[query]
+        fn admin() -> Result<Option<Addr>, Self::Error> { }
+    }
 }
@@ -0,0 +1,299 @@
+//! Builders for the messages of the auction, so that callers only have
+//! to set the parameters that they care about. Anything that isn't set
+//! keeps the default that the contract applies to a missing value.
+
+use fadroma::cosmwasm_std::{StdError, Uint128, Decimal, Binary};
+
+use crate::{
+    CoSeller, CoSellers, Config, Denom, ExecuteMsg, ExtensionVoteConfig, InstantiateMsg,
+    Pagination, QueryMsg, RaffleConfig, snip20::BidToken, snip721::ReceiptNft, oracle::PriceOracle
+};
+#[cfg(feature = "vk")]
+use crate::keys;
+
+/// Builds the message that auctions are instantiated with.
+/// The name and the end block are required.
+pub struct AuctionInit;
+
+impl AuctionInit {
+    pub fn builder() -> AuctionInitBuilder {
+        AuctionInitBuilder {
+            name: None,
+            end_block: None,
+            config: Config::new(String::new(), 0)
+        }
+    }
+}
+
+pub struct AuctionInitBuilder {
+    name: Option<String>,
+    end_block: Option<u64>,
+    // Holds every other parameter. The name and the end block are
+    // kept separately because they are the only required ones.
+    config: Config
+}
+
+impl AuctionInitBuilder {
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn end_block(mut self, end_block: u64) -> Self {
+        self.end_block = Some(end_block);
+        self
+    }
+
+    pub fn end_time(mut self, end_time: u64) -> Self {
+        self.config.end_time = Some(end_time);
+        self
+    }
+
+    pub fn admin(mut self, admin: impl Into<String>) -> Self {
+        self.config.admin = Some(admin.into());
+        self
+    }
+
+    pub fn start_block(mut self, start_block: u64) -> Self {
+        self.config.start_block = Some(start_block);
+        self
+    }
+
+    pub fn metadata(mut self, metadata: impl Into<String>) -> Self {
+        self.config.metadata = Some(metadata.into());
+        self
+    }
+
+    pub fn guard_queries(mut self, guard_queries: bool) -> Self {
+        self.config.guard_queries = Some(guard_queries);
+        self
+    }
+
+    pub fn denom(mut self, denom: Denom) -> Self {
+        self.config.denom = Some(denom);
+        self
+    }
+
+    pub fn reserve(mut self, reserve: impl Into<Uint128>) -> Self {
+        self.config.reserve = Some(reserve.into());
+        self
+    }
+
+    pub fn min_increment(mut self, min_increment: impl Into<Uint128>) -> Self {
+        self.config.min_increment = Some(min_increment.into());
+        self
+    }
+
+    pub fn beneficiary(mut self, beneficiary: impl Into<String>) -> Self {
+        self.config.beneficiary = Some(beneficiary.into());
+        self
+    }
+
+    pub fn receipt_nft(mut self, receipt_nft: ReceiptNft) -> Self {
+        self.config.receipt_nft = Some(receipt_nft);
+        self
+    }
+
+    pub fn tradable_claims(mut self, tradable_claims: bool) -> Self {
+        self.config.tradable_claims = Some(tradable_claims);
+        self
+    }
+
+    pub fn raffle(mut self, raffle: RaffleConfig) -> Self {
+        self.config.raffle = Some(raffle);
+        self
+    }
+
+    pub fn manual_winner(mut self, manual_winner: bool) -> Self {
+        self.config.manual_winner = Some(manual_winner);
+        self
+    }
+
+    pub fn dispute_blocks(mut self, dispute_blocks: u64) -> Self {
+        self.config.dispute_blocks = Some(dispute_blocks);
+        self
+    }
+
+    pub fn arbiter(mut self, arbiter: impl Into<String>) -> Self {
+        self.config.arbiter = Some(arbiter.into());
+        self
+    }
+
+    pub fn early_retraction_penalty(mut self, bps: u16) -> Self {
+        self.config.early_retraction_penalty = Some(bps);
+        self
+    }
+
+    pub fn bid_cooldown(mut self, blocks: u64) -> Self {
+        self.config.bid_cooldown = Some(blocks);
+        self
+    }
+
+    pub fn oracle(mut self, oracle: PriceOracle) -> Self {
+        self.config.oracle = Some(oracle);
+        self
+    }
+
+    pub fn usd_reserve(mut self, usd_reserve: Decimal) -> Self {
+        self.config.usd_reserve = Some(usd_reserve);
+        self
+    }
+
+    pub fn usd_min_increment(mut self, usd_min_increment: Decimal) -> Self {
+        self.config.usd_min_increment = Some(usd_min_increment);
+        self
+    }
+
+    pub fn winner_payload(mut self, winner_payload: impl Into<Binary>) -> Self {
+        self.config.winner_payload = Some(winner_payload.into());
+        self
+    }
+
+    pub fn show_highest_bidder(mut self, show_highest_bidder: bool) -> Self {
+        self.config.show_highest_bidder = Some(show_highest_bidder);
+        self
+    }
+
+    pub fn hide_current_highest(mut self, hide_current_highest: bool) -> Self {
+        self.config.hide_current_highest = Some(hide_current_highest);
+        self
+    }
+
+    pub fn public_active_bids(mut self, public_active_bids: bool) -> Self {
+        self.config.public_active_bids = Some(public_active_bids);
+        self
+    }
+
+    pub fn extension_vote(mut self, threshold: u16, max_blocks: u64) -> Self {
+        self.config.extension_vote = Some(ExtensionVoteConfig { threshold, max_blocks });
+        self
+    }
+
+    pub fn exclude_contracts(mut self, exclude_contracts: bool) -> Self {
+        self.config.exclude_contracts = Some(exclude_contracts);
+        self
+    }
+
+    pub fn bid_token(mut self, bid_token: BidToken) -> Self {
+        self.config.bid_token = Some(bid_token);
+        self
+    }
+
+    pub fn seller_deposit(mut self, seller_deposit: impl Into<Uint128>) -> Self {
+        self.config.seller_deposit = Some(seller_deposit.into());
+        self
+    }
+
+    /// The shares are in basis points.
+    pub fn co_sellers<'a>(mut self, threshold: u16, sellers: impl IntoIterator<Item = (&'a str, u16)>) -> Self {
+        self.config.co_sellers = Some(CoSellers {
+            sellers: sellers.into_iter()
+                .map(|(address, share)| CoSeller { address: address.into(), share })
+                .collect(),
+            threshold
+        });
+        self
+    }
+
+    /// Returns only the config, e.g. to pass it to the factory.
+    pub fn config(self) -> Result<Config, StdError> {
+        let Some(name) = self.name else {
+            return Err(StdError::generic_err("Auction name must be set."));
+        };
+
+        let Some(end_block) = self.end_block else {
+            return Err(StdError::generic_err("End block must be set."));
+        };
+
+        Ok(Config {
+            name,
+            end_block,
+            ..self.config
+        })
+    }
+
+    pub fn build(self) -> Result<InstantiateMsg, StdError> {
+        Ok(InstantiateMsg { config: self.config()? })
+    }
+}
+
+/// Builds an [`ExecuteMsg::UpdateConfig`] which leaves
+/// any parameters that aren't set unchanged.
+#[derive(Default)]
+pub struct ConfigUpdate {
+    name: Option<String>,
+    metadata: Option<String>,
+    reserve: Option<Uint128>,
+    end_block: Option<u64>
+}
+
+impl ConfigUpdate {
+    pub fn builder() -> Self {
+        Self::default()
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn metadata(mut self, metadata: impl Into<String>) -> Self {
+        self.metadata = Some(metadata.into());
+        self
+    }
+
+    pub fn reserve(mut self, reserve: impl Into<Uint128>) -> Self {
+        self.reserve = Some(reserve.into());
+        self
+    }
+
+    pub fn end_block(mut self, end_block: u64) -> Self {
+        self.end_block = Some(end_block);
+        self
+    }
+
+    pub fn build(self) -> ExecuteMsg {
+        ExecuteMsg::UpdateConfig {
+            name: self.name,
+            metadata: self.metadata,
+            reserve: self.reserve,
+            end_block: self.end_block
+        }
+    }
+}
+
+impl ExecuteMsg {
+    pub fn transfer_claim(claim_id: u64, recipient: impl Into<String>) -> Self {
+        Self::TransferClaim { claim_id, recipient: recipient.into() }
+    }
+
+    pub fn set_auditor(address: impl Into<String>) -> Self {
+        Self::SetAuditor { address: Some(address.into()) }
+    }
+
+    pub fn clear_auditor() -> Self {
+        Self::SetAuditor { address: None }
+    }
+}
+
+impl QueryMsg {
+    pub fn active_bids(start: u64, limit: u8) -> Self {
+        Self::ActiveBids { pagination: Pagination { start, limit } }
+    }
+}
+
+#[cfg(feature = "vk")]
+impl keys::QueryMsg {
+    pub fn view_bid(address: impl Into<String>, key: impl Into<String>) -> Self {
+        Self::ViewBid { address: address.into(), key: key.into() }
+    }
+
+    /// Views the claim that originates from the bid of the address.
+    pub fn view_claim(address: impl Into<String>, key: impl Into<String>) -> Self {
+        Self::ViewClaim { address: address.into(), key: key.into(), claim_id: None }
+    }
+
+    pub fn audit_bids(key: impl Into<String>, start: u64, limit: u8) -> Self {
+        Self::AuditBids { key: key.into(), pagination: Pagination { start, limit } }
+    }
+}
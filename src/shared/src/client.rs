@@ -0,0 +1,550 @@
+//! Typed wrappers around the messages of the auction and the factory, for
+//! contracts that interact with them. Executes return a [`WasmMsg`] to be
+//! added to a response and queries return a [`QueryRequest`] to be passed
+//! to the querier together with the type of the response.
+
+use fadroma::{
+    core::ContractLink,
+    admin::{self, Mode},
+    killswitch::{self, ContractStatus},
+    scrt::permit::Permit,
+    cosmwasm_std::{
+        Addr, Coin, Empty, QueryRequest, StdResult, Uint128, WasmMsg, WasmQuery, to_binary
+    }
+};
+use serde::Serialize;
+
+use crate::{
+    AuctionPermission, Config, CurrencyPolicy, SellerAction, ExecuteMsg, FactoryExecuteMsg, FactoryPermission, FactoryQueryMsg,
+    AuctionEnd, FeeConfig, Pagination, QueryMsg, builders::ConfigUpdate
+};
+#[cfg(feature = "vk")]
+use {
+    fadroma::scrt::vk::auth as vk,
+    crate::keys
+};
+
+#[derive(Clone, Debug)]
+pub struct AuctionClient(pub ContractLink<Addr>);
+
+#[derive(Clone, Debug)]
+pub struct FactoryClient(pub ContractLink<Addr>);
+
+fn execute(link: &ContractLink<Addr>, msg: &impl Serialize, funds: Vec<Coin>) -> StdResult<WasmMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: link.address.to_string(),
+        code_hash: link.code_hash.clone(),
+        msg: to_binary(msg)?,
+        funds
+    })
+}
+
+fn query(link: &ContractLink<Addr>, msg: &impl Serialize) -> StdResult<QueryRequest<Empty>> {
+    Ok(QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: link.address.to_string(),
+        code_hash: link.code_hash.clone(),
+        msg: to_binary(msg)?
+    }))
+}
+
+impl AuctionClient {
+    /// The bid is made with the funds sent along with it.
+    pub fn bid(&self, funds: Vec<Coin>) -> StdResult<WasmMsg> {
+        execute(&self.0, &ExecuteMsg::Bid { auto_refund: None, memo: None }, funds)
+    }
+
+    #[cfg(feature = "vk")]
+    pub fn bid_with_key(&self, key: impl Into<String>, funds: Vec<Coin>) -> StdResult<WasmMsg> {
+        execute(&self.0, &keys::ExecuteMsg::BidWithKey { key: key.into(), padding: None }, funds)
+    }
+
+    pub fn bid_for(&self, beneficiary: impl Into<String>, funds: Vec<Coin>) -> StdResult<WasmMsg> {
+        execute(&self.0, &ExecuteMsg::BidFor { beneficiary: beneficiary.into() }, funds)
+    }
+
+    pub fn proxy_bid(&self, funds: Vec<Coin>) -> StdResult<WasmMsg> {
+        execute(&self.0, &ExecuteMsg::ProxyBid { }, funds)
+    }
+
+    pub fn bid_from_allowance(&self, amount: impl Into<Uint128>) -> StdResult<WasmMsg> {
+        execute(&self.0, &ExecuteMsg::BidFromAllowance { amount: amount.into() }, vec![])
+    }
+
+    pub fn set_auto_refund(&self, enabled: bool) -> StdResult<WasmMsg> {
+        execute(&self.0, &ExecuteMsg::SetAutoRefund { enabled }, vec![])
+    }
+
+    pub fn accept_bids_for(&self, accept: bool) -> StdResult<WasmMsg> {
+        execute(&self.0, &ExecuteMsg::AcceptBidsFor { accept }, vec![])
+    }
+
+    pub fn retract_bid(&self) -> StdResult<WasmMsg> {
+        execute(&self.0, &ExecuteMsg::RetractBid { }, vec![])
+    }
+
+    pub fn claim_proceeds(&self, amount: Option<Uint128>) -> StdResult<WasmMsg> {
+        execute(&self.0, &ExecuteMsg::ClaimProceeds { amount }, vec![])
+    }
+
+    pub fn claim(&self) -> StdResult<WasmMsg> {
+        execute(&self.0, &ExecuteMsg::Claim { }, vec![])
+    }
+
+    pub fn settle(&self) -> StdResult<WasmMsg> {
+        execute(&self.0, &ExecuteMsg::Settle { }, vec![])
+    }
+
+    pub fn propose_extension(&self, blocks: u64) -> StdResult<WasmMsg> {
+        execute(&self.0, &ExecuteMsg::ProposeExtension { blocks }, vec![])
+    }
+
+    pub fn vote_extension(&self, proposal: u64) -> StdResult<WasmMsg> {
+        execute(&self.0, &ExecuteMsg::VoteExtension { proposal }, vec![])
+    }
+
+    pub fn update_config(&self, update: ConfigUpdate) -> StdResult<WasmMsg> {
+        execute(&self.0, &update.build(), vec![])
+    }
+
+    pub fn set_auditor(&self, address: Option<String>) -> StdResult<WasmMsg> {
+        execute(&self.0, &ExecuteMsg::SetAuditor { address }, vec![])
+    }
+
+    pub fn set_pauser(&self, address: Option<String>) -> StdResult<WasmMsg> {
+        execute(&self.0, &ExecuteMsg::SetPauser { address }, vec![])
+    }
+
+    pub fn transfer_ownership(&self, new_owner: Option<String>) -> StdResult<WasmMsg> {
+        execute(&self.0, &ExecuteMsg::TransferOwnership { new_owner }, vec![])
+    }
+
+    pub fn accept_ownership(&self) -> StdResult<WasmMsg> {
+        execute(&self.0, &ExecuteMsg::AcceptOwnership { }, vec![])
+    }
+
+    pub fn export_bidders(&self, pagination: Pagination) -> StdResult<WasmMsg> {
+        execute(&self.0, &ExecuteMsg::ExportBidders { pagination }, vec![])
+    }
+
+    #[cfg(feature = "vk")]
+    pub fn revoke_all_keys(&self) -> StdResult<WasmMsg> {
+        execute(&self.0, &keys::ExecuteMsg::RevokeAllKeys { }, vec![])
+    }
+
+    pub fn revoke_permit(&self, name: impl Into<String>) -> StdResult<WasmMsg> {
+        execute(&self.0, &ExecuteMsg::RevokePermit { name: name.into() }, vec![])
+    }
+
+    pub fn transfer_claim(&self, claim_id: u64, recipient: impl Into<String>) -> StdResult<WasmMsg> {
+        execute(&self.0, &ExecuteMsg::transfer_claim(claim_id, recipient), vec![])
+    }
+
+    pub fn select_winner(&self, address: impl Into<String>) -> StdResult<WasmMsg> {
+        execute(&self.0, &ExecuteMsg::SelectWinner { address: address.into() }, vec![])
+    }
+
+    pub fn cancel_sale(&self, reason: impl Into<String>) -> StdResult<WasmMsg> {
+        execute(&self.0, &ExecuteMsg::CancelSale { reason: reason.into() }, vec![])
+    }
+
+    pub fn approve_action(&self, action: SellerAction) -> StdResult<WasmMsg> {
+        execute(&self.0, &ExecuteMsg::ApproveAction { action }, vec![])
+    }
+
+    pub fn redeem_claim(&self, claim_id: u64) -> StdResult<WasmMsg> {
+        execute(&self.0, &ExecuteMsg::RedeemClaim { claim_id }, vec![])
+    }
+
+    pub fn refund_batch(&self, limit: u8) -> StdResult<WasmMsg> {
+        execute(&self.0, &ExecuteMsg::RefundBatch { limit }, vec![])
+    }
+
+    pub fn close(&self) -> StdResult<WasmMsg> {
+        execute(&self.0, &ExecuteMsg::Close { }, vec![])
+    }
+
+    pub fn send_delivery_details(&self, details: impl Into<String>) -> StdResult<WasmMsg> {
+        execute(&self.0, &ExecuteMsg::SendDeliveryDetails { details: details.into() }, vec![])
+    }
+
+    pub fn send_fulfillment_proof(&self, proof: impl Into<String>) -> StdResult<WasmMsg> {
+        execute(&self.0, &ExecuteMsg::SendFulfillmentProof { proof: proof.into() }, vec![])
+    }
+
+    pub fn add_settlement_hook(&self, contract: ContractLink<String>) -> StdResult<WasmMsg> {
+        execute(&self.0, &ExecuteMsg::AddSettlementHook { contract }, vec![])
+    }
+
+    pub fn remove_settlement_hook(&self, address: impl Into<String>) -> StdResult<WasmMsg> {
+        execute(&self.0, &ExecuteMsg::RemoveSettlementHook { address: address.into() }, vec![])
+    }
+
+    #[cfg(feature = "vk")]
+    pub fn create_viewing_key(&self, entropy: impl Into<String>) -> StdResult<WasmMsg> {
+        let msg = vk::ExecuteMsg::CreateViewingKey { entropy: entropy.into(), padding: None };
+
+        execute(&self.0, &msg, vec![])
+    }
+
+    #[cfg(feature = "vk")]
+    pub fn set_viewing_key(&self, key: impl Into<String>) -> StdResult<WasmMsg> {
+        let msg = vk::ExecuteMsg::SetViewingKey { key: key.into(), padding: None };
+
+        execute(&self.0, &msg, vec![])
+    }
+
+    pub fn set_status(&self, status: ContractStatus<Addr>) -> StdResult<WasmMsg> {
+        execute(&self.0, &killswitch::ExecuteMsg::SetStatus { status }, vec![])
+    }
+
+    pub fn change_admin(&self, mode: Option<Mode>) -> StdResult<WasmMsg> {
+        execute(&self.0, &admin::ExecuteMsg::ChangeAdmin { mode }, vec![])
+    }
+
+    #[cfg(feature = "vk")]
+    pub fn view_bid(&self, address: impl Into<String>, key: impl Into<String>) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &keys::QueryMsg::view_bid(address, key))
+    }
+
+    pub fn view_bid_with_permit(&self, permit: Permit<AuctionPermission>) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &QueryMsg::ViewBidWithPermit { permit })
+    }
+
+    pub fn view_bid_of_with_permit(
+        &self,
+        bidder: impl Into<String>,
+        permit: Permit<AuctionPermission>
+    ) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &QueryMsg::ViewBidOfWithPermit { bidder: bidder.into(), permit })
+    }
+
+    #[cfg(feature = "vk")]
+    pub fn min_bid_to_win(&self, address: impl Into<String>, key: impl Into<String>) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &keys::QueryMsg::MinBidToWin { address: address.into(), key: key.into() })
+    }
+
+    pub fn min_bid_to_win_with_permit(&self, permit: Permit<AuctionPermission>) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &QueryMsg::MinBidToWinWithPermit { permit })
+    }
+
+    pub fn simulate_bid(
+        &self,
+        permit: Permit<AuctionPermission>,
+        amount: Uint128
+    ) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &QueryMsg::SimulateBid { permit, amount })
+    }
+
+    #[cfg(feature = "vk")]
+    pub fn view_payout(&self, address: impl Into<String>, key: impl Into<String>) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &keys::QueryMsg::ViewPayout { address: address.into(), key: key.into() })
+    }
+
+    pub fn view_payout_with_permit(&self, permit: Permit<AuctionPermission>) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &QueryMsg::ViewPayoutWithPermit { permit })
+    }
+
+    #[cfg(feature = "vk")]
+    pub fn view_proxy_bid(&self, address: impl Into<String>, key: impl Into<String>) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &keys::QueryMsg::ViewProxyBid { address: address.into(), key: key.into() })
+    }
+
+    pub fn view_proxy_bid_with_permit(&self, permit: Permit<AuctionPermission>) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &QueryMsg::ViewProxyBidWithPermit { permit })
+    }
+
+    #[cfg(feature = "vk")]
+    pub fn my_rank(&self, address: impl Into<String>, key: impl Into<String>) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &keys::QueryMsg::MyRank { address: address.into(), key: key.into() })
+    }
+
+    pub fn my_rank_with_permit(&self, permit: Permit<AuctionPermission>) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &QueryMsg::MyRankWithPermit { permit })
+    }
+
+    #[cfg(feature = "vk")]
+    pub fn winner_payload(&self, address: impl Into<String>, key: impl Into<String>) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &keys::QueryMsg::WinnerPayload { address: address.into(), key: key.into() })
+    }
+
+    pub fn winner_payload_with_permit(&self, permit: Permit<AuctionPermission>) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &QueryMsg::WinnerPayloadWithPermit { permit })
+    }
+
+    #[cfg(feature = "vk")]
+    pub fn view_claim(
+        &self,
+        address: impl Into<String>,
+        key: impl Into<String>,
+        claim_id: Option<u64>
+    ) -> StdResult<QueryRequest<Empty>> {
+        let msg = keys::QueryMsg::ViewClaim { address: address.into(), key: key.into(), claim_id };
+
+        query(&self.0, &msg)
+    }
+
+    pub fn active_bids(&self, pagination: Pagination) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &QueryMsg::ActiveBids { pagination })
+    }
+
+    pub fn active_bids_with_permit(
+        &self,
+        permit: Permit<AuctionPermission>,
+        pagination: Pagination
+    ) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &QueryMsg::ActiveBidsWithPermit { permit, pagination })
+    }
+
+    #[cfg(feature = "vk")]
+    pub fn audit_bids(&self, key: impl Into<String>, pagination: Pagination) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &keys::QueryMsg::AuditBids { key: key.into(), pagination })
+    }
+
+    pub fn audit_bids_with_permit(
+        &self,
+        permit: Permit<AuctionPermission>,
+        pagination: Pagination
+    ) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &QueryMsg::AuditBidsWithPermit { permit, pagination })
+    }
+
+    pub fn auditor(&self) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &QueryMsg::Auditor { })
+    }
+
+    pub fn pauser(&self) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &QueryMsg::Pauser { })
+    }
+
+    pub fn pending_owner(&self) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &QueryMsg::PendingOwner { })
+    }
+
+    pub fn selected_winner(&self) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &QueryMsg::SelectedWinner { })
+    }
+
+    pub fn early_retraction_penalty(&self) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &QueryMsg::EarlyRetractionPenalty { })
+    }
+
+    pub fn settlement_hooks(&self) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &QueryMsg::SettlementHooks { })
+    }
+
+    pub fn status_info(&self) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &QueryMsg::StatusInfo { })
+    }
+
+    pub fn sale_status(&self) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &QueryMsg::SaleStatus { })
+    }
+
+    pub fn config(&self) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &QueryMsg::Config { })
+    }
+
+    pub fn factory(&self) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &QueryMsg::Factory { })
+    }
+
+    pub fn approvals(&self) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &QueryMsg::Approvals { })
+    }
+
+    pub fn bid_receipt(&self, receipt: u64) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &QueryMsg::BidReceipt { receipt })
+    }
+
+    pub fn time_remaining(&self) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &QueryMsg::TimeRemaining { })
+    }
+
+    pub fn extension_proposal(&self, proposal: u64) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &QueryMsg::ExtensionProposal { proposal })
+    }
+
+    pub fn state(&self) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &QueryMsg::State { })
+    }
+
+    pub fn invariants(&self) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &QueryMsg::Invariants { })
+    }
+
+    pub fn metrics(&self) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &QueryMsg::Metrics { })
+    }
+
+    pub fn version(&self) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &QueryMsg::Version { })
+    }
+
+    pub fn status(&self) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &killswitch::QueryMsg::Status { })
+    }
+
+    pub fn admin(&self) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &admin::QueryMsg::Admin { })
+    }
+}
+
+impl FactoryClient {
+    pub fn create_auction(
+        &self,
+        config: Config,
+        tags: Option<Vec<String>>,
+        end: Option<AuctionEnd>
+    ) -> StdResult<WasmMsg> {
+        execute(&self.0, &FactoryExecuteMsg::CreateAuction { config, tags, end }, vec![])
+    }
+
+    pub fn clone_auction(&self, index: u64, new_end_block: u64) -> StdResult<WasmMsg> {
+        execute(&self.0, &FactoryExecuteMsg::CloneAuction { index, new_end_block }, vec![])
+    }
+
+    /// Must be sent by the contract that subscribes, with its own code hash.
+    pub fn subscribe(&self, code_hash: impl Into<String>) -> StdResult<WasmMsg> {
+        execute(&self.0, &FactoryExecuteMsg::Subscribe { code_hash: code_hash.into() }, vec![])
+    }
+
+    pub fn remove_subscriber(&self, address: impl Into<String>) -> StdResult<WasmMsg> {
+        execute(&self.0, &FactoryExecuteMsg::RemoveSubscriber { address: address.into() }, vec![])
+    }
+
+    pub fn set_fee_config(&self, fee: Option<FeeConfig<String>>) -> StdResult<WasmMsg> {
+        execute(&self.0, &FactoryExecuteMsg::SetFeeConfig { fee }, vec![])
+    }
+
+    pub fn process_ended(&self, limit: u8) -> StdResult<WasmMsg> {
+        execute(&self.0, &FactoryExecuteMsg::ProcessEnded { limit }, vec![])
+    }
+
+    pub fn set_keeper_reward(&self, reward: Option<Coin>) -> StdResult<WasmMsg> {
+        execute(&self.0, &FactoryExecuteMsg::SetKeeperReward { reward }, vec![])
+    }
+
+    pub fn set_creator_limit(&self, limit: Option<u64>) -> StdResult<WasmMsg> {
+        execute(&self.0, &FactoryExecuteMsg::SetCreatorLimit { limit }, vec![])
+    }
+
+    pub fn set_default_auction_admin(&self, admin: Option<String>) -> StdResult<WasmMsg> {
+        execute(&self.0, &FactoryExecuteMsg::SetDefaultAuctionAdmin { admin }, vec![])
+    }
+
+    pub fn archive_auctions(&self, indices: Vec<u64>) -> StdResult<WasmMsg> {
+        execute(&self.0, &FactoryExecuteMsg::ArchiveAuctions { indices }, vec![])
+    }
+
+    /// Sent by an auction when it is closed.
+    pub fn auction_closed(&self, settled: bool) -> StdResult<WasmMsg> {
+        execute(&self.0, &FactoryExecuteMsg::AuctionClosed { settled }, vec![])
+    }
+
+    /// Sent by an auction when its end block changes.
+    pub fn end_block_changed(&self, end_block: u64) -> StdResult<WasmMsg> {
+        execute(&self.0, &FactoryExecuteMsg::EndBlockChanged { end_block }, vec![])
+    }
+
+    pub fn register_existing(&self, contract: ContractLink<String>) -> StdResult<WasmMsg> {
+        execute(&self.0, &FactoryExecuteMsg::RegisterExisting { contract }, vec![])
+    }
+
+    pub fn auction_settled(
+        &self,
+        auction: ContractLink<Addr>,
+        winner: Option<Addr>,
+        price: Uint128,
+        denom: String
+    ) -> StdResult<WasmMsg> {
+        execute(&self.0, &FactoryExecuteMsg::AuctionSettled { auction, winner, price, denom }, vec![])
+    }
+
+    pub fn set_currency_policy(&self, policy: Option<CurrencyPolicy<String>>) -> StdResult<WasmMsg> {
+        execute(&self.0, &FactoryExecuteMsg::SetCurrencyPolicy { policy }, vec![])
+    }
+
+    pub fn change_admin(&self, mode: Option<Mode>) -> StdResult<WasmMsg> {
+        execute(&self.0, &admin::ExecuteMsg::ChangeAdmin { mode }, vec![])
+    }
+
+    pub fn list_auctions(&self, pagination: Pagination) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &FactoryQueryMsg::ListAuctions { pagination })
+    }
+
+    pub fn list_archive(&self, pagination: Pagination) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &FactoryQueryMsg::ListArchive { pagination })
+    }
+
+    pub fn list_ending_soon(&self, before_block: u64, pagination: Pagination) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &FactoryQueryMsg::ListEndingSoon { before_block, pagination })
+    }
+
+    pub fn list_unsettled(&self, pagination: Pagination) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &FactoryQueryMsg::ListUnsettled { pagination })
+    }
+
+    pub fn list_auctions_by_tag(&self, tag: impl Into<String>, pagination: Pagination) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &FactoryQueryMsg::ListAuctionsByTag { tag: tag.into(), pagination })
+    }
+
+    pub fn subscribers(&self) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &FactoryQueryMsg::Subscribers { })
+    }
+
+    pub fn auction_code(&self) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &FactoryQueryMsg::AuctionCode { })
+    }
+
+    pub fn version(&self) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &FactoryQueryMsg::Version { })
+    }
+
+    pub fn fee_config(&self) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &FactoryQueryMsg::FeeConfig { })
+    }
+
+    pub fn keeper_reward(&self) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &FactoryQueryMsg::KeeperReward { })
+    }
+
+    pub fn creator_limit(&self) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &FactoryQueryMsg::CreatorLimit { })
+    }
+
+    pub fn default_auction_admin(&self) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &FactoryQueryMsg::DefaultAuctionAdmin { })
+    }
+
+    pub fn active_auctions(&self, creator: impl Into<String>) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &FactoryQueryMsg::ActiveAuctions { creator: creator.into() })
+    }
+
+    pub fn is_closed(&self, index: u64) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &FactoryQueryMsg::IsClosed { index })
+    }
+
+    pub fn top_sales(&self, denom: impl Into<String>, limit: u8) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &FactoryQueryMsg::TopSales { denom: denom.into(), limit })
+    }
+
+    pub fn volume_stats(&self) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &FactoryQueryMsg::VolumeStats { })
+    }
+
+    pub fn currency_policy(&self) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &FactoryQueryMsg::CurrencyPolicy { })
+    }
+
+    pub fn export_registry(
+        &self,
+        permit: Permit<FactoryPermission>,
+        pagination: Pagination
+    ) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &FactoryQueryMsg::ExportRegistry { permit, pagination })
+    }
+
+    pub fn admin(&self) -> StdResult<QueryRequest<Empty>> {
+        query(&self.0, &admin::QueryMsg::Admin { })
+    }
+}
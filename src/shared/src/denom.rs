@@ -0,0 +1,102 @@
+use std::{fmt, convert::TryFrom};
+
+use fadroma::{
+    schemars::{self, JsonSchema},
+    cosmwasm_std::{self, StdError},
+    bin_serde::{FadromaSerialize, FadromaDeserialize},
+    impl_canonize_default
+};
+use serde::{Serialize, Deserialize};
+
+/// A native coin denomination. Can only be constructed from a valid
+/// denomination string, including when deserializing messages.
+#[derive(
+    Serialize, Deserialize, FadromaSerialize, FadromaDeserialize,
+    JsonSchema, Clone, PartialEq, Eq, Hash, Debug
+)]
+#[serde(try_from = "String", into = "String")]
+#[schemars(transparent)]
+pub struct Denom(String);
+
+impl_canonize_default!(Denom);
+
+impl Denom {
+    pub const MIN_LEN: usize = 3;
+    pub const MAX_LEN: usize = 128;
+
+    /// Follows the rules of the Cosmos SDK: must start with a letter
+    /// followed by letters, digits or any of `/:._-`.
+    pub fn new(denom: impl Into<String>) -> Result<Self, StdError> {
+        let denom = denom.into();
+
+        if denom.len() < Self::MIN_LEN || denom.len() > Self::MAX_LEN {
+            return Err(StdError::generic_err(format!(
+                "Denom must be between {} and {} characters long, got: {:?}.",
+                Self::MIN_LEN,
+                Self::MAX_LEN,
+                denom
+            )));
+        }
+
+        let mut chars = denom.chars();
+        let is_valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic()) &&
+            chars.all(|c| c.is_ascii_alphanumeric() || "/:._-".contains(c));
+
+        if !is_valid {
+            return Err(StdError::generic_err(format!(
+                "Denom contains invalid characters: {:?}.",
+                denom
+            )));
+        }
+
+        Ok(Self(denom))
+    }
+
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl TryFrom<String> for Denom {
+    type Error = StdError;
+
+    #[inline]
+    fn try_from(denom: String) -> Result<Self, Self::Error> {
+        Self::new(denom)
+    }
+}
+
+impl From<Denom> for String {
+    #[inline]
+    fn from(denom: Denom) -> Self {
+        denom.0
+    }
+}
+
+impl AsRef<str> for Denom {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl PartialEq<str> for Denom {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<String> for Denom {
+    #[inline]
+    fn eq(&self, other: &String) -> bool {
+        &self.0 == other
+    }
+}
+
+impl fmt::Display for Denom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
@@ -0,0 +1,309 @@
+use fadroma::cosmwasm_std::{StdError, Uint128};
+use thiserror::Error;
+
+use crate::{
+    SaleInfo, MAX_TAGS, MAX_TAG_LEN, MAX_REFUND_BATCH,
+    MAX_SETTLEMENT_HOOKS, MAX_BPS, MAX_CURRENCIES, MAX_CO_SELLERS
+};
+
+/// The error type returned by the auction and the factory.
+/// Errors coming from fadroma or cosmwasm are wrapped in [`ContractError::Std`].
+#[derive(Error, PartialEq, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+    #[error(transparent)]
+    Auth(#[from] AuthError),
+    #[error(transparent)]
+    Lifecycle(#[from] LifecycleError),
+    #[error(transparent)]
+    Pagination(#[from] PaginationError),
+    #[error(transparent)]
+    Name(#[from] NameError),
+    #[error(transparent)]
+    Tag(#[from] TagError),
+    #[error(transparent)]
+    Claim(#[from] ClaimError),
+    #[error(transparent)]
+    Raffle(#[from] RaffleError),
+    #[error(transparent)]
+    Reply(#[from] ReplyError),
+    #[error(transparent)]
+    Selection(#[from] SelectionError),
+    #[error(transparent)]
+    Oracle(#[from] OracleError),
+    #[error(transparent)]
+    Hook(#[from] HookError),
+    #[error(transparent)]
+    Retraction(#[from] RetractionError),
+    #[error(transparent)]
+    Extension(#[from] ExtensionError),
+    #[error(transparent)]
+    Token(#[from] TokenError),
+    #[error(transparent)]
+    Deposit(#[from] DepositError),
+    #[error(transparent)]
+    Currency(#[from] CurrencyError),
+    #[error(transparent)]
+    CoSeller(#[from] CoSellerError)
+}
+
+#[derive(Error, PartialEq, Debug)]
+pub enum AuthError {
+    #[error("Unauthorized")]
+    Unauthorized,
+    #[error("{0} does not accept bids on their behalf.")]
+    BidsForNotAccepted(String),
+    #[error("{0} is a contract and this sale only accepts bids from accounts.")]
+    ContractBidder(String),
+    #[error("No ownership transfer is pending.")]
+    NoPendingOwner
+}
+
+/// Actions that are not allowed at the current stage of the auction.
+#[derive(Error, PartialEq, Debug)]
+pub enum LifecycleError {
+    #[error("Sale hasn't started yet.")]
+    NotStarted,
+    #[error("Sale has finished.")]
+    Finished,
+    #[error("Sale hasn't finished yet.")]
+    NotFinished,
+    #[error("End block has already passed.")]
+    EndBlockPassed,
+    #[error("End time has already passed.")]
+    EndTimePassed,
+    #[error("A bid must be more than zero.")]
+    ZeroBid,
+    #[error("Start block must be before the end block.")]
+    StartAfterEnd,
+    #[error("You have won the sale and cannot retract your bid.")]
+    WinnerCannotRetract,
+    #[error("You have no bid to retract.")]
+    NothingToRefund,
+    #[error("You have nothing to claim.")]
+    NothingToClaim,
+    #[error("Reserve price was not met.")]
+    ReserveNotMet,
+    #[error("Sale hasn't been settled yet.")]
+    NotSettled,
+    #[error("Proceeds have already been claimed.")]
+    AlreadySettled,
+    #[error("Config cannot be changed after the first bid.")]
+    ConfigLocked,
+    #[error("Contract paused.")]
+    Paused,
+    #[error("Proceeds can only be claimed after block {0}.")]
+    ProceedsLocked(u64),
+    #[error("Only {0} of the proceeds are left to claim.")]
+    ProceedsExceeded(Uint128),
+    #[error("Sale can only be cancelled during the dispute window.")]
+    NotInDisputeWindow,
+    #[error("Sale has been cancelled.")]
+    Cancelled,
+    #[error("You can place another bid at block {0}.")]
+    BidCooldown(u64),
+    #[error("You already have the maximum of {0} auctions that aren't settled.")]
+    TooManyActive(u64),
+    #[error("The proceeds have to be claimed first.")]
+    ProceedsUnclaimed,
+    #[error("Every bid has to be refunded first.")]
+    RefundsPending,
+    #[error("You have no active bid.")]
+    NoActiveBid,
+    #[error("The maximum of {0} bidders already have auto refund enabled.")]
+    AutoRefundsFull(u64)
+}
+
+#[derive(Error, PartialEq, Debug)]
+pub enum PaginationError {
+    #[error("Refund batch limit must be between 1 and {}.", MAX_REFUND_BATCH)]
+    InvalidBatchLimit { limit: u8 }
+}
+
+#[derive(Error, PartialEq, Debug)]
+pub enum NameError {
+    #[error("Auction name cannot be empty.")]
+    Empty,
+    #[error("Auction name is {len} bytes long but the maximum is {}.", SaleInfo::MAX_NAME_LEN)]
+    TooLong { len: usize },
+    #[error("Auction name contains an invalid character: {0:?}.")]
+    InvalidCharacter(char)
+}
+
+#[derive(Error, PartialEq, Debug)]
+pub enum TagError {
+    #[error("Got {count} tags but the maximum is {}.", MAX_TAGS)]
+    TooMany { count: usize },
+    #[error("Tag {0:?} must be 1 to {} lowercase letters, digits or dashes.", MAX_TAG_LEN)]
+    Invalid(String),
+    #[error("Tag {0:?} was given more than once.")]
+    Duplicate(String)
+}
+
+/// Errors related to tradable refund claims.
+#[derive(Error, PartialEq, Debug)]
+pub enum ClaimError {
+    #[error("Refund claims are not tradable in this auction.")]
+    NotTradable,
+    #[error("Claim {0} does not exist.")]
+    NotFound(u64),
+    #[error("Claim {0} has nothing left to redeem.")]
+    Empty(u64),
+    #[error("The address has not placed any bids.")]
+    NoBid,
+    #[error("Your refund claim has been transferred to another address.")]
+    Transferred
+}
+
+#[derive(Error, PartialEq, Debug)]
+pub enum RaffleError {
+    #[error("Ticket price cannot be zero.")]
+    ZeroTicketPrice,
+    #[error("Refund rate cannot be more than 1.")]
+    RefundRateTooHigh,
+    #[error("{0} cannot be used in raffles.")]
+    Unsupported(&'static str),
+    #[error("Bid of {amount} is not a multiple of the ticket price of {ticket_price}.")]
+    InvalidBid { amount: Uint128, ticket_price: Uint128 },
+    #[error("Raffle hasn't been drawn yet.")]
+    NotDrawn
+}
+
+/// Errors related to the manual selection of the winner.
+#[derive(Error, PartialEq, Debug)]
+pub enum SelectionError {
+    #[error("The winner of this auction is not selected manually.")]
+    NotManual,
+    #[error("The winner hasn't been selected yet.")]
+    NotSelected,
+    #[error("The winner has already been selected.")]
+    AlreadySelected,
+    #[error("{0} has not placed any bids.")]
+    NoBid(String)
+}
+
+/// Errors related to converting USD denominated amounts.
+#[derive(Error, PartialEq, Debug)]
+pub enum OracleError {
+    #[error("USD denominated amounts require a price oracle.")]
+    Missing,
+    #[error("Price was last updated at {updated_at} which is more than {max_age} seconds ago.")]
+    Stale { updated_at: u64, max_age: u64 },
+    #[error("Price of {0} is zero.")]
+    ZeroPrice(String)
+}
+
+#[derive(Error, PartialEq, Debug)]
+pub enum HookError {
+    #[error("{0} is already a settlement hook.")]
+    AlreadyAdded(String),
+    #[error("{0} is not a settlement hook.")]
+    NotFound(String),
+    #[error("The maximum of {} settlement hooks has been reached.", MAX_SETTLEMENT_HOOKS)]
+    TooMany
+}
+
+#[derive(Error, PartialEq, Debug)]
+pub enum RetractionError {
+    #[error("Early retraction penalty of {0} basis points is more than {}.", MAX_BPS)]
+    PenaltyTooHigh(u16)
+}
+
+/// Errors related to voting on extensions of the sale.
+#[derive(Error, PartialEq, Debug)]
+pub enum ExtensionError {
+    #[error("Extensions can't be voted on in this auction.")]
+    NotEnabled,
+    #[error("Extension vote threshold must be between 1 and {} basis points.", MAX_BPS)]
+    InvalidThreshold,
+    #[error("Extension votes can't be combined with an end time.")]
+    WithEndTime,
+    #[error("Extension of {blocks} blocks must be between 1 and the {left} blocks that are left.")]
+    InvalidBlocks { blocks: u64, left: u64 },
+    #[error("Extension proposal {0} does not exist.")]
+    NotFound(u64),
+    #[error("Extension proposal {0} has already been applied.")]
+    AlreadyApplied(u64),
+    #[error("You have already voted for extension proposal {0}.")]
+    AlreadyVoted(u64),
+    #[error("Only bidders can vote on extensions.")]
+    NoBid
+}
+
+/// Errors related to auctions that take bids in an SNIP-20 token.
+#[derive(Error, PartialEq, Debug)]
+pub enum TokenError {
+    #[error("This sale doesn't take bids in a token.")]
+    NotEnabled,
+    #[error("This sale only takes bids in its token through bid_from_allowance.")]
+    TokenOnly,
+    #[error("Token bids can't be combined with USD denominated amounts.")]
+    WithOracle
+}
+
+/// Errors related to the deposit of the seller.
+#[derive(Error, PartialEq, Debug)]
+pub enum DepositError {
+    #[error("A seller deposit of exactly {0} has to be sent when creating the auction.")]
+    Mismatch(Uint128),
+    #[error("A seller deposit can't be combined with a bid token.")]
+    WithToken
+}
+
+/// Currencies that the currency policy of the factory doesn't allow.
+#[derive(Error, PartialEq, Debug)]
+pub enum CurrencyError {
+    #[error("A currency policy has to allow at least one denom or token.")]
+    Empty,
+    #[error("Got {0} currencies of one kind but the maximum is {}.", MAX_CURRENCIES)]
+    TooMany(usize),
+    #[error("Auctions can't take bids in {0}.")]
+    DenomNotAllowed(String),
+    #[error("Auctions can't take bids in the token {0}.")]
+    TokenNotAllowed(String),
+    #[error("Auctions have to take bids in one of the allowed tokens.")]
+    TokenRequired
+}
+
+/// Errors related to the co-sellers of an auction and their approvals.
+#[derive(Error, PartialEq, Debug)]
+pub enum CoSellerError {
+    #[error("Got {0} co-sellers but there have to be between 1 and {}.", MAX_CO_SELLERS)]
+    InvalidCount(usize),
+    #[error("The share of {0} must be more than zero.")]
+    ZeroShare(String),
+    #[error("{0} is listed as a co-seller more than once.")]
+    Duplicate(String),
+    #[error("Shares add up to {0} but have to add up to {}.", MAX_BPS)]
+    SharesMismatch(u32),
+    #[error("Threshold is {0} but must be between 1 and the number of co-sellers.")]
+    InvalidThreshold(u16),
+    #[error("Co-sellers can't be combined with a beneficiary.")]
+    WithBeneficiary,
+    #[error("This sale has no co-sellers.")]
+    NotEnabled,
+    #[error("This action has to be approved by the co-sellers.")]
+    ApprovalRequired,
+    #[error("You have already approved this action.")]
+    AlreadyApproved,
+    #[error("The sale has to be extended by at least one block.")]
+    NoBlocks,
+    #[error("A sale with an end time can't be extended.")]
+    EndTime
+}
+
+/// Replies to the factory that don't have the expected shape.
+#[derive(Error, PartialEq, Debug)]
+pub enum ReplyError {
+    #[error("Auction instantiation failed: {0}")]
+    InstantiateFailed(String),
+    #[error("The instantiated auction didn't return its address and code hash.")]
+    MissingReplyData
+}
+
+impl From<NameError> for StdError {
+    fn from(err: NameError) -> Self {
+        StdError::generic_err(err.to_string())
+    }
+}
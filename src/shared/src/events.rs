@@ -0,0 +1,135 @@
+//! Event types and attribute keys emitted by the auction and the factory.
+
+/// Emitted by the factory once an auction has been instantiated.
+pub const AUCTION_CREATED: &str = "auction_created";
+/// Emitted by the factory when instantiating an auction failed.
+pub const AUCTION_INSTANTIATION_FAILED: &str = "auction_instantiation_failed";
+/// Emitted by the factory when a subscriber failed to handle a callback.
+pub const SUBSCRIBER_CALLBACK_FAILED: &str = "subscriber_callback_failed";
+/// Emitted by the factory for every auction settled by `process_ended`.
+pub const AUCTION_SETTLED: &str = "auction_settled";
+/// Emitted by the factory when `process_ended` failed to settle an auction.
+pub const AUCTION_SETTLEMENT_FAILED: &str = "auction_settlement_failed";
+/// Emitted by an auction when a settlement hook failed to handle its callback.
+pub const SETTLEMENT_HOOK_FAILED: &str = "settlement_hook_failed";
+
+pub const ACTION: &str = "action";
+pub const BIDDER: &str = "bidder";
+/// The sender of a bid placed on behalf of the bidder.
+pub const PAYER: &str = "payer";
+pub const ACCEPT: &str = "accept";
+pub const ENABLED: &str = "enabled";
+pub const RECIPIENT: &str = "recipient";
+pub const FEE: &str = "fee";
+/// The amount sent by a claim.
+pub const AMOUNT: &str = "amount";
+/// The amount refunded to a bidder that retracted their bid.
+pub const REFUND: &str = "refund";
+/// The seller deposit returned to the admin at settlement, or
+/// set aside for the bidders when the sale is cancelled.
+pub const DEPOSIT: &str = "deposit";
+/// The id of the receipt of an accepted bid.
+pub const RECEIPT: &str = "receipt";
+/// Deducted from the refund of a bid retracted before the sale has ended.
+pub const PENALTY: &str = "penalty";
+/// The admin that handed over the auction.
+pub const PREVIOUS_OWNER: &str = "previous_owner";
+/// The address nominated by, or accepted as, the new admin of the auction.
+pub const NEW_OWNER: &str = "new_owner";
+pub const FEE_RECIPIENT: &str = "fee_recipient";
+pub const AUCTION_ADDRESS: &str = "auction_address";
+pub const AUCTION_NAME: &str = "auction_name";
+pub const SUBSCRIBER: &str = "subscriber";
+pub const HOOK: &str = "hook";
+pub const ERROR: &str = "error";
+pub const CLAIM_ID: &str = "claim_id";
+pub const WINNER: &str = "winner";
+pub const REASON: &str = "reason";
+/// The number of bids refunded by a `refund_batch` call.
+pub const REFUNDED: &str = "refunded";
+/// The number of bidders that `refund_batch` or ended
+/// auctions that `process_ended` still have to process.
+pub const REMAINING: &str = "remaining";
+/// The number of auctions settled by a `process_ended` call.
+pub const PROCESSED: &str = "processed";
+/// The number of auctions archived by `archive_auctions`.
+pub const ARCHIVED: &str = "archived";
+/// The id of an extension proposal.
+pub const PROPOSAL: &str = "proposal";
+/// The number of blocks that a proposal would extend the sale by.
+pub const BLOCKS: &str = "blocks";
+/// The end block after a proposal has been applied or the sale was extended.
+pub const END_BLOCK: &str = "end_block";
+/// How many co-sellers have approved an action so far.
+pub const APPROVALS: &str = "approvals";
+/// The full description of the status set by `set_status`, same as the one used by the killswitch module.
+pub const STATUS: &str = "status";
+/// The status that the contract had before `set_status`, one of [`statuses`].
+pub const PREVIOUS_STATUS: &str = "previous_status";
+/// The status set by `set_status`, one of [`statuses`].
+pub const NEW_STATUS: &str = "new_status";
+/// The address that changed the status of the contract.
+pub const ACTOR: &str = "actor";
+/// The storage layout version that a contract was migrated from.
+pub const LAYOUT_VERSION: &str = "layout_version";
+/// The release that a contract was migrated from.
+pub const CONTRACT_VERSION: &str = "contract_version";
+
+/// Values of the [`ACTION`] attribute.
+pub mod actions {
+    pub const CREATE_AUCTION: &str = "create_auction";
+    pub const CLONE_AUCTION: &str = "clone_auction";
+    pub const BID: &str = "bid";
+    pub const BID_FOR: &str = "bid_for";
+    pub const PROXY_BID: &str = "proxy_bid";
+    pub const BID_FROM_ALLOWANCE: &str = "bid_from_allowance";
+    pub const ACCEPT_BIDS_FOR: &str = "accept_bids_for";
+    pub const SET_AUTO_REFUND: &str = "set_auto_refund";
+    pub const RETRACT_BID: &str = "retract_bid";
+    pub const CLAIM_PROCEEDS: &str = "claim_proceeds";
+    pub const CLAIM: &str = "claim";
+    pub const SETTLE: &str = "settle";
+    pub const PROPOSE_EXTENSION: &str = "propose_extension";
+    pub const VOTE_EXTENSION: &str = "vote_extension";
+    pub const UPDATE_CONFIG: &str = "update_config";
+    pub const SET_AUDITOR: &str = "set_auditor";
+    pub const SET_PAUSER: &str = "set_pauser";
+    pub const TRANSFER_OWNERSHIP: &str = "transfer_ownership";
+    pub const ACCEPT_OWNERSHIP: &str = "accept_ownership";
+    pub const EXPORT_BIDDERS: &str = "export_bidders";
+    /// Same as the one used by the killswitch module.
+    pub const SET_STATUS: &str = "set_status";
+    pub const SUBSCRIBE: &str = "subscribe";
+    pub const REMOVE_SUBSCRIBER: &str = "remove_subscriber";
+    pub const TRANSFER_CLAIM: &str = "transfer_claim";
+    pub const REDEEM_CLAIM: &str = "redeem_claim";
+    pub const REFUND_BATCH: &str = "refund_batch";
+    pub const SELECT_WINNER: &str = "select_winner";
+    pub const SEND_DELIVERY_DETAILS: &str = "send_delivery_details";
+    pub const SEND_FULFILLMENT_PROOF: &str = "send_fulfillment_proof";
+    pub const CANCEL_SALE: &str = "cancel_sale";
+    pub const APPROVE_ACTION: &str = "approve_action";
+    pub const EXTEND: &str = "extend";
+    pub const SET_FEE_CONFIG: &str = "set_fee_config";
+    pub const PROCESS_ENDED: &str = "process_ended";
+    pub const SET_KEEPER_REWARD: &str = "set_keeper_reward";
+    pub const SET_CREATOR_LIMIT: &str = "set_creator_limit";
+    pub const SET_DEFAULT_AUCTION_ADMIN: &str = "set_default_auction_admin";
+    pub const ARCHIVE_AUCTIONS: &str = "archive_auctions";
+    pub const CLOSE: &str = "close";
+    pub const AUCTION_CLOSED: &str = "auction_closed";
+    pub const END_BLOCK_CHANGED: &str = "end_block_changed";
+    pub const REGISTER_EXISTING: &str = "register_existing";
+    pub const AUCTION_SETTLED: &str = "auction_settled";
+    pub const SET_CURRENCY_POLICY: &str = "set_currency_policy";
+    pub const MIGRATE: &str = "migrate";
+    pub const ADD_SETTLEMENT_HOOK: &str = "add_settlement_hook";
+    pub const REMOVE_SETTLEMENT_HOOK: &str = "remove_settlement_hook";
+}
+
+/// Values of the [`PREVIOUS_STATUS`] and [`NEW_STATUS`] attributes.
+pub mod statuses {
+    pub const OPERATIONAL: &str = "operational";
+    pub const PAUSED: &str = "paused";
+    pub const MIGRATING: &str = "migrating";
+}
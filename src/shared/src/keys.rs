@@ -0,0 +1,110 @@
+//! The messages of the auction that authenticate with a viewing key rather
+//! than a permit, together with [`VkAuth`] which sets the keys. Only compiled
+//! with the `vk` feature, which is on by default. Deployments that only use
+//! permits can turn it off to leave the keys out of the contract entirely.
+//! The auditor then views the bids with `view_bid_of_with_permit` and
+//! `audit_bids_with_permit` instead.
+
+use fadroma::{
+    dsl::*,
+    schemars,
+    cosmwasm_std::{self, Response, Uint128, Binary},
+    scrt::vk::auth::VkAuth
+};
+
+use crate::{BidEntry, BidRank, Claim, Delivery, PaginatedResponse, Pagination, ProxyBid};
+
+/// Apart from the winner payload, the delivery and the memo, the
+/// queries also accept the key of the auditor instead of the one of the address.
+#[interface]
+pub trait ViewingKeys: VkAuth {
+    type Error: std::fmt::Display;
+
+    /// Same as `bid` but also sets the viewing key of the sender,
+    /// so that the bid can be viewed without another transaction.
+    #[execute]
+    fn bid_with_key(
+        key: String,
+        _padding: Option<String>
+    ) -> Result<Response, <Self as ViewingKeys>::Error>;
+
+    /// Removes the sender's viewing key so that it can no longer be used to
+    /// authenticate. Calling `set_viewing_key` or `create_viewing_key` rotates the key
+    /// instead since only the most recently stored one is ever accepted.
+    #[execute]
+    fn revoke_all_keys() -> Result<Response, <Self as ViewingKeys>::Error>;
+
+    /// Same as `view_bid_of_with_permit` but authenticated
+    /// with a viewing key of the bidder or the auditor.
+    #[query]
+    fn view_bid(
+        address: String,
+        key: String
+    ) -> Result<Uint128, <Self as ViewingKeys>::Error>;
+
+    /// Same as `min_bid_to_win_with_permit` but authenticated with a viewing key.
+    #[query]
+    fn min_bid_to_win(
+        address: String,
+        key: String
+    ) -> Result<Uint128, <Self as ViewingKeys>::Error>;
+
+    /// Same as `view_payout_with_permit` but authenticated with a viewing key.
+    #[query]
+    fn view_payout(
+        address: String,
+        key: String
+    ) -> Result<Uint128, <Self as ViewingKeys>::Error>;
+
+    /// Same as `view_proxy_bid_with_permit` but authenticated with a viewing key.
+    #[query]
+    fn view_proxy_bid(
+        address: String,
+        key: String
+    ) -> Result<ProxyBid, <Self as ViewingKeys>::Error>;
+
+    /// Same as `my_rank_with_permit` but authenticated with a viewing key.
+    #[query]
+    fn my_rank(
+        address: String,
+        key: String
+    ) -> Result<BidRank, <Self as ViewingKeys>::Error>;
+
+    /// Same as `winner_payload_with_permit` but authenticated with a viewing key.
+    #[query]
+    fn winner_payload(
+        address: String,
+        key: String
+    ) -> Result<Option<Binary>, <Self as ViewingKeys>::Error>;
+
+    /// Same as `delivery_with_permit` but authenticated with a viewing key.
+    #[query]
+    fn delivery(
+        address: String,
+        key: String
+    ) -> Result<Delivery, <Self as ViewingKeys>::Error>;
+
+    /// Same as `view_memo_with_permit` but authenticated with a viewing key of the viewer.
+    #[query]
+    fn view_memo(
+        bidder: String,
+        viewer: String,
+        key: String
+    ) -> Result<Option<String>, <Self as ViewingKeys>::Error>;
+
+    /// Returns the given claim or, if no id is given, the one
+    /// that originates from the bid of the given address.
+    #[query]
+    fn view_claim(
+        address: String,
+        key: String,
+        claim_id: Option<u64>
+    ) -> Result<Claim, <Self as ViewingKeys>::Error>;
+
+    /// Same as `audit_bids_with_permit` but authenticated with a viewing key of the auditor.
+    #[query]
+    fn audit_bids(
+        key: String,
+        pagination: Pagination
+    ) -> Result<PaginatedResponse<BidEntry>, <Self as ViewingKeys>::Error>;
+}
@@ -0,0 +1,198 @@
+//! The storage layout of the auction and the factory. Every namespace that
+//! they own starts with the version of the layout that it belongs to and the
+//! version itself is stored when a contract is instantiated, so that a
+//! migration can tell which layout the stored entries were written with and
+//! convert them. Namespaces of fadroma modules (admin, killswitch, viewing
+//! keys and permits) are not versioned since their layout is not ours to change.
+
+use fadroma::cosmwasm_std::{StdError, StdResult, Storage};
+
+/// Prefixed to every namespace of the current layout.
+pub const VERSION: u8 = 1;
+/// Contracts instantiated before namespaces were versioned
+/// have nothing stored under the version key.
+pub const LEGACY: u8 = 0;
+
+// Not versioned itself so that it can always be found.
+const VERSION_KEY: &[u8] = b"layout_version";
+
+/// The namespaces of the auction in the current layout, without the version.
+pub const AUCTION: &[&[u8]] = &[
+    b"info",
+    b"created_at",
+    b"highest_bid",
+    b"balances",
+    b"bidder_addresses",
+    b"bid_ranks",
+    b"sorted_bids",
+    b"settings",
+    b"claim_ids",
+    b"claim_owners",
+    b"accepts_bids_for",
+    b"auto_refund",
+    b"auto_refunds",
+    b"proxy_unspent",
+    b"total_escrowed",
+    b"num_bids",
+    b"bid_receipts",
+    b"status_changed",
+    b"metrics",
+    b"refund_cursor",
+    b"cancelled",
+    b"settled",
+    b"end_time",
+    b"extension_proposals",
+    b"extension_votes",
+    b"cast_votes",
+    b"extended_blocks",
+    b"last_bid",
+    b"penalties",
+    b"payouts",
+    b"unclaimed",
+    b"closed",
+    b"deposit",
+    b"compensation",
+    b"proceeds",
+    b"auditor",
+    b"pauser",
+    b"pending_owner",
+    b"settlement_hooks",
+    b"winner_payload",
+    b"memos",
+    b"delivery_details",
+    b"fulfillment_proof",
+    b"co_sellers",
+    b"approvals",
+    b"selected_winner",
+    b"ticket_ranges",
+    b"raffle_seed",
+    b"raffle_pot",
+    b"converted"
+];
+
+/// The namespaces of the factory in the current layout, without the version.
+pub const FACTORY: &[&[u8]] = &[
+    b"contract",
+    b"next_reply_id",
+    b"fee_config",
+    b"currency_policy",
+    b"keeper_reward",
+    b"default_auction_admin",
+    b"creator_limit",
+    b"active_counts",
+    b"process_cursor",
+    b"deferred_positions",
+    b"settled",
+    b"pending_settlements",
+    b"pending",
+    b"origins",
+    b"indices",
+    b"closed",
+    b"top_sales",
+    b"volume_stats",
+    b"auctions",
+    b"listed",
+    b"listed_positions",
+    b"deferred",
+    b"archive",
+    b"subscribers",
+    b"by_end_block",
+    b"tagged"
+];
+
+/// The keys that the unversioned layout of the factory, which its first release
+/// was deployed with, stored its entries under, each with the namespace of the
+/// current layout that they are converted to.
+pub const LEGACY_FACTORY: &[(&[u8], &[u8])] = &[
+    (b"contract", b"contract"),
+    (b"auctions", b"auctions")
+];
+
+/// Same as [`LEGACY_FACTORY`] for the auction. Its bids were kept in a map
+/// whose keys can't be listed, so they can't be moved to the current layout
+/// and auctions of the first release have to run to their end on its code.
+pub const LEGACY_AUCTION: &[(&[u8], &[u8])] = &[
+    (b"info", b"info"),
+    (b"highest_bid", b"highest_bid"),
+    (b"bidders", b"balances")
+];
+
+/// Converts the entries of the unversioned layout to the current one.
+pub type ConvertLegacy = fn(&mut dyn Storage) -> StdResult<()>;
+
+/// Records that the contract uses the current layout.
+/// Called when the contract is instantiated or migrated.
+pub fn save(storage: &mut dyn Storage) {
+    storage.set(VERSION_KEY, &[VERSION]);
+}
+
+/// Returns the layout version that the contract's storage was written with.
+pub fn load(storage: &dyn Storage) -> StdResult<u8> {
+    match storage.get(VERSION_KEY).as_deref() {
+        None => Ok(LEGACY),
+        Some([version]) => Ok(*version),
+        Some(_) => Err(StdError::generic_err("Stored layout version is not a single byte."))
+    }
+}
+
+/// Records the current layout after converting the stored entries and returns
+/// the version that they were written with. Entries of the unversioned layout
+/// are converted by `legacy`, contracts which can't convert them pass `None`.
+/// Any change to the layout has to bump [`VERSION`] and add a step here which
+/// converts the entries of the previous version.
+pub fn migrate(storage: &mut dyn Storage, legacy: Option<ConvertLegacy>) -> StdResult<u8> {
+    let from = load(storage)?;
+
+    let unsupported = || StdError::generic_err(format!(
+        "Storage layout version {} can't be migrated.",
+        from
+    ));
+
+    if from > VERSION {
+        return Err(unsupported());
+    }
+
+    if from == LEGACY {
+        let convert = legacy.ok_or_else(unsupported)?;
+        convert(storage)?;
+    }
+
+    save(storage);
+
+    Ok(from)
+}
+
+/// Prefixes the byte string literal with the current layout version
+/// and returns it as a `&'static [u8]`.
+#[macro_export]
+macro_rules! versioned_key {
+    ($bytes:literal) => {{
+        const KEY: &[u8] = &{
+            let bytes: &[u8] = $bytes;
+            let mut key = [0u8; $bytes.len() + 1];
+            key[0] = $crate::layout::VERSION;
+
+            let mut i = 0;
+            while i < bytes.len() {
+                key[i + 1] = bytes[i];
+                i += 1;
+            }
+
+            key
+        };
+
+        KEY
+    }};
+}
+
+/// Same as fadroma's `namespace!` but prefixed with the current layout version.
+#[macro_export]
+macro_rules! versioned_namespace {
+    ($visibility:vis $name:ident, $bytes:literal) => {
+        $visibility struct $name;
+
+        impl ::fadroma::storage::Namespace for $name {
+            const NAMESPACE: &'static [u8] = $crate::versioned_key!($bytes);
+        }
+    };
+}
@@ -1,64 +1,1241 @@
 use fadroma::{
     dsl::*,
+    core::{ContractLink, Canonize},
     schemars,
-    cosmwasm_std::{self, Response, Uint128},
+    cosmwasm_std::{self, Response, Uint128, Decimal, Addr, Binary, Coin, StdError, DepsMut, Env},
     bin_serde::{FadromaSerialize, FadromaDeserialize},
-    killswitch::Killswitch,
-    scrt::vk::auth::VkAuth,
+    killswitch::{Killswitch, ContractStatus},
+    scrt::permit::Permit,
     impl_canonize_default
 };
 use serde::{Serialize, Deserialize};
 
+mod denom;
+pub mod builders;
+pub mod client;
+pub mod errors;
+pub mod events;
+#[cfg(feature = "vk")]
+pub mod keys;
+pub mod layout;
+pub mod oracle;
+pub mod snip20;
+pub mod snip721;
+pub mod version;
+
+pub use denom::Denom;
+
+use errors::{CoSellerError, CurrencyError, NameError, TagError};
+use version::ContractVersion;
+
+/// The maximum number of tags that an auction can be listed under.
+pub const MAX_TAGS: usize = 5;
+pub const MAX_TAG_LEN: usize = 32;
+/// The maximum number of bidders that a single `refund_batch` call processes.
+pub const MAX_REFUND_BATCH: u8 = 30;
+/// The maximum number of bidders that can have auto refund enabled in an auction.
+pub const MAX_AUTO_REFUNDS: u64 = 50;
+/// The maximum number of contracts that can be notified when an auction is settled.
+pub const MAX_SETTLEMENT_HOOKS: u64 = 10;
+/// The maximum length in bytes of the memo that can be attached to a bid.
+pub const MAX_MEMO_LEN: usize = 256;
+/// The maximum length in bytes of what the winner and the seller can send each other.
+pub const MAX_DELIVERY_LEN: usize = 1024;
+/// Penalties are expressed in basis points of this.
+pub const MAX_BPS: u16 = 10_000;
+/// The maximum number of denoms and tokens, each, that a currency policy can allow.
+pub const MAX_CURRENCIES: usize = 10;
+/// The maximum number of co-sellers that an auction can have.
+pub const MAX_CO_SELLERS: usize = 10;
+
+/// The message that contracts are migrated with.
+#[derive(Serialize, Deserialize, schemars::JsonSchema, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct MigrateMsg {
+    /// Sets the admin of a factory of the first release, which had none.
+    /// Ignored by contracts which already have an admin.
+    pub admin: Option<String>
+}
+
+/// The DSL can't declare a migrate method in an interface, so it is a separate
+/// trait which every [`Auction`] has to implement. Lets the factory migrate any
+/// auction that it has created with the same [`MigrateMsg`].
+pub trait Migrate {
+    type Error: std::fmt::Display;
+
+    /// Brings the storage up to the current layout and fails
+    /// if it was written with a layout that can't be converted.
+    fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, <Self as Migrate>::Error>;
+}
+
 #[interface]
-pub trait Auction: Killswitch + VkAuth {
+pub trait Auction: Killswitch + Migrate {
     type Error: std::fmt::Display;
 
     #[init]
-    fn new(
-        admin: Option<String>,
-        name: String,
-        end_block: u64
+    fn new(config: Config) -> Result<Response, <Self as Auction>::Error>;
+
+    /// Sets a [`BidResult`] as the data of the response. If `auto_refund`
+    /// is given, it is stored the same way as by `set_auto_refund`. A `memo`,
+    /// e.g. delivery details for the seller, replaces the one of any earlier
+    /// bid of the sender and can only be viewed through `view_memo`.
+    #[execute]
+    fn bid(
+        auto_refund: Option<bool>,
+        memo: Option<String>
     ) -> Result<Response, <Self as Auction>::Error>;
 
+    /// Bids the sent funds on behalf of the beneficiary, who is then treated
+    /// as the bidder, i.e. gets the refund or wins the sale. The beneficiary has
+    /// to opt in with `accept_bids_for` first. Sets a [`BidResult`] with the
+    /// balance of the beneficiary as the data of the response.
+    #[execute]
+    fn bid_for(beneficiary: String) -> Result<Response, <Self as Auction>::Error>;
+
+    /// Deposits the sent funds as the maximum that the sender is willing to bid.
+    /// Their bid is raised from the deposit just enough to take the first place,
+    /// and again whenever they are outbid until the maximum is reached. Whatever
+    /// wasn't bid is refunded by `retract_bid`, also to the winner.
+    /// Sets a [`BidResult`] with the resulting bid as the data of the response.
+    #[execute]
+    fn proxy_bid() -> Result<Response, <Self as Auction>::Error>;
+
+    /// Bids the given amount of the bid token, which the sender has allowed the
+    /// auction to spend beforehand with `increase_allowance`. Only auctions that
+    /// take bids in a token accept it. Sets a [`BidResult`] as the data of the response.
     #[execute]
-    fn bid() -> Result<Response, <Self as Auction>::Error>;
+    fn bid_from_allowance(amount: Uint128) -> Result<Response, <Self as Auction>::Error>;
 
+    /// Whether `refund_batch` should send the refund of the sender to them once
+    /// the sale has finished instead of adding it to their payout, which has to
+    /// be claimed. Doesn't apply once the claim of the sender was transferred.
+    /// Off by default. Can only be enabled by bidders with an active bid and
+    /// by at most [`MAX_AUTO_REFUNDS`] of them.
+    #[execute]
+    fn set_auto_refund(enabled: bool) -> Result<Response, <Self as Auction>::Error>;
+
+    /// Allows or disallows others to bid on behalf of the sender.
+    #[execute]
+    fn accept_bids_for(accept: bool) -> Result<Response, <Self as Auction>::Error>;
+
+    /// Before the sale has ended, bids can only be retracted if the auction allows
+    /// early retraction and the penalty is deducted from the refund. The refund
+    /// is paid out to be claimed. Sets a [`RetractResult`] as the data of the response.
     #[execute]
     fn retract_bid() -> Result<Response, <Self as Auction>::Error>;
 
+    /// Pays out the given amount of the proceeds or everything that is left if `None`,
+    /// so that they can be claimed in installments. The first call settles the sale.
+    /// Sets a [`ProceedsResult`] as the data of the response. In auctions with
+    /// co-sellers, the admin has to go through `approve_action` instead.
+    #[execute]
+    fn claim_proceeds(amount: Option<Uint128>) -> Result<Response, <Self as Auction>::Error>;
+
+    /// Sends everything that was paid out to the sender: refunds, proceeds and fees.
+    /// Only auto refunds, which `refund_batch` sends right away, and the leftovers
+    /// swept by `close` are sent otherwise, so a transfer of anything else that
+    /// fails only reverts the claim.
+    #[execute]
+    fn claim() -> Result<Response, <Self as Auction>::Error>;
+
+    /// Settles the sale like the first `claim_proceeds` would but leaves the proceeds
+    /// to be claimed by the admin. Can be called by anyone once they can be claimed.
+    #[execute]
+    fn settle() -> Result<Response, <Self as Auction>::Error>;
+
+    /// Proposes to extend the sale by the given number of blocks and votes for it
+    /// like `vote_extension`. Only possible before the sale has ended in auctions
+    /// with extension votes. Sets the id of the proposal as the data of the response.
+    #[execute]
+    fn propose_extension(blocks: u64) -> Result<Response, <Self as Auction>::Error>;
+
+    /// Votes for the proposal with the sender's current bid and unspent proxy deposit.
+    /// Bids placed afterwards don't change the vote but retracting the bid early takes
+    /// it back from proposals that haven't been applied. Extends the sale if the
+    /// threshold is reached.
+    #[execute]
+    fn vote_extension(proposal: u64) -> Result<Response, <Self as Auction>::Error>;
+
+    /// Updates the given parameters. Only possible while the sale is running and
+    /// before the first bid is placed, even if that bid was retracted since.
+    /// The factory registry keeps the values that the auction was created with,
+    /// apart from the end block which the auction reports to it.
+    #[execute]
+    fn update_config(
+        name: Option<String>,
+        metadata: Option<String>,
+        reserve: Option<Uint128>,
+        end_block: Option<u64>
+    ) -> Result<Response, <Self as Auction>::Error>;
+
+    /// Sets or clears the address which is allowed to view any bid
+    /// using its own permit or viewing key. The auditor has no additional
+    /// permissions when it comes to executing messages.
+    #[execute]
+    fn set_auditor(address: Option<String>) -> Result<Response, <Self as Auction>::Error>;
+
+    /// Sets or clears the address which can pause the contract in an emergency,
+    /// e.g. a monitoring bot. It can't resume the contract or set any other status.
+    #[execute]
+    fn set_pauser(address: Option<String>) -> Result<Response, <Self as Auction>::Error>;
+
+    /// Nominates the address that the auction is handed over to, or withdraws
+    /// the nomination if [`None`]. Only possible before the sale is settled or
+    /// cancelled. The new owner becomes the admin once it calls [`Auction::accept_ownership`],
+    /// which lets it change the config and claim the proceeds, unless a beneficiary is set.
+    #[execute]
+    fn transfer_ownership(new_owner: Option<String>) -> Result<Response, <Self as Auction>::Error>;
+
+    /// Called by the address nominated in [`Auction::transfer_ownership`]
+    /// to become the admin of the auction.
+    #[execute]
+    fn accept_ownership() -> Result<Response, <Self as Auction>::Error>;
+
+    /// Lists the addresses and balances of all bidders, e.g. for reconciliation or
+    /// audits. Sent as a message rather than a query so that every access shows up
+    /// as an event. Sets a [`PaginatedResponse`] of [`BidEntry`] as the data of the response.
+    #[execute]
+    fn export_bidders(pagination: Pagination) -> Result<Response, <Self as Auction>::Error>;
+
+    /// Marks any permit signed by the sender with the given name as revoked.
+    #[execute]
+    fn revoke_permit(name: String) -> Result<Response, <Self as Auction>::Error>;
+
+    /// Assigns the refund of a losing bid to another address. Only available
+    /// after the sale has ended and if the auction has tradable claims.
+    #[execute]
+    fn transfer_claim(
+        claim_id: u64,
+        recipient: String
+    ) -> Result<Response, <Self as Auction>::Error>;
+
+    /// Selects the winner of an auction with manual winner selection. Only possible
+    /// after the sale has ended. Every other bidder can then retract their bid.
+    #[execute]
+    fn select_winner(address: String) -> Result<Response, <Self as Auction>::Error>;
+
+    /// Cancels the sale during the dispute window so that every bid, including
+    /// the winning one, can be retracted. Only the admin or the arbiter can cancel.
+    /// In auctions with co-sellers, the admin has to go through `approve_action`.
+    #[execute]
+    fn cancel_sale(reason: String) -> Result<Response, <Self as Auction>::Error>;
+
+    /// Approves the action on behalf of the sender, who has to be one of the
+    /// co-sellers. The action is carried out by the approval that reaches
+    /// the threshold, after which it has to be approved anew to repeat it.
     #[execute]
-    fn claim_proceeds() -> Result<Response, <Self as Auction>::Error>;
+    fn approve_action(action: SellerAction) -> Result<Response, <Self as Auction>::Error>;
+
+    /// Pays out the refund of the given claim to its current owner.
+    #[execute]
+    fn redeem_claim(claim_id: u64) -> Result<Response, <Self as Auction>::Error>;
+
+    /// Refunds the losing bids of up to `limit` bidders, continuing from where the
+    /// previous call stopped. Can be called by anyone once the sale has ended and
+    /// needs to be repeated until the `remaining` attribute is zero.
+    #[execute]
+    fn refund_batch(limit: u8) -> Result<Response, <Self as Auction>::Error>;
+
+    /// Ends the lifecycle of a settled or cancelled sale once the proceeds have been
+    /// claimed and every bid refunded. Sends whatever is left over after the unclaimed
+    /// payouts, e.g. rounding dust, to the beneficiary, sets the contract to migrating
+    /// and tells the factory. Payouts can still be claimed afterwards.
+    #[execute]
+    fn close() -> Result<Response, <Self as Auction>::Error>;
+
+    /// Lets the winner of a settled sale tell the seller where to deliver,
+    /// replacing what was sent before. Only they can view it, see `delivery`.
+    #[execute]
+    fn send_delivery_details(details: String) -> Result<Response, <Self as Auction>::Error>;
+
+    /// Lets the admin of a settled sale show the winner that it was fulfilled,
+    /// e.g. with a tracking number, replacing what was sent before.
+    #[execute]
+    fn send_fulfillment_proof(proof: String) -> Result<Response, <Self as Auction>::Error>;
+
+    /// Registers a contract to receive a [`SettlementHookMsg`] when the sale is
+    /// settled. A hook that fails to handle it doesn't prevent the settlement.
+    #[execute]
+    fn add_settlement_hook(
+        contract: ContractLink<String>
+    ) -> Result<Response, <Self as Auction>::Error>;
+
+    #[execute]
+    fn remove_settlement_hook(address: String) -> Result<Response, <Self as Auction>::Error>;
+
+    #[query]
+    fn view_bid_with_permit(
+        permit: Permit<AuctionPermission>
+    ) -> Result<Uint128, <Self as Auction>::Error>;
 
+    /// The bid of the given bidder. The signer of the permit
+    /// must be either the bidder or the auditor.
     #[query]
-    fn view_bid(
-        address: String,
-        key: String
+    fn view_bid_of_with_permit(
+        bidder: String,
+        permit: Permit<AuctionPermission>
     ) -> Result<Uint128, <Self as Auction>::Error>;
 
+    /// How much the address would have to bid right now to become the highest bidder,
+    /// given its current bid, the minimum increment and the reserve price.
+    #[query]
+    fn min_bid_to_win_with_permit(
+        permit: Permit<AuctionPermission>
+    ) -> Result<Uint128, <Self as Auction>::Error>;
+
+    /// What would happen if the signer of the permit bid the amount right now.
+    /// Nothing is stored, so wallets can use it to check a bid before sending it.
+    #[query]
+    fn simulate_bid(
+        permit: Permit<AuctionPermission>,
+        amount: Uint128
+    ) -> Result<BidSimulation, <Self as Auction>::Error>;
+
+    /// What the address has been paid out and can send to itself with `claim`.
+    #[query]
+    fn view_payout_with_permit(
+        permit: Permit<AuctionPermission>
+    ) -> Result<Uint128, <Self as Auction>::Error>;
+
+    /// The current bid of the address and the maximum that it committed to.
+    #[query]
+    fn view_proxy_bid_with_permit(
+        permit: Permit<AuctionPermission>
+    ) -> Result<ProxyBid, <Self as Auction>::Error>;
+
+    /// The position of the address among all bidders.
+    #[query]
+    fn my_rank_with_permit(
+        permit: Permit<AuctionPermission>
+    ) -> Result<BidRank, <Self as Auction>::Error>;
+
+    /// The payload that the auction was created with. Only the winner
+    /// can view it and only once the sale has been settled.
+    #[query]
+    fn winner_payload_with_permit(
+        permit: Permit<AuctionPermission>
+    ) -> Result<Option<Binary>, <Self as Auction>::Error>;
+
+    /// What the winner and the admin have sent each other
+    /// since the sale was settled. Only the two of them can view it.
+    #[query]
+    fn delivery_with_permit(
+        permit: Permit<AuctionPermission>
+    ) -> Result<Delivery, <Self as Auction>::Error>;
+
+    /// The memo of the bidder's bid. Only the bidder can view it
+    /// and the admin too, once the sale has been settled.
+    #[query]
+    fn view_memo_with_permit(
+        bidder: String,
+        permit: Permit<AuctionPermission>
+    ) -> Result<Option<String>, <Self as Auction>::Error>;
+
     #[query]
     fn active_bids(
         pagination: Pagination
     ) -> Result<PaginatedResponse<Uint128>, <Self as Auction>::Error>;
 
+    /// Same as `active_bids` but authenticated with a permit of the admin
+    /// or the auditor, for auctions where the bids aren't public.
+    #[query]
+    fn active_bids_with_permit(
+        permit: Permit<AuctionPermission>,
+        pagination: Pagination
+    ) -> Result<PaginatedResponse<Uint128>, <Self as Auction>::Error>;
+
+    /// Lists the bids of all bidders. Only for the auditor.
+    #[query]
+    fn audit_bids_with_permit(
+        permit: Permit<AuctionPermission>,
+        pagination: Pagination
+    ) -> Result<PaginatedResponse<BidEntry>, <Self as Auction>::Error>;
+
+    #[query]
+    fn auditor() -> Result<Option<Addr>, <Self as Auction>::Error>;
+
+    #[query]
+    fn pauser() -> Result<Option<Addr>, <Self as Auction>::Error>;
+
+    /// The address nominated to take over the auction, if any.
+    #[query]
+    fn pending_owner() -> Result<Option<Addr>, <Self as Auction>::Error>;
+
+    /// The winner selected by the admin, if the auction has manual winner selection.
+    #[query]
+    fn selected_winner() -> Result<Option<Addr>, <Self as Auction>::Error>;
+
+    /// The penalty in basis points for retracting a bid before the sale has
+    /// ended or `None` if bids can only be retracted after it has ended.
+    #[query]
+    fn early_retraction_penalty() -> Result<Option<u16>, <Self as Auction>::Error>;
+
+    #[query]
+    fn settlement_hooks() -> Result<Vec<ContractLink<Addr>>, <Self as Auction>::Error>;
+
     #[query]
     fn sale_status() -> Result<SaleStatus, <Self as Auction>::Error>;
+
+    /// Everything that the auction was configured with in one response.
+    #[query]
+    fn config() -> Result<AuctionConfig, <Self as Auction>::Error>;
+
+    /// The factory that created the auction, `None` if it was instantiated directly.
+    /// Unlike the `config` query, doesn't query the factory for its fee.
+    #[query]
+    fn factory() -> Result<Option<ContractLink<Addr>>, <Self as Auction>::Error>;
+
+    /// Returns `None` if no bid was accepted with the receipt. Every bid
+    /// and proxy deposit gets the next receipt id, starting from 1.
+    #[query]
+    fn bid_receipt(receipt: u64) -> Result<Option<BidReceipt>, <Self as Auction>::Error>;
+
+    /// How long bids are still accepted for. Doesn't account for the start block.
+    #[query]
+    fn time_remaining() -> Result<TimeRemaining, <Self as Auction>::Error>;
+
+    /// The actions that have been approved by some of the co-sellers but
+    /// not enough of them yet, in the order of their first approval.
+    #[query]
+    fn approvals() -> Result<Vec<PendingApproval>, <Self as Auction>::Error>;
+
+    #[query]
+    fn extension_proposal(proposal: u64) -> Result<ExtensionProposal, <Self as Auction>::Error>;
+
+    #[query]
+    fn state() -> Result<AuctionState, <Self as Auction>::Error>;
+
+    /// Compares the escrow counter against the sum of outstanding bids.
+    #[query]
+    fn invariants() -> Result<Invariants, <Self as Auction>::Error>;
+
+    /// Same as the `status` query but also tells when the status was last changed.
+    #[query]
+    fn status_info() -> Result<StatusInfo, <Self as Auction>::Error>;
+
+    /// How many executes of each kind the auction has processed successfully.
+    #[query]
+    fn metrics() -> Result<Metrics, <Self as Auction>::Error>;
+
+    /// The name and release of the code that the auction is running.
+    #[query]
+    fn version() -> Result<ContractVersion, <Self as Auction>::Error>;
 }
 
-#[derive(Serialize, Deserialize, FadromaSerialize, FadromaDeserialize, PartialEq, Debug)]
+/// The parameters that an auction is instantiated with. New parameters
+/// must be added as optional fields so that older messages remain valid.
+#[derive(Serialize, Deserialize, schemars::JsonSchema, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct Config {
+    /// The version of this struct that the message was created with.
+    pub version: u16,
+    pub admin: Option<String>,
+    pub name: String,
+    pub end_block: u64,
+    /// When set, the sale also ends after the block whose time in seconds
+    /// passes this, if that happens before the end block. The dispute
+    /// window still ends `dispute_blocks` after the end block and the
+    /// factory lists auctions by their end block only.
+    pub end_time: Option<u64>,
+    /// Bids are only accepted from this block onwards. Defaults to the
+    /// block at which the auction was instantiated.
+    pub start_block: Option<u64>,
+    /// Free-form description of the item being sold.
+    pub metadata: Option<String>,
+    /// When set, privacy-sensitive queries fail while
+    /// the contract is paused or migrating.
+    pub guard_queries: Option<bool>,
+    /// The native denomination that bids are made in.
+    /// Defaults to [`Config::DEFAULT_DENOM`].
+    pub denom: Option<Denom>,
+    /// The minimum highest bid required for the sale to go through.
+    /// Otherwise it is settled without a winner and every bid is refunded.
+    pub reserve: Option<Uint128>,
+    /// The minimum amount by which a bid must exceed the current highest one.
+    pub min_increment: Option<Uint128>,
+    /// The address that receives the proceeds. Defaults to the admin.
+    pub beneficiary: Option<String>,
+    /// When set, the winner receives a certificate minted on this contract at settlement.
+    pub receipt_nft: Option<snip721::ReceiptNft>,
+    /// When set, the refunds of losing bidders become claims which
+    /// can be transferred to other addresses once the sale has ended.
+    pub tradable_claims: Option<bool>,
+    /// When set, the auction runs as a raffle instead.
+    pub raffle: Option<RaffleConfig>,
+    /// When set, bids only express interest and the admin selects the winner
+    /// once the sale has ended, e.g. among bidders that passed KYC.
+    pub manual_winner: Option<bool>,
+    /// The number of blocks after the end block during which the proceeds can't
+    /// be claimed and the sale can be cancelled, refunding every bidder.
+    pub dispute_blocks: Option<u64>,
+    /// Can cancel the sale during the dispute window, besides the admin.
+    pub arbiter: Option<String>,
+    /// When set, bids can be retracted before the sale has ended, minus this
+    /// penalty in basis points of the bid which is added to the proceeds.
+    pub early_retraction_penalty: Option<u16>,
+    /// The number of blocks that an address has to wait after placing
+    /// a bid before it can place another one, including proxy bids.
+    pub bid_cooldown: Option<u64>,
+    /// Converts the USD denominated amounts below at the time they are needed.
+    pub oracle: Option<oracle::PriceOracle>,
+    /// A reserve price in USD. The higher of it and `reserve` applies.
+    pub usd_reserve: Option<Decimal>,
+    /// A minimum increment in USD. The higher of it and `min_increment` applies.
+    pub usd_min_increment: Option<Decimal>,
+    /// Delivered privately to the winner once the sale has
+    /// been settled, e.g. a redemption code or a URI.
+    pub winner_payload: Option<Binary>,
+    /// When set, `sale_status` includes the address of the highest bidder.
+    /// Otherwise only the amount of the highest bid is public.
+    pub show_highest_bidder: Option<bool>,
+    /// When set, `sale_status` hides the highest bid and bidder until the
+    /// sale has ended so that the price can't be followed while bidding.
+    pub hide_current_highest: Option<bool>,
+    /// When set, anyone can list the amounts of all bids with `active_bids`.
+    /// Otherwise it requires a permit of the admin.
+    pub public_active_bids: Option<bool>,
+    /// When set, bidders can vote with their bids to extend the sale.
+    pub extension_vote: Option<ExtensionVoteConfig>,
+    /// When set, only accounts can bid and bids sent by
+    /// contracts, or placed on their behalf, are rejected.
+    pub exclude_contracts: Option<bool>,
+    /// When set, bids are made in this token with `bid_from_allowance`
+    /// and everything that is claimed is paid out in it, instead of `denom`.
+    pub bid_token: Option<snip20::BidToken>,
+    /// When set, this amount of `denom` has to be sent when the auction is created.
+    /// It is returned to the admin at settlement, unless the sale is cancelled while
+    /// bids are escrowed, in which case it is split among the bidders by their bids.
+    pub seller_deposit: Option<Uint128>,
+    /// The factory whose fee configuration applies at settlement. Always
+    /// set by the factory. Auctions instantiated directly don't pay fees.
+    pub factory: Option<ContractLink<String>>,
+    /// When set, the proceeds are split among these sellers and claiming them,
+    /// cancelling and extending the sale require their approval.
+    /// Can't be combined with a beneficiary.
+    pub co_sellers: Option<CoSellers<String>>
+}
+
+impl Config {
+    pub const VERSION: u16 = 1;
+    pub const DEFAULT_DENOM: &'static str = "uscrt";
+
+    /// Returns the configured denomination or the default one.
+    pub fn denom(&self) -> Denom {
+        self.denom.clone().unwrap_or_else(||
+            Denom::new(Self::DEFAULT_DENOM).unwrap()
+        )
+    }
+
+    /// Creates a config of the current version with all optional parameters unset.
+    pub fn new(name: impl Into<String>, end_block: u64) -> Self {
+        Self {
+            version: Self::VERSION,
+            admin: None,
+            name: name.into(),
+            end_block,
+            end_time: None,
+            start_block: None,
+            metadata: None,
+            guard_queries: None,
+            denom: None,
+            reserve: None,
+            min_increment: None,
+            beneficiary: None,
+            receipt_nft: None,
+            tradable_claims: None,
+            raffle: None,
+            manual_winner: None,
+            dispute_blocks: None,
+            arbiter: None,
+            early_retraction_penalty: None,
+            bid_cooldown: None,
+            oracle: None,
+            usd_reserve: None,
+            usd_min_increment: None,
+            winner_payload: None,
+            show_highest_bidder: None,
+            hide_current_highest: None,
+            public_active_bids: None,
+            extension_vote: None,
+            exclude_contracts: None,
+            bid_token: None,
+            seller_deposit: None,
+            factory: None,
+            co_sellers: None
+        }
+    }
+
+    /// Configs created by a newer version than this one are rejected
+    /// as they may contain parameters that would otherwise be ignored.
+    pub fn assert_supported(&self) -> Result<(), StdError> {
+        if self.version > Self::VERSION {
+            return Err(StdError::generic_err(format!(
+                "Unsupported config version: {}. Latest supported is {}.",
+                self.version,
+                Self::VERSION
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// The configuration that an auction runs with, with the defaults of the [`Config`]
+/// it was instantiated with applied. The name, metadata and end block are part of
+/// [`SaleStatus`] since the admin can change them.
+#[derive(Serialize, Deserialize, schemars::JsonSchema, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct AuctionConfig {
+    /// Same as in [`SaleStatus`].
+    pub created_at: Option<CreatedAt>,
+    pub end_time: Option<u64>,
+    pub start_block: u64,
+    pub guard_queries: bool,
+    pub denom: Denom,
+    /// Before the USD reserve is applied, if any.
+    pub reserve: Uint128,
+    /// Before the USD minimum increment is applied, if any.
+    pub min_increment: Uint128,
+    /// Whoever receives the proceeds, i.e. the admin unless a beneficiary was set.
+    pub beneficiary: Addr,
+    pub receipt_nft: Option<ContractLink<Addr>>,
+    pub tradable_claims: bool,
+    pub raffle: Option<RaffleConfig>,
+    pub manual_winner: bool,
+    pub dispute_blocks: u64,
+    pub arbiter: Option<Addr>,
+    pub early_retraction_penalty: Option<u16>,
+    pub bid_cooldown: u64,
+    pub oracle: Option<oracle::PriceOracle>,
+    pub usd_reserve: Option<Decimal>,
+    pub usd_min_increment: Option<Decimal>,
+    pub show_highest_bidder: bool,
+    pub hide_current_highest: bool,
+    pub public_active_bids: bool,
+    pub extension_vote: Option<ExtensionVoteConfig>,
+    pub exclude_contracts: bool,
+    pub bid_token: Option<ContractLink<Addr>>,
+    pub seller_deposit: Uint128,
+    pub factory: Option<ContractLink<Addr>>,
+    /// The fee that the factory currently charges at settlement.
+    pub fee: Option<FeeConfig<Addr>>,
+    pub co_sellers: Option<CoSellers<Addr>>
+}
+
+/// Every bid buys tickets and the winner is drawn at random when the proceeds
+/// are claimed, so the chance of winning is proportional to the amount bid.
+/// The whole winning bid and the non-refunded part of every losing one go to the seller.
+#[derive(Serialize, Deserialize, schemars::JsonSchema, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct RaffleConfig {
+    /// Bids must be a multiple of the ticket price.
+    pub ticket_price: Uint128,
+    /// The share of a losing bid that can be retracted after the draw.
+    /// Defaults to none.
+    pub refund_rate: Option<Decimal>
+}
+
+/// Sellers that share the proceeds of an auction and its privileged actions.
+/// The admin keeps the rest of its permissions.
+#[derive(Serialize, Deserialize, FadromaSerialize, FadromaDeserialize, Canonize, schemars::JsonSchema, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct CoSellers<A> {
+    pub sellers: Vec<CoSeller<A>>,
+    /// How many of the sellers have to approve an action.
+    pub threshold: u16
+}
+
+#[derive(Serialize, Deserialize, FadromaSerialize, FadromaDeserialize, Canonize, schemars::JsonSchema, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct CoSeller<A> {
+    pub address: A,
+    /// The share of the proceeds in basis points. The
+    /// shares of all sellers have to add up to [`MAX_BPS`].
+    pub share: u16
+}
+
+impl<A: PartialEq + ToString> CoSellers<A> {
+    pub fn validate(&self) -> Result<(), CoSellerError> {
+        if self.sellers.is_empty() || self.sellers.len() > MAX_CO_SELLERS {
+            return Err(CoSellerError::InvalidCount(self.sellers.len()));
+        }
+
+        for (i, seller) in self.sellers.iter().enumerate() {
+            if seller.share == 0 {
+                return Err(CoSellerError::ZeroShare(seller.address.to_string()));
+            }
+
+            if self.sellers[..i].iter().any(|x| x.address == seller.address) {
+                return Err(CoSellerError::Duplicate(seller.address.to_string()));
+            }
+        }
+
+        let total: u32 = self.sellers.iter().map(|x| u32::from(x.share)).sum();
+        if total != u32::from(MAX_BPS) {
+            return Err(CoSellerError::SharesMismatch(total));
+        }
+
+        if self.threshold == 0 || usize::from(self.threshold) > self.sellers.len() {
+            return Err(CoSellerError::InvalidThreshold(self.threshold));
+        }
+
+        Ok(())
+    }
+}
+
+/// The actions that the co-sellers of an auction approve with `approve_action`.
+#[derive(Serialize, Deserialize, schemars::JsonSchema, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum SellerAction {
+    /// Same as `claim_proceeds`, except that the amount is split among the sellers by their share.
+    ClaimProceeds {
+        amount: Option<Uint128>
+    },
+    /// Same as `cancel_sale`.
+    CancelSale {
+        reason: String
+    },
+    /// Moves the end block of a sale which hasn't finished yet by the given
+    /// number of blocks. Can't be used if the sale has an end time.
+    Extend {
+        blocks: u64
+    }
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct PendingApproval {
+    pub action: SellerAction,
+    pub approvers: Vec<Addr>
+}
+
+/// Bidders propose to extend the sale by a number of blocks and vote for the
+/// proposals with their bids. A proposal is applied as soon as its votes reach
+/// the threshold. Can't be combined with an end time, which isn't extended.
+#[derive(Serialize, Deserialize, schemars::JsonSchema, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct ExtensionVoteConfig {
+    /// The share of the total escrowed funds in basis points
+    /// that has to vote for a proposal for it to be applied.
+    pub threshold: u16,
+    /// The maximum number of blocks that all applied
+    /// proposals together can extend the sale by.
+    pub max_blocks: u64
+}
+
+#[derive(Serialize, Deserialize, FadromaSerialize, FadromaDeserialize, schemars::JsonSchema, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct ExtensionProposal {
+    pub blocks: u64,
+    /// The sum of the bids and unspent proxy deposits of the voters
+    /// at the time that they voted, minus those retracted since.
+    pub votes: Uint128,
+    /// The number of blocks that the sale was extended by once the proposal
+    /// passed. Less than proposed if earlier proposals used up the rest.
+    pub applied: Option<u64>
+}
+
+#[derive(Serialize, Deserialize, FadromaSerialize, FadromaDeserialize, schemars::JsonSchema, PartialEq, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct SaleInfo {
     pub name: String,
-    pub end_block: u64
+    pub end_block: u64,
+    pub metadata: Option<String>
 }
 
 impl_canonize_default!(SaleInfo);
 
-#[derive(Serialize, Deserialize, FadromaSerialize, FadromaDeserialize, PartialEq, Debug)]
+impl SaleInfo {
+    /// The name ends up in the contract label so it is kept short.
+    pub const MAX_NAME_LEN: usize = 64;
+    pub const MAX_METADATA_LEN: usize = 1024;
+
+    /// The end block is the last block in which bids are accepted. The sale is
+    /// finished from the block after it, which is when bids can be retracted
+    /// and the proceeds claimed.
+    #[inline]
+    pub fn is_finished(&self, height: u64) -> bool {
+        height > self.end_block
+    }
+
+    /// Names must be non-empty, at most [`SaleInfo::MAX_NAME_LEN`] bytes long
+    /// and consist of ASCII letters, digits, spaces and basic punctuation.
+    pub fn validate_name(name: &str) -> Result<(), NameError> {
+        if name.trim().is_empty() {
+            return Err(NameError::Empty);
+        }
+
+        if name.len() > Self::MAX_NAME_LEN {
+            return Err(NameError::TooLong { len: name.len() });
+        }
+
+        if let Some(c) = name.chars().find(|c|
+            !c.is_ascii_alphanumeric() && !" -_.,:#'&()".contains(*c)
+        ) {
+            return Err(NameError::InvalidCharacter(c));
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_metadata(metadata: &str) -> Result<(), StdError> {
+        if metadata.len() > Self::MAX_METADATA_LEN {
+            return Err(StdError::generic_err(format!(
+                "Auction metadata is {} bytes long but the maximum is {}.",
+                metadata.len(),
+                Self::MAX_METADATA_LEN
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Tags are short identifiers made up of lowercase letters, digits and dashes.
+pub fn validate_tags(tags: &[String]) -> Result<(), TagError> {
+    if tags.len() > MAX_TAGS {
+        return Err(TagError::TooMany { count: tags.len() });
+    }
+
+    for (i, tag) in tags.iter().enumerate() {
+        if tag.is_empty() || tag.len() > MAX_TAG_LEN || !tag.chars().all(|c|
+            c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-'
+        ) {
+            return Err(TagError::Invalid(tag.clone()));
+        }
+
+        if tags[..i].contains(tag) {
+            return Err(TagError::Duplicate(tag.clone()));
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, FadromaSerialize, FadromaDeserialize, schemars::JsonSchema, PartialEq, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct SaleStatus {
     pub info: SaleInfo,
-    pub current_highest: Uint128,
-    pub is_finished: bool
+    /// Hidden until the sale has ended if the auction was configured so.
+    pub current_highest: Option<Uint128>,
+    /// Only given if the auction was configured to show it.
+    pub highest_bidder: Option<Addr>,
+    /// The number of addresses that have placed a bid.
+    pub bidder_count: u64,
+    /// The number of bids placed, counting every bid and proxy deposit of each
+    /// bidder. Bids raised automatically from a proxy deposit don't count.
+    pub num_bids: u64,
+    pub is_finished: bool,
+    /// Not recorded by auctions instantiated before it was introduced.
+    pub created_at: Option<CreatedAt>
+}
+
+/// The block in which an auction was instantiated.
+#[derive(Serialize, Deserialize, FadromaSerialize, FadromaDeserialize, schemars::JsonSchema, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct CreatedAt {
+    pub height: u64,
+    /// Seconds since the UNIX epoch.
+    pub time: u64
+}
+
+/// Both are zero once the sale has finished.
+#[derive(Serialize, Deserialize, schemars::JsonSchema, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct TimeRemaining {
+    /// The number of blocks after the current one in which bids are accepted.
+    pub blocks: u64,
+    /// The number of seconds until the end time, if the auction has one.
+    pub seconds: Option<u64>
+}
+
+/// When an auction created through the factory ends. Overrides the end block of
+/// the config, which the factory then sets to the resulting height.
+#[derive(Serialize, Deserialize, schemars::JsonSchema, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum AuctionEnd {
+    /// The number of blocks from the one in which the auction is created.
+    Duration { blocks: u64 },
+    AbsoluteHeight { height: u64 }
+}
+
+impl AuctionEnd {
+    pub fn to_height(self, current: u64) -> Result<u64, StdError> {
+        match self {
+            Self::Duration { blocks } => current.checked_add(blocks)
+                .ok_or_else(|| StdError::generic_err("Auction duration is too long.")),
+            Self::AbsoluteHeight { height } => Ok(height)
+        }
+    }
+}
+
+/// The callbacks that the factory sends to the contracts subscribed to it.
+#[derive(Serialize, Deserialize, schemars::JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum SubscriberMsg {
+    AuctionCreated {
+        auction: ContractLink<Addr>,
+        info: SaleInfo
+    }
+}
+
+/// The callback that an auction sends to its settlement hooks. The price
+/// is the winning bid before any fees and zero if no one placed a bid.
+#[derive(Serialize, Deserialize, schemars::JsonSchema, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum SettlementHookMsg {
+    AuctionSettled {
+        auction: ContractLink<Addr>,
+        winner: Option<Addr>,
+        price: Uint128,
+        /// What the price is paid in, the address of the
+        /// token for auctions that take bids in one.
+        denom: String
+    }
+}
+
+/// The messages of the factory, apart from those of the admin module. They are
+/// defined by the factory contract itself and repeated here so that auctions
+/// and other contracts can send them without depending on the factory crate.
+#[derive(Serialize, Deserialize, schemars::JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
+pub enum FactoryExecuteMsg {
+    CreateAuction {
+        config: Config,
+        tags: Option<Vec<String>>,
+        end: Option<AuctionEnd>
+    },
+    CloneAuction {
+        index: u64,
+        new_end_block: u64
+    },
+    Subscribe {
+        code_hash: String
+    },
+    RemoveSubscriber {
+        address: String
+    },
+    SetFeeConfig {
+        fee: Option<FeeConfig<String>>
+    },
+    ProcessEnded {
+        limit: u8
+    },
+    SetKeeperReward {
+        reward: Option<Coin>
+    },
+    SetCreatorLimit {
+        limit: Option<u64>
+    },
+    SetDefaultAuctionAdmin {
+        admin: Option<String>
+    },
+    ArchiveAuctions {
+        indices: Vec<u64>
+    },
+    AuctionClosed {
+        settled: bool
+    },
+    EndBlockChanged {
+        end_block: u64
+    },
+    RegisterExisting {
+        contract: ContractLink<String>
+    },
+    /// Same as [`SettlementHookMsg::AuctionSettled`].
+    AuctionSettled {
+        auction: ContractLink<Addr>,
+        winner: Option<Addr>,
+        price: Uint128,
+        denom: String
+    },
+    SetCurrencyPolicy {
+        policy: Option<CurrencyPolicy<String>>
+    }
+}
+
+/// See [`FactoryExecuteMsg`].
+#[derive(Serialize, Deserialize, schemars::JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum FactoryQueryMsg {
+    ListAuctions {
+        pagination: Pagination
+    },
+    ListArchive {
+        pagination: Pagination
+    },
+    ListEndingSoon {
+        before_block: u64,
+        pagination: Pagination
+    },
+    ListUnsettled {
+        pagination: Pagination
+    },
+    ListAuctionsByTag {
+        tag: String,
+        pagination: Pagination
+    },
+    ExportRegistry {
+        permit: Permit<FactoryPermission>,
+        pagination: Pagination
+    },
+    Subscribers { },
+    AuctionCode { },
+    Version { },
+    FeeConfig { },
+    KeeperReward { },
+    CreatorLimit { },
+    DefaultAuctionAdmin { },
+    ActiveAuctions {
+        creator: String
+    },
+    IsClosed {
+        index: u64
+    },
+    TopSales {
+        denom: String,
+        limit: u8
+    },
+    VolumeStats { },
+    CurrencyPolicy { }
+}
+
+/// The share of the proceeds of every auction that goes to the platform.
+/// Auctions look it up when they are settled so changes to it
+/// apply to all auctions which haven't been settled yet.
+#[derive(Serialize, Deserialize, FadromaSerialize, FadromaDeserialize, Canonize, schemars::JsonSchema, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct FeeConfig<A> {
+    pub rate: Decimal,
+    pub recipient: A
+}
+
+impl<A> FeeConfig<A> {
+    pub const MAX_RATE_PERCENT: u64 = 20;
+
+    pub fn validate(&self) -> Result<(), StdError> {
+        if self.rate > Decimal::percent(Self::MAX_RATE_PERCENT) {
+            return Err(StdError::generic_err(format!(
+                "Fee rate cannot be more than {}%.",
+                Self::MAX_RATE_PERCENT
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// The currencies that auctions created through the factory can take bids in.
+/// The factory checks the currency of every auction that it creates against it
+/// and sets it in the config, so auctions don't need to know about the policy.
+#[derive(Serialize, Deserialize, FadromaSerialize, FadromaDeserialize, Canonize, schemars::JsonSchema, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct CurrencyPolicy<A> {
+    /// Native denoms. The first one is given to auctions
+    /// that don't take bids in a token and don't set a denom.
+    pub denoms: Vec<Denom>,
+    /// Tokens that can be used as the bid token. The code hash
+    /// of the token is taken from here rather than from the config.
+    pub tokens: Vec<ContractLink<A>>
+}
+
+impl<A> CurrencyPolicy<A> {
+    pub fn validate(&self) -> Result<(), CurrencyError> {
+        if self.denoms.is_empty() && self.tokens.is_empty() {
+            return Err(CurrencyError::Empty);
+        }
+
+        for count in [self.denoms.len(), self.tokens.len()] {
+            if count > MAX_CURRENCIES {
+                return Err(CurrencyError::TooMany(count));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Accounting figures of an auction that must always agree.
+/// A mismatch means that funds were not tracked correctly.
+#[derive(Serialize, Deserialize, schemars::JsonSchema, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct Invariants {
+    /// The funds held in escrow as tracked by bids, refunds and claims.
+    pub total_escrowed: Uint128,
+    /// The sum of the balances and unspent proxy deposits of all bidders.
+    pub outstanding_bids: Uint128,
+    pub holds: bool
+}
+
+/// Counters of the executes that an auction has processed. Failed
+/// executes are reverted along with everything else they changed.
+#[derive(Serialize, Deserialize, FadromaSerialize, FadromaDeserialize, schemars::JsonSchema, Default, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct Metrics {
+    /// Every kind of bid, including proxy bids and bids placed on behalf of others.
+    pub bids: u64,
+    pub retractions: u64,
+    /// Claims of payouts, of the proceeds and redeemed refund claims.
+    pub claims: u64,
+    /// Viewing keys created, set or revoked and permits revoked.
+    pub key_operations: u64
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct StatusInfo {
+    /// Paused and migrating statuses include the reason for the change.
+    pub status: ContractStatus<Addr>,
+    /// Not set if the status was never changed.
+    pub changed_at: Option<StatusChange>
+}
+
+#[derive(Serialize, Deserialize, FadromaSerialize, FadromaDeserialize, schemars::JsonSchema, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct StatusChange {
+    pub height: u64,
+    /// Seconds since the UNIX epoch.
+    pub time: u64
+}
+
+/// The lifecycle stage of an auction.
+#[derive(Serialize, Deserialize, schemars::JsonSchema, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum AuctionState {
+    /// The start block hasn't been reached yet.
+    NotStarted,
+    /// Bids are being accepted.
+    Active,
+    /// The end block has passed but the proceeds haven't been claimed yet.
+    Ended,
+    /// The proceeds have been claimed.
+    Settled,
+    /// The contract was permanently disabled using the killswitch
+    /// or the sale was cancelled during the dispute window.
+    Cancelled,
+    /// The contract was temporarily disabled using the killswitch.
+    Paused
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct BidEntry {
+    pub address: Addr,
+    pub amount: Uint128
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct ProxyBid {
+    /// The amount that is currently bid.
+    pub active: Uint128,
+    /// The active amount plus the part of the proxy deposit that wasn't bid yet.
+    pub committed: Uint128
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct BidRank {
+    /// Where 0 is the highest bid. `None` if the address has no bid.
+    pub rank: Option<u64>,
+    /// How much the highest bid exceeds the bid of the address.
+    pub behind_highest: Uint128
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct BidSimulation {
+    pub accepted: bool,
+    /// Why the bid would be rejected.
+    pub error: Option<String>,
+    /// Whether the bidder would have the highest bid afterwards, after any proxy
+    /// bids have been raised in response. Always false in raffles.
+    pub would_be_highest: bool,
+    /// The bid of the bidder afterwards, or their current one if it would be rejected.
+    pub balance: Uint128,
+    /// The reserve price that applies, including the USD one.
+    pub reserve: Uint128,
+    /// The minimum increment that applies, including the USD one.
+    pub min_increment: Uint128,
+    /// The share of the proceeds that the factory currently deducts as a fee.
+    pub fee_rate: Decimal
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct BidResult {
+    /// The total of the bids of the sender so far.
+    pub balance: Uint128,
+    /// Identifies this bid, e.g. in support requests. Can be looked up with `bid_receipt`.
+    pub receipt: u64
+}
+
+/// When a bid was accepted. Doesn't reveal the bidder or the amount.
+#[derive(Serialize, Deserialize, FadromaSerialize, FadromaDeserialize, schemars::JsonSchema, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct BidReceipt {
+    pub height: u64,
+    /// In seconds.
+    pub time: u64
+}
+
+/// Sent privately between the winner and the seller after settlement.
+#[derive(Serialize, Deserialize, schemars::JsonSchema, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct Delivery {
+    /// Sent by the winner.
+    pub details: Option<String>,
+    /// Sent by the seller.
+    pub fulfillment_proof: Option<String>
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct RetractResult {
+    /// Includes the share of the seller deposit if the sale was cancelled.
+    pub refunded: Uint128,
+    /// Deducted from the refund of a bid retracted before the sale has ended.
+    pub penalty: Uint128
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct ProceedsResult {
+    /// `None` if no bids were made.
+    pub winner: Option<Addr>,
+    /// What the beneficiary received after the fee.
+    pub proceeds: Uint128,
+    /// Only taken when the sale is settled by the first claim.
+    pub fee: Uint128,
+    /// What is left to be claimed.
+    pub remaining: Uint128
+}
+
+/// The right to the refund of a bid. The id is assigned
+/// in the order in which bidders placed their first bid.
+#[derive(Serialize, Deserialize, schemars::JsonSchema, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct Claim {
+    pub id: u64,
+    pub owner: Addr,
+    pub amount: Uint128
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum FactoryPermission {
+    ExportRegistry
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum AuctionPermission {
+    ViewBid,
+    ViewPayload,
+    ViewActiveBids,
+    ViewMemo,
+    ViewDelivery
 }
 
 #[derive(Serialize, Deserialize, schemars::JsonSchema, Debug)]
@@ -77,4 +1254,10 @@ pub struct PaginatedResponse<T: Serialize> {
 
 impl Pagination {
     pub const LIMIT: u8 = 30;
+
+    /// The requested limit, capped at [`Pagination::LIMIT`].
+    #[inline]
+    pub fn limit(&self) -> u8 {
+        self.limit.min(Self::LIMIT)
+    }
 }
@@ -0,0 +1,30 @@
+//! The interface of the price oracle that USD denominated
+//! amounts are converted into the bid denomination with.
+
+use fadroma::{schemars, cosmwasm_std::Decimal};
+use serde::{Serialize, Deserialize};
+
+/// The oracle contract and how old its prices may be.
+#[derive(Serialize, Deserialize, schemars::JsonSchema, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct PriceOracle {
+    pub address: String,
+    pub code_hash: String,
+    /// Prices last updated more than this many seconds ago are rejected.
+    pub max_age: u64
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Price { denom: String }
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct PriceResponse {
+    /// The USD value of one unit of the denomination, e.g. 1uscrt.
+    pub rate: Decimal,
+    /// When the price was last updated, in seconds since the Unix epoch.
+    pub updated_at: u64
+}
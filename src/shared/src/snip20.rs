@@ -0,0 +1,29 @@
+//! The part of the SNIP-20 interface that auctions use to take
+//! bids in a token and to pay out what is claimed in it.
+
+use fadroma::{schemars, cosmwasm_std::Uint128};
+use serde::{Serialize, Deserialize};
+
+/// An SNIP-20 token that bids are made in instead of native coins.
+#[derive(Serialize, Deserialize, schemars::JsonSchema, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct BidToken {
+    pub address: String,
+    pub code_hash: String
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    Transfer {
+        recipient: String,
+        amount: Uint128,
+        memo: Option<String>
+    },
+    TransferFrom {
+        owner: String,
+        recipient: String,
+        amount: Uint128,
+        memo: Option<String>
+    }
+}
@@ -0,0 +1,36 @@
+//! The part of the SNIP-721 interface that auctions use
+//! to mint a certificate for the winner of the sale.
+
+use fadroma::schemars;
+use serde::{Serialize, Deserialize};
+
+/// An SNIP-721 contract on which the auction has been made a minter.
+#[derive(Serialize, Deserialize, schemars::JsonSchema, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct ReceiptNft {
+    pub address: String,
+    pub code_hash: String
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    MintNft {
+        token_id: Option<String>,
+        owner: Option<String>,
+        public_metadata: Option<Metadata>,
+        memo: Option<String>
+    }
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema, Default, PartialEq, Debug)]
+pub struct Metadata {
+    pub token_uri: Option<String>,
+    pub extension: Option<Extension>
+}
+
+#[derive(Serialize, Deserialize, schemars::JsonSchema, Default, PartialEq, Debug)]
+pub struct Extension {
+    pub name: Option<String>,
+    pub description: Option<String>
+}
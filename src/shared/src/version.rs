@@ -0,0 +1,98 @@
+//! The name and release of the code that a contract is running. Unlike the
+//! storage [`layout`](crate::layout), which only changes when the stored entries
+//! do, the version changes with every release, so that a deployed contract can
+//! tell what it is running and a migration can refuse code that isn't an upgrade.
+
+use fadroma::{
+    schemars,
+    storage,
+    cosmwasm_std::{StdError, StdResult, Storage},
+    bin_serde::{FadromaSerialize, FadromaDeserialize}
+};
+use serde::{Serialize, Deserialize};
+
+/// The name that the auction records itself under.
+pub const AUCTION_CONTRACT_NAME: &str = "auction";
+/// The name that the factory records itself under.
+pub const FACTORY_CONTRACT_NAME: &str = "factory";
+/// Both contracts are released together with this crate.
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// Not versioned by the layout so that it can always be found.
+const KEY: &[u8] = b"contract_version";
+
+/// What a contract was instantiated or last migrated with.
+#[derive(
+    Serialize, Deserialize, FadromaSerialize, FadromaDeserialize,
+    schemars::JsonSchema, Clone, PartialEq, Debug
+)]
+#[serde(rename_all = "snake_case")]
+pub struct ContractVersion {
+    pub contract: String,
+    /// A semantic version, e.g. `1.2.0`.
+    pub version: String
+}
+
+impl ContractVersion {
+    /// The name given with the version of this release.
+    pub fn current(contract: &str) -> Self {
+        Self {
+            contract: contract.into(),
+            version: CONTRACT_VERSION.into()
+        }
+    }
+}
+
+/// Records that the contract runs the current release.
+/// Called when the contract is instantiated or migrated.
+pub fn save(storage: &mut dyn Storage, contract: &str) -> StdResult<()> {
+    storage::save(storage, KEY, &ContractVersion::current(contract))
+}
+
+/// `None` for contracts instantiated before the version was recorded.
+pub fn load(storage: &dyn Storage) -> StdResult<Option<ContractVersion>> {
+    storage::load(storage, KEY)
+}
+
+/// Records the current release and returns the one that the contract was running.
+/// Fails if the contract is a different one or if it runs a newer release, since
+/// that would be a downgrade. Contracts that didn't record a version yet are
+/// upgraded, the storage layout decides whether they can be.
+pub fn migrate(storage: &mut dyn Storage, contract: &str) -> StdResult<Option<ContractVersion>> {
+    let from = load(storage)?;
+
+    if let Some(from) = &from {
+        if from.contract != contract {
+            return Err(StdError::generic_err(format!(
+                "Can't migrate {} to {}.",
+                from.contract,
+                contract
+            )));
+        }
+
+        if parse(&from.version)? > parse(CONTRACT_VERSION)? {
+            return Err(StdError::generic_err(format!(
+                "Can't migrate from version {} to the older {}.",
+                from.version,
+                CONTRACT_VERSION
+            )));
+        }
+    }
+
+    save(storage, contract)?;
+
+    Ok(from)
+}
+
+// Only major, minor and patch are compared, anything after them is ignored.
+fn parse(version: &str) -> StdResult<(u64, u64, u64)> {
+    let core = version.split(['-', '+']).next().unwrap_or_default();
+    let parts = core.split('.')
+        .map(|x| x.parse::<u64>())
+        .collect::<Result<Vec<_>, _>>();
+
+    match parts.as_deref() {
+        Ok([major, minor, patch]) => Ok((*major, *minor, *patch)),
+        _ => Err(StdError::generic_err(format!("Invalid version: {}.", version)))
+    }
+}
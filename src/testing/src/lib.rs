@@ -0,0 +1,879 @@
+//! Ensemble harnesses of the auction and the factory together with a [`Suite`]
+//! that sets them up, so that contracts which build on them can be tested
+//! the same way that the contracts of this workspace are.
+
+use fadroma::{
+    core::*,
+    ensemble::{
+        ContractEnsemble, ContractHarness,
+        MockEnv, EnsembleResult, EnsembleError, ExecuteResponse, AnyResult,
+        anyhow::anyhow
+    },
+    cosmwasm_std::{
+        DepsMut, Deps, Env, MessageInfo, Addr, Response, Binary, Coin, Reply, Storage, Empty,
+        Uint128, Decimal, WasmMsg, WasmQuery, QueryRequest, Querier, QuerierWrapper, QuerierResult,
+        SystemResult, SystemError, ContractInfoResponse, StdError, StdResult,
+        to_binary, from_binary, to_vec, from_slice
+    },
+    serde::{Serialize, Deserialize}
+};
+use ::factory::factory::{self, AuctionEntry};
+use auction::auction;
+use shared::{
+    Config, Pagination, PaginatedResponse, SaleStatus, SettlementHookMsg, SubscriberMsg, snip721, oracle,
+    errors::ContractError
+};
+
+/// The address that the factory of a [`Suite`] is instantiated at.
+pub const FACTORY: &str = "factory";
+/// The admin of the auctions created with [`config`].
+pub const ADMIN: &str = "admin";
+
+pub struct Auction;
+
+impl ContractHarness for Auction {
+    fn instantiate(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: Binary
+    ) -> AnyResult<Response> {
+        let querier = ContractInfoQuerier(&*deps.querier);
+        let deps = DepsMut { storage: deps.storage, api: deps.api, querier: QuerierWrapper::new(&querier) };
+
+        let resp = auction::instantiate(deps, env, info, from_binary(&msg)?)?;
+
+        Ok(resp)
+    }
+
+    fn execute(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: Binary
+    ) -> AnyResult<Response> {
+        let querier = ContractInfoQuerier(&*deps.querier);
+        let deps = DepsMut { storage: deps.storage, api: deps.api, querier: QuerierWrapper::new(&querier) };
+
+        let resp = auction::execute(deps, env, info, from_binary(&msg)?)?;
+
+        Ok(resp)
+    }
+
+    fn query(
+        &self,
+        deps: Deps,
+        env: Env,
+        msg: Binary
+    ) -> AnyResult<Binary> {
+        let querier = ContractInfoQuerier(&*deps.querier);
+        let deps = Deps { storage: deps.storage, api: deps.api, querier: QuerierWrapper::new(&querier) };
+
+        let resp = auction::query(deps, env, from_binary(&msg)?)?;
+
+        Ok(resp)
+    }
+
+    fn reply(&self, deps: DepsMut, env: Env, reply: Reply) -> AnyResult<Response> {
+        let resp = auction::Contract::reply(deps, env, reply)?;
+
+        Ok(resp)
+    }
+}
+
+/// The ensemble doesn't implement contract info queries, so this answers them
+/// with whether the address belongs to an instance and passes the rest through.
+struct ContractInfoQuerier<'a>(&'a dyn Querier);
+
+impl Querier for ContractInfoQuerier<'_> {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        let Ok(QueryRequest::Wasm(WasmQuery::ContractInfo { contract_addr })) =
+            from_slice::<QueryRequest<Empty>>(bin_request)
+        else {
+            return self.0.raw_query(bin_request);
+        };
+
+        // Only instances can be queried at all, whatever the message.
+        let probe = QueryRequest::<Empty>::Wasm(WasmQuery::Smart {
+            contract_addr: contract_addr.clone(),
+            code_hash: String::new(),
+            msg: Binary::default()
+        });
+
+        match self.0.raw_query(&to_vec(&probe).unwrap()) {
+            SystemResult::Err(SystemError::NoSuchContract { .. }) =>
+                SystemResult::Err(SystemError::NoSuchContract { addr: contract_addr }),
+            _ => SystemResult::Ok(to_binary(&ContractInfoResponse::new(0, "")).into())
+        }
+    }
+}
+
+/// Forwards the message that it is executed with to another contract
+/// together with the funds it was sent, so that contracts can bid.
+pub struct Forwarder;
+
+/// The message of [`Forwarder`].
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "fadroma::serde", rename_all = "snake_case")]
+pub struct ForwardMsg {
+    pub contract: ContractLink<Addr>,
+    pub msg: Binary
+}
+
+impl ContractHarness for Forwarder {
+    fn instantiate(
+        &self,
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Binary
+    ) -> AnyResult<Response> {
+        Ok(Response::default())
+    }
+
+    fn execute(
+        &self,
+        _deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        msg: Binary
+    ) -> AnyResult<Response> {
+        let ForwardMsg { contract, msg } = from_binary(&msg)?;
+
+        Ok(Response::default().add_message(WasmMsg::Execute {
+            contract_addr: contract.address.into_string(),
+            code_hash: contract.code_hash,
+            msg,
+            funds: info.funds
+        }))
+    }
+
+    fn query(
+        &self,
+        _deps: Deps,
+        _env: Env,
+        _msg: Binary
+    ) -> AnyResult<Binary> {
+        Err(StdError::generic_err("Forwarder has no queries.").into())
+    }
+}
+
+pub struct Factory;
+
+impl ContractHarness for Factory {
+    fn instantiate(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: Binary
+    ) -> AnyResult<Response> {
+        let resp = factory::instantiate(deps, env, info, from_binary(&msg)?)?;
+
+        Ok(resp)
+    }
+
+    fn execute(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: Binary
+    ) -> AnyResult<Response> {
+        let resp = factory::execute(deps, env, info, from_binary(&msg)?)?;
+
+        Ok(resp)
+    }
+
+    fn query(
+        &self,
+        deps: Deps,
+        env: Env,
+        msg: Binary
+    ) -> AnyResult<Binary> {
+        let resp = factory::query(deps, env, from_binary(&msg)?)?;
+
+        Ok(resp)
+    }
+
+    fn reply(&self, deps: DepsMut, env: Env, reply: Reply) -> AnyResult<Response> {
+        let resp = factory::Contract::reply(deps, env, reply)?;
+
+        Ok(resp)
+    }
+}
+
+/// Runs the code of the [`Factory`] without instantiating it, so that a test can
+/// seed its storage with the layout of an earlier release and migrate it.
+pub struct UninitializedFactory;
+
+impl ContractHarness for UninitializedFactory {
+    fn instantiate(
+        &self,
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Binary
+    ) -> AnyResult<Response> {
+        Ok(Response::default())
+    }
+
+    fn execute(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: Binary
+    ) -> AnyResult<Response> {
+        Factory.execute(deps, env, info, msg)
+    }
+
+    fn query(
+        &self,
+        deps: Deps,
+        env: Env,
+        msg: Binary
+    ) -> AnyResult<Binary> {
+        Factory.query(deps, env, msg)
+    }
+
+    fn reply(&self, deps: DepsMut, env: Env, reply: Reply) -> AnyResult<Response> {
+        Factory.reply(deps, env, reply)
+    }
+}
+
+/// Instantiates like an auction but claims to have a different code hash.
+pub struct Impostor;
+
+impl ContractHarness for Impostor {
+    fn instantiate(
+        &self,
+        _deps: DepsMut,
+        env: Env,
+        _info: MessageInfo,
+        _msg: Binary
+    ) -> AnyResult<Response> {
+        Ok(Response::default().set_data(to_binary(&ContractLink {
+            address: env.contract.address,
+            code_hash: "impostor".to_string()
+        })?))
+    }
+
+    fn execute(
+        &self,
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Binary
+    ) -> AnyResult<Response> {
+        Err(anyhow!("Impostor doesn't handle executes"))
+    }
+
+    fn query(
+        &self,
+        _deps: Deps,
+        _env: Env,
+        _msg: Binary
+    ) -> AnyResult<Binary> {
+        Err(anyhow!("Impostor doesn't handle queries"))
+    }
+}
+
+/// Instantiates successfully but doesn't return the link to itself.
+pub struct Mute;
+
+impl ContractHarness for Mute {
+    fn instantiate(
+        &self,
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Binary
+    ) -> AnyResult<Response> {
+        Ok(Response::default())
+    }
+
+    fn execute(
+        &self,
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Binary
+    ) -> AnyResult<Response> {
+        Err(anyhow!("Mute doesn't handle executes"))
+    }
+
+    fn query(
+        &self,
+        _deps: Deps,
+        _env: Env,
+        _msg: Binary
+    ) -> AnyResult<Binary> {
+        Err(anyhow!("Mute doesn't handle queries"))
+    }
+}
+
+/// Records the names of the auctions that the factory notifies it about.
+pub struct Subscriber {
+    pub fail: bool
+}
+
+impl ContractHarness for Subscriber {
+    fn instantiate(
+        &self,
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Binary
+    ) -> AnyResult<Response> {
+        Ok(Response::default())
+    }
+
+    fn execute(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: Binary
+    ) -> AnyResult<Response> {
+        if self.fail {
+            return Err(StdError::generic_err("Subscriber failed.").into());
+        }
+
+        let SubscriberMsg::AuctionCreated { info, .. } = from_binary(&msg)?;
+
+        let mut names: Vec<String> = deps.storage.get(b"names")
+            .map(|x| from_slice(&x))
+            .transpose()?
+            .unwrap_or_default();
+        names.push(info.name);
+
+        deps.storage.set(b"names", &to_vec(&names)?);
+
+        Ok(Response::default())
+    }
+
+    fn query(
+        &self,
+        deps: Deps,
+        _env: Env,
+        _msg: Binary
+    ) -> AnyResult<Binary> {
+        Ok(deps.storage.get(b"names").unwrap_or(b"[]".to_vec()).into())
+    }
+}
+
+/// Records the callbacks that auctions send to it when they are settled.
+pub struct Hook {
+    pub fail: bool
+}
+
+impl ContractHarness for Hook {
+    fn instantiate(
+        &self,
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Binary
+    ) -> AnyResult<Response> {
+        Ok(Response::default())
+    }
+
+    fn execute(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: Binary
+    ) -> AnyResult<Response> {
+        if self.fail {
+            return Err(StdError::generic_err("Hook failed.").into());
+        }
+
+        let msg: SettlementHookMsg = from_binary(&msg)?;
+
+        let mut received: Vec<SettlementHookMsg> = deps.storage.get(b"received")
+            .map(|x| from_slice(&x))
+            .transpose()?
+            .unwrap_or_default();
+        received.push(msg);
+
+        deps.storage.set(b"received", &to_vec(&received)?);
+
+        Ok(Response::default())
+    }
+
+    fn query(
+        &self,
+        deps: Deps,
+        _env: Env,
+        _msg: Binary
+    ) -> AnyResult<Binary> {
+        Ok(deps.storage.get(b"received").unwrap_or(b"[]".to_vec()).into())
+    }
+}
+
+/// Keeps the mint messages it receives so that they can be queried.
+pub struct Nft;
+
+impl ContractHarness for Nft {
+    fn instantiate(
+        &self,
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Binary
+    ) -> AnyResult<Response> {
+        Ok(Response::default())
+    }
+
+    fn execute(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: Binary
+    ) -> AnyResult<Response> {
+        let snip721::ExecuteMsg::MintNft {
+            token_id,
+            owner,
+            public_metadata,
+            ..
+        } = from_binary(&msg)?;
+
+        let mut minted: Vec<(String, String, snip721::Metadata)> = deps.storage.get(b"minted")
+            .map(|x| from_slice(&x))
+            .transpose()?
+            .unwrap_or_default();
+        minted.push((token_id.unwrap(), owner.unwrap(), public_metadata.unwrap()));
+
+        deps.storage.set(b"minted", &to_vec(&minted)?);
+
+        Ok(Response::default())
+    }
+
+    fn query(
+        &self,
+        deps: Deps,
+        _env: Env,
+        _msg: Binary
+    ) -> AnyResult<Binary> {
+        Ok(deps.storage.get(b"minted").unwrap_or(b"[]".to_vec()).into())
+    }
+}
+
+/// The messages of [`Snip20`]. A subset of the SNIP-20 standard
+/// with the same encoding, apart from `mint` which anyone can call.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "fadroma::serde", rename_all = "snake_case")]
+pub enum Snip20Msg {
+    Mint {
+        recipient: String,
+        amount: Uint128
+    },
+    Transfer {
+        recipient: String,
+        amount: Uint128
+    },
+    Send {
+        recipient: String,
+        recipient_code_hash: Option<String>,
+        amount: Uint128,
+        msg: Option<Binary>
+    },
+    IncreaseAllowance {
+        spender: String,
+        amount: Uint128
+    },
+    TransferFrom {
+        owner: String,
+        recipient: String,
+        amount: Uint128
+    }
+}
+
+/// The callback that [`Snip20`] sends to the recipient of a `send`.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "fadroma::serde", rename_all = "snake_case")]
+pub enum Snip20ReceiverMsg {
+    Receive {
+        sender: Addr,
+        from: Addr,
+        amount: Uint128,
+        msg: Option<Binary>
+    }
+}
+
+/// Keeps balances and allowances without any authentication. Its
+/// only query returns the balance of the address it is sent.
+pub struct Snip20;
+
+impl Snip20 {
+    fn balance_key(address: &str) -> Vec<u8> {
+        [b"balance:", address.as_bytes()].concat()
+    }
+
+    fn balance(storage: &dyn Storage, address: &str) -> StdResult<Uint128> {
+        storage.get(&Self::balance_key(address))
+            .map(|x| from_slice(&x))
+            .transpose()
+            .map(Option::unwrap_or_default)
+    }
+
+    fn update(
+        storage: &mut dyn Storage,
+        address: &str,
+        update: impl FnOnce(Uint128) -> StdResult<Uint128>
+    ) -> StdResult<()> {
+        let balance = update(Self::balance(storage, address)?)?;
+
+        storage.set(&Self::balance_key(address), &to_vec(&balance)?);
+
+        Ok(())
+    }
+
+    fn allowance_key(owner: &str, spender: &str) -> Vec<u8> {
+        [b"allowance:", owner.as_bytes(), b":", spender.as_bytes()].concat()
+    }
+
+    fn allowance(storage: &dyn Storage, owner: &str, spender: &str) -> StdResult<Uint128> {
+        storage.get(&Self::allowance_key(owner, spender))
+            .map(|x| from_slice(&x))
+            .transpose()
+            .map(Option::unwrap_or_default)
+    }
+
+    fn transfer(storage: &mut dyn Storage, from: &str, to: &str, amount: Uint128) -> StdResult<()> {
+        Self::update(storage, from, |x| x.checked_sub(amount)
+            .map_err(|_| StdError::generic_err("Insufficient funds.")))?;
+
+        Self::update(storage, to, |x| Ok(x.checked_add(amount)?))
+    }
+}
+
+impl ContractHarness for Snip20 {
+    fn instantiate(
+        &self,
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Binary
+    ) -> AnyResult<Response> {
+        Ok(Response::default())
+    }
+
+    fn execute(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        msg: Binary
+    ) -> AnyResult<Response> {
+        match from_binary(&msg)? {
+            Snip20Msg::Mint { recipient, amount } => {
+                Self::update(deps.storage, &recipient, |x| Ok(x.checked_add(amount)?))?;
+            }
+            Snip20Msg::Transfer { recipient, amount } => {
+                Self::transfer(deps.storage, info.sender.as_str(), &recipient, amount)?;
+            }
+            Snip20Msg::Send { recipient, recipient_code_hash, amount, msg } => {
+                Self::transfer(deps.storage, info.sender.as_str(), &recipient, amount)?;
+
+                let Some(code_hash) = recipient_code_hash else {
+                    return Ok(Response::default());
+                };
+
+                return Ok(Response::default().add_message(WasmMsg::Execute {
+                    contract_addr: recipient,
+                    code_hash,
+                    msg: to_binary(&Snip20ReceiverMsg::Receive {
+                        sender: info.sender.clone(),
+                        from: info.sender,
+                        amount,
+                        msg
+                    })?,
+                    funds: vec![]
+                }));
+            }
+            Snip20Msg::IncreaseAllowance { spender, amount } => {
+                let allowance = Self::allowance(deps.storage, info.sender.as_str(), &spender)?
+                    .checked_add(amount)?;
+
+                deps.storage.set(&Self::allowance_key(info.sender.as_str(), &spender), &to_vec(&allowance)?);
+            }
+            Snip20Msg::TransferFrom { owner, recipient, amount } => {
+                let allowance = Self::allowance(deps.storage, &owner, info.sender.as_str())?
+                    .checked_sub(amount)
+                    .map_err(|_| StdError::generic_err("Insufficient allowance."))?;
+
+                deps.storage.set(&Self::allowance_key(&owner, info.sender.as_str()), &to_vec(&allowance)?);
+                Self::transfer(deps.storage, &owner, &recipient, amount)?;
+            }
+        }
+
+        Ok(Response::default())
+    }
+
+    fn query(
+        &self,
+        deps: Deps,
+        _env: Env,
+        msg: Binary
+    ) -> AnyResult<Binary> {
+        let address: String = from_binary(&msg)?;
+
+        Ok(to_binary(&Self::balance(deps.storage, &address)?)?)
+    }
+}
+
+/// Returns the price that it was last executed with for any denomination.
+pub struct Oracle;
+
+impl ContractHarness for Oracle {
+    fn instantiate(
+        &self,
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Binary
+    ) -> AnyResult<Response> {
+        Ok(Response::default())
+    }
+
+    fn execute(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: Binary
+    ) -> AnyResult<Response> {
+        let price: oracle::PriceResponse = from_binary(&msg)?;
+        deps.storage.set(b"price", &to_vec(&price)?);
+
+        Ok(Response::default())
+    }
+
+    fn query(
+        &self,
+        deps: Deps,
+        _env: Env,
+        msg: Binary
+    ) -> AnyResult<Binary> {
+        let oracle::QueryMsg::Price { .. } = from_binary(&msg)?;
+
+        deps.storage.get(b"price")
+            .map(Binary::from)
+            .ok_or_else(|| StdError::generic_err("No price has been set.").into())
+    }
+}
+
+/// A config with only the required parameters and [`ADMIN`] as the admin.
+pub fn config(name: impl Into<String>, end_block: u64) -> Config {
+    Config {
+        admin: Some(ADMIN.into()),
+        ..Config::new(name, end_block)
+    }
+}
+
+/// Extracts the error returned by either the auction or the factory.
+pub fn contract_error(err: EnsembleError) -> ContractError {
+    let err = match err.unwrap_contract_error().downcast::<auction::Error>() {
+        Ok(auction::Error::Auction(err) | auction::Error::ViewingKeys(err)) => return err,
+        Ok(err) => panic!("Unexpected auction error: {}", err),
+        Err(err) => err
+    };
+
+    let err = match err.downcast::<factory::Error>() {
+        Ok(factory::Error::Base(err)) => return err,
+        Ok(err) => panic!("Unexpected factory error: {}", err),
+        Err(err) => err
+    };
+
+    // Replies are called directly and so their errors aren't wrapped.
+    err.downcast::<ContractError>()
+        .unwrap_or_else(|err| panic!("Unexpected error: {}", err))
+}
+
+/// An ensemble with the auction and the factory uploaded and the factory
+/// instantiated. Auctions are created through the factory by `"sender"`.
+pub struct Suite {
+    pub ensemble: ContractEnsemble,
+    pub factory: ContractLink<Addr>
+}
+
+impl Default for Suite {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Suite {
+    pub fn new() -> Self {
+        let mut ensemble = ContractEnsemble::new();
+
+        // Upload contracts
+        let auction = ensemble.register(Box::new(Auction));
+        let factory = ensemble.register(Box::new(Factory));
+
+        // Instantiate factory
+        let factory = ensemble.instantiate(
+            factory.id,
+            &factory::InstantiateMsg { admin: None, auction, initial_auctions: None },
+            MockEnv::new("sender", FACTORY)
+        )
+        .unwrap()
+        .instance;
+
+        Self { ensemble, factory }
+    }
+
+    pub fn new_auction(&mut self, end_block: u64) -> EnsembleResult<AuctionEntry<Addr>> {
+        self.create_auction(config("Road 23", end_block))
+    }
+
+    pub fn create_auction(&mut self, config: Config) -> EnsembleResult<AuctionEntry<Addr>> {
+        self.create_tagged_auction(config, None)
+    }
+
+    pub fn create_tagged_auction(
+        &mut self,
+        config: Config,
+        tags: Option<Vec<String>>
+    ) -> EnsembleResult<AuctionEntry<Addr>> {
+        self.create_funded_auction(config, tags, vec![])
+    }
+
+    /// Sends the funds along with the config, e.g. for a seller deposit.
+    /// They have to be added to `"sender"` beforehand.
+    pub fn create_funded_auction(
+        &mut self,
+        config: Config,
+        tags: Option<Vec<String>>,
+        funds: Vec<Coin>
+    ) -> EnsembleResult<AuctionEntry<Addr>> {
+        self.ensemble.execute(
+            &factory::ExecuteMsg::CreateAuction { config, tags, end: None },
+            MockEnv::new("sender", self.factory.address.clone())
+                .sent_funds(funds)
+        )?;
+
+        // The newly created auction is always the last one listed.
+        let total = self.list_auctions(0, 0)?.total;
+        let auctions = self.list_auctions(total - 1, 1)?;
+
+        Ok(auctions.entries.into_iter().next().unwrap())
+    }
+
+    pub fn list_auctions(
+        &self,
+        start: u64,
+        limit: u8
+    ) -> EnsembleResult<PaginatedResponse<AuctionEntry<Addr>>> {
+        self.ensemble.query(
+            &self.factory.address,
+            &factory::QueryMsg::ListAuctions {
+                pagination: Pagination { start, limit }
+            }
+        )
+    }
+
+    pub fn advance_blocks(&mut self, blocks: u64) {
+        self.ensemble.block_mut().height += blocks;
+    }
+
+    /// Uploads and instantiates a [`Snip20`] token at the given address.
+    pub fn new_token(&mut self, address: &str) -> ContractLink<Addr> {
+        let token = self.ensemble.register(Box::new(Snip20));
+
+        self.ensemble.instantiate(token.id, &Empty { }, MockEnv::new("sender", address))
+            .unwrap()
+            .instance
+    }
+
+    /// Allows the spender to transfer the given amount of the owner's tokens.
+    pub fn increase_allowance(
+        &mut self,
+        token: &ContractLink<Addr>,
+        owner: &str,
+        spender: &ContractLink<Addr>,
+        amount: u128
+    ) {
+        self.ensemble.execute(
+            &Snip20Msg::IncreaseAllowance { spender: spender.address.to_string(), amount: amount.into() },
+            MockEnv::new(owner, &token.address)
+        ).unwrap();
+    }
+
+    /// Uploads and instantiates an [`Oracle`] at the given address.
+    pub fn new_oracle(&mut self, address: &str) -> ContractLink<Addr> {
+        let oracle = self.ensemble.register(Box::new(Oracle));
+
+        self.ensemble.instantiate(oracle.id, &Empty { }, MockEnv::new("sender", address))
+            .unwrap()
+            .instance
+    }
+
+    /// Sets the price returned by the oracle, updated at the current block time.
+    pub fn set_price(&mut self, oracle: &ContractLink<Addr>, rate: Decimal) {
+        let updated_at = self.ensemble.block().time;
+
+        self.ensemble.execute(
+            &oracle::PriceResponse { rate, updated_at },
+            MockEnv::new("sender", &oracle.address)
+        ).unwrap();
+    }
+
+    pub fn mint_tokens(&mut self, token: &ContractLink<Addr>, recipient: &str, amount: u128) {
+        self.ensemble.execute(
+            &Snip20Msg::Mint { recipient: recipient.into(), amount: amount.into() },
+            MockEnv::new("sender", &token.address)
+        ).unwrap();
+    }
+
+    /// Sends the tokens to the contract together with the message
+    /// which the contract is then called back with.
+    pub fn send_tokens(
+        &mut self,
+        token: &ContractLink<Addr>,
+        sender: &str,
+        recipient: &ContractLink<Addr>,
+        amount: u128,
+        msg: Option<Binary>
+    ) -> EnsembleResult<ExecuteResponse> {
+        self.ensemble.execute(
+            &Snip20Msg::Send {
+                recipient: recipient.address.to_string(),
+                recipient_code_hash: Some(recipient.code_hash.clone()),
+                amount: amount.into(),
+                msg
+            },
+            MockEnv::new(sender, &token.address)
+        )
+    }
+
+    pub fn token_balance(&self, token: &ContractLink<Addr>, address: &str) -> Uint128 {
+        self.ensemble.query(&token.address, &address).unwrap()
+    }
+
+    /// Sends everything that the auction paid out to the address.
+    pub fn claim(&mut self, auction: &ContractLink<Addr>, address: &str) {
+        self.ensemble.execute(
+            &auction::ExecuteMsg::Claim { },
+            MockEnv::new(address, auction.address.clone())
+        ).unwrap();
+    }
+
+    /// Moves the current block height to right after the
+    /// end block stored in the given auction contract.
+    pub fn finish_auction(&mut self, auction: &ContractLink<Addr>) {
+        let status: SaleStatus = self.ensemble.query(
+            &auction.address,
+            &auction::QueryMsg::SaleStatus { }
+        ).unwrap();
+
+        let height = self.ensemble.block().height;
+        self.advance_blocks((status.info.end_block + 1).saturating_sub(height));
+    }
+}
@@ -1,123 +1,42 @@
 use fadroma::{
     core::*,
-    ensemble::{
-        ContractEnsemble, ContractHarness,
-        MockEnv, EnsembleResult, AnyResult
-    },
+    ensemble::{ContractEnsemble, MockEnv, ResponseVariants},
     cosmwasm_std::{
-        DepsMut, Deps, Env, MessageInfo, Addr,
-        Response, Binary, Reply, Uint128, from_binary, coin
+        Addr, Attribute, Binary, Empty, Uint128, Decimal, WasmMsg, WasmQuery, QueryRequest, DepsMut, QuerierWrapper,
+        Api, CanonicalAddr,
+        from_binary, to_binary, StdError, coin,
+        testing::{MockApi, MockQuerier, mock_env}
     },
+    scrt::permit::{Permit, PermitParams},
+    killswitch::ContractStatus,
     tokens::one_token,
-    contract_harness
+    storage::{iterable::IterableStorage, StaticKey},
+    bin_serde::{FadromaSerialize, FadromaDeserialize}
 };
-use ::factory::factory::{self, AuctionEntry};
+use ::factory::factory::{self, AuctionEntry, DenomVolume, RegistryEntry, TopSale, VolumeStats};
 use auction::auction;
-use shared::{Pagination, PaginatedResponse, SaleStatus};
-
-const FACTORY: &str = "factory";
-const ADMIN: &str = "admin";
-
-contract_harness! {
-    Auction, 
-    init: auction::instantiate,
-    execute: auction::execute,
-    query: auction::query
-}
-
-struct Factory;
-
-impl ContractHarness for Factory {
-    fn instantiate(
-        &self,
-        deps: DepsMut,
-        env: Env,
-        info: MessageInfo,
-        msg: Binary
-    ) -> AnyResult<Response> {
-        let resp = factory::instantiate(deps, env, info, from_binary(&msg)?)?;
-
-        Ok(resp)
-    }
-
-    fn execute(
-        &self,
-        deps: DepsMut,
-        env: Env,
-        info: MessageInfo,
-        msg: Binary
-    ) -> AnyResult<Response> {
-        let resp = factory::execute(deps, env, info, from_binary(&msg)?)?;
-
-        Ok(resp)
-    }
-
-    fn query(
-        &self,
-        deps: Deps,
-        env: Env,
-        msg: Binary
-    ) -> AnyResult<Binary> {
-        let resp = factory::query(deps, env, from_binary(&msg)?)?;
-
-        Ok(resp)
-    }
-
-    fn reply(&self, deps: DepsMut, env: Env, reply: Reply) -> AnyResult<Response> {
-        let resp = factory::Contract::reply(deps, env, reply)?;
-
-        Ok(resp)
-    }
-}
-
-struct Suite {
-    ensemble: ContractEnsemble,
-    factory: ContractLink<Addr>
-}
-
-impl Suite {
-    fn new() -> Self {
-        let mut ensemble = ContractEnsemble::new();
-
-        // Upload contracts
-        let auction = ensemble.register(Box::new(Auction));
-        let factory = ensemble.register(Box::new(Factory));
-
-        // Instantiate factory
-        let factory = ensemble.instantiate(
-            factory.id,
-            &factory::InstantiateMsg { auction },
-            MockEnv::new("sender", FACTORY)
-        )
-        .unwrap()
-        .instance;
-
-        Self { ensemble, factory }
-    }
-
-    fn new_auction(&mut self, end_block: u64) -> EnsembleResult<AuctionEntry<Addr>> {
-        self.ensemble.execute(
-            &factory::ExecuteMsg::CreateAuction {
-                admin: Some(ADMIN.into()),
-                name: "Road 23".into(),
-                end_block
-            },
-            MockEnv::new("sender", self.factory.address.clone())
-        )?;
-
-        let auctions: PaginatedResponse<AuctionEntry<Addr>> = self.ensemble.query(
-            &self.factory.address,
-            &factory::QueryMsg::ListAuctions {
-                pagination: Pagination {
-                    start: 0,
-                    limit: 30
-                }
-            }
-        )?;
-
-        Ok(auctions.entries.into_iter().rev().last().unwrap())
-    }
-}
+use shared::{
+    AuctionConfig, AuctionEnd, AuctionPermission, AuctionState, BidEntry, BidRank, BidReceipt, BidResult, BidSimulation, Claim, Config, CreatedAt, CurrencyPolicy, Delivery,
+    ExtensionProposal, ExtensionVoteConfig, FactoryPermission, FeeConfig, Metrics, Migrate, MigrateMsg,
+    PendingApproval, ProceedsResult, ProxyBid, RaffleConfig, RetractResult, SellerAction, SettlementHookMsg, StatusChange, StatusInfo, TimeRemaining, snip721,
+    builders::{AuctionInit, ConfigUpdate},
+    client::{AuctionClient, FactoryClient},
+    Denom, Invariants, Pagination, PaginatedResponse, SaleStatus,
+    errors::{
+        AuthError, ClaimError, CoSellerError, ContractError, CurrencyError, ExtensionError, HookError, LifecycleError, NameError,
+        OracleError, PaginationError, RaffleError, ReplyError, RetractionError, SelectionError, TagError,
+        TokenError
+    },
+    oracle::PriceOracle,
+    snip20::BidToken,
+    events, layout, version::{self, ContractVersion}
+};
+use crate::scenario::Scenario;
+use testing::{
+    Suite, Auction, Factory, Forwarder, ForwardMsg, Hook, Impostor, Mute, Subscriber, Nft, Snip20Msg,
+    UninitializedFactory,
+    ADMIN, FACTORY, config, contract_error
+};
 
 #[test]
 fn instantiate_auction() {
@@ -135,8 +54,8 @@ fn instantiate_auction() {
 
     assert_eq!(status.info.name, "Road 23");
     assert_eq!(status.info.end_block, block);
-    assert_eq!(status.current_highest, Uint128::zero());
-    assert_eq!(status.is_finished, false);
+    assert_eq!(status.current_highest, Some(Uint128::zero()));
+    assert!(!status.is_finished);
 
     let admin: Option<Addr> = suite.ensemble.query(
         &auction.contract.address,
@@ -146,16 +65,86 @@ fn instantiate_auction() {
     assert_eq!(admin, Some(Addr::unchecked(ADMIN)));
 }
 
+#[test]
+fn events_are_emitted() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let resp = suite.ensemble.execute(
+        &factory::ExecuteMsg::CreateAuction {
+            config: config("Road 23", block),
+            tags: None,
+            end: None
+        },
+        MockEnv::new("sender", &suite.factory.address)
+    ).unwrap();
+
+    assert_eq!(resp.response.attributes[0].key, events::ACTION);
+    assert_eq!(resp.response.attributes[0].value, events::actions::CREATE_AUCTION);
+
+    let auction = suite.list_auctions(0, 1).unwrap().entries.remove(0);
+
+    let event = resp.iter()
+        .filter_map(|x| match x {
+            ResponseVariants::Reply(reply) => Some(&reply.response.events),
+            _ => None
+        })
+        .flatten()
+        .find(|x| x.ty == events::AUCTION_CREATED)
+        .unwrap();
+
+    assert_eq!(event.attributes[0].key, events::AUCTION_NAME);
+    assert_eq!(event.attributes[0].value, "Road 23");
+    assert_eq!(event.attributes[1].key, events::AUCTION_ADDRESS);
+    assert_eq!(event.attributes[1].value, auction.contract.address.as_str());
+
+    suite.ensemble.add_funds("bidder", vec![coin(100, "uscrt")]);
+
+    let resp = suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder", &auction.contract.address)
+            .sent_funds(vec![coin(100, "uscrt")])
+    ).unwrap();
+
+    assert_eq!(resp.response.attributes[0].value, events::actions::BID);
+    assert_eq!(resp.response.attributes[1].key, events::BIDDER);
+    assert_eq!(resp.response.attributes[1].value, "bidder");
+}
+
 #[test]
 fn cannot_instantiate_auction_with_end_block_in_the_past() {
     let mut suite = Suite::new();
     let block = suite.ensemble.block().height;
 
-    let err = suite.new_auction(block).unwrap_err();
-    assert_eq!(
-        err.unwrap_contract_error().to_string(),
-        "Generic error: End block has already passed."
-    );
+    let resp = suite.ensemble.execute(
+        &factory::ExecuteMsg::CreateAuction {
+            config: config("Road 23", block),
+            tags: None,
+            end: None
+        },
+        MockEnv::new("sender", &suite.factory.address)
+    ).unwrap();
+
+    // The factory doesn't keep an entry for the failed auction...
+    let auctions = suite.list_auctions(0, 30).unwrap();
+    assert_eq!(auctions.total, 0);
+    assert!(auctions.entries.is_empty());
+
+    // ...and reports the reason it failed instead.
+    let event = resp.iter()
+        .filter_map(|x| match x {
+            ResponseVariants::Reply(reply) => Some(&reply.response.events),
+            _ => None
+        })
+        .flatten()
+        .find(|x| x.ty == events::AUCTION_INSTANTIATION_FAILED)
+        .unwrap();
+
+    assert_eq!(event.attributes[0].key, events::AUCTION_NAME);
+    assert_eq!(event.attributes[0].value, "Road 23");
+    assert_eq!(event.attributes[1].key, events::ERROR);
+    assert!(event.attributes[1].value.starts_with("Auction instantiation failed: "));
+    assert!(event.attributes[1].value.contains("End block has already passed."));
 }
 
 #[test]
@@ -175,7 +164,7 @@ fn bidding() {
     suite.ensemble.add_funds(bidder, vec![coin(bid_amount, "uscrt")]);
 
     suite.ensemble.execute(
-        &auction::ExecuteMsg::Bid { },
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
         MockEnv::new(bidder, &auction.address)
             .sent_funds(vec![coin(bid_amount, "uscrt")])
     ).unwrap();
@@ -185,7 +174,7 @@ fn bidding() {
         &auction::QueryMsg::SaleStatus { }
     ).unwrap();
 
-    assert_eq!(status.current_highest.u128(), bid_amount);
+    assert_eq!(status.current_highest.unwrap().u128(), bid_amount);
 
     // We check that the auction contract has indeed received the uscrt
     // sent by the bidder
@@ -213,45 +202,128 @@ fn bidding() {
 
 #[test]
 fn cannot_retract_bid_before_the_end_or_if_winner() {
+    Scenario::new()
+        .bid("bidder", one_token(6) * 100)
+        .fails_with(LifecycleError::NotFinished)
+        .retract("bidder")
+        // We move the current block height past the end of the
+        // sale to simulate the passage of time.
+        .finish()
+        // Now that the sale has ended we see the error message change...
+        .fails_with(LifecycleError::WinnerCannotRetract)
+        .retract("bidder");
+}
+
+#[test]
+fn cannot_retract_without_a_bid() {
+    Scenario::new()
+        .bid("bidder_1", 100)
+        .bid("bidder_2", 150)
+        .finish()
+        .fails_with(LifecycleError::NothingToRefund)
+        .retract("stranger")
+        .retract("bidder_1")
+        .expect_attribute(events::REFUND, "100")
+        // The bid can only be refunded once.
+        .fails_with(LifecycleError::NothingToRefund)
+        .retract("bidder_1");
+}
+
+#[test]
+fn losing_bidders_get_their_bids_back() {
+    Scenario::new()
+        .bid("alice", 100)
+        .bid("bob", 150)
+        .bid("alice", 60)
+        .expect_highest(160)
+        .advance(10)
+        .bid("carol", 200)
+        .expect_highest(200)
+        .finish()
+        .expect_state(AuctionState::Ended)
+        .settle()
+        .expect_state(AuctionState::Settled)
+        .fails_with(LifecycleError::WinnerCannotRetract)
+        .retract("carol")
+        .retract("alice")
+        .retract("bob")
+        .claim("alice")
+        .claim("bob")
+        .claim(ADMIN)
+        .expect_balance("alice", 160)
+        .expect_balance("bob", 150)
+        .expect_balance("carol", 0)
+        .expect_balance(ADMIN, 200);
+}
+
+#[test]
+fn end_block_is_the_last_block_of_the_sale() {
     let mut suite = Suite::new();
-    let block = suite.ensemble.block().height + 1000;
+    let end_block = suite.ensemble.block().height + 1000;
 
-    let auction = suite.new_auction(block).unwrap().contract;
+    let auction = suite.new_auction(end_block).unwrap().contract;
 
-    let bidder = "bidder";
-    let bid_amount = one_token(6) * 100;
+    for bidder in ["bidder_1", "bidder_2", "bidder_3"] {
+        suite.ensemble.add_funds(bidder, vec![coin(100, "uscrt")]);
+    }
 
-    suite.ensemble.add_funds(bidder, vec![coin(bid_amount, "uscrt")]);
-    suite.ensemble.execute(
-        &auction::ExecuteMsg::Bid { },
+    let bid = |suite: &mut Suite, bidder: &str, amount: u128| suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
         MockEnv::new(bidder, &auction.address)
-            .sent_funds(vec![coin(bid_amount, "uscrt")])
-    ).unwrap();
+            .sent_funds(vec![coin(amount, "uscrt")])
+    );
+
+    let sale_status = |suite: &Suite| -> (bool, AuctionState) {
+        let status: SaleStatus = suite.ensemble.query(
+            &auction.address,
+            &auction::QueryMsg::SaleStatus { }
+        ).unwrap();
+
+        let state: AuctionState = suite.ensemble.query(
+            &auction.address,
+            &auction::QueryMsg::State { }
+        ).unwrap();
+
+        (status.is_finished, state)
+    };
+
+    bid(&mut suite, "bidder_1", 50).unwrap();
+
+    // Bids are still accepted in the end block but nothing can be retracted or claimed.
+    suite.ensemble.block_mut().height = end_block;
+    assert_eq!(sale_status(&suite), (false, AuctionState::Active));
+
+    bid(&mut suite, "bidder_2", 100).unwrap();
+
+    suite.ensemble.block_mut().height = end_block;
 
     let err = suite.ensemble.execute(
         &auction::ExecuteMsg::RetractBid { },
-        MockEnv::new(bidder, &auction.address)
+        MockEnv::new("bidder_1", &auction.address)
     ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::NotFinished.into());
 
-    assert_eq!(
-        err.unwrap_contract_error().to_string(),
-        "Generic error: Sale hasn't finished yet."
-    );
-
-    // We manually set the current block height to simulate
-    // the passage of time.
-    suite.ensemble.block_mut().height = block + 1;
+    suite.ensemble.block_mut().height = end_block;
 
     let err = suite.ensemble.execute(
-        &auction::ExecuteMsg::RetractBid { },
-        MockEnv::new(bidder, &auction.address)
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &auction.address)
     ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::NotFinished.into());
 
-    // Now that the sale has ended we see the error message change...
-    assert_eq!(
-        err.unwrap_contract_error().to_string(),
-        "Generic error: You have won the sale and cannot retract your bid."
-    );
+    // The sale is finished from the next block onwards.
+    suite.ensemble.block_mut().height = end_block + 1;
+    assert_eq!(sale_status(&suite), (true, AuctionState::Ended));
+
+    let err = bid(&mut suite, "bidder_3", 100).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::Finished.into());
+
+    suite.ensemble.block_mut().height = end_block + 1;
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::RetractBid { },
+        MockEnv::new("bidder_1", &auction.address)
+    ).unwrap();
 }
 
 #[test]
@@ -268,13 +340,13 @@ fn highest_bid_gets_updated() {
     suite.ensemble.add_funds(bidder_2.0, vec![coin(bidder_2.1, "uscrt")]);
 
     suite.ensemble.execute(
-        &auction::ExecuteMsg::Bid { },
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
         MockEnv::new(bidder_1.0, &auction.address)
             .sent_funds(vec![coin(bidder_1.1, "uscrt")])
     ).unwrap();
 
     suite.ensemble.execute(
-        &auction::ExecuteMsg::Bid { },
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
         MockEnv::new(bidder_2.0, &auction.address)
             .sent_funds(vec![coin(bidder_2.1, "uscrt")])
     ).unwrap();
@@ -284,15 +356,21 @@ fn highest_bid_gets_updated() {
         &auction::QueryMsg::SaleStatus { }
     ).unwrap();
 
-    assert_eq!(status.current_highest.u128(), bidder_2.1);
+    assert_eq!(status.current_highest.unwrap().u128(), bidder_2.1);
 
-    suite.ensemble.block_mut().height = block + 1;
+    suite.finish_auction(&auction);
 
     suite.ensemble.execute(
         &auction::ExecuteMsg::RetractBid { },
         MockEnv::new(bidder_1.0, &auction.address)
     ).unwrap();
 
+    // The refund is only sent once it is claimed.
+    let auction_balances = suite.ensemble.balances(&auction.address).unwrap();
+    assert_eq!(auction_balances["uscrt"].u128(), bidder_1.1 + bidder_2.1);
+
+    suite.claim(&auction, bidder_1.0);
+
     // Check that the contract has indeed sent the uscrt
     // back to the losing bidder
     let auction_balances = suite.ensemble.balances(&auction.address).unwrap();
@@ -301,3 +379,6399 @@ fn highest_bid_gets_updated() {
     let bidder_1_balances = suite.ensemble.balances(bidder_1.0).unwrap();
     assert_eq!(bidder_1_balances["uscrt"].u128(), bidder_1.1);
 }
+
+#[test]
+fn highest_bid_follows_raises_and_ties() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    let mut bid = |bidder: &str, amount: u128| {
+        suite.ensemble.add_funds(bidder, vec![coin(amount, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new(bidder, &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        ).unwrap();
+
+        let status: SaleStatus = suite.ensemble.query(
+            &auction.address,
+            &auction::QueryMsg::SaleStatus { }
+        ).unwrap();
+
+        status.current_highest.unwrap().u128()
+    };
+
+    assert_eq!(bid("bidder_1", 100), 100);
+    assert_eq!(bid("bidder_2", 50), 100);
+    assert_eq!(bid("bidder_3", 75), 100);
+    // bidder_2 now has 125 in total.
+    assert_eq!(bid("bidder_2", 75), 125);
+    // bidder_3 ties with bidder_2 but didn't reach the amount first.
+    assert_eq!(bid("bidder_3", 50), 125);
+
+    suite.finish_auction(&auction);
+
+    // bidder_2 won so everyone else can get their funds back.
+    for bidder in ["bidder_1", "bidder_3"] {
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::RetractBid { },
+            MockEnv::new(bidder, &auction.address)
+        ).unwrap();
+        suite.claim(&auction, bidder);
+    }
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::RetractBid { },
+        MockEnv::new("bidder_2", &auction.address)
+    ).unwrap_err();
+
+    assert_eq!(contract_error(err), LifecycleError::WinnerCannotRetract.into());
+
+    let balances = suite.ensemble.balances(&auction.address).unwrap();
+    assert_eq!(balances["uscrt"].u128(), 125);
+}
+
+#[test]
+fn rotated_and_revoked_viewing_keys_are_rejected() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    let bidder = "bidder";
+    let old_vk = "old_vk";
+    let new_vk = "new_vk";
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::SetViewingKey {
+            key: old_vk.into(),
+            padding: None
+        },
+        MockEnv::new(bidder, &auction.address)
+    ).unwrap();
+
+    // Setting a new key replaces the previous one.
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::SetViewingKey {
+            key: new_vk.into(),
+            padding: None
+        },
+        MockEnv::new(bidder, &auction.address)
+    ).unwrap();
+
+    let err = suite.ensemble.query::<_, Uint128>(
+        &auction.address,
+        &auction::QueryMsg::ViewBid {
+            address: bidder.into(),
+            key: old_vk.into()
+        }
+    ).unwrap_err();
+
+    assert_eq!(err.to_string(), AuthError::Unauthorized.to_string());
+
+    let stored_amount: Uint128 = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::ViewBid {
+            address: bidder.into(),
+            key: new_vk.into()
+        }
+    ).unwrap();
+
+    assert_eq!(stored_amount, Uint128::zero());
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::RevokeAllKeys { },
+        MockEnv::new(bidder, &auction.address)
+    ).unwrap();
+
+    let err = suite.ensemble.query::<_, Uint128>(
+        &auction.address,
+        &auction::QueryMsg::ViewBid {
+            address: bidder.into(),
+            key: new_vk.into()
+        }
+    ).unwrap_err();
+
+    assert_eq!(err.to_string(), AuthError::Unauthorized.to_string());
+}
+
+#[test]
+fn viewing_key_can_be_set_when_bidding() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    suite.ensemble.add_funds("bidder", vec![coin(100, "uscrt")]);
+    let resp = suite.ensemble.execute(
+        &auction::ExecuteMsg::BidWithKey { key: "key".into(), padding: None },
+        MockEnv::new("bidder", &auction.address)
+            .sent_funds(vec![coin(100, "uscrt")])
+    ).unwrap();
+
+    let result: BidResult = from_binary(&resp.response.data.unwrap()).unwrap();
+    assert_eq!(result.balance.u128(), 100);
+
+    let stored_amount: Uint128 = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::ViewBid { address: "bidder".into(), key: "key".into() }
+    ).unwrap();
+    assert_eq!(stored_amount.u128(), 100);
+
+    // The key is not changed if the bid fails.
+    suite.finish_auction(&auction);
+
+    suite.ensemble.add_funds("bidder", vec![coin(100, "uscrt")]);
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::BidWithKey { key: "other".into(), padding: None },
+        MockEnv::new("bidder", &auction.address)
+            .sent_funds(vec![coin(100, "uscrt")])
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::Finished.into());
+
+    suite.ensemble.query::<_, Uint128>(
+        &auction.address,
+        &auction::QueryMsg::ViewBid { address: "bidder".into(), key: "key".into() }
+    ).unwrap();
+}
+
+#[test]
+fn bids_can_be_placed_on_behalf_of_others() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    suite.ensemble.add_funds("custodian", vec![coin(300, "uscrt")]);
+    suite.ensemble.add_funds("bidder", vec![coin(200, "uscrt")]);
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::BidFor { beneficiary: "user".into() },
+        MockEnv::new("custodian", &auction.address)
+            .sent_funds(vec![coin(100, "uscrt")])
+    ).unwrap_err();
+    assert_eq!(contract_error(err), AuthError::BidsForNotAccepted("user".into()).into());
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::AcceptBidsFor { accept: true },
+        MockEnv::new("user", &auction.address)
+    ).unwrap();
+
+    let resp = suite.ensemble.execute(
+        &auction::ExecuteMsg::BidFor { beneficiary: "user".into() },
+        MockEnv::new("custodian", &auction.address)
+            .sent_funds(vec![coin(100, "uscrt")])
+    ).unwrap();
+
+    let result: BidResult = from_binary(&resp.response.data.unwrap()).unwrap();
+    assert_eq!(result.balance.u128(), 100);
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder", &auction.address)
+            .sent_funds(vec![coin(200, "uscrt")])
+    ).unwrap();
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::BidFor { beneficiary: "user".into() },
+        MockEnv::new("custodian", &auction.address)
+            .sent_funds(vec![coin(200, "uscrt")])
+    ).unwrap();
+
+    let status: SaleStatus = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::SaleStatus { }
+    ).unwrap();
+    assert_eq!(status.current_highest.unwrap().u128(), 300);
+
+    // Opting out prevents any further credits.
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::AcceptBidsFor { accept: false },
+        MockEnv::new("user", &auction.address)
+    ).unwrap();
+
+    suite.ensemble.add_funds("custodian", vec![coin(100, "uscrt")]);
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::BidFor { beneficiary: "user".into() },
+        MockEnv::new("custodian", &auction.address)
+            .sent_funds(vec![coin(100, "uscrt")])
+    ).unwrap_err();
+    assert_eq!(contract_error(err), AuthError::BidsForNotAccepted("user".into()).into());
+
+    suite.finish_auction(&auction);
+
+    // The beneficiary is the winner, so the payer has nothing to retract.
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::RetractBid { },
+        MockEnv::new("custodian", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::NothingToRefund.into());
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::RetractBid { },
+        MockEnv::new("user", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::WinnerCannotRetract.into());
+}
+
+#[test]
+fn contracts_can_be_excluded_from_bidding() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let open = suite.new_auction(block).unwrap().contract;
+    let restricted = suite.create_auction(Config {
+        exclude_contracts: Some(true),
+        ..config("Road 24", block)
+    }).unwrap().contract;
+
+    let forwarder = suite.ensemble.register(Box::new(Forwarder));
+    let forwarder = suite.ensemble.instantiate(
+        forwarder.id,
+        &Empty { },
+        MockEnv::new("owner", "forwarder")
+    ).unwrap().instance;
+
+    suite.ensemble.add_funds("owner", vec![coin(500, "uscrt")]);
+
+    let forward = |auction: &ContractLink<Addr>, msg: &auction::ExecuteMsg| ForwardMsg {
+        contract: auction.clone(),
+        msg: to_binary(msg).unwrap()
+    };
+
+    let bid = auction::ExecuteMsg::Bid { auto_refund: None, memo: None };
+
+    for msg in [&bid, &auction::ExecuteMsg::ProxyBid { }] {
+        let err = suite.ensemble.execute(
+            &forward(&restricted, msg),
+            MockEnv::new("owner", &forwarder.address)
+                .sent_funds(vec![coin(100, "uscrt")])
+        ).unwrap_err();
+        assert_eq!(contract_error(err), AuthError::ContractBidder("forwarder".into()).into());
+    }
+
+    // Contracts can't pay for the bids of accounts either.
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::AcceptBidsFor { accept: true },
+        MockEnv::new("user", &restricted.address)
+    ).unwrap();
+
+    let err = suite.ensemble.execute(
+        &forward(&restricted, &auction::ExecuteMsg::BidFor { beneficiary: "user".into() }),
+        MockEnv::new("owner", &forwarder.address)
+            .sent_funds(vec![coin(100, "uscrt")])
+    ).unwrap_err();
+    assert_eq!(contract_error(err), AuthError::ContractBidder("forwarder".into()).into());
+
+    let resp = suite.ensemble.execute(
+        &bid,
+        MockEnv::new("owner", &restricted.address)
+            .sent_funds(vec![coin(100, "uscrt")])
+    ).unwrap();
+    let result: BidResult = from_binary(&resp.response.data.unwrap()).unwrap();
+    assert_eq!(result.balance.u128(), 100);
+
+    // Other auctions still accept bids from contracts.
+    suite.ensemble.execute(
+        &forward(&open, &bid),
+        MockEnv::new("owner", &forwarder.address)
+            .sent_funds(vec![coin(100, "uscrt")])
+    ).unwrap();
+
+    let status: SaleStatus = suite.ensemble.query(
+        &open.address,
+        &auction::QueryMsg::SaleStatus { }
+    ).unwrap();
+    assert_eq!(status.current_highest.unwrap().u128(), 100);
+}
+
+#[test]
+fn revoked_permits_are_rejected() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    let bidder = "bidder";
+    let bid_amount = one_token(6) * 100;
+
+    suite.ensemble.add_funds(bidder, vec![coin(bid_amount, "uscrt")]);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new(bidder, &auction.address)
+            .sent_funds(vec![coin(bid_amount, "uscrt")])
+    ).unwrap();
+
+    let permit = Permit::new(
+        bidder,
+        PermitParams::new(auction.address.as_str())
+            .permissions([AuctionPermission::ViewBid])
+            .name("bids")
+    );
+
+    let stored_amount: Uint128 = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::ViewBidWithPermit { permit: permit.clone() }
+    ).unwrap();
+
+    assert_eq!(stored_amount.u128(), bid_amount);
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::RevokePermit { name: "bids".into() },
+        MockEnv::new(bidder, &auction.address)
+    ).unwrap();
+
+    let err = suite.ensemble.query::<_, Uint128>(
+        &auction.address,
+        &auction::QueryMsg::ViewBidWithPermit { permit }
+    ).unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "Generic error: Permit \"bids\" was revoked by account \"bidder\""
+    );
+}
+
+#[test]
+fn auditor_can_view_all_bids() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    let auditor = "auditor";
+    let auditor_vk = "auditor_vk";
+    let bidder = "bidder";
+    let bid_amount = one_token(6) * 100;
+
+    suite.ensemble.add_funds(bidder, vec![coin(bid_amount, "uscrt")]);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new(bidder, &auction.address)
+            .sent_funds(vec![coin(bid_amount, "uscrt")])
+    ).unwrap();
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::SetViewingKey {
+            key: auditor_vk.into(),
+            padding: None
+        },
+        MockEnv::new(auditor, &auction.address)
+    ).unwrap();
+
+    let audit_bids = auction::QueryMsg::AuditBids {
+        key: auditor_vk.into(),
+        pagination: Pagination { start: 0, limit: 30 }
+    };
+
+    let err = suite.ensemble.query::<_, PaginatedResponse<BidEntry>>(
+        &auction.address,
+        &audit_bids
+    ).unwrap_err();
+
+    assert_eq!(err.to_string(), AuthError::Unauthorized.to_string());
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::SetAuditor { address: Some(auditor.into()) },
+        MockEnv::new(bidder, &auction.address)
+    ).unwrap_err();
+
+    assert_eq!(err.unwrap_contract_error().to_string(), "Generic error: Unauthorized");
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::SetAuditor { address: Some(auditor.into()) },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    let stored_amount: Uint128 = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::ViewBid {
+            address: bidder.into(),
+            key: auditor_vk.into()
+        }
+    ).unwrap();
+
+    assert_eq!(stored_amount.u128(), bid_amount);
+
+    let bids: PaginatedResponse<BidEntry> = suite.ensemble.query(
+        &auction.address,
+        &audit_bids
+    ).unwrap();
+
+    assert_eq!(bids.total, 1);
+    assert_eq!(bids.entries, vec![BidEntry {
+        address: Addr::unchecked(bidder),
+        amount: Uint128::new(bid_amount)
+    }]);
+}
+
+#[test]
+fn auditor_can_view_all_bids_with_a_permit() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    for (bidder, amount) in [("bidder_1", 100), ("bidder_2", 150)] {
+        suite.ensemble.add_funds(bidder, vec![coin(amount, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new(bidder, &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        ).unwrap();
+    }
+
+    let permit = |address: &str, permission| Permit::new(
+        address,
+        PermitParams::new(auction.address.as_str())
+            .permissions([permission])
+            .name("bids")
+    );
+    let pagination = || Pagination { start: 0, limit: 30 };
+
+    let view_bid_of = |bidder: &str, viewer: &str| auction::QueryMsg::ViewBidOfWithPermit {
+        bidder: bidder.into(),
+        permit: permit(viewer, AuctionPermission::ViewBid)
+    };
+    let audit_bids = || auction::QueryMsg::AuditBidsWithPermit {
+        permit: permit("auditor", AuctionPermission::ViewActiveBids),
+        pagination: pagination()
+    };
+    let active_bids = || auction::QueryMsg::ActiveBidsWithPermit {
+        permit: permit("auditor", AuctionPermission::ViewActiveBids),
+        pagination: pagination()
+    };
+
+    let amount: Uint128 = suite.ensemble.query(
+        &auction.address,
+        &view_bid_of("bidder_1", "bidder_1")
+    ).unwrap();
+    assert_eq!(amount, Uint128::new(100));
+
+    let err = suite.ensemble.query::<_, Uint128>(
+        &auction.address,
+        &view_bid_of("bidder_1", "bidder_2")
+    ).unwrap_err();
+    assert_eq!(err.to_string(), AuthError::Unauthorized.to_string());
+
+    let err = suite.ensemble.query::<_, Uint128>(
+        &auction.address,
+        &view_bid_of("bidder_1", "auditor")
+    ).unwrap_err();
+    assert_eq!(err.to_string(), AuthError::Unauthorized.to_string());
+
+    let err = suite.ensemble.query::<_, PaginatedResponse<BidEntry>>(
+        &auction.address,
+        &audit_bids()
+    ).unwrap_err();
+    assert_eq!(err.to_string(), AuthError::Unauthorized.to_string());
+
+    let err = suite.ensemble.query::<_, PaginatedResponse<Uint128>>(
+        &auction.address,
+        &active_bids()
+    ).unwrap_err();
+    assert_eq!(err.to_string(), AuthError::Unauthorized.to_string());
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::SetAuditor { address: Some("auditor".into()) },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    let amount: Uint128 = suite.ensemble.query(
+        &auction.address,
+        &view_bid_of("bidder_1", "auditor")
+    ).unwrap();
+    assert_eq!(amount, Uint128::new(100));
+
+    let bids: PaginatedResponse<BidEntry> = suite.ensemble.query(
+        &auction.address,
+        &audit_bids()
+    ).unwrap();
+    assert_eq!(bids.total, 2);
+    assert_eq!(bids.entries, vec![
+        BidEntry { address: Addr::unchecked("bidder_1"), amount: Uint128::new(100) },
+        BidEntry { address: Addr::unchecked("bidder_2"), amount: Uint128::new(150) }
+    ]);
+
+    let bids: PaginatedResponse<Uint128> = suite.ensemble.query(
+        &auction.address,
+        &active_bids()
+    ).unwrap();
+    assert_eq!(bids.entries, vec![Uint128::new(100), Uint128::new(150)]);
+
+    // The admin can list the active bids but isn't an auditor.
+    let err = suite.ensemble.query::<_, PaginatedResponse<BidEntry>>(
+        &auction.address,
+        &auction::QueryMsg::AuditBidsWithPermit {
+            permit: permit(ADMIN, AuctionPermission::ViewActiveBids),
+            pagination: pagination()
+        }
+    ).unwrap_err();
+    assert_eq!(err.to_string(), AuthError::Unauthorized.to_string());
+}
+
+#[test]
+fn guarded_queries_fail_while_paused() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.create_auction(Config {
+        guard_queries: Some(true),
+        public_active_bids: Some(true),
+        ..config("Road 23", block)
+    }).unwrap().contract;
+
+    let active_bids = auction::QueryMsg::ActiveBids {
+        pagination: Pagination { start: 0, limit: 30 }
+    };
+
+    let bids: PaginatedResponse<Uint128> = suite.ensemble.query(
+        &auction.address,
+        &active_bids
+    ).unwrap();
+
+    assert_eq!(bids.total, 0);
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::SetStatus {
+            status: ContractStatus::Paused { reason: "Maintenance".into() }
+        },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    let err = suite.ensemble.query::<_, PaginatedResponse<Uint128>>(
+        &auction.address,
+        &active_bids
+    ).unwrap_err();
+
+    assert_eq!(err.to_string(), LifecycleError::Paused.to_string());
+
+    // Public queries are not affected.
+    suite.ensemble.query::<_, SaleStatus>(
+        &auction.address,
+        &auction::QueryMsg::SaleStatus { }
+    ).unwrap();
+
+    // Unguarded auctions keep answering while paused.
+    let unguarded = suite.create_auction(Config {
+        public_active_bids: Some(true),
+        ..config("Road 24", block)
+    }).unwrap().contract;
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::SetStatus {
+            status: ContractStatus::Paused { reason: "Maintenance".into() }
+        },
+        MockEnv::new(ADMIN, &unguarded.address)
+    ).unwrap();
+
+    suite.ensemble.query::<_, PaginatedResponse<Uint128>>(
+        &unguarded.address,
+        &active_bids
+    ).unwrap();
+}
+
+#[test]
+fn concurrent_auctions_are_isolated() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let names = ["Road 23", "Road 24", "Road 25"];
+    let auctions = names.iter().enumerate().map(|(i, name)|
+        suite.create_auction(config(*name, block + i as u64)).unwrap()
+    ).collect::<Vec<_>>();
+
+    // The registry has an entry with a distinct address for each auction.
+    let registry = suite.list_auctions(0, 30).unwrap();
+    assert_eq!(registry.total, names.len() as u64);
+
+    for (i, entry) in registry.entries.iter().enumerate() {
+        assert_eq!(entry.info.name, names[i]);
+        assert_eq!(entry.info.end_block, block + i as u64);
+        assert_eq!(entry.contract.address, auctions[i].contract.address);
+
+        let status: SaleStatus = suite.ensemble.query(
+            &entry.contract.address,
+            &auction::QueryMsg::SaleStatus { }
+        ).unwrap();
+
+        assert_eq!(status.info.name, names[i]);
+    }
+
+    // Both bidders bid on every auction, with a different winner in each.
+    let bidders = ["bidder_1", "bidder_2"];
+    let base = one_token(6) * 100;
+
+    for (i, auction) in auctions.iter().enumerate() {
+        for (j, bidder) in bidders.iter().enumerate() {
+            let amount = base + ((i + j) % 2) as u128;
+
+            suite.ensemble.add_funds(bidder, vec![coin(amount, "uscrt")]);
+            suite.ensemble.execute(
+                &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+                MockEnv::new(*bidder, &auction.contract.address)
+                    .sent_funds(vec![coin(amount, "uscrt")])
+            ).unwrap();
+        }
+    }
+
+    for auction in auctions.iter() {
+        let status: SaleStatus = suite.ensemble.query(
+            &auction.contract.address,
+            &auction::QueryMsg::SaleStatus { }
+        ).unwrap();
+
+        assert_eq!(status.current_highest.unwrap().u128(), base + 1);
+
+        let balances = suite.ensemble.balances(&auction.contract.address).unwrap();
+        assert_eq!(balances["uscrt"].u128(), base * 2 + 1);
+    }
+
+    // Retracting from one auction leaves the others untouched.
+    suite.finish_auction(&auctions[0].contract);
+
+    // bidder_1 lost the first auction.
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::RetractBid { },
+        MockEnv::new(bidders[0], &auctions[0].contract.address)
+    ).unwrap();
+    suite.claim(&auctions[0].contract, bidders[0]);
+
+    let balances = suite.ensemble.balances(&auctions[0].contract.address).unwrap();
+    assert_eq!(balances["uscrt"].u128(), base + 1);
+
+    for auction in auctions[1..].iter() {
+        let balances = suite.ensemble.balances(&auction.contract.address).unwrap();
+        assert_eq!(balances["uscrt"].u128(), base * 2 + 1);
+    }
+}
+
+#[test]
+fn failed_creation_does_not_affect_later_auctions() {
+    let mut suite = Suite::new();
+    let height = suite.ensemble.block().height;
+
+    suite.new_auction(height + 1000).unwrap();
+
+    // Fails to instantiate.
+    suite.ensemble.execute(
+        &factory::ExecuteMsg::CreateAuction {
+            config: config("Road 24", height),
+            tags: None,
+            end: None
+        },
+        MockEnv::new("sender", &suite.factory.address)
+    ).unwrap();
+
+    let auction = suite.create_auction(config("Road 25", height + 1000)).unwrap();
+
+    let auctions = suite.list_auctions(0, 30).unwrap();
+    assert_eq!(auctions.total, 2);
+    assert_eq!(auctions.entries[0].info.name, "Road 23");
+    assert_eq!(auctions.entries[1].info.name, "Road 25");
+
+    let status: SaleStatus = suite.ensemble.query(
+        &auction.contract.address,
+        &auction::QueryMsg::SaleStatus { }
+    ).unwrap();
+
+    assert_eq!(status.info.name, "Road 25");
+}
+
+#[test]
+fn invalid_auction_names_are_rejected() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let cases = [
+        ("".to_string(), NameError::Empty),
+        ("a".repeat(65), NameError::TooLong { len: 65 }),
+        ("Road\n23".to_string(), NameError::InvalidCharacter('\n'))
+    ];
+
+    for (name, expected) in cases {
+        let err = suite.create_auction(config(name, block)).unwrap_err();
+
+        assert_eq!(contract_error(err), expected.into());
+    }
+
+    assert_eq!(suite.list_auctions(0, 30).unwrap().total, 0);
+}
+
+#[test]
+fn reserve_price_and_beneficiary() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let bidder = "bidder";
+    let beneficiary = "beneficiary";
+    let bid_amount = one_token(6) * 100;
+
+    let unmet = suite.create_auction(Config {
+        reserve: Some(Uint128::new(bid_amount + 1)),
+        beneficiary: Some(beneficiary.into()),
+        ..config("Road 23", block)
+    }).unwrap().contract;
+
+    let met = suite.create_auction(Config {
+        reserve: Some(Uint128::new(bid_amount)),
+        beneficiary: Some(beneficiary.into()),
+        ..config("Road 24", block)
+    }).unwrap().contract;
+
+    for auction in [&unmet, &met] {
+        suite.ensemble.add_funds(bidder, vec![coin(bid_amount, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new(bidder, &auction.address)
+                .sent_funds(vec![coin(bid_amount, "uscrt")])
+        ).unwrap();
+    }
+
+    suite.finish_auction(&unmet);
+
+    // The sale is settled without a winner.
+    let resp = suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &unmet.address)
+    ).unwrap();
+
+    let result: ProceedsResult = from_binary(&resp.response.data.unwrap()).unwrap();
+    assert_eq!(result, ProceedsResult {
+        winner: None,
+        proceeds: Uint128::zero(),
+        fee: Uint128::zero(),
+        remaining: Uint128::zero()
+    });
+
+    // The highest bidder can get their funds back since the reserve wasn't met.
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::RetractBid { },
+        MockEnv::new(bidder, &unmet.address)
+    ).unwrap();
+    suite.claim(&unmet, bidder);
+
+    let bidder_balances = suite.ensemble.balances(bidder).unwrap();
+    assert_eq!(bidder_balances["uscrt"].u128(), bid_amount);
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &met.address)
+    ).unwrap();
+    suite.claim(&met, beneficiary);
+
+    let beneficiary_balances = suite.ensemble.balances(beneficiary).unwrap();
+    assert_eq!(beneficiary_balances["uscrt"].u128(), bid_amount);
+    assert!(suite.ensemble.balances(ADMIN).is_none());
+}
+
+#[test]
+fn min_increment_in_custom_denom() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.create_auction(Config {
+        denom: Some(Denom::new("uatom").unwrap()),
+        min_increment: Some(Uint128::new(10)),
+        ..config("Road 23", block)
+    }).unwrap().contract;
+
+    let bidder_1 = "bidder_1";
+    let bidder_2 = "bidder_2";
+
+    suite.ensemble.add_funds(bidder_1, vec![coin(100, "uatom")]);
+    suite.ensemble.add_funds(bidder_2, vec![coin(110, "uatom")]);
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new(bidder_1, &auction.address)
+            .sent_funds(vec![coin(100, "uatom")])
+    ).unwrap();
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new(bidder_2, &auction.address)
+            .sent_funds(vec![coin(105, "uatom")])
+    ).unwrap_err();
+
+    assert_eq!(
+        err.unwrap_contract_error().to_string(),
+        "Generic error: Bid must exceed the current highest by at least 10uatom."
+    );
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new(bidder_2, &auction.address)
+            .sent_funds(vec![coin(110, "uatom")])
+    ).unwrap();
+
+    let status: SaleStatus = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::SaleStatus { }
+    ).unwrap();
+
+    assert_eq!(status.current_highest.unwrap().u128(), 110);
+}
+
+#[test]
+fn min_bid_to_win_accounts_for_balance_and_increment() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.create_auction(Config {
+        reserve: Some(Uint128::new(50)),
+        min_increment: Some(Uint128::new(10)),
+        ..config("Road 23", block)
+    }).unwrap().contract;
+
+    for bidder in ["bidder_1", "bidder_2"] {
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::SetViewingKey { key: "key".into(), padding: None },
+            MockEnv::new(bidder, &auction.address)
+        ).unwrap();
+    }
+
+    let min_bid_to_win = |suite: &Suite, bidder: &str| -> u128 {
+        suite.ensemble.query::<_, Uint128>(
+            &auction.address,
+            &auction::QueryMsg::MinBidToWin { address: bidder.into(), key: "key".into() }
+        ).unwrap().u128()
+    };
+
+    // Without any bids the reserve has to be met.
+    assert_eq!(min_bid_to_win(&suite, "bidder_1"), 50);
+
+    suite.ensemble.add_funds("bidder_1", vec![coin(100, "uscrt")]);
+    suite.ensemble.add_funds("bidder_2", vec![coin(110, "uscrt")]);
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder_1", &auction.address)
+            .sent_funds(vec![coin(100, "uscrt")])
+    ).unwrap();
+
+    assert_eq!(min_bid_to_win(&suite, "bidder_1"), 0);
+    assert_eq!(min_bid_to_win(&suite, "bidder_2"), 110);
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder_2", &auction.address)
+            .sent_funds(vec![coin(110, "uscrt")])
+    ).unwrap();
+
+    // The existing balance counts towards the amount.
+    assert_eq!(min_bid_to_win(&suite, "bidder_1"), 20);
+
+    let permit = Permit::new(
+        "bidder_1",
+        PermitParams::new(auction.address.as_str())
+            .permissions([AuctionPermission::ViewBid])
+            .name("bids")
+    );
+
+    let amount: Uint128 = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::MinBidToWinWithPermit { permit }
+    ).unwrap();
+    assert_eq!(amount.u128(), 20);
+
+    let err = suite.ensemble.query::<_, Uint128>(
+        &auction.address,
+        &auction::QueryMsg::MinBidToWin { address: "bidder_2".into(), key: "wrong".into() }
+    ).unwrap_err();
+    assert_eq!(err.to_string(), AuthError::Unauthorized.to_string());
+
+    suite.ensemble.block_mut().height = block + 1;
+
+    let err = suite.ensemble.query::<_, Uint128>(
+        &auction.address,
+        &auction::QueryMsg::MinBidToWin { address: "bidder_2".into(), key: "key".into() }
+    ).unwrap_err();
+    assert_eq!(err.to_string(), LifecycleError::Finished.to_string());
+}
+
+#[test]
+fn bidders_can_view_their_rank() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    for (bidder, amount) in [("bidder_1", 100), ("bidder_2", 300), ("bidder_3", 200)] {
+        suite.ensemble.add_funds(bidder, vec![coin(amount, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new(bidder, &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        ).unwrap();
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::SetViewingKey { key: "key".into(), padding: None },
+            MockEnv::new(bidder, &auction.address)
+        ).unwrap();
+    }
+
+    let my_rank = |suite: &Suite, bidder: &str| -> BidRank {
+        suite.ensemble.query(
+            &auction.address,
+            &auction::QueryMsg::MyRank { address: bidder.into(), key: "key".into() }
+        ).unwrap()
+    };
+
+    assert_eq!(
+        my_rank(&suite, "bidder_2"),
+        BidRank { rank: Some(0), behind_highest: Uint128::zero() }
+    );
+    assert_eq!(
+        my_rank(&suite, "bidder_3"),
+        BidRank { rank: Some(1), behind_highest: Uint128::new(100) }
+    );
+    assert_eq!(
+        my_rank(&suite, "bidder_1"),
+        BidRank { rank: Some(2), behind_highest: Uint128::new(200) }
+    );
+
+    let permit = Permit::new(
+        "bidder_3",
+        PermitParams::new(auction.address.as_str())
+            .permissions([AuctionPermission::ViewBid])
+            .name("bids")
+    );
+
+    let rank: BidRank = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::MyRankWithPermit { permit }
+    ).unwrap();
+    assert_eq!(rank.rank, Some(1));
+
+    let err = suite.ensemble.query::<_, BidRank>(
+        &auction.address,
+        &auction::QueryMsg::MyRank { address: "bidder_1".into(), key: "wrong".into() }
+    ).unwrap_err();
+    assert_eq!(err.to_string(), AuthError::Unauthorized.to_string());
+
+    suite.finish_auction(&auction);
+
+    // Refunded bidders are no longer ranked.
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::RetractBid { },
+        MockEnv::new("bidder_1", &auction.address)
+    ).unwrap();
+
+    assert_eq!(
+        my_rank(&suite, "bidder_1"),
+        BidRank { rank: None, behind_highest: Uint128::new(300) }
+    );
+}
+
+#[test]
+fn usd_amounts_are_converted_with_the_oracle() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let oracle = suite.new_oracle("oracle");
+    // 1uscrt is worth $0.01.
+    suite.set_price(&oracle, Decimal::percent(1));
+
+    let auction = suite.create_auction(Config {
+        oracle: Some(PriceOracle {
+            address: oracle.address.to_string(),
+            code_hash: oracle.code_hash.clone(),
+            max_age: 60
+        }),
+        usd_reserve: Some(Decimal::from_ratio(3u128, 2u128)),
+        usd_min_increment: Some(Decimal::percent(10)),
+        ..config("Road 23", block)
+    }).unwrap().contract;
+
+    suite.ensemble.add_funds("bidder_1", vec![coin(100, "uscrt")]);
+    suite.ensemble.add_funds("bidder_2", vec![coin(200, "uscrt")]);
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder_1", &auction.address)
+            .sent_funds(vec![coin(100, "uscrt")])
+    ).unwrap();
+
+    // $0.10 is 10uscrt at the current price.
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder_2", &auction.address)
+            .sent_funds(vec![coin(105, "uscrt")])
+    ).unwrap_err();
+    assert_eq!(
+        err.unwrap_contract_error().to_string(),
+        "Generic error: Bid must exceed the current highest by at least 10uscrt."
+    );
+
+    // The price is too old once more than a minute has passed.
+    suite.ensemble.block_mut().time += 61;
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder_2", &auction.address)
+            .sent_funds(vec![coin(110, "uscrt")])
+    ).unwrap_err();
+    assert!(matches!(contract_error(err), ContractError::Oracle(OracleError::Stale { .. })));
+
+    suite.set_price(&oracle, Decimal::percent(1));
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder_2", &auction.address)
+            .sent_funds(vec![coin(110, "uscrt")])
+    ).unwrap();
+
+    suite.finish_auction(&auction);
+
+    // The reserve of $1.50 was 150uscrt at the last bid, which isn't met.
+    // A later price doesn't change it once the sale has finished.
+    suite.set_price(&oracle, Decimal::percent(2));
+    let resp = suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    let result: ProceedsResult = from_binary(&resp.response.data.unwrap()).unwrap();
+    assert_eq!(result.winner, None);
+
+    // Refunds don't need the oracle, even when its price is too old.
+    suite.ensemble.block_mut().time += 61;
+
+    for (bidder, amount) in [("bidder_1", 100), ("bidder_2", 110)] {
+        let resp = suite.ensemble.execute(
+            &auction::ExecuteMsg::RetractBid { },
+            MockEnv::new(bidder, &auction.address)
+        ).unwrap();
+
+        let result: RetractResult = from_binary(&resp.response.data.unwrap()).unwrap();
+        assert_eq!(result.refunded.u128(), amount);
+    }
+}
+
+#[test]
+fn newer_config_versions_are_rejected() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let err = suite.create_auction(Config {
+        version: Config::VERSION + 1,
+        ..config("Road 23", block)
+    }).unwrap_err();
+
+    assert_eq!(
+        err.unwrap_contract_error().to_string(),
+        "Generic error: Unsupported config version: 2. Latest supported is 1."
+    );
+}
+
+#[test]
+fn config_can_only_be_updated_before_the_first_bid() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    let update = auction::ExecuteMsg::UpdateConfig {
+        name: Some("Road 24".into()),
+        metadata: Some("Two bedroom apartment".into()),
+        reserve: Some(Uint128::new(100)),
+        end_block: Some(block + 1000)
+    };
+
+    let err = suite.ensemble.execute(
+        &update,
+        MockEnv::new("bidder", &auction.address)
+    ).unwrap_err();
+
+    assert_eq!(err.unwrap_contract_error().to_string(), "Generic error: Unauthorized");
+
+    suite.ensemble.execute(
+        &update,
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    let status: SaleStatus = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::SaleStatus { }
+    ).unwrap();
+
+    assert_eq!(status.info.name, "Road 24");
+    assert_eq!(status.info.metadata.as_deref(), Some("Two bedroom apartment"));
+    assert_eq!(status.info.end_block, block + 1000);
+
+    let bid_amount = one_token(6) * 100;
+
+    suite.ensemble.add_funds("bidder", vec![coin(bid_amount, "uscrt")]);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder", &auction.address)
+            .sent_funds(vec![coin(bid_amount, "uscrt")])
+    ).unwrap();
+
+    let err = suite.ensemble.execute(
+        &update,
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap_err();
+
+    assert_eq!(contract_error(err), LifecycleError::ConfigLocked.into());
+}
+
+#[test]
+fn config_stays_locked_after_early_retractions() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.create_auction(Config {
+        early_retraction_penalty: Some(0),
+        ..config("Road 23", block)
+    }).unwrap().contract;
+
+    suite.ensemble.add_funds("bidder", vec![coin(100, "uscrt")]);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder", &auction.address)
+            .sent_funds(vec![coin(100, "uscrt")])
+    ).unwrap();
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::RetractBid { },
+        MockEnv::new("bidder", &auction.address)
+    ).unwrap();
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::UpdateConfig {
+            name: None,
+            metadata: None,
+            reserve: Some(Uint128::new(100)),
+            end_block: None
+        },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::ConfigLocked.into());
+}
+
+#[test]
+fn empty_bids_are_rejected_and_dont_lock_the_config() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.create_auction(config("Road 23", block)).unwrap().contract;
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::ZeroBid.into());
+
+    // Funds in other denoms don't count either.
+    suite.ensemble.add_funds("bidder", vec![coin(100, "uatom")]);
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder", &auction.address)
+            .sent_funds(vec![coin(100, "uatom")])
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::ZeroBid.into());
+
+    let status: SaleStatus = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::SaleStatus { }
+    ).unwrap();
+    assert_eq!(status.num_bids, 0);
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::UpdateConfig {
+            name: None,
+            metadata: None,
+            reserve: Some(Uint128::new(100)),
+            end_block: None
+        },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+}
+
+#[test]
+fn ended_sales_can_not_be_restarted_by_updating_the_config() {
+    let mut suite = Suite::new();
+    let height = suite.ensemble.block().height;
+
+    let auction = suite.create_auction(Config {
+        start_block: Some(height + 100),
+        ..config("Road 23", height + 1000)
+    }).unwrap().contract;
+
+    let update = |suite: &mut Suite, end_block: u64| suite.ensemble.execute(
+        &auction::ExecuteMsg::UpdateConfig {
+            name: None,
+            metadata: None,
+            reserve: None,
+            end_block: Some(end_block)
+        },
+        MockEnv::new(ADMIN, &auction.address)
+    );
+
+    let err = update(&mut suite, height + 100).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::StartAfterEnd.into());
+
+    update(&mut suite, height + 101).unwrap();
+
+    suite.finish_auction(&auction);
+
+    let err = update(&mut suite, height + 2000).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::Finished.into());
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Settle { },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    let err = update(&mut suite, height + 2000).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::Finished.into());
+
+    let status: SaleStatus = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::SaleStatus { }
+    ).unwrap();
+    assert_eq!(status.info.end_block, height + 101);
+}
+
+#[test]
+fn lifecycle_state() {
+    let mut suite = Suite::new();
+    let height = suite.ensemble.block().height;
+
+    let auction = suite.create_auction(Config {
+        start_block: Some(height + 10),
+        ..config("Road 23", height + 1000)
+    }).unwrap().contract;
+
+    let state = |suite: &Suite| -> AuctionState {
+        suite.ensemble.query(
+            &auction.address,
+            &auction::QueryMsg::State { }
+        ).unwrap()
+    };
+
+    assert_eq!(state(&suite), AuctionState::NotStarted);
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder", &auction.address)
+    ).unwrap_err();
+
+    assert_eq!(contract_error(err), LifecycleError::NotStarted.into());
+
+    suite.advance_blocks(10);
+    assert_eq!(state(&suite), AuctionState::Active);
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::SetStatus {
+            status: ContractStatus::Paused { reason: "Maintenance".into() }
+        },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+    assert_eq!(state(&suite), AuctionState::Paused);
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::SetStatus { status: ContractStatus::Operational },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    suite.finish_auction(&auction);
+    assert_eq!(state(&suite), AuctionState::Ended);
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+    assert_eq!(state(&suite), AuctionState::Settled);
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap_err();
+
+    assert_eq!(contract_error(err), LifecycleError::AlreadySettled.into());
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::SetStatus {
+            status: ContractStatus::Migrating {
+                reason: "Cancelled".into(),
+                new_address: None
+            }
+        },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+    assert_eq!(state(&suite), AuctionState::Cancelled);
+}
+
+#[test]
+fn invalid_denoms_are_rejected() {
+    assert!(Denom::new("uscrt").is_ok());
+    assert!(Denom::new("ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2").is_ok());
+
+    assert_eq!(
+        Denom::new("ab").unwrap_err().to_string(),
+        "Generic error: Denom must be between 3 and 128 characters long, got: \"ab\"."
+    );
+    assert_eq!(
+        Denom::new("1uscrt").unwrap_err().to_string(),
+        "Generic error: Denom contains invalid characters: \"1uscrt\"."
+    );
+    assert_eq!(
+        Denom::new("u scrt").unwrap_err().to_string(),
+        "Generic error: Denom contains invalid characters: \"u scrt\"."
+    );
+
+    // Invalid denoms can't make it into messages either.
+    let msg = fadroma::cosmwasm_std::to_vec(&factory::ExecuteMsg::CreateAuction {
+        config: config("Road 23", 1000),
+        tags: None,
+        end: None
+    }).unwrap();
+    let msg = String::from_utf8(msg).unwrap()
+        .replace(r#""denom":null"#, r#""denom":"u scrt""#);
+
+    let err = fadroma::cosmwasm_std::from_slice::<factory::ExecuteMsg>(
+        msg.as_bytes()
+    ).unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "Error parsing into type factory::factory::ExecuteMsg: \
+        Generic error: Denom contains invalid characters: \"u scrt\"."
+    );
+}
+
+#[test]
+fn pagination_limit_is_capped() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    for _ in 0..=Pagination::LIMIT {
+        suite.new_auction(block).unwrap();
+    }
+
+    let page = suite.list_auctions(0, u8::MAX).unwrap();
+    assert_eq!(page.total, Pagination::LIMIT as u64 + 1);
+    assert_eq!(page.entries.len(), Pagination::LIMIT as usize);
+}
+
+#[test]
+fn escrow_matches_outstanding_bids() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    let invariants = |suite: &Suite| -> Invariants {
+        suite.ensemble.query(
+            &auction.address,
+            &auction::QueryMsg::Invariants { }
+        ).unwrap()
+    };
+
+    assert_eq!(invariants(&suite), Invariants {
+        total_escrowed: Uint128::zero(),
+        outstanding_bids: Uint128::zero(),
+        holds: true
+    });
+
+    for (bidder, amount) in [("bidder_1", 100), ("bidder_2", 150), ("bidder_1", 80)] {
+        suite.ensemble.add_funds(bidder, vec![coin(amount, "uscrt"), coin(amount, "uatom")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new(bidder, &auction.address)
+                // Funds in other denoms are not counted.
+                .sent_funds(vec![coin(amount, "uscrt"), coin(amount, "uatom")])
+        ).unwrap();
+    }
+
+    let result = invariants(&suite);
+    assert_eq!(result.total_escrowed.u128(), 330);
+    assert!(result.holds);
+
+    suite.finish_auction(&auction);
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::RetractBid { },
+        MockEnv::new("bidder_2", &auction.address)
+    ).unwrap();
+
+    let result = invariants(&suite);
+    assert_eq!(result.total_escrowed.u128(), 180);
+    assert!(result.holds);
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    assert_eq!(invariants(&suite), Invariants {
+        total_escrowed: Uint128::zero(),
+        outstanding_bids: Uint128::zero(),
+        holds: true
+    });
+}
+
+#[test]
+fn proxy_bids_are_raised_automatically() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.create_auction(Config {
+        min_increment: Some(Uint128::new(10)),
+        ..config("Road 23", block)
+    }).unwrap().contract;
+
+    for (bidder, amount) in [("alice", 500), ("bob", 350), ("carol", 300)] {
+        suite.ensemble.add_funds(bidder, vec![coin(amount, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::SetViewingKey { key: "key".into(), padding: None },
+            MockEnv::new(bidder, &auction.address)
+        ).unwrap();
+    }
+
+    let proxy_bid = |suite: &mut Suite, bidder: &str, amount: u128| -> u128 {
+        let resp = suite.ensemble.execute(
+            &auction::ExecuteMsg::ProxyBid { },
+            MockEnv::new(bidder, &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        ).unwrap();
+
+        from_binary::<BidResult>(&resp.response.data.unwrap()).unwrap().balance.u128()
+    };
+    let bid = |suite: &mut Suite, bidder: &str, amount: u128| {
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new(bidder, &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        ).unwrap();
+    };
+    let view = |suite: &Suite, bidder: &str| -> ProxyBid {
+        suite.ensemble.query(
+            &auction.address,
+            &auction::QueryMsg::ViewProxyBid { address: bidder.into(), key: "key".into() }
+        ).unwrap()
+    };
+
+    // Without competition the lowest possible bid is placed.
+    assert_eq!(proxy_bid(&mut suite, "alice", 500), 1);
+
+    // Outbidding the proxy raises it just above the new bid.
+    bid(&mut suite, "bob", 100);
+    assert_eq!(view(&suite, "alice"), ProxyBid {
+        active: Uint128::new(110),
+        committed: Uint128::new(500)
+    });
+
+    // The lower of two proxies bids everything and the other one
+    // ends up ahead by the minimum increment.
+    assert_eq!(proxy_bid(&mut suite, "carol", 300), 300);
+    assert_eq!(view(&suite, "alice").active.u128(), 310);
+
+    bid(&mut suite, "bob", 250);
+    assert_eq!(view(&suite, "alice").active.u128(), 360);
+
+    let status: SaleStatus = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::SaleStatus { }
+    ).unwrap();
+    assert_eq!(status.current_highest.unwrap().u128(), 360);
+
+    let invariants: Invariants = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::Invariants { }
+    ).unwrap();
+    assert_eq!(invariants.total_escrowed.u128(), 1150);
+    assert!(invariants.holds);
+
+    suite.finish_auction(&auction);
+
+    // The winner gets back the part of the deposit that wasn't bid.
+    for (bidder, refunded) in [("alice", 140), ("bob", 350), ("carol", 300)] {
+        let resp = suite.ensemble.execute(
+            &auction::ExecuteMsg::RetractBid { },
+            MockEnv::new(bidder, &auction.address)
+        ).unwrap();
+
+        let result: RetractResult = from_binary(&resp.response.data.unwrap()).unwrap();
+        assert_eq!(result.refunded.u128(), refunded);
+    }
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::RetractBid { },
+        MockEnv::new("alice", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::WinnerCannotRetract.into());
+
+    let resp = suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    let result: ProceedsResult = from_binary(&resp.response.data.unwrap()).unwrap();
+    assert_eq!(result.proceeds.u128(), 360);
+}
+
+#[test]
+fn factory_creates_initial_auctions() {
+    let mut ensemble = ContractEnsemble::new();
+
+    let auction = ensemble.register(Box::new(Auction));
+    let factory = ensemble.register(Box::new(Factory));
+
+    let block = ensemble.block().height + 1000;
+
+    let factory = ensemble.instantiate(
+        factory.id,
+        &factory::InstantiateMsg {
+            admin: None,
+            auction,
+            initial_auctions: Some(vec![
+                config("Road 23", block),
+                config("Road 24", block),
+                // A failed auction doesn't prevent the factory from being created.
+                config("Road 25", ensemble.block().height)
+            ])
+        },
+        MockEnv::new("sender", FACTORY)
+    )
+    .unwrap()
+    .instance;
+
+    let mut suite = Suite { ensemble, factory };
+
+    let auctions = suite.list_auctions(0, 30).unwrap();
+    assert_eq!(auctions.total, 2);
+    assert_eq!(auctions.entries[0].info.name, "Road 23");
+    assert_eq!(auctions.entries[1].info.name, "Road 24");
+
+    // Auctions can still be created as usual afterwards.
+    let auction = suite.create_auction(config("Road 26", block)).unwrap();
+    assert_eq!(auction.info.name, "Road 26");
+    assert_eq!(suite.list_auctions(0, 30).unwrap().total, 3);
+}
+
+#[test]
+fn auctions_with_unexpected_code_hash_are_rejected() {
+    let mut ensemble = ContractEnsemble::new();
+
+    let impostor = ensemble.register(Box::new(Impostor));
+    let factory = ensemble.register(Box::new(Factory));
+
+    let factory = ensemble.instantiate(
+        factory.id,
+        &factory::InstantiateMsg { admin: None, auction: impostor, initial_auctions: None },
+        MockEnv::new("sender", FACTORY)
+    )
+    .unwrap()
+    .instance;
+
+    let mut suite = Suite { ensemble, factory };
+    let block = suite.ensemble.block().height + 1000;
+
+    let err = suite.create_auction(config("Road 23", block)).unwrap_err();
+    assert_eq!(
+        contract_error(err).to_string(),
+        "Generic error: Expected an auction with code hash test_contract_0 but got impostor."
+    );
+
+    assert_eq!(suite.list_auctions(0, 30).unwrap().total, 0);
+}
+
+#[test]
+fn auctions_without_reply_data_are_rejected() {
+    let mut ensemble = ContractEnsemble::new();
+
+    let mute = ensemble.register(Box::new(Mute));
+    let factory = ensemble.register(Box::new(Factory));
+
+    let factory = ensemble.instantiate(
+        factory.id,
+        &factory::InstantiateMsg { admin: None, auction: mute, initial_auctions: None },
+        MockEnv::new("sender", FACTORY)
+    )
+    .unwrap()
+    .instance;
+
+    let mut suite = Suite { ensemble, factory };
+    let block = suite.ensemble.block().height + 1000;
+
+    let err = suite.create_auction(config("Road 23", block)).unwrap_err();
+    assert_eq!(contract_error(err), ReplyError::MissingReplyData.into());
+
+    assert_eq!(suite.list_auctions(0, 30).unwrap().total, 0);
+}
+
+#[test]
+fn auctions_can_be_listed_by_tag() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let tags = |tags: &[&str]| Some(tags.iter().map(|x| x.to_string()).collect());
+
+    suite.create_tagged_auction(config("Road 23", block), tags(&["cars", "vintage"])).unwrap();
+    suite.create_tagged_auction(config("Road 24", block), tags(&["art"])).unwrap();
+    suite.create_tagged_auction(config("Road 25", block), tags(&["vintage"])).unwrap();
+    suite.new_auction(block).unwrap();
+
+    let by_tag = |suite: &Suite, tag: &str, start: u64| {
+        let resp: PaginatedResponse<AuctionEntry<Addr>> = suite.ensemble.query(
+            &suite.factory.address,
+            &factory::QueryMsg::ListAuctionsByTag {
+                tag: tag.into(),
+                pagination: Pagination { start, limit: 30 }
+            }
+        ).unwrap();
+
+        (resp.total, resp.entries.into_iter().map(|x| x.info.name).collect::<Vec<_>>())
+    };
+
+    assert_eq!(by_tag(&suite, "vintage", 0), (2, vec!["Road 23".into(), "Road 25".into()]));
+    assert_eq!(by_tag(&suite, "vintage", 1), (2, vec!["Road 25".into()]));
+    assert_eq!(by_tag(&suite, "art", 0), (1, vec!["Road 24".into()]));
+    assert_eq!(by_tag(&suite, "boats", 0), (0, vec![]));
+
+    let cases = [
+        (tags(&["a", "b", "c", "d", "e", "f"]), TagError::TooMany { count: 6 }),
+        (tags(&["Cars"]), TagError::Invalid("Cars".into())),
+        (tags(&[""]), TagError::Invalid("".into())),
+        (tags(&["cars", "cars"]), TagError::Duplicate("cars".into()))
+    ];
+
+    for (tags, expected) in cases {
+        let err = suite.create_tagged_auction(config("Road 26", block), tags).unwrap_err();
+        assert_eq!(contract_error(err), expected.into());
+    }
+
+    assert_eq!(suite.list_auctions(0, 30).unwrap().total, 4);
+}
+
+#[test]
+fn auctions_ending_soon() {
+    let mut suite = Suite::new();
+    let height = suite.ensemble.block().height;
+
+    for (name, end_block) in [
+        ("Road 23", height + 500),
+        ("Road 24", height + 100),
+        ("Road 25", height + 300),
+        ("Road 26", height + 100),
+        ("Road 27", height + 1000)
+    ] {
+        suite.create_auction(config(name, end_block)).unwrap();
+    }
+
+    let ending_soon = |suite: &Suite, before_block: u64, start: u64| {
+        let resp: PaginatedResponse<AuctionEntry<Addr>> = suite.ensemble.query(
+            &suite.factory.address,
+            &factory::QueryMsg::ListEndingSoon {
+                before_block,
+                pagination: Pagination { start, limit: 30 }
+            }
+        ).unwrap();
+
+        (resp.total, resp.entries.into_iter().map(|x| x.info.name).collect::<Vec<_>>())
+    };
+
+    assert_eq!(
+        ending_soon(&suite, height + 400, 0),
+        (3, vec!["Road 24".into(), "Road 26".into(), "Road 25".into()])
+    );
+    assert_eq!(ending_soon(&suite, height + 400, 2), (3, vec!["Road 25".into()]));
+    assert_eq!(ending_soon(&suite, height + 100, 0), (0, vec![]));
+
+    // Auctions that have already ended are left out.
+    suite.advance_blocks(150);
+    assert_eq!(ending_soon(&suite, height + 600, 0), (2, vec!["Road 25".into(), "Road 23".into()]));
+    assert_eq!(ending_soon(&suite, height, 0), (0, vec![]));
+}
+
+#[test]
+fn auctions_ending_soon_follow_changes_to_their_end_block() {
+    let mut suite = Suite::new();
+    let height = suite.ensemble.block().height;
+
+    let road_23 = suite.create_auction(config("Road 23", height + 100)).unwrap().contract;
+    let road_24 = suite.create_auction(config("Road 24", height + 300)).unwrap().contract;
+    let road_25 = suite.create_auction(config("Road 25", height + 500)).unwrap().contract;
+
+    let move_end = |suite: &mut Suite, auction: &ContractLink<Addr>, end_block: u64| {
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::UpdateConfig {
+                name: None,
+                metadata: None,
+                reserve: None,
+                end_block: Some(end_block)
+            },
+            MockEnv::new(ADMIN, &auction.address)
+        ).unwrap();
+    };
+    let ending_soon = |suite: &Suite, before_block: u64| {
+        let resp: PaginatedResponse<AuctionEntry<Addr>> = suite.ensemble.query(
+            &suite.factory.address,
+            &factory::QueryMsg::ListEndingSoon {
+                before_block,
+                pagination: Pagination { start: 0, limit: 30 }
+            }
+        ).unwrap();
+
+        resp.entries.into_iter().map(|x| (x.info.name, x.info.end_block)).collect::<Vec<_>>()
+    };
+
+    move_end(&mut suite, &road_23, height + 400);
+    assert_eq!(ending_soon(&suite, height + 450), vec![
+        ("Road 24".into(), height + 300),
+        ("Road 23".into(), height + 400)
+    ]);
+
+    move_end(&mut suite, &road_25, height + 200);
+    assert_eq!(ending_soon(&suite, height + 450), vec![
+        ("Road 25".into(), height + 200),
+        ("Road 24".into(), height + 300),
+        ("Road 23".into(), height + 400)
+    ]);
+
+    suite.advance_blocks(250);
+    suite.ensemble.execute(
+        &factory::ExecuteMsg::ProcessEnded { limit: 10 },
+        MockEnv::new("keeper", &suite.factory.address)
+    ).unwrap();
+
+    move_end(&mut suite, &road_24, height + 600);
+    assert_eq!(ending_soon(&suite, height + 700), vec![
+        ("Road 23".into(), height + 400),
+        ("Road 24".into(), height + 600)
+    ]);
+
+    let listed = suite.list_auctions(0, 30).unwrap();
+    assert_eq!(listed.entries.len(), 2);
+    assert_eq!(listed.entries[1].info.end_block, height + 600);
+
+    suite.advance_blocks(400);
+    let unsettled: PaginatedResponse<AuctionEntry<Addr>> = suite.ensemble.query(
+        &suite.factory.address,
+        &factory::QueryMsg::ListUnsettled { pagination: Pagination { start: 0, limit: 30 } }
+    ).unwrap();
+    assert_eq!(unsettled.total, 2);
+    assert_eq!(
+        unsettled.entries.into_iter().map(|x| x.contract).collect::<Vec<_>>(),
+        vec![road_23, road_24]
+    );
+}
+
+#[test]
+fn auction_end_can_be_given_as_a_duration() {
+    let mut suite = Suite::new();
+
+    let absolute = suite.ensemble.block().height + 2000;
+    let mut expected = vec![];
+
+    for end in [
+        AuctionEnd::Duration { blocks: 500 },
+        AuctionEnd::AbsoluteHeight { height: absolute }
+    ] {
+        expected.push(match end {
+            AuctionEnd::Duration { blocks } => suite.ensemble.block().height + blocks,
+            AuctionEnd::AbsoluteHeight { height } => height
+        });
+
+        suite.ensemble.execute(
+            &factory::ExecuteMsg::CreateAuction {
+                // The end block of the config is ignored.
+                config: config("Road 23", 1),
+                tags: None,
+                end: Some(end)
+            },
+            MockEnv::new("sender", &suite.factory.address)
+        ).unwrap();
+    }
+
+    let auctions = suite.list_auctions(0, 30).unwrap();
+
+    for (auction, end_block) in auctions.entries.iter().zip(expected) {
+        assert_eq!(auction.info.end_block, end_block);
+
+        let status: SaleStatus = suite.ensemble.query(
+            &auction.contract.address,
+            &auction::QueryMsg::SaleStatus { }
+        ).unwrap();
+        assert_eq!(status.info.end_block, end_block);
+    }
+}
+
+#[test]
+fn subscribers_are_notified_of_new_auctions() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let subscriber = suite.ensemble.register(Box::new(Subscriber { fail: false }));
+    let failing = suite.ensemble.register(Box::new(Subscriber { fail: true }));
+
+    let mut subscribe = |code: ContractCode, label: &str| {
+        let contract = suite.ensemble.instantiate(
+            code.id,
+            &(),
+            MockEnv::new("sender", label)
+        ).unwrap().instance;
+
+        suite.ensemble.execute(
+            &factory::ExecuteMsg::Subscribe { code_hash: contract.code_hash.clone() },
+            MockEnv::new(contract.address.as_str(), &suite.factory.address)
+        ).unwrap();
+
+        contract
+    };
+
+    let failing = subscribe(failing, "failing");
+    let subscriber = subscribe(subscriber, "subscriber");
+
+    let subscribers: Vec<ContractLink<Addr>> = suite.ensemble.query(
+        &suite.factory.address,
+        &factory::QueryMsg::Subscribers { }
+    ).unwrap();
+    assert_eq!(subscribers.len(), 2);
+
+    // The failing subscriber doesn't prevent auctions from being created.
+    suite.create_auction(config("Road 23", block)).unwrap();
+
+    let names = |suite: &Suite| -> Vec<String> {
+        suite.ensemble.query(&subscriber.address, &()).unwrap()
+    };
+    assert_eq!(names(&suite), vec!["Road 23".to_string()]);
+
+    let err = suite.ensemble.execute(
+        &factory::ExecuteMsg::Subscribe { code_hash: subscriber.code_hash.clone() },
+        MockEnv::new(subscriber.address.as_str(), &suite.factory.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err).to_string(), "Generic error: Already subscribed.");
+
+    // Only the admin can remove subscribers.
+    let remove = factory::ExecuteMsg::RemoveSubscriber {
+        address: failing.address.to_string()
+    };
+
+    let err = suite.ensemble.execute(
+        &remove,
+        MockEnv::new(failing.address.as_str(), &suite.factory.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err).to_string(), "Generic error: Unauthorized");
+
+    suite.ensemble.execute(&remove, MockEnv::new("sender", &suite.factory.address)).unwrap();
+
+    let subscribers: Vec<ContractLink<Addr>> = suite.ensemble.query(
+        &suite.factory.address,
+        &factory::QueryMsg::Subscribers { }
+    ).unwrap();
+    assert_eq!(subscribers.len(), 1);
+    assert_eq!(subscribers[0].address, subscriber.address);
+
+    suite.create_auction(config("Road 24", block)).unwrap();
+    assert_eq!(names(&suite), vec!["Road 23".to_string(), "Road 24".to_string()]);
+}
+
+#[test]
+fn winner_receives_a_receipt_nft() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let nft = suite.ensemble.register(Box::new(Nft));
+    let nft = suite.ensemble.instantiate(
+        nft.id,
+        &(),
+        MockEnv::new("sender", "nft")
+    ).unwrap().instance;
+
+    let receipt_nft = Some(snip721::ReceiptNft {
+        address: nft.address.to_string(),
+        code_hash: nft.code_hash.clone()
+    });
+
+    let auction = suite.create_auction(Config {
+        metadata: Some("A house on Road 23".into()),
+        receipt_nft: receipt_nft.clone(),
+        ..config("Road 23", block)
+    }).unwrap().contract;
+
+    // No certificate is minted if there was no winner.
+    let unsold = suite.create_auction(Config {
+        receipt_nft,
+        ..config("Road 24", block)
+    }).unwrap().contract;
+
+    suite.ensemble.add_funds("bidder", vec![coin(100, "uscrt")]);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder", &auction.address)
+            .sent_funds(vec![coin(100, "uscrt")])
+    ).unwrap();
+
+    suite.finish_auction(&auction);
+
+    for auction in [&auction, &unsold] {
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::ClaimProceeds { amount: None },
+            MockEnv::new(ADMIN, &auction.address)
+        ).unwrap();
+    }
+
+    let minted: Vec<(String, String, snip721::Metadata)> = suite.ensemble.query(
+        &nft.address,
+        &()
+    ).unwrap();
+
+    assert_eq!(minted, vec![(
+        auction.address.to_string(),
+        "bidder".to_string(),
+        snip721::Metadata {
+            token_uri: None,
+            extension: Some(snip721::Extension {
+                name: Some("Road 23".into()),
+                description: Some("A house on Road 23".into())
+            })
+        }
+    )]);
+}
+
+#[test]
+fn refund_claims_can_be_transferred() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.create_auction(Config {
+        tradable_claims: Some(true),
+        ..config("Road 23", block)
+    }).unwrap().contract;
+
+    for (bidder, amount) in [("bidder_1", 100), ("bidder_2", 150)] {
+        suite.ensemble.add_funds(bidder, vec![coin(amount, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new(bidder, &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        ).unwrap();
+    }
+
+    let transfer = auction::ExecuteMsg::TransferClaim {
+        claim_id: 0,
+        recipient: "buyer".into()
+    };
+
+    let err = suite.ensemble.execute(
+        &transfer,
+        MockEnv::new("bidder_1", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::NotFinished.into());
+
+    suite.finish_auction(&auction);
+
+    // The winning bid goes to the seller.
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::TransferClaim {
+            claim_id: 1,
+            recipient: "buyer".into()
+        },
+        MockEnv::new("bidder_2", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::WinnerCannotRetract.into());
+
+    let err = suite.ensemble.execute(
+        &transfer,
+        MockEnv::new("bidder_2", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), AuthError::Unauthorized.into());
+
+    let resp = suite.ensemble.execute(
+        &transfer,
+        MockEnv::new("bidder_1", &auction.address)
+    ).unwrap();
+
+    assert!(resp.response.attributes.iter().any(|x|
+        x.key == events::ACTION && x.value == events::actions::TRANSFER_CLAIM
+    ));
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::RetractBid { },
+        MockEnv::new("bidder_1", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), ClaimError::Transferred.into());
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::SetViewingKey {
+            key: "key".into(),
+            padding: None
+        },
+        MockEnv::new("bidder_1", &auction.address)
+    ).unwrap();
+
+    let claim: Claim = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::ViewClaim {
+            address: "bidder_1".into(),
+            key: "key".into(),
+            claim_id: None
+        }
+    ).unwrap();
+
+    assert_eq!(claim, Claim {
+        id: 0,
+        owner: Addr::unchecked("buyer"),
+        amount: Uint128::new(100)
+    });
+
+    let redeem = auction::ExecuteMsg::RedeemClaim { claim_id: 0 };
+
+    let err = suite.ensemble.execute(
+        &redeem,
+        MockEnv::new("bidder_1", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), AuthError::Unauthorized.into());
+
+    suite.ensemble.execute(
+        &redeem,
+        MockEnv::new("buyer", &auction.address)
+    ).unwrap();
+    suite.claim(&auction, "buyer");
+
+    let buyer_balances = suite.ensemble.balances("buyer").unwrap();
+    assert_eq!(buyer_balances["uscrt"].u128(), 100);
+
+    let err = suite.ensemble.execute(
+        &redeem,
+        MockEnv::new("buyer", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), ClaimError::Empty(0).into());
+
+    let invariants: Invariants = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::Invariants { }
+    ).unwrap();
+    assert_eq!(invariants.total_escrowed.u128(), 150);
+    assert!(invariants.holds);
+}
+
+#[test]
+fn refund_claims_are_not_tradable_by_default() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    suite.ensemble.add_funds("bidder", vec![coin(100, "uscrt")]);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder", &auction.address)
+            .sent_funds(vec![coin(100, "uscrt")])
+    ).unwrap();
+
+    suite.finish_auction(&auction);
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::RedeemClaim { claim_id: 0 },
+        MockEnv::new("bidder", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), ClaimError::NotTradable.into());
+}
+
+#[test]
+fn losing_bids_are_refunded_in_batches() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.create_auction(Config {
+        tradable_claims: Some(true),
+        ..config("Road 23", block)
+    }).unwrap().contract;
+
+    let bids = [("bidder_1", 100), ("bidder_2", 120), ("bidder_3", 130), ("bidder_4", 200)];
+
+    for (bidder, amount) in bids {
+        suite.ensemble.add_funds(bidder, vec![coin(amount, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new(bidder, &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        ).unwrap();
+    }
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::RefundBatch { limit: 2 },
+        MockEnv::new("anyone", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::NotFinished.into());
+
+    suite.finish_auction(&auction);
+
+    for limit in [0, shared::MAX_REFUND_BATCH + 1] {
+        let err = suite.ensemble.execute(
+            &auction::ExecuteMsg::RefundBatch { limit },
+            MockEnv::new("anyone", &auction.address)
+        ).unwrap_err();
+        assert_eq!(contract_error(err), PaginationError::InvalidBatchLimit { limit }.into());
+    }
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::TransferClaim {
+            claim_id: 1,
+            recipient: "buyer".into()
+        },
+        MockEnv::new("bidder_2", &auction.address)
+    ).unwrap();
+
+    let mut refund_batch = |expected_refunded: &str, expected_remaining: &str| {
+        let resp = suite.ensemble.execute(
+            &auction::ExecuteMsg::RefundBatch { limit: 2 },
+            MockEnv::new("anyone", &auction.address)
+        ).unwrap();
+
+        let attr = |key: &str| resp.response.attributes.iter()
+            .find(|x| x.key == key)
+            .unwrap()
+            .value
+            .clone();
+
+        assert_eq!(attr(events::ACTION), events::actions::REFUND_BATCH);
+        assert_eq!(attr(events::REFUNDED), expected_refunded);
+        assert_eq!(attr(events::REMAINING), expected_remaining);
+    };
+
+    refund_batch("2", "2");
+    // The winner is skipped.
+    refund_batch("1", "0");
+    // Further calls don't do anything.
+    refund_batch("0", "0");
+
+    for (account, amount) in [("bidder_1", 100), ("buyer", 120), ("bidder_3", 130)] {
+        suite.claim(&auction, account);
+
+        let balances = suite.ensemble.balances(account).unwrap();
+        assert_eq!(balances["uscrt"].u128(), amount);
+    }
+
+    let balances = suite.ensemble.balances("bidder_2").unwrap();
+    assert!(balances.get("uscrt").is_none_or(|x| x.is_zero()));
+
+    let invariants: Invariants = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::Invariants { }
+    ).unwrap();
+    assert_eq!(invariants.total_escrowed.u128(), 200);
+    assert!(invariants.holds);
+}
+
+#[test]
+fn refunds_of_those_who_opted_in_are_paid_by_refund_batch() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    // Only bidders with an active bid can opt in.
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::SetAutoRefund { enabled: true },
+        MockEnv::new("bidder_1", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::NoActiveBid.into());
+
+    let bids = [("bidder_1", 100, Some(true)), ("bidder_2", 120, None), ("bidder_3", 130, Some(true))];
+
+    for (bidder, amount, auto_refund) in bids {
+        suite.ensemble.add_funds(bidder, vec![coin(amount, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund, memo: None },
+            MockEnv::new(bidder, &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        ).unwrap();
+    }
+
+    // The preference can be changed after bidding.
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::SetAutoRefund { enabled: true },
+        MockEnv::new("bidder_2", &auction.address)
+    ).unwrap();
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::SetAutoRefund { enabled: false },
+        MockEnv::new("bidder_2", &auction.address)
+    ).unwrap();
+
+    suite.finish_auction(&auction);
+
+    // Settlement doesn't go through the bidders.
+    let resp = suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+    assert!(!resp.response.attributes.iter().any(|x| x.key == events::REFUNDED));
+
+    let resp = suite.ensemble.execute(
+        &auction::ExecuteMsg::RefundBatch { limit: 1 },
+        MockEnv::new("keeper", &auction.address)
+    ).unwrap();
+    assert!(resp.response.attributes.iter().any(|x|
+        x.key == events::REFUNDED && x.value == "1"
+    ));
+
+    // The refund is sent without having to be claimed.
+    let balances = suite.ensemble.balances("bidder_1").unwrap();
+    assert_eq!(balances["uscrt"].u128(), 100);
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::Claim { },
+        MockEnv::new("bidder_1", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::NothingToClaim.into());
+
+    let resp = suite.ensemble.execute(
+        &auction::ExecuteMsg::RetractBid { },
+        MockEnv::new("bidder_2", &auction.address)
+    ).unwrap();
+
+    let result: RetractResult = from_binary(&resp.response.data.unwrap()).unwrap();
+    assert_eq!(result.refunded.u128(), 120);
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::RetractBid { },
+        MockEnv::new("bidder_1", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::NothingToRefund.into());
+}
+
+#[test]
+fn auto_refund_is_capped() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    let bid = |suite: &mut Suite, bidder: &str, amount: u128| {
+        suite.ensemble.add_funds(bidder, vec![coin(amount, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: Some(true), memo: None },
+            MockEnv::new(bidder, &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        )
+    };
+
+    for i in 0..shared::MAX_AUTO_REFUNDS {
+        bid(&mut suite, &format!("bidder_{}", i), 100 + i as u128).unwrap();
+    }
+
+    let err = bid(&mut suite, "late_bidder", 1000).unwrap_err();
+    assert_eq!(
+        contract_error(err),
+        LifecycleError::AutoRefundsFull(shared::MAX_AUTO_REFUNDS).into()
+    );
+
+    // Opting out frees a slot.
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::SetAutoRefund { enabled: false },
+        MockEnv::new("bidder_0", &auction.address)
+    ).unwrap();
+
+    bid(&mut suite, "late_bidder", 1000).unwrap();
+}
+
+#[test]
+fn payouts_are_claimed_separately() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    for (bidder, amount) in [("bidder_1", 100), ("bidder_2", 150)] {
+        suite.ensemble.add_funds(bidder, vec![coin(amount, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new(bidder, &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        ).unwrap();
+    }
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::SetViewingKey { key: "key".into(), padding: None },
+        MockEnv::new("bidder_1", &auction.address)
+    ).unwrap();
+
+    let payout = |suite: &Suite| -> Uint128 {
+        suite.ensemble.query(
+            &auction.address,
+            &auction::QueryMsg::ViewPayout { address: "bidder_1".into(), key: "key".into() }
+        ).unwrap()
+    };
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::Claim { },
+        MockEnv::new("bidder_1", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::NothingToClaim.into());
+
+    suite.finish_auction(&auction);
+
+    // Retracting only records the refund.
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::RetractBid { },
+        MockEnv::new("bidder_1", &auction.address)
+    ).unwrap();
+
+    assert_eq!(payout(&suite).u128(), 100);
+    let balances = suite.ensemble.balances("bidder_1").unwrap();
+    assert!(balances.get("uscrt").is_none_or(|x| x.is_zero()));
+
+    let resp = suite.ensemble.execute(
+        &auction::ExecuteMsg::Claim { },
+        MockEnv::new("bidder_1", &auction.address)
+    ).unwrap();
+
+    let attr = |key: &str| resp.response.attributes.iter()
+        .find(|x| x.key == key)
+        .unwrap()
+        .value
+        .clone();
+
+    assert_eq!(attr(events::ACTION), events::actions::CLAIM);
+    assert_eq!(attr(events::RECIPIENT), "bidder_1");
+    assert_eq!(attr(events::AMOUNT), "100");
+
+    assert!(payout(&suite).is_zero());
+    let balances = suite.ensemble.balances("bidder_1").unwrap();
+    assert_eq!(balances["uscrt"].u128(), 100);
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::Claim { },
+        MockEnv::new("bidder_1", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::NothingToClaim.into());
+
+    let err = suite.ensemble.query::<_, Uint128>(
+        &auction.address,
+        &auction::QueryMsg::ViewPayout { address: "bidder_1".into(), key: "wrong".into() }
+    ).unwrap_err();
+    assert_eq!(err.to_string(), AuthError::Unauthorized.to_string());
+}
+
+#[test]
+fn raffle_winner_is_drawn_at_settlement() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let raffle = RaffleConfig {
+        ticket_price: Uint128::new(10),
+        refund_rate: Some(Decimal::percent(50))
+    };
+
+    let auction = suite.create_auction(Config {
+        raffle: Some(raffle),
+        ..config("Road 23", block)
+    }).unwrap().contract;
+
+    // The highest bid doesn't necessarily win.
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::UpdateConfig {
+            name: None,
+            metadata: None,
+            reserve: Some(Uint128::new(100)),
+            end_block: None
+        },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), RaffleError::Unsupported("A reserve price").into());
+
+    suite.ensemble.add_funds("bidder_0", vec![coin(15, "uscrt")]);
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder_0", &auction.address)
+            .sent_funds(vec![coin(15, "uscrt")])
+    ).unwrap_err();
+
+    assert_eq!(contract_error(err), RaffleError::InvalidBid {
+        amount: Uint128::new(15),
+        ticket_price: Uint128::new(10)
+    }.into());
+
+    let bids = [("bidder_1", 100), ("bidder_2", 200), ("bidder_3", 300)];
+
+    for (bidder, amount) in bids {
+        suite.ensemble.add_funds(bidder, vec![coin(amount, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new(bidder, &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        ).unwrap();
+    }
+
+    suite.finish_auction(&auction);
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::RetractBid { },
+        MockEnv::new("bidder_1", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), RaffleError::NotDrawn.into());
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    let mut winners = vec![];
+
+    for (bidder, amount) in bids {
+        let result = suite.ensemble.execute(
+            &auction::ExecuteMsg::RetractBid { },
+            MockEnv::new(bidder, &auction.address)
+        );
+
+        match result {
+            Ok(_) => {
+                suite.claim(&auction, bidder);
+
+                // Losers get back half of what they bid.
+                let balances = suite.ensemble.balances(bidder).unwrap();
+                assert_eq!(balances["uscrt"].u128(), amount / 2);
+            },
+            Err(err) => {
+                assert_eq!(contract_error(err), LifecycleError::WinnerCannotRetract.into());
+                winners.push(amount);
+            }
+        }
+    }
+
+    assert_eq!(winners.len(), 1);
+
+    // The whole winning bid plus the other halves of the losing ones.
+    suite.claim(&auction, ADMIN);
+
+    let admin_balances = suite.ensemble.balances(ADMIN).unwrap();
+    assert_eq!(admin_balances["uscrt"].u128(), 300 + winners[0] / 2);
+
+    let auction_balances = suite.ensemble.balances(&auction.address).unwrap();
+    assert!(auction_balances["uscrt"].is_zero());
+}
+
+#[test]
+fn fees_are_looked_up_at_settlement() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    suite.ensemble.add_funds("bidder", vec![coin(1000, "uscrt")]);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder", &auction.address)
+            .sent_funds(vec![coin(1000, "uscrt")])
+    ).unwrap();
+
+    let set_fee = |rate| factory::ExecuteMsg::SetFeeConfig {
+        fee: Some(FeeConfig {
+            rate: Decimal::percent(rate),
+            recipient: "platform".into()
+        })
+    };
+
+    let err = suite.ensemble.execute(
+        &set_fee(5),
+        MockEnv::new("bidder", &suite.factory.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), StdError::generic_err("Unauthorized").into());
+
+    let err = suite.ensemble.execute(
+        &set_fee(25),
+        MockEnv::new("sender", &suite.factory.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), StdError::generic_err("Fee rate cannot be more than 20%.").into());
+
+    // The auction already exists but the fee still applies.
+    suite.ensemble.execute(
+        &set_fee(5),
+        MockEnv::new("sender", &suite.factory.address)
+    ).unwrap();
+
+    let fee: Option<FeeConfig<Addr>> = suite.ensemble.query(
+        &suite.factory.address,
+        &factory::QueryMsg::FeeConfig { }
+    ).unwrap();
+    assert_eq!(fee.unwrap().recipient, Addr::unchecked("platform"));
+
+    suite.finish_auction(&auction);
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    for account in ["platform", ADMIN] {
+        suite.claim(&auction, account);
+    }
+
+    let platform_balances = suite.ensemble.balances("platform").unwrap();
+    assert_eq!(platform_balances["uscrt"].u128(), 50);
+
+    let admin_balances = suite.ensemble.balances(ADMIN).unwrap();
+    assert_eq!(admin_balances["uscrt"].u128(), 950);
+}
+
+#[test]
+fn messages_can_be_built() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let err = AuctionInit::builder().name("Road 23").build().unwrap_err();
+    assert_eq!(err, StdError::generic_err("End block must be set."));
+
+    let msg = AuctionInit::builder()
+        .name("Road 23")
+        .end_block(block)
+        .admin(ADMIN)
+        .reserve(100u128)
+        .public_active_bids(true)
+        .build()
+        .unwrap();
+
+    assert_eq!(msg.config, Config {
+        reserve: Some(Uint128::new(100)),
+        public_active_bids: Some(true),
+        ..config("Road 23", block)
+    });
+
+    let auction = suite.ensemble.register(Box::new(Auction));
+    let auction = suite.ensemble.instantiate(
+        auction.id,
+        &msg,
+        MockEnv::new(ADMIN, "auction")
+    ).unwrap().instance;
+
+    suite.ensemble.execute(
+        &ConfigUpdate::builder().name("Road 24").build(),
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    let status: SaleStatus = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::SaleStatus { }
+    ).unwrap();
+    assert_eq!(status.info.name, "Road 24");
+
+    let bids: PaginatedResponse<Uint128> = suite.ensemble.query(
+        &auction.address,
+        &shared::QueryMsg::active_bids(0, 10)
+    ).unwrap();
+    assert_eq!(bids.total, 0);
+}
+
+#[test]
+fn client_messages_are_accepted() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let factory = FactoryClient(suite.factory.clone());
+
+    // Messages are sent the same way a contract would send them.
+    let WasmMsg::Execute { msg, .. } = factory.create_auction(
+        config("Road 23", block),
+        Some(vec!["house".into()]),
+        None
+    ).unwrap() else {
+        unreachable!()
+    };
+
+    suite.ensemble.execute(
+        &from_binary::<factory::ExecuteMsg>(&msg).unwrap(),
+        MockEnv::new("sender", &suite.factory.address)
+    ).unwrap();
+
+    let QueryRequest::Wasm(WasmQuery::Smart { msg, .. }) = factory.list_auctions_by_tag(
+        "house",
+        Pagination { start: 0, limit: 10 }
+    ).unwrap() else {
+        unreachable!()
+    };
+
+    let auctions: PaginatedResponse<AuctionEntry<Addr>> = suite.ensemble.query(
+        &suite.factory.address,
+        &from_binary::<factory::QueryMsg>(&msg).unwrap()
+    ).unwrap();
+    assert_eq!(auctions.total, 1);
+
+    let auction = AuctionClient(auctions.entries[0].contract.clone());
+
+    suite.ensemble.add_funds("bidder", vec![coin(100, "uscrt")]);
+
+    let WasmMsg::Execute { msg, funds, .. } = auction.bid(vec![coin(100, "uscrt")]).unwrap() else {
+        unreachable!()
+    };
+
+    suite.ensemble.execute(
+        &from_binary::<auction::ExecuteMsg>(&msg).unwrap(),
+        MockEnv::new("bidder", &auction.0.address).sent_funds(funds)
+    ).unwrap();
+
+    let QueryRequest::Wasm(WasmQuery::Smart { msg, .. }) = auction.sale_status().unwrap() else {
+        unreachable!()
+    };
+
+    let status: SaleStatus = suite.ensemble.query(
+        &auction.0.address,
+        &from_binary::<auction::QueryMsg>(&msg).unwrap()
+    ).unwrap();
+    assert_eq!(status.current_highest.unwrap().u128(), 100);
+
+    for msg in [
+        auction.set_viewing_key("key").unwrap(),
+        auction.set_status(ContractStatus::Operational).unwrap(),
+        auction.change_admin(None).unwrap()
+    ] {
+        let WasmMsg::Execute { msg, .. } = msg else { unreachable!() };
+        from_binary::<auction::ExecuteMsg>(&msg).unwrap();
+    }
+
+    for query in [auction.status().unwrap(), auction.admin().unwrap()] {
+        let QueryRequest::Wasm(WasmQuery::Smart { msg, .. }) = query else { unreachable!() };
+        from_binary::<auction::QueryMsg>(&msg).unwrap();
+    }
+
+    let QueryRequest::Wasm(WasmQuery::Smart { msg, .. }) = factory.admin().unwrap() else {
+        unreachable!()
+    };
+    from_binary::<factory::QueryMsg>(&msg).unwrap();
+}
+
+#[test]
+fn status_changes_are_recorded() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    let status_info = |suite: &Suite| -> StatusInfo {
+        suite.ensemble.query(
+            &auction.address,
+            &auction::QueryMsg::StatusInfo { }
+        ).unwrap()
+    };
+
+    assert_eq!(status_info(&suite), StatusInfo {
+        status: ContractStatus::Operational,
+        changed_at: None
+    });
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::SetStatus {
+            status: ContractStatus::Paused { reason: " ".into() }
+        },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap_err();
+
+    assert_eq!(
+        err.unwrap_contract_error().to_string(),
+        "Generic error: A reason for the status change is required."
+    );
+
+    // The block is advanced after the message is executed.
+    let paused_at = suite.ensemble.block().clone();
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::SetStatus {
+            status: ContractStatus::Paused { reason: "Investigating a bug".into() }
+        },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    assert_eq!(status_info(&suite), StatusInfo {
+        status: ContractStatus::Paused { reason: "Investigating a bug".into() },
+        changed_at: Some(StatusChange {
+            height: paused_at.height,
+            time: paused_at.time
+        })
+    });
+}
+
+#[test]
+fn executes_are_counted_in_metrics() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    let metrics = |suite: &Suite| -> Metrics {
+        suite.ensemble.query(&auction.address, &auction::QueryMsg::Metrics { }).unwrap()
+    };
+
+    assert_eq!(metrics(&suite), Metrics::default());
+
+    for (bidder, amount) in [("bidder_1", 100), ("bidder_2", 150)] {
+        suite.ensemble.add_funds(bidder, vec![coin(amount, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new(bidder, &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        ).unwrap();
+    }
+
+    // Rejected executes don't count.
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Claim { },
+        MockEnv::new("bidder_1", &auction.address)
+    ).unwrap_err();
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::SetViewingKey { key: "key".into(), padding: None },
+        MockEnv::new("bidder_1", &auction.address)
+    ).unwrap();
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::RevokePermit { name: "bids".into() },
+        MockEnv::new("bidder_1", &auction.address)
+    ).unwrap();
+
+    suite.finish_auction(&auction);
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::RetractBid { },
+        MockEnv::new("bidder_1", &auction.address)
+    ).unwrap();
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+    suite.claim(&auction, "bidder_1");
+
+    assert_eq!(metrics(&suite), Metrics {
+        bids: 2,
+        retractions: 1,
+        claims: 2,
+        key_operations: 2
+    });
+}
+
+#[test]
+fn pauser_can_only_pause() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    let pause = auction::ExecuteMsg::SetStatus {
+        status: ContractStatus::Paused { reason: "Suspicious bids".into() }
+    };
+    let resume = auction::ExecuteMsg::SetStatus { status: ContractStatus::Operational };
+
+    let err = suite.ensemble.execute(
+        &pause,
+        MockEnv::new("monitor", &auction.address)
+    ).unwrap_err();
+    assert_eq!(err.unwrap_contract_error().to_string(), "Generic error: Unauthorized");
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::SetPauser { address: Some("monitor".into()) },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    let pauser: Option<Addr> = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::Pauser { }
+    ).unwrap();
+    assert_eq!(pauser, Some(Addr::unchecked("monitor")));
+
+    let migrate = auction::ExecuteMsg::SetStatus {
+        status: ContractStatus::Migrating { reason: "Upgrade".into(), new_address: None }
+    };
+
+    let err = suite.ensemble.execute(
+        &migrate,
+        MockEnv::new("monitor", &auction.address)
+    ).unwrap_err();
+    assert_eq!(err.unwrap_contract_error().to_string(), "Generic error: Unauthorized");
+
+    let resp = suite.ensemble.execute(
+        &pause,
+        MockEnv::new("monitor", &auction.address)
+    ).unwrap();
+
+    let attr = |attributes: &[Attribute], key: &str| attributes.iter()
+        .find(|x| x.key == key)
+        .map(|x| x.value.clone());
+
+    let attributes = &resp.response.attributes;
+    assert_eq!(attr(attributes, events::ACTION).unwrap(), events::actions::SET_STATUS);
+    assert_eq!(attr(attributes, events::PREVIOUS_STATUS).unwrap(), events::statuses::OPERATIONAL);
+    assert_eq!(attr(attributes, events::NEW_STATUS).unwrap(), events::statuses::PAUSED);
+    assert_eq!(attr(attributes, events::ACTOR).unwrap(), "monitor");
+    assert_eq!(attr(attributes, events::REASON).unwrap(), "Suspicious bids");
+
+    let state: AuctionState = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::State { }
+    ).unwrap();
+    assert_eq!(state, AuctionState::Paused);
+
+    let err = suite.ensemble.execute(
+        &resume,
+        MockEnv::new("monitor", &auction.address)
+    ).unwrap_err();
+    assert_eq!(err.unwrap_contract_error().to_string(), "Generic error: Unauthorized");
+
+    // Resuming is up to the admin.
+    let resp = suite.ensemble.execute(
+        &resume,
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    let attributes = &resp.response.attributes;
+    assert_eq!(attr(attributes, events::PREVIOUS_STATUS).unwrap(), events::statuses::PAUSED);
+    assert_eq!(attr(attributes, events::NEW_STATUS).unwrap(), events::statuses::OPERATIONAL);
+    assert_eq!(attr(attributes, events::ACTOR).unwrap(), ADMIN);
+    assert_eq!(attr(attributes, events::REASON), None);
+}
+
+#[test]
+fn ownership_is_transferred_once_accepted() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    let transfer = auction::ExecuteMsg::TransferOwnership { new_owner: Some("buyer".into()) };
+    let accept = auction::ExecuteMsg::AcceptOwnership { };
+
+    let err = suite.ensemble.execute(
+        &transfer,
+        MockEnv::new("buyer", &auction.address)
+    ).unwrap_err();
+    assert_eq!(err.unwrap_contract_error().to_string(), "Generic error: Unauthorized");
+
+    let err = suite.ensemble.execute(
+        &accept,
+        MockEnv::new("buyer", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), AuthError::NoPendingOwner.into());
+
+    let resp = suite.ensemble.execute(
+        &transfer,
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    let attr = |attributes: &[Attribute], key: &str| attributes.iter()
+        .find(|x| x.key == key)
+        .unwrap()
+        .value
+        .clone();
+
+    assert_eq!(attr(&resp.response.attributes, events::ACTION), events::actions::TRANSFER_OWNERSHIP);
+    assert_eq!(attr(&resp.response.attributes, events::PREVIOUS_OWNER), ADMIN);
+    assert_eq!(attr(&resp.response.attributes, events::NEW_OWNER), "buyer");
+
+    let pending: Option<Addr> = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::PendingOwner { }
+    ).unwrap();
+    assert_eq!(pending, Some(Addr::unchecked("buyer")));
+
+    // Nothing changes hands until the new owner accepts.
+    let admin: Option<Addr> = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::Admin { }
+    ).unwrap();
+    assert_eq!(admin, Some(Addr::unchecked(ADMIN)));
+
+    let err = suite.ensemble.execute(
+        &accept,
+        MockEnv::new("bidder_1", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), AuthError::Unauthorized.into());
+
+    let resp = suite.ensemble.execute(
+        &accept,
+        MockEnv::new("buyer", &auction.address)
+    ).unwrap();
+
+    assert_eq!(attr(&resp.response.attributes, events::ACTION), events::actions::ACCEPT_OWNERSHIP);
+    assert_eq!(attr(&resp.response.attributes, events::PREVIOUS_OWNER), ADMIN);
+    assert_eq!(attr(&resp.response.attributes, events::NEW_OWNER), "buyer");
+
+    let admin: Option<Addr> = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::Admin { }
+    ).unwrap();
+    assert_eq!(admin, Some(Addr::unchecked("buyer")));
+
+    let pending: Option<Addr> = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::PendingOwner { }
+    ).unwrap();
+    assert_eq!(pending, None);
+
+    let update = auction::ExecuteMsg::UpdateConfig {
+        name: Some("Handed over".into()),
+        metadata: None,
+        reserve: None,
+        end_block: None
+    };
+
+    let err = suite.ensemble.execute(
+        &update,
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap_err();
+    assert_eq!(err.unwrap_contract_error().to_string(), "Generic error: Unauthorized");
+
+    suite.ensemble.execute(
+        &update,
+        MockEnv::new("buyer", &auction.address)
+    ).unwrap();
+
+    suite.ensemble.add_funds("bidder_1", vec![coin(100, "uscrt")]);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder_1", &auction.address)
+            .sent_funds(vec![coin(100, "uscrt")])
+    ).unwrap();
+
+    suite.finish_auction(&auction);
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap_err();
+    assert_eq!(err.unwrap_contract_error().to_string(), "Generic error: Unauthorized");
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new("buyer", &auction.address)
+    ).unwrap();
+    suite.claim(&auction, "buyer");
+
+    let balances = suite.ensemble.balances("buyer").unwrap();
+    assert_eq!(balances["uscrt"].u128(), 100);
+
+    // The owner of a settled auction stays the same.
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::TransferOwnership { new_owner: Some(ADMIN.into()) },
+        MockEnv::new("buyer", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::AlreadySettled.into());
+}
+
+#[test]
+fn tokens_cannot_be_sent_as_bids() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+    let token = suite.new_token("token");
+
+    suite.mint_tokens(&token, "bidder_1", 100);
+
+    suite.ensemble.execute(
+        &Snip20Msg::Transfer { recipient: "bidder_2".into(), amount: Uint128::new(40) },
+        MockEnv::new("bidder_1", &token.address)
+    ).unwrap();
+
+    assert_eq!(suite.token_balance(&token, "bidder_1").u128(), 60);
+    assert_eq!(suite.token_balance(&token, "bidder_2").u128(), 40);
+
+    // Tokens are bid from an allowance instead, so the auction rejects
+    // the callback, which reverts the transfer of the tokens.
+    suite.send_tokens(&token, "bidder_1", &auction, 60, None).unwrap_err();
+
+    assert_eq!(suite.token_balance(&token, "bidder_1").u128(), 60);
+    assert_eq!(suite.token_balance(&token, auction.address.as_str()).u128(), 0);
+}
+
+#[test]
+fn tokens_can_be_bid_from_an_allowance() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let token = suite.new_token("token");
+    let bid_token = BidToken {
+        address: token.address.to_string(),
+        code_hash: token.code_hash.clone()
+    };
+
+    let native = suite.new_auction(block).unwrap().contract;
+    let auction = suite.create_auction(Config {
+        bid_token: Some(bid_token.clone()),
+        ..config("Road 24", block)
+    }).unwrap().contract;
+
+    let bid = |amount: u128| auction::ExecuteMsg::BidFromAllowance { amount: Uint128::new(amount) };
+
+    let err = suite.ensemble.execute(
+        &bid(100),
+        MockEnv::new("bidder_1", &native.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), TokenError::NotEnabled.into());
+
+    suite.ensemble.add_funds("bidder_1", vec![coin(100, "uscrt")]);
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder_1", &auction.address)
+            .sent_funds(vec![coin(100, "uscrt")])
+    ).unwrap_err();
+    assert_eq!(contract_error(err), TokenError::TokenOnly.into());
+
+    let err = suite.ensemble.execute(
+        &bid(0),
+        MockEnv::new("bidder_1", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::ZeroBid.into());
+
+    for (bidder, amount) in [("bidder_1", 100), ("bidder_2", 150)] {
+        suite.mint_tokens(&token, bidder, amount);
+    }
+
+    // Without an allowance the transfer fails and the bid is reverted.
+    suite.ensemble.execute(
+        &bid(100),
+        MockEnv::new("bidder_1", &auction.address)
+    ).unwrap_err();
+
+    let status: SaleStatus = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::SaleStatus { }
+    ).unwrap();
+    assert_eq!(status.num_bids, 0);
+
+    for (bidder, amount) in [("bidder_1", 100), ("bidder_2", 150)] {
+        suite.increase_allowance(&token, bidder, &auction, amount);
+
+        let resp = suite.ensemble.execute(
+            &bid(amount),
+            MockEnv::new(bidder, &auction.address)
+        ).unwrap();
+
+        let result: BidResult = from_binary(&resp.response.data.unwrap()).unwrap();
+        assert_eq!(result.balance.u128(), amount);
+        assert!(resp.response.attributes.iter().any(|x|
+            x.key == events::ACTION && x.value == events::actions::BID_FROM_ALLOWANCE
+        ));
+    }
+
+    assert_eq!(suite.token_balance(&token, auction.address.as_str()).u128(), 250);
+    assert!(suite.token_balance(&token, "bidder_1").is_zero());
+
+    suite.finish_auction(&auction);
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::RetractBid { },
+        MockEnv::new("bidder_1", &auction.address)
+    ).unwrap();
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    // Payouts are made in the token as well.
+    for (account, amount) in [("bidder_1", 100), (ADMIN, 150)] {
+        suite.claim(&auction, account);
+        assert_eq!(suite.token_balance(&token, account).u128(), amount);
+    }
+
+    assert!(suite.token_balance(&token, auction.address.as_str()).is_zero());
+
+    let auction = suite.ensemble.register(Box::new(Auction));
+    let err = suite.ensemble.instantiate(
+        auction.id,
+        &auction::InstantiateMsg {
+            config: Config {
+                bid_token: Some(bid_token),
+                oracle: Some(PriceOracle {
+                    address: "oracle".into(),
+                    code_hash: "oracle".into(),
+                    max_age: 60
+                }),
+                ..config("Road 25", suite.ensemble.block().height + 1000)
+            }
+        },
+        MockEnv::new(ADMIN, "invalid")
+    ).unwrap_err();
+    assert_eq!(contract_error(err), TokenError::WithOracle.into());
+}
+
+#[test]
+fn responses_carry_the_outcome() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    let mut bid = |bidder: &str, amount: u128| -> BidResult {
+        suite.ensemble.add_funds(bidder, vec![coin(amount, "uscrt")]);
+
+        let resp = suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new(bidder, &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        ).unwrap();
+
+        from_binary(&resp.response.data.unwrap()).unwrap()
+    };
+
+    assert_eq!(bid("bidder_1", 100), BidResult { balance: Uint128::new(100), receipt: 1 });
+    assert_eq!(bid("bidder_2", 150), BidResult { balance: Uint128::new(150), receipt: 2 });
+    assert_eq!(bid("bidder_1", 100), BidResult { balance: Uint128::new(200), receipt: 3 });
+
+    suite.finish_auction(&auction);
+
+    let resp = suite.ensemble.execute(
+        &auction::ExecuteMsg::RetractBid { },
+        MockEnv::new("bidder_2", &auction.address)
+    ).unwrap();
+
+    let result: RetractResult = from_binary(&resp.response.data.unwrap()).unwrap();
+    assert_eq!(result, RetractResult { refunded: Uint128::new(150), penalty: Uint128::zero() });
+
+    let resp = suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    let result: ProceedsResult = from_binary(&resp.response.data.unwrap()).unwrap();
+    assert_eq!(result, ProceedsResult {
+        winner: Some(Addr::unchecked("bidder_1")),
+        proceeds: Uint128::new(200),
+        fee: Uint128::zero(),
+        remaining: Uint128::zero()
+    });
+}
+
+#[test]
+fn proceeds_can_be_claimed_in_installments() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.create_auction(Config {
+        beneficiary: Some("seller".into()),
+        ..config("Road 23", block)
+    }).unwrap().contract;
+
+    suite.ensemble.add_funds("bidder", vec![coin(300, "uscrt")]);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder", &auction.address)
+            .sent_funds(vec![coin(300, "uscrt")])
+    ).unwrap();
+
+    suite.finish_auction(&auction);
+
+    let mut claim = |amount: Option<u128>| {
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::ClaimProceeds { amount: amount.map(Uint128::new) },
+            MockEnv::new(ADMIN, &auction.address)
+        ).map(|resp| from_binary::<ProceedsResult>(&resp.response.data.unwrap()).unwrap())
+    };
+
+    let result = claim(Some(100)).unwrap();
+    assert_eq!(result.proceeds.u128(), 100);
+    assert_eq!(result.remaining.u128(), 200);
+
+    let err = claim(Some(250)).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::ProceedsExceeded(Uint128::new(200)).into());
+
+    let result = claim(None).unwrap();
+    assert_eq!(result.winner, Some(Addr::unchecked("bidder")));
+    assert_eq!(result.proceeds.u128(), 200);
+    assert!(result.remaining.is_zero());
+
+    let err = claim(None).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::AlreadySettled.into());
+
+    // Both installments were paid out to the beneficiary.
+    suite.claim(&auction, "seller");
+
+    let balances = suite.ensemble.balances("seller").unwrap();
+    assert_eq!(balances["uscrt"].u128(), 300);
+}
+
+#[test]
+fn admin_selects_the_winner_manually() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.create_auction(Config {
+        manual_winner: Some(true),
+        ..config("Road 23", block)
+    }).unwrap().contract;
+
+    for (bidder, amount) in [("bidder_1", 100), ("bidder_2", 300), ("bidder_3", 200)] {
+        suite.ensemble.add_funds(bidder, vec![coin(amount, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new(bidder, &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        ).unwrap();
+    }
+
+    let select = |address: &str| auction::ExecuteMsg::SelectWinner { address: address.into() };
+
+    let err = suite.ensemble.execute(
+        &select("bidder_3"),
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::NotFinished.into());
+
+    suite.finish_auction(&auction);
+
+    // No one can be refunded until the winner is known.
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::RetractBid { },
+        MockEnv::new("bidder_1", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), SelectionError::NotSelected.into());
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), SelectionError::NotSelected.into());
+
+    let err = suite.ensemble.execute(
+        &select("stranger"),
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), SelectionError::NoBid("stranger".into()).into());
+
+    let resp = suite.ensemble.execute(
+        &select("bidder_3"),
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+    assert!(resp.response.attributes.iter().any(|x|
+        x.key == events::WINNER && x.value == "bidder_3"
+    ));
+
+    let err = suite.ensemble.execute(
+        &select("bidder_2"),
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), SelectionError::AlreadySelected.into());
+
+    let winner: Option<Addr> = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::SelectedWinner { }
+    ).unwrap();
+    assert_eq!(winner, Some(Addr::unchecked("bidder_3")));
+
+    // The highest bidder wasn't selected and gets their bid back.
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::RetractBid { },
+        MockEnv::new("bidder_2", &auction.address)
+    ).unwrap();
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::RetractBid { },
+        MockEnv::new("bidder_3", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::WinnerCannotRetract.into());
+
+    let resp = suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    let result: ProceedsResult = from_binary(&resp.response.data.unwrap()).unwrap();
+    assert_eq!(result.winner, Some(Addr::unchecked("bidder_3")));
+    assert_eq!(result.proceeds.u128(), 200);
+
+    suite.claim(&auction, "bidder_2");
+
+    let balances = suite.ensemble.balances("bidder_2").unwrap();
+    assert_eq!(balances["uscrt"].u128(), 300);
+}
+
+#[test]
+fn winner_cannot_be_selected_by_default() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+    suite.finish_auction(&auction);
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::SelectWinner { address: "bidder".into() },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), SelectionError::NotManual.into());
+}
+
+#[test]
+fn sale_can_be_cancelled_during_the_dispute_window() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.create_auction(Config {
+        dispute_blocks: Some(100),
+        arbiter: Some("arbiter".into()),
+        ..config("Road 23", block)
+    }).unwrap().contract;
+
+    for (bidder, amount) in [("bidder_1", 100), ("bidder_2", 150)] {
+        suite.ensemble.add_funds(bidder, vec![coin(amount, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new(bidder, &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        ).unwrap();
+    }
+
+    let cancel = auction::ExecuteMsg::CancelSale { reason: "Seller is a fraud.".into() };
+
+    let err = suite.ensemble.execute(
+        &cancel,
+        MockEnv::new("arbiter", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::NotInDisputeWindow.into());
+
+    suite.finish_auction(&auction);
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::ProceedsLocked(block + 100).into());
+
+    let err = suite.ensemble.execute(
+        &cancel,
+        MockEnv::new("bidder_1", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), AuthError::Unauthorized.into());
+
+    let resp = suite.ensemble.execute(
+        &cancel,
+        MockEnv::new("arbiter", &auction.address)
+    ).unwrap();
+    assert!(resp.response.attributes.iter().any(|x|
+        x.key == events::REASON && x.value == "Seller is a fraud."
+    ));
+
+    let state: AuctionState = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::State { }
+    ).unwrap();
+    assert_eq!(state, AuctionState::Cancelled);
+
+    // Every bid is refunded, including the highest one.
+    for (bidder, amount) in [("bidder_1", 100), ("bidder_2", 150)] {
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::RetractBid { },
+            MockEnv::new(bidder, &auction.address)
+        ).unwrap();
+        suite.claim(&auction, bidder);
+
+        let balances = suite.ensemble.balances(bidder).unwrap();
+        assert_eq!(balances["uscrt"].u128(), amount);
+    }
+
+    suite.advance_blocks(100);
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::Cancelled.into());
+}
+
+#[test]
+fn seller_deposit_compensates_bidders_of_cancelled_sales() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let deposit_config = |name: &str| Config {
+        seller_deposit: Some(Uint128::new(100)),
+        dispute_blocks: Some(100),
+        ..config(name, block)
+    };
+
+    // The factory sends back the funds of an auction that failed to instantiate.
+    suite.ensemble.add_funds("sender", vec![coin(50, "uscrt")]);
+    suite.ensemble.execute(
+        &factory::ExecuteMsg::CreateAuction { config: deposit_config("Road 22"), tags: None, end: None },
+        MockEnv::new("sender", &suite.factory.address)
+            .sent_funds(vec![coin(50, "uscrt")])
+    ).unwrap();
+
+    assert_eq!(suite.list_auctions(0, 0).unwrap().total, 0);
+    assert_eq!(suite.ensemble.balances("sender").unwrap()["uscrt"].u128(), 50);
+
+    suite.ensemble.add_funds("sender", vec![coin(150, "uscrt")]);
+    let cancelled = suite.create_funded_auction(
+        deposit_config("Road 23"),
+        None,
+        vec![coin(100, "uscrt")]
+    ).unwrap().contract;
+    let settled = suite.create_funded_auction(
+        deposit_config("Road 24"),
+        None,
+        vec![coin(100, "uscrt")]
+    ).unwrap().contract;
+
+    for (bidder, amount) in [("bidder_1", 100), ("bidder_2", 300)] {
+        suite.ensemble.add_funds(bidder, vec![coin(amount, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new(bidder, &cancelled.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        ).unwrap();
+    }
+
+    suite.finish_auction(&cancelled);
+
+    let resp = suite.ensemble.execute(
+        &auction::ExecuteMsg::CancelSale { reason: "Item was damaged.".into() },
+        MockEnv::new(ADMIN, &cancelled.address)
+    ).unwrap();
+    assert!(resp.response.attributes.iter().any(|x|
+        x.key == events::DEPOSIT && x.value == "100"
+    ));
+
+    // Split by the size of each bid.
+    for (bidder, refund) in [("bidder_1", 125), ("bidder_2", 375)] {
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::RetractBid { },
+            MockEnv::new(bidder, &cancelled.address)
+        ).unwrap();
+        suite.claim(&cancelled, bidder);
+
+        let balances = suite.ensemble.balances(bidder).unwrap();
+        assert_eq!(balances["uscrt"].u128(), refund);
+    }
+
+    // Returned to the admin of a sale without bids.
+    suite.advance_blocks(100);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &settled.address)
+    ).unwrap();
+    suite.claim(&settled, ADMIN);
+
+    let balances = suite.ensemble.balances(ADMIN).unwrap();
+    assert_eq!(balances["uscrt"].u128(), 100);
+}
+
+#[test]
+fn proceeds_can_be_claimed_after_the_dispute_window() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.create_auction(Config {
+        dispute_blocks: Some(100),
+        ..config("Road 23", block)
+    }).unwrap().contract;
+
+    suite.ensemble.add_funds("bidder", vec![coin(100, "uscrt")]);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder", &auction.address)
+            .sent_funds(vec![coin(100, "uscrt")])
+    ).unwrap();
+
+    suite.ensemble.block_mut().height = block + 100;
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::ProceedsLocked(block + 100).into());
+
+    suite.ensemble.block_mut().height = block + 101;
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::CancelSale { reason: "Too late.".into() },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::NotInDisputeWindow.into());
+
+    suite.ensemble.block_mut().height = block + 101;
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+}
+
+#[test]
+fn only_the_winner_can_view_the_payload() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.create_auction(Config {
+        winner_payload: Some(Binary::from(b"redeem: 1234")),
+        ..config("Road 23", block)
+    }).unwrap().contract;
+
+    for (bidder, amount) in [("bidder_1", 100), ("bidder_2", 150)] {
+        suite.ensemble.add_funds(bidder, vec![coin(amount, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::BidWithKey { key: "key".into(), padding: None },
+            MockEnv::new(bidder, &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        ).unwrap();
+    }
+
+    let payload = |suite: &Suite, bidder: &str| {
+        suite.ensemble.query::<_, Option<Binary>>(
+            &auction.address,
+            &auction::QueryMsg::WinnerPayload { address: bidder.into(), key: "key".into() }
+        )
+    };
+
+    suite.finish_auction(&auction);
+
+    let err = payload(&suite, "bidder_2").unwrap_err();
+    assert_eq!(err.to_string(), LifecycleError::NotSettled.to_string());
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    assert_eq!(payload(&suite, "bidder_2").unwrap(), Some(Binary::from(b"redeem: 1234")));
+
+    let err = payload(&suite, "bidder_1").unwrap_err();
+    assert_eq!(err.to_string(), AuthError::Unauthorized.to_string());
+
+    // The auditor can view bids but not the payload.
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::SetAuditor { address: Some("auditor".into()) },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::SetViewingKey { key: "auditor_key".into(), padding: None },
+        MockEnv::new("auditor", &auction.address)
+    ).unwrap();
+
+    let err = suite.ensemble.query::<_, Option<Binary>>(
+        &auction.address,
+        &auction::QueryMsg::WinnerPayload { address: "bidder_2".into(), key: "auditor_key".into() }
+    ).unwrap_err();
+    assert_eq!(err.to_string(), AuthError::Unauthorized.to_string());
+
+    let permit = |permission| Permit::new(
+        "bidder_2",
+        PermitParams::new(auction.address.as_str())
+            .permissions([permission])
+            .name("payload")
+    );
+
+    let payload: Option<Binary> = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::WinnerPayloadWithPermit { permit: permit(AuctionPermission::ViewPayload) }
+    ).unwrap();
+    assert_eq!(payload, Some(Binary::from(b"redeem: 1234")));
+
+    suite.ensemble.query::<_, Option<Binary>>(
+        &auction.address,
+        &auction::QueryMsg::WinnerPayloadWithPermit { permit: permit(AuctionPermission::ViewBid) }
+    ).unwrap_err();
+}
+
+#[test]
+fn bid_memos_are_only_shown_to_the_bidder_and_the_seller() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    for (bidder, amount, memo) in [
+        ("bidder_1", 100, Some("ship to bidder_1")),
+        ("bidder_2", 150, Some("ship to bidder_2")),
+        // Later bids without a memo keep the earlier one.
+        ("bidder_2", 50, None)
+    ] {
+        suite.ensemble.add_funds(bidder, vec![coin(amount, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: memo.map(Into::into) },
+            MockEnv::new(bidder, &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        ).unwrap();
+    }
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: Some("a".repeat(shared::MAX_MEMO_LEN + 1)) },
+        MockEnv::new("bidder_1", &auction.address)
+    ).unwrap_err();
+    assert_eq!(
+        contract_error(err),
+        StdError::generic_err(format!(
+            "Memo is {} bytes long but the maximum is {}.",
+            shared::MAX_MEMO_LEN + 1,
+            shared::MAX_MEMO_LEN
+        )).into()
+    );
+
+    for viewer in ["bidder_1", "bidder_2", ADMIN, "auditor"] {
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::SetViewingKey { key: "key".into(), padding: None },
+            MockEnv::new(viewer, &auction.address)
+        ).unwrap();
+    }
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::SetAuditor { address: Some("auditor".into()) },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    let memo = |suite: &Suite, bidder: &str, viewer: &str| {
+        suite.ensemble.query::<_, Option<String>>(
+            &auction.address,
+            &auction::QueryMsg::ViewMemo { bidder: bidder.into(), viewer: viewer.into(), key: "key".into() }
+        )
+    };
+
+    assert_eq!(memo(&suite, "bidder_2", "bidder_2").unwrap().as_deref(), Some("ship to bidder_2"));
+
+    for viewer in ["bidder_1", "auditor"] {
+        let err = memo(&suite, "bidder_2", viewer).unwrap_err();
+        assert_eq!(err.to_string(), AuthError::Unauthorized.to_string());
+    }
+
+    let err = memo(&suite, "bidder_2", ADMIN).unwrap_err();
+    assert_eq!(err.to_string(), LifecycleError::NotSettled.to_string());
+
+    suite.finish_auction(&auction);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    assert_eq!(memo(&suite, "bidder_2", ADMIN).unwrap().as_deref(), Some("ship to bidder_2"));
+    assert_eq!(memo(&suite, "bidder_1", ADMIN).unwrap().as_deref(), Some("ship to bidder_1"));
+
+    let err = memo(&suite, "bidder_2", "auditor").unwrap_err();
+    assert_eq!(err.to_string(), AuthError::Unauthorized.to_string());
+
+    let permit = |permission| Permit::new(
+        ADMIN,
+        PermitParams::new(auction.address.as_str())
+            .permissions([permission])
+            .name("memo")
+    );
+
+    let memo: Option<String> = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::ViewMemoWithPermit {
+            bidder: "bidder_2".into(),
+            permit: permit(AuctionPermission::ViewMemo)
+        }
+    ).unwrap();
+    assert_eq!(memo.as_deref(), Some("ship to bidder_2"));
+
+    suite.ensemble.query::<_, Option<String>>(
+        &auction.address,
+        &auction::QueryMsg::ViewMemoWithPermit {
+            bidder: "bidder_2".into(),
+            permit: permit(AuctionPermission::ViewBid)
+        }
+    ).unwrap_err();
+}
+
+#[test]
+fn winner_and_seller_exchange_delivery_information() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    for (bidder, amount) in [("bidder_1", 100), ("bidder_2", 150)] {
+        suite.ensemble.add_funds(bidder, vec![coin(amount, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::BidWithKey { key: "key".into(), padding: None },
+            MockEnv::new(bidder, &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        ).unwrap();
+    }
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::SetViewingKey { key: "key".into(), padding: None },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    let details = |details: &str| auction::ExecuteMsg::SendDeliveryDetails { details: details.into() };
+    let proof = |proof: &str| auction::ExecuteMsg::SendFulfillmentProof { proof: proof.into() };
+    let delivery = |suite: &Suite, address: &str| {
+        suite.ensemble.query::<_, Delivery>(
+            &auction.address,
+            &auction::QueryMsg::Delivery { address: address.into(), key: "key".into() }
+        )
+    };
+
+    let err = suite.ensemble.execute(
+        &details("bidder_2, road 23"),
+        MockEnv::new("bidder_2", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::NotSettled.into());
+
+    let err = delivery(&suite, ADMIN).unwrap_err();
+    assert_eq!(err.to_string(), LifecycleError::NotSettled.to_string());
+
+    suite.finish_auction(&auction);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    // Only the winner can send the details.
+    for sender in ["bidder_1", ADMIN] {
+        let err = suite.ensemble.execute(
+            &details("road 24"),
+            MockEnv::new(sender, &auction.address)
+        ).unwrap_err();
+        assert_eq!(contract_error(err), AuthError::Unauthorized.into());
+    }
+
+    let err = suite.ensemble.execute(
+        &details(&"a".repeat(shared::MAX_DELIVERY_LEN + 1)),
+        MockEnv::new("bidder_2", &auction.address)
+    ).unwrap_err();
+    assert_eq!(
+        contract_error(err),
+        StdError::generic_err(format!(
+            "Delivery details is {} bytes long but the maximum is {}.",
+            shared::MAX_DELIVERY_LEN + 1,
+            shared::MAX_DELIVERY_LEN
+        )).into()
+    );
+
+    let resp = suite.ensemble.execute(
+        &details("bidder_2, road 23"),
+        MockEnv::new("bidder_2", &auction.address)
+    ).unwrap();
+    // Nothing of it is revealed in the response.
+    assert!(resp.response.attributes.iter().all(|x| !x.value.contains("road 23")));
+
+    assert_eq!(delivery(&suite, ADMIN).unwrap(), Delivery {
+        details: Some("bidder_2, road 23".into()),
+        fulfillment_proof: None
+    });
+
+    // Only the admin can send the proof.
+    let err = suite.ensemble.execute(
+        &proof("tracking: 1234"),
+        MockEnv::new("bidder_2", &auction.address)
+    ).unwrap_err();
+    assert_eq!(err.unwrap_contract_error().to_string(), "Generic error: Unauthorized");
+
+    suite.ensemble.execute(
+        &proof("tracking: 1234"),
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    let expected = Delivery {
+        details: Some("bidder_2, road 23".into()),
+        fulfillment_proof: Some("tracking: 1234".into())
+    };
+    assert_eq!(delivery(&suite, "bidder_2").unwrap(), expected);
+    assert_eq!(delivery(&suite, ADMIN).unwrap(), expected);
+
+    let err = delivery(&suite, "bidder_1").unwrap_err();
+    assert_eq!(err.to_string(), AuthError::Unauthorized.to_string());
+
+    let permit = |permission| Permit::new(
+        "bidder_2",
+        PermitParams::new(auction.address.as_str())
+            .permissions([permission])
+            .name("delivery")
+    );
+
+    let delivery: Delivery = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::DeliveryWithPermit { permit: permit(AuctionPermission::ViewDelivery) }
+    ).unwrap();
+    assert_eq!(delivery, expected);
+
+    suite.ensemble.query::<_, Delivery>(
+        &auction.address,
+        &auction::QueryMsg::DeliveryWithPermit { permit: permit(AuctionPermission::ViewBid) }
+    ).unwrap_err();
+}
+
+#[test]
+fn settlement_hooks_are_notified() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    let hook = suite.ensemble.register(Box::new(Hook { fail: false }));
+    let failing = suite.ensemble.register(Box::new(Hook { fail: true }));
+
+    let mut add_hook = |code: ContractCode, label: &str| {
+        let contract = suite.ensemble.instantiate(
+            code.id,
+            &(),
+            MockEnv::new("sender", label)
+        ).unwrap().instance;
+
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::AddSettlementHook {
+                contract: ContractLink {
+                    address: contract.address.to_string(),
+                    code_hash: contract.code_hash.clone()
+                }
+            },
+            MockEnv::new(ADMIN, &auction.address)
+        ).unwrap();
+
+        contract
+    };
+
+    let failing = add_hook(failing, "failing");
+    let hook = add_hook(hook, "hook");
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::AddSettlementHook {
+            contract: ContractLink {
+                address: hook.address.to_string(),
+                code_hash: hook.code_hash.clone()
+            }
+        },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), HookError::AlreadyAdded(hook.address.to_string()).into());
+
+    let hooks: Vec<ContractLink<Addr>> = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::SettlementHooks { }
+    ).unwrap();
+    assert_eq!(hooks, vec![failing.clone(), hook.clone()]);
+
+    // Only the admin can remove hooks.
+    let remove = auction::ExecuteMsg::RemoveSettlementHook {
+        address: failing.address.to_string()
+    };
+
+    let err = suite.ensemble.execute(
+        &remove,
+        MockEnv::new("bidder", &auction.address)
+    ).unwrap_err();
+    assert_eq!(err.unwrap_contract_error().to_string(), "Generic error: Unauthorized");
+
+    suite.ensemble.execute(&remove, MockEnv::new(ADMIN, &auction.address)).unwrap();
+
+    let err = suite.ensemble.execute(&remove, MockEnv::new(ADMIN, &auction.address)).unwrap_err();
+    assert_eq!(contract_error(err), HookError::NotFound(failing.address.to_string()).into());
+
+    // The failing hook is added back to show that it doesn't prevent the settlement.
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::AddSettlementHook {
+            contract: ContractLink {
+                address: failing.address.to_string(),
+                code_hash: failing.code_hash.clone()
+            }
+        },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    suite.ensemble.add_funds("bidder", vec![coin(100, "uscrt")]);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder", &auction.address)
+            .sent_funds(vec![coin(100, "uscrt")])
+    ).unwrap();
+
+    suite.finish_auction(&auction);
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    let received: Vec<SettlementHookMsg> = suite.ensemble.query(&hook.address, &()).unwrap();
+    assert_eq!(received, vec![SettlementHookMsg::AuctionSettled {
+        auction: auction.clone(),
+        winner: Some(Addr::unchecked("bidder")),
+        price: Uint128::new(100),
+        denom: "uscrt".into()
+    }]);
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::AddSettlementHook {
+            contract: ContractLink {
+                address: "late".into(),
+                code_hash: "hash".into()
+            }
+        },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::AlreadySettled.into());
+}
+
+#[test]
+fn bids_can_be_retracted_early_with_a_penalty() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let code = suite.ensemble.register(Box::new(Auction));
+    let err = suite.ensemble.instantiate(
+        code.id,
+        &auction::InstantiateMsg {
+            config: Config {
+                early_retraction_penalty: Some(10_001),
+                ..config("Road 23", block)
+            }
+        },
+        MockEnv::new(ADMIN, "invalid")
+    ).unwrap_err();
+    assert_eq!(contract_error(err), RetractionError::PenaltyTooHigh(10_001).into());
+
+    let auction = suite.create_auction(Config {
+        early_retraction_penalty: Some(1000),
+        ..config("Road 23", block)
+    }).unwrap().contract;
+
+    let penalty: Option<u16> = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::EarlyRetractionPenalty { }
+    ).unwrap();
+    assert_eq!(penalty, Some(1000));
+
+    for (bidder, amount) in [("bidder_1", 100), ("bidder_2", 150)] {
+        suite.ensemble.add_funds(bidder, vec![coin(amount, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new(bidder, &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        ).unwrap();
+    }
+
+    // Even the highest bid can be retracted and the next one takes its place.
+    let resp = suite.ensemble.execute(
+        &auction::ExecuteMsg::RetractBid { },
+        MockEnv::new("bidder_2", &auction.address)
+    ).unwrap();
+
+    let result: RetractResult = from_binary(&resp.response.data.unwrap()).unwrap();
+    assert_eq!(result, RetractResult { refunded: Uint128::new(135), penalty: Uint128::new(15) });
+
+    suite.claim(&auction, "bidder_2");
+    assert_eq!(suite.ensemble.balances("bidder_2").unwrap()["uscrt"].u128(), 135);
+
+    let status: SaleStatus = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::SaleStatus { }
+    ).unwrap();
+    assert_eq!(status.current_highest, Some(Uint128::new(100)));
+
+    let invariants: Invariants = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::Invariants { }
+    ).unwrap();
+    assert!(invariants.holds);
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::RetractBid { },
+        MockEnv::new("bidder_2", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::NothingToRefund.into());
+
+    suite.finish_auction(&auction);
+
+    // The penalty is paid out together with the winning bid.
+    let resp = suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    let result: ProceedsResult = from_binary(&resp.response.data.unwrap()).unwrap();
+    assert_eq!(result.winner, Some(Addr::unchecked("bidder_1")));
+    assert_eq!(result.proceeds, Uint128::new(115));
+}
+
+#[test]
+fn bids_cannot_be_retracted_early_by_default() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    let penalty: Option<u16> = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::EarlyRetractionPenalty { }
+    ).unwrap();
+    assert_eq!(penalty, None);
+
+    suite.ensemble.add_funds("bidder", vec![coin(100, "uscrt")]);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder", &auction.address)
+            .sent_funds(vec![coin(100, "uscrt")])
+    ).unwrap();
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::RetractBid { },
+        MockEnv::new("bidder", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::NotFinished.into());
+}
+
+#[test]
+fn bids_from_the_same_address_require_a_cooldown() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.create_auction(Config {
+        bid_cooldown: Some(10),
+        ..config("Road 23", block)
+    }).unwrap().contract;
+
+    suite.ensemble.add_funds("bidder_1", vec![coin(300, "uscrt")]);
+    suite.ensemble.add_funds("bidder_2", vec![coin(100, "uscrt")]);
+
+    let bid = |suite: &mut Suite, bidder: &str, amount: u128| {
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new(bidder, &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        )
+    };
+
+    let height = suite.ensemble.block().height;
+    bid(&mut suite, "bidder_1", 100).unwrap();
+
+    // Other addresses aren't affected.
+    bid(&mut suite, "bidder_2", 100).unwrap();
+
+    suite.ensemble.block_mut().height = height + 9;
+
+    let err = bid(&mut suite, "bidder_1", 100).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::BidCooldown(height + 10).into());
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::ProxyBid { },
+        MockEnv::new("bidder_1", &auction.address)
+            .sent_funds(vec![coin(100, "uscrt")])
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::BidCooldown(height + 10).into());
+
+    suite.ensemble.block_mut().height = height + 10;
+    bid(&mut suite, "bidder_1", 100).unwrap();
+}
+
+#[test]
+fn settled_auctions_can_be_cloned() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.create_tagged_auction(
+        Config {
+            reserve: Some(Uint128::new(50)),
+            ..config("Road 23", block)
+        },
+        Some(vec!["house".into()])
+    ).unwrap().contract;
+
+    let clone = |suite: &mut Suite, sender: &str, new_end_block: u64| {
+        suite.ensemble.execute(
+            &factory::ExecuteMsg::CloneAuction { index: 0, new_end_block },
+            MockEnv::new(sender, &suite.factory.address)
+        )
+    };
+
+    let err = clone(&mut suite, "sender", block + 1000).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::NotSettled.into());
+
+    suite.ensemble.add_funds("bidder", vec![coin(100, "uscrt")]);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder", &auction.address)
+            .sent_funds(vec![coin(100, "uscrt")])
+    ).unwrap();
+
+    suite.finish_auction(&auction);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    let err = clone(&mut suite, "bidder", block + 1000).unwrap_err();
+    assert_eq!(contract_error(err), AuthError::Unauthorized.into());
+
+    clone(&mut suite, "sender", block + 1000).unwrap();
+
+    // The original was archived once it was settled.
+    let auctions = suite.list_auctions(0, 10).unwrap();
+    assert_eq!(auctions.total, 1);
+
+    let cloned = &auctions.entries[0];
+    assert_eq!(cloned.info.name, "Road 23");
+    assert_eq!(cloned.info.end_block, block + 1000);
+
+    let tagged: PaginatedResponse<AuctionEntry<Addr>> = suite.ensemble.query(
+        &suite.factory.address,
+        &factory::QueryMsg::ListAuctionsByTag {
+            tag: "house".into(),
+            pagination: Pagination { start: 0, limit: 10 }
+        }
+    ).unwrap();
+    assert_eq!(tagged.total, 2);
+
+    // The reserve is carried over.
+    suite.ensemble.add_funds("bidder", vec![coin(40, "uscrt")]);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder", &cloned.contract.address)
+            .sent_funds(vec![coin(40, "uscrt")])
+    ).unwrap();
+
+    suite.finish_auction(&cloned.contract);
+
+    let resp = suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &cloned.contract.address)
+    ).unwrap();
+
+    let result: ProceedsResult = from_binary(&resp.response.data.unwrap()).unwrap();
+    assert_eq!(result.winner, None);
+}
+
+#[test]
+fn ended_auctions_are_settled_by_keepers() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height;
+
+    let first = suite.create_auction(config("Road 23", block + 100)).unwrap().contract;
+    let second = suite.create_auction(config("Road 24", block + 200)).unwrap().contract;
+    let later = suite.create_auction(config("Road 25", block + 1000)).unwrap().contract;
+
+    let process = |suite: &mut Suite, limit: u8| {
+        suite.ensemble.execute(
+            &factory::ExecuteMsg::ProcessEnded { limit },
+            MockEnv::new("keeper", &suite.factory.address)
+        )
+    };
+    let state = |suite: &Suite, auction: &ContractLink<Addr>| -> AuctionState {
+        suite.ensemble.query(&auction.address, &auction::QueryMsg::State { }).unwrap()
+    };
+
+    let err = process(&mut suite, 0).unwrap_err();
+    assert_eq!(
+        contract_error(err),
+        StdError::generic_err(format!("Limit must be between 1 and {}.", factory::MAX_PROCESS_BATCH)).into()
+    );
+
+    let err = suite.ensemble.execute(
+        &factory::ExecuteMsg::SetKeeperReward { reward: Some(coin(10, "uscrt")) },
+        MockEnv::new("keeper", &suite.factory.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), StdError::generic_err("Unauthorized").into());
+
+    suite.ensemble.execute(
+        &factory::ExecuteMsg::SetKeeperReward { reward: Some(coin(10, "uscrt")) },
+        MockEnv::new("sender", &suite.factory.address)
+    ).unwrap();
+
+    let reward: Option<fadroma::cosmwasm_std::Coin> = suite.ensemble.query(
+        &suite.factory.address,
+        &factory::QueryMsg::KeeperReward { }
+    ).unwrap();
+    assert_eq!(reward, Some(coin(10, "uscrt")));
+
+    suite.ensemble.add_funds(&suite.factory.address, vec![coin(15, "uscrt")]);
+
+    for (auction, amount) in [(&first, 100), (&second, 200), (&later, 300)] {
+        suite.ensemble.add_funds("bidder", vec![coin(amount, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new("bidder", &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        ).unwrap();
+    }
+
+    suite.finish_auction(&second);
+
+    // The second auction is settled by its admin before a keeper gets to it.
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &second.address)
+    ).unwrap();
+
+    let resp = process(&mut suite, 10).unwrap();
+    let processed = resp.response.attributes.iter()
+        .find(|x| x.key == events::PROCESSED)
+        .unwrap();
+    assert_eq!(processed.value, "1");
+
+    assert_eq!(state(&suite, &first), AuctionState::Settled);
+    assert_eq!(state(&suite, &second), AuctionState::Settled);
+    assert_eq!(state(&suite, &later), AuctionState::Active);
+
+    // Settling doesn't pay out the proceeds.
+    let auction_balances = suite.ensemble.balances(&first.address).unwrap();
+    assert_eq!(auction_balances["uscrt"].u128(), 100);
+
+    let keeper_balances = suite.ensemble.balances("keeper").unwrap();
+    assert_eq!(keeper_balances["uscrt"].u128(), 10);
+
+    // Nothing left to process until the last auction ends.
+    let resp = process(&mut suite, 10).unwrap();
+    let processed = resp.response.attributes.iter()
+        .find(|x| x.key == events::PROCESSED)
+        .unwrap();
+    assert_eq!(processed.value, "0");
+
+    // The factory can't cover another reward but the auction still gets settled.
+    suite.finish_auction(&later);
+    process(&mut suite, 1).unwrap();
+
+    assert_eq!(state(&suite, &later), AuctionState::Settled);
+
+    let keeper_balances = suite.ensemble.balances("keeper").unwrap();
+    assert_eq!(keeper_balances["uscrt"].u128(), 10);
+}
+
+#[test]
+fn keepers_are_only_rewarded_for_auctions_with_bids_of_others() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height;
+
+    let empty = suite.create_auction(config("Road 23", block + 100)).unwrap().contract;
+    let with_bid = suite.create_auction(config("Road 24", block + 200)).unwrap().contract;
+    let own = suite.create_auction(config("Road 25", block + 300)).unwrap().contract;
+
+    suite.ensemble.execute(
+        &factory::ExecuteMsg::SetKeeperReward { reward: Some(coin(10, "uscrt")) },
+        MockEnv::new("sender", &suite.factory.address)
+    ).unwrap();
+    suite.ensemble.add_funds(&suite.factory.address, vec![coin(100, "uscrt")]);
+
+    for auction in [&with_bid, &own] {
+        suite.ensemble.add_funds("bidder", vec![coin(100, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new("bidder", &auction.address)
+                .sent_funds(vec![coin(100, "uscrt")])
+        ).unwrap();
+    }
+
+    suite.finish_auction(&own);
+
+    let state = |suite: &Suite, auction: &ContractLink<Addr>| -> AuctionState {
+        suite.ensemble.query(&auction.address, &auction::QueryMsg::State { }).unwrap()
+    };
+    let balance = |suite: &Suite, address: &str| suite.ensemble.balances(address)
+        .and_then(|x| x.get("uscrt"))
+        .map_or(0, |x| x.u128());
+
+    suite.ensemble.execute(
+        &factory::ExecuteMsg::ProcessEnded { limit: 2 },
+        MockEnv::new("keeper", &suite.factory.address)
+    ).unwrap();
+
+    assert_eq!(state(&suite, &empty), AuctionState::Settled);
+    assert_eq!(state(&suite, &with_bid), AuctionState::Settled);
+    assert_eq!(balance(&suite, "keeper"), 10);
+
+    // The creator of the last auction settles it.
+    let before = balance(&suite, "sender");
+
+    suite.ensemble.execute(
+        &factory::ExecuteMsg::ProcessEnded { limit: 1 },
+        MockEnv::new("sender", &suite.factory.address)
+    ).unwrap();
+
+    assert_eq!(state(&suite, &own), AuctionState::Settled);
+    assert_eq!(balance(&suite, "sender"), before);
+    assert_eq!(balance(&suite, suite.factory.address.as_str()), 90);
+}
+
+#[test]
+fn auctions_are_built_from_the_stored_code() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let code: ContractCode = suite.ensemble.query(
+        &suite.factory.address,
+        &factory::QueryMsg::AuctionCode { }
+    ).unwrap();
+
+    let entry = suite.new_auction(block).unwrap();
+    assert_eq!(entry.contract.code_hash, code.code_hash);
+}
+
+#[test]
+fn auctions_created_elsewhere_can_be_registered() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let code: ContractCode = suite.ensemble.query(
+        &suite.factory.address,
+        &factory::QueryMsg::AuctionCode { }
+    ).unwrap();
+
+    let instantiate = |suite: &mut Suite, name: &str, code_id: u64| suite.ensemble.instantiate(
+        code_id,
+        &AuctionInit::builder().name(name).end_block(block).admin(ADMIN).build().unwrap(),
+        MockEnv::new(ADMIN, name.to_lowercase().replace(' ', "_"))
+    ).unwrap().instance;
+
+    let auction = instantiate(&mut suite, "Road 23", code.id);
+    let register = |contract: &ContractLink<Addr>| factory::ExecuteMsg::RegisterExisting {
+        contract: ContractLink {
+            address: contract.address.to_string(),
+            code_hash: contract.code_hash.clone()
+        }
+    };
+
+    let err = suite.ensemble.execute(
+        &register(&auction),
+        MockEnv::new("creator", &suite.factory.address)
+    ).unwrap_err();
+    assert_eq!(err.unwrap_contract_error().to_string(), "Generic error: Unauthorized");
+
+    suite.ensemble.execute(
+        &register(&auction),
+        MockEnv::new("sender", &suite.factory.address)
+    ).unwrap();
+
+    let listed = suite.list_auctions(0, 10).unwrap();
+    assert_eq!(listed.total, 1);
+    assert_eq!(listed.entries[0].contract, auction);
+    assert_eq!(listed.entries[0].info.name, "Road 23");
+
+    let err = suite.ensemble.execute(
+        &register(&auction),
+        MockEnv::new("sender", &suite.factory.address)
+    ).unwrap_err();
+    assert_eq!(
+        contract_error(err),
+        StdError::generic_err(format!("Auction {} is already registered.", auction.address)).into()
+    );
+
+    // Built from code other than that of the factory.
+    let other = suite.ensemble.register(Box::new(Auction));
+    let impostor = instantiate(&mut suite, "Road 24", other.id);
+
+    let err = suite.ensemble.execute(
+        &register(&impostor),
+        MockEnv::new("sender", &suite.factory.address)
+    ).unwrap_err();
+    assert_eq!(
+        contract_error(err),
+        StdError::generic_err(format!(
+            "Expected an auction with code hash {} but got {}.",
+            code.code_hash,
+            impostor.code_hash
+        )).into()
+    );
+
+    let finished = instantiate(&mut suite, "Road 25", code.id);
+    suite.finish_auction(&finished);
+
+    let err = suite.ensemble.execute(
+        &register(&finished),
+        MockEnv::new("sender", &suite.factory.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::Finished.into());
+}
+
+#[test]
+fn creators_are_limited_in_active_auctions() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height;
+
+    let set_limit = factory::ExecuteMsg::SetCreatorLimit { limit: Some(2) };
+
+    let err = suite.ensemble.execute(
+        &set_limit,
+        MockEnv::new("creator", &suite.factory.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), StdError::generic_err("Unauthorized").into());
+
+    suite.ensemble.execute(
+        &set_limit,
+        MockEnv::new("sender", &suite.factory.address)
+    ).unwrap();
+
+    let limit: Option<u64> = suite.ensemble.query(
+        &suite.factory.address,
+        &factory::QueryMsg::CreatorLimit { }
+    ).unwrap();
+    assert_eq!(limit, Some(2));
+
+    let active = |suite: &Suite, creator: &str| -> u64 {
+        suite.ensemble.query(
+            &suite.factory.address,
+            &factory::QueryMsg::ActiveAuctions { creator: creator.into() }
+        ).unwrap()
+    };
+
+    let first = suite.create_auction(config("Road 23", block + 100)).unwrap().contract;
+    suite.create_auction(config("Road 24", block + 1000)).unwrap();
+    assert_eq!(active(&suite, "sender"), 2);
+
+    let err = suite.create_auction(config("Road 25", block + 1000)).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::TooManyActive(2).into());
+
+    // The limit applies to each creator separately.
+    suite.ensemble.execute(
+        &factory::ExecuteMsg::CreateAuction { config: config("Road 26", block + 1000), tags: None, end: None },
+        MockEnv::new("creator", &suite.factory.address)
+    ).unwrap();
+    assert_eq!(active(&suite, "creator"), 1);
+
+    suite.ensemble.add_funds("bidder", vec![coin(100, "uscrt")]);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder", &first.address)
+            .sent_funds(vec![coin(100, "uscrt")])
+    ).unwrap();
+
+    suite.finish_auction(&first);
+
+    // Ending isn't enough, the factory has to know that the auction was settled.
+    let err = suite.create_auction(config("Road 25", block + 1000)).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::TooManyActive(2).into());
+
+    suite.ensemble.execute(
+        &factory::ExecuteMsg::ProcessEnded { limit: 1 },
+        MockEnv::new("keeper", &suite.factory.address)
+    ).unwrap();
+    assert_eq!(active(&suite, "sender"), 1);
+
+    suite.create_auction(config("Road 25", block + 1000)).unwrap();
+    assert_eq!(active(&suite, "sender"), 2);
+}
+
+#[test]
+fn finished_auctions_are_archived() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height;
+
+    let first = suite.create_auction(config("Road 23", block + 100)).unwrap().contract;
+    let second = suite.create_auction(Config {
+        dispute_blocks: Some(100),
+        ..config("Road 24", block + 200)
+    }).unwrap().contract;
+    suite.create_auction(config("Road 25", block + 1000)).unwrap();
+
+    for (auction, amount) in [(&first, 100), (&second, 200)] {
+        suite.ensemble.add_funds("bidder", vec![coin(amount, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new("bidder", &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        ).unwrap();
+    }
+
+    let names = |page: PaginatedResponse<AuctionEntry<Addr>>| -> Vec<String> {
+        page.entries.into_iter().map(|x| x.info.name).collect()
+    };
+    let archive = |suite: &Suite| -> PaginatedResponse<AuctionEntry<Addr>> {
+        suite.ensemble.query(
+            &suite.factory.address,
+            &factory::QueryMsg::ListArchive { pagination: Pagination { start: 0, limit: 10 } }
+        ).unwrap()
+    };
+
+    // Settling through the factory archives the auction right away.
+    suite.finish_auction(&first);
+    suite.ensemble.execute(
+        &factory::ExecuteMsg::ProcessEnded { limit: 1 },
+        MockEnv::new("keeper", &suite.factory.address)
+    ).unwrap();
+
+    // The last auction takes the place of the archived one.
+    assert_eq!(names(suite.list_auctions(0, 10).unwrap()), ["Road 25", "Road 24"]);
+    assert_eq!(names(archive(&suite)), ["Road 23"]);
+
+    // The second one is cancelled by its admin, so it's up to the factory admin to archive it.
+    suite.finish_auction(&second);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::CancelSale { reason: "Item was damaged.".into() },
+        MockEnv::new(ADMIN, &second.address)
+    ).unwrap();
+
+    let sweep = |indices: Vec<u64>| factory::ExecuteMsg::ArchiveAuctions { indices };
+
+    let err = suite.ensemble.execute(
+        &sweep(vec![1]),
+        MockEnv::new("keeper", &suite.factory.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), StdError::generic_err("Unauthorized").into());
+
+    let err = suite.ensemble.execute(
+        &sweep(vec![]),
+        MockEnv::new("sender", &suite.factory.address)
+    ).unwrap_err();
+    assert_eq!(
+        contract_error(err),
+        StdError::generic_err(format!(
+            "Between 1 and {} auctions can be archived at once.",
+            factory::MAX_PROCESS_BATCH
+        )).into()
+    );
+
+    // Archived and running auctions are skipped.
+    let resp = suite.ensemble.execute(
+        &sweep(vec![0, 1, 2]),
+        MockEnv::new("sender", &suite.factory.address)
+    ).unwrap();
+    assert!(resp.response.attributes.iter().any(|x|
+        x.key == events::ARCHIVED && x.value == "1"
+    ));
+
+    let listed = suite.list_auctions(0, 10).unwrap();
+    assert_eq!(listed.total, 1);
+    assert_eq!(names(listed), ["Road 25"]);
+
+    let archived = archive(&suite);
+    assert_eq!(archived.total, 2);
+    assert_eq!(names(archived), ["Road 23", "Road 24"]);
+}
+
+#[test]
+fn largest_sales_are_ranked_by_the_factory() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 100;
+
+    let token = suite.new_token("token");
+    let auctions = ["Road 23", "Road 24", "Road 25", "Road 26"]
+        .map(|name| suite.create_auction(config(name, block)).unwrap().contract);
+    let in_token = suite.create_auction(Config {
+        bid_token: Some(BidToken {
+            address: token.address.to_string(),
+            code_hash: token.code_hash.clone()
+        }),
+        ..config("Road 27", block)
+    }).unwrap().contract;
+
+    // No bids were placed on the last native one.
+    for (auction, amount) in auctions.iter().zip([150, 300, 150]) {
+        suite.ensemble.add_funds("bidder", vec![coin(amount, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new("bidder", &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        ).unwrap();
+    }
+
+    suite.mint_tokens(&token, "bidder", 1000);
+    suite.increase_allowance(&token, "bidder", &in_token, 1000);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::BidFromAllowance { amount: Uint128::new(1000) },
+        MockEnv::new("bidder", &in_token.address)
+    ).unwrap();
+
+    let top_sales = |suite: &Suite, denom: &str, limit| suite.ensemble.query::<_, Vec<TopSale>>(
+        &suite.factory.address,
+        &factory::QueryMsg::TopSales { denom: denom.into(), limit }
+    );
+    assert_eq!(top_sales(&suite, "uscrt", 10).unwrap(), vec![]);
+
+    suite.finish_auction(&auctions[0]);
+
+    // Settled by the admin and by a keeper alike.
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &auctions[0].address)
+    ).unwrap();
+    suite.ensemble.execute(
+        &factory::ExecuteMsg::ProcessEnded { limit: factory::MAX_PROCESS_BATCH },
+        MockEnv::new("keeper", &suite.factory.address)
+    ).unwrap();
+
+    // Prices in the token aren't compared to those in the native denom.
+    let ranked = top_sales(&suite, "uscrt", 10).unwrap();
+    assert_eq!(ranked[0].auction.info.name, "Road 24");
+
+    // The earlier of two equal sales stays ahead.
+    assert_eq!(
+        ranked.into_iter().map(|x| (x.index, x.auction.contract, x.price.u128())).collect::<Vec<_>>(),
+        vec![
+            (1, auctions[1].clone(), 300),
+            (0, auctions[0].clone(), 150),
+            (2, auctions[2].clone(), 150)
+        ]
+    );
+    assert_eq!(top_sales(&suite, "uscrt", 1).unwrap().len(), 1);
+
+    let ranked = top_sales(&suite, token.address.as_str(), 10).unwrap();
+    assert_eq!(
+        ranked.into_iter().map(|x| (x.index, x.price.u128())).collect::<Vec<_>>(),
+        vec![(4, 1000)]
+    );
+
+    for limit in [0, factory::MAX_TOP_SALES + 1] {
+        let err = top_sales(&suite, "uscrt", limit).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            StdError::generic_err(format!(
+                "Limit must be between 1 and {}.",
+                factory::MAX_TOP_SALES
+            )).to_string()
+        );
+    }
+
+    // Only auctions in the registry can report a sale.
+    let err = suite.ensemble.execute(
+        &factory::ExecuteMsg::AuctionSettled {
+            auction: ContractLink {
+                address: Addr::unchecked("impostor"),
+                code_hash: auctions[0].code_hash.clone()
+            },
+            winner: None,
+            price: Uint128::new(1000),
+            denom: "uscrt".into()
+        },
+        MockEnv::new("impostor", &suite.factory.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), AuthError::Unauthorized.into());
+}
+
+#[test]
+fn settled_volume_is_totalled_by_denom() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 100;
+
+    let token = suite.new_token("token");
+    let native = ["Road 23", "Road 24", "Road 25"]
+        .map(|name| suite.create_auction(config(name, block)).unwrap().contract);
+    let in_token = suite.create_auction(Config {
+        bid_token: Some(BidToken {
+            address: token.address.to_string(),
+            code_hash: token.code_hash.clone()
+        }),
+        ..config("Road 26", block)
+    }).unwrap().contract;
+
+    // No bids were placed on the last native one.
+    for (auction, amount) in native.iter().zip([100, 250]) {
+        suite.ensemble.add_funds("bidder", vec![coin(amount, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new("bidder", &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        ).unwrap();
+    }
+
+    suite.mint_tokens(&token, "bidder", 400);
+    suite.increase_allowance(&token, "bidder", &in_token, 400);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::BidFromAllowance { amount: Uint128::new(400) },
+        MockEnv::new("bidder", &in_token.address)
+    ).unwrap();
+
+    let stats = |suite: &Suite| suite.ensemble.query::<_, VolumeStats>(
+        &suite.factory.address,
+        &factory::QueryMsg::VolumeStats { }
+    ).unwrap();
+    assert_eq!(stats(&suite), VolumeStats::default());
+
+    suite.finish_auction(&in_token);
+
+    for auction in native.iter().chain([&in_token]) {
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::ClaimProceeds { amount: None },
+            MockEnv::new(ADMIN, &auction.address)
+        ).unwrap();
+    }
+
+    assert_eq!(stats(&suite), VolumeStats {
+        sales: 3,
+        by_denom: vec![
+            DenomVolume {
+                denom: "uscrt".into(),
+                sales: 2,
+                total: Uint128::new(350)
+            },
+            DenomVolume {
+                denom: token.address.to_string(),
+                sales: 1,
+                total: Uint128::new(400)
+            }
+        ]
+    });
+}
+#[test]
+fn finished_auctions_can_be_closed() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.create_auction(Config {
+        beneficiary: Some("beneficiary".into()),
+        ..config("Road 23", block)
+    }).unwrap().contract;
+
+    for (bidder, amount) in [("bidder_1", 100), ("bidder_2", 300)] {
+        suite.ensemble.add_funds(bidder, vec![coin(amount, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new(bidder, &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        ).unwrap();
+    }
+
+    // Sent directly rather than through a bid, so it belongs to no one.
+    suite.ensemble.add_funds(&auction.address, vec![coin(7, "uscrt")]);
+
+    let close = |suite: &mut Suite, sender: &str| suite.ensemble.execute(
+        &auction::ExecuteMsg::Close { },
+        MockEnv::new(sender, &auction.address)
+    );
+
+    let err = close(&mut suite, ADMIN).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::NotSettled.into());
+
+    suite.finish_auction(&auction);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: Some(Uint128::new(100)) },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    let err = close(&mut suite, ADMIN).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::ProceedsUnclaimed.into());
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    let err = close(&mut suite, ADMIN).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::RefundsPending.into());
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::RetractBid { },
+        MockEnv::new("bidder_1", &auction.address)
+    ).unwrap();
+
+    let err = close(&mut suite, "bidder_1").unwrap_err();
+    assert_eq!(err.unwrap_contract_error().to_string(), "Generic error: Unauthorized");
+
+    let resp = close(&mut suite, ADMIN).unwrap();
+    assert!(resp.response.attributes.iter().any(|x|
+        x.key == events::AMOUNT && x.value == "7"
+    ));
+
+    // Only the dust has been sent, the payouts are still there to be claimed.
+    let balances = suite.ensemble.balances("beneficiary").unwrap();
+    assert_eq!(balances["uscrt"].u128(), 7);
+
+    suite.claim(&auction, "beneficiary");
+    suite.claim(&auction, "bidder_1");
+
+    let balances = suite.ensemble.balances("beneficiary").unwrap();
+    assert_eq!(balances["uscrt"].u128(), 307);
+    let balances = suite.ensemble.balances("bidder_1").unwrap();
+    assert_eq!(balances["uscrt"].u128(), 100);
+
+    let state: AuctionState = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::State { }
+    ).unwrap();
+    assert_eq!(state, AuctionState::Settled);
+
+    let closed: bool = suite.ensemble.query(
+        &suite.factory.address,
+        &factory::QueryMsg::IsClosed { index: 0 }
+    ).unwrap();
+    assert!(closed);
+    assert_eq!(suite.list_auctions(0, 10).unwrap().total, 0);
+
+    let err = close(&mut suite, ADMIN).unwrap_err();
+    assert_eq!(err.unwrap_contract_error().to_string(), "Generic error: Migrating\nReason: Closed");
+
+    // Only auctions of the registry can report being closed.
+    let err = suite.ensemble.execute(
+        &factory::ExecuteMsg::AuctionClosed { settled: true },
+        MockEnv::new("bidder_1", &suite.factory.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), AuthError::Unauthorized.into());
+}
+
+#[test]
+fn auctions_whose_reserve_was_not_met_can_be_settled_and_closed() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    suite.ensemble.add_funds("sender", vec![coin(100, "uscrt")]);
+    let auction = suite.create_funded_auction(
+        Config {
+            reserve: Some(Uint128::new(500)),
+            early_retraction_penalty: Some(1000),
+            seller_deposit: Some(Uint128::new(100)),
+            ..config("Road 23", block)
+        },
+        None,
+        vec![coin(100, "uscrt")]
+    ).unwrap().contract;
+
+    for (bidder, amount) in [("bidder_1", 100), ("bidder_2", 200)] {
+        suite.ensemble.add_funds(bidder, vec![coin(amount, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new(bidder, &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        ).unwrap();
+    }
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::RetractBid { },
+        MockEnv::new("bidder_1", &auction.address)
+    ).unwrap();
+
+    suite.finish_auction(&auction);
+
+    // The highest bid is refunded before the sale is settled.
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::RetractBid { },
+        MockEnv::new("bidder_2", &auction.address)
+    ).unwrap();
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Settle { },
+        MockEnv::new("keeper", &auction.address)
+    ).unwrap();
+
+    // Only the penalty is left as the proceeds.
+    let resp = suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    let result: ProceedsResult = from_binary(&resp.response.data.unwrap()).unwrap();
+    assert_eq!(result, ProceedsResult {
+        winner: None,
+        proceeds: Uint128::new(10),
+        fee: Uint128::zero(),
+        remaining: Uint128::zero()
+    });
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Close { },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    // The seller deposit is returned along with the proceeds.
+    for (address, amount) in [(ADMIN, 110), ("bidder_1", 90), ("bidder_2", 200)] {
+        suite.claim(&auction, address);
+        assert_eq!(suite.ensemble.balances(address).unwrap()["uscrt"].u128(), amount);
+    }
+
+    let closed: bool = suite.ensemble.query(
+        &suite.factory.address,
+        &factory::QueryMsg::IsClosed { index: 0 }
+    ).unwrap();
+    assert!(closed);
+}
+
+#[test]
+fn unsettled_auctions_are_listed() {
+    let mut suite = Suite::new();
+    let height = suite.ensemble.block().height;
+
+    for (name, end_block) in [
+        ("Road 23", height + 200),
+        ("Road 24", height + 100),
+        ("Road 25", height + 1000)
+    ] {
+        let auction = suite.create_auction(config(name, end_block)).unwrap().contract;
+
+        suite.ensemble.add_funds("bidder", vec![coin(100, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new("bidder", &auction.address)
+                .sent_funds(vec![coin(100, "uscrt")])
+        ).unwrap();
+    }
+
+    let unsettled = |suite: &Suite, start: u64| {
+        let resp: PaginatedResponse<AuctionEntry<Addr>> = suite.ensemble.query(
+            &suite.factory.address,
+            &factory::QueryMsg::ListUnsettled {
+                pagination: Pagination { start, limit: 30 }
+            }
+        ).unwrap();
+
+        (resp.total, resp.entries.into_iter().map(|x| x.info.name).collect::<Vec<_>>())
+    };
+
+    assert_eq!(unsettled(&suite, 0), (0, vec![]));
+
+    suite.advance_blocks(300);
+    assert_eq!(unsettled(&suite, 0), (2, vec!["Road 24".into(), "Road 23".into()]));
+    assert_eq!(unsettled(&suite, 1), (2, vec!["Road 23".into()]));
+
+    suite.ensemble.execute(
+        &factory::ExecuteMsg::ProcessEnded { limit: 1 },
+        MockEnv::new("keeper", &suite.factory.address)
+    ).unwrap();
+    assert_eq!(unsettled(&suite, 0), (1, vec!["Road 23".into()]));
+
+    // Auctions settled through themselves report it to the factory.
+    let road_23 = suite.list_auctions(0, 10).unwrap().entries.into_iter()
+        .find(|x| x.info.name == "Road 23")
+        .unwrap()
+        .contract;
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Settle { },
+        MockEnv::new("keeper", &road_23.address)
+    ).unwrap();
+    assert_eq!(unsettled(&suite, 0), (0, vec![]));
+
+    let active: u64 = suite.ensemble.query(
+        &suite.factory.address,
+        &factory::QueryMsg::ActiveAuctions { creator: "sender".into() }
+    ).unwrap();
+    assert_eq!(active, 1);
+}
+
+#[test]
+fn auctions_skipped_by_process_ended_stay_listed_as_unsettled() {
+    let mut suite = Suite::new();
+    let height = suite.ensemble.block().height;
+
+    let road_23 = suite.create_auction(config("Road 23", height + 100)).unwrap().contract;
+    suite.create_auction(config("Road 24", height + 200)).unwrap();
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::SetStatus {
+            status: ContractStatus::Paused { reason: "Maintenance".into() }
+        },
+        MockEnv::new(ADMIN, &road_23.address)
+    ).unwrap();
+
+    let unsettled = |suite: &Suite, start: u64| {
+        let resp: PaginatedResponse<AuctionEntry<Addr>> = suite.ensemble.query(
+            &suite.factory.address,
+            &factory::QueryMsg::ListUnsettled {
+                pagination: Pagination { start, limit: 30 }
+            }
+        ).unwrap();
+
+        (resp.total, resp.entries.into_iter().map(|x| x.info.name).collect::<Vec<_>>())
+    };
+
+    suite.advance_blocks(300);
+    assert_eq!(unsettled(&suite, 0), (2, vec!["Road 23".into(), "Road 24".into()]));
+
+    suite.ensemble.execute(
+        &factory::ExecuteMsg::ProcessEnded { limit: 10 },
+        MockEnv::new("keeper", &suite.factory.address)
+    ).unwrap();
+    assert_eq!(unsettled(&suite, 0), (1, vec!["Road 23".into()]));
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::SetStatus { status: ContractStatus::Operational },
+        MockEnv::new(ADMIN, &road_23.address)
+    ).unwrap();
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Settle { },
+        MockEnv::new("keeper", &road_23.address)
+    ).unwrap();
+    assert_eq!(unsettled(&suite, 0), (0, vec![]));
+}
+
+#[test]
+fn extended_auctions_are_processed_once_they_end() {
+    let mut suite = Suite::new();
+    let height = suite.ensemble.block().height;
+
+    let extended = suite.create_auction(Config {
+        extension_vote: Some(ExtensionVoteConfig { threshold: 5000, max_blocks: 100 }),
+        ..config("Road 23", height + 100)
+    }).unwrap().contract;
+    suite.create_auction(config("Road 24", height + 150)).unwrap();
+
+    suite.ensemble.add_funds("alice", vec![coin(100, "uscrt")]);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("alice", &extended.address)
+            .sent_funds(vec![coin(100, "uscrt")])
+    ).unwrap();
+
+    // Applied right away since alice holds all of the escrowed funds.
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::ProposeExtension { blocks: 100 },
+        MockEnv::new("alice", &extended.address)
+    ).unwrap();
+
+    let unsettled = |suite: &Suite| {
+        let resp: PaginatedResponse<AuctionEntry<Addr>> = suite.ensemble.query(
+            &suite.factory.address,
+            &factory::QueryMsg::ListUnsettled {
+                pagination: Pagination { start: 0, limit: 30 }
+            }
+        ).unwrap();
+
+        (resp.total, resp.entries.into_iter().map(|x| x.info.name).collect::<Vec<_>>())
+    };
+    let process = |suite: &mut Suite| {
+        suite.ensemble.execute(
+            &factory::ExecuteMsg::ProcessEnded { limit: 10 },
+            MockEnv::new("keeper", &suite.factory.address)
+        ).unwrap();
+    };
+    let state = |suite: &Suite| -> AuctionState {
+        suite.ensemble.query(&extended.address, &auction::QueryMsg::State { }).unwrap()
+    };
+
+    suite.advance_blocks(160);
+    assert_eq!(state(&suite), AuctionState::Active);
+    assert_eq!(unsettled(&suite), (1, vec!["Road 24".into()]));
+
+    process(&mut suite);
+    assert_eq!(unsettled(&suite), (0, vec![]));
+
+    suite.finish_auction(&extended);
+    assert_eq!(unsettled(&suite), (1, vec!["Road 23".into()]));
+
+    process(&mut suite);
+    assert_eq!(state(&suite), AuctionState::Settled);
+    assert_eq!(unsettled(&suite), (0, vec![]));
+}
+
+#[test]
+fn highest_bidder_is_only_shown_if_configured() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let public = suite.create_auction(Config {
+        show_highest_bidder: Some(true),
+        ..config("Road 23", block)
+    }).unwrap().contract;
+    let private = suite.create_auction(config("Road 24", block)).unwrap().contract;
+
+    let status = |suite: &Suite, auction: &ContractLink<Addr>| -> SaleStatus {
+        suite.ensemble.query(&auction.address, &auction::QueryMsg::SaleStatus { }).unwrap()
+    };
+
+    assert_eq!(status(&suite, &public).highest_bidder, None);
+
+    for auction in [&public, &private] {
+        suite.ensemble.add_funds("bidder", vec![coin(100, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new("bidder", &auction.address)
+                .sent_funds(vec![coin(100, "uscrt")])
+        ).unwrap();
+    }
+
+    let public = status(&suite, &public);
+    assert_eq!(public.current_highest.unwrap().u128(), 100);
+    assert_eq!(public.highest_bidder, Some(Addr::unchecked("bidder")));
+
+    let private = status(&suite, &private);
+    assert_eq!(private.current_highest.unwrap().u128(), 100);
+    assert_eq!(private.highest_bidder, None);
+}
+
+#[test]
+fn current_highest_can_be_hidden_until_the_end() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+    let created = suite.ensemble.block().clone();
+
+    let auction = suite.create_auction(Config {
+        show_highest_bidder: Some(true),
+        hide_current_highest: Some(true),
+        ..config("Road 23", block)
+    }).unwrap().contract;
+
+    for (bidder, amount) in [("bidder_1", 100), ("bidder_2", 150)] {
+        suite.ensemble.add_funds(bidder, vec![coin(amount, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new(bidder, &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        ).unwrap();
+    }
+
+    let status: SaleStatus = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::SaleStatus { }
+    ).unwrap();
+
+    assert_eq!(status.current_highest, None);
+    assert_eq!(status.highest_bidder, None);
+    assert_eq!(status.bidder_count, 2);
+    assert!(!status.is_finished);
+    assert_eq!(status.created_at, Some(CreatedAt { height: created.height, time: created.time }));
+
+    suite.finish_auction(&auction);
+
+    let status: SaleStatus = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::SaleStatus { }
+    ).unwrap();
+
+    assert_eq!(status.current_highest, Some(Uint128::new(150)));
+    assert_eq!(status.highest_bidder, Some(Addr::unchecked("bidder_2")));
+    assert_eq!(status.bidder_count, 2);
+    assert!(status.is_finished);
+}
+
+#[test]
+fn config_has_the_defaults_applied() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let fee = FeeConfig {
+        rate: Decimal::percent(5),
+        recipient: Addr::unchecked("platform")
+    };
+
+    suite.ensemble.execute(
+        &factory::ExecuteMsg::SetFeeConfig {
+            fee: Some(FeeConfig {
+                rate: fee.rate,
+                recipient: fee.recipient.to_string()
+            })
+        },
+        MockEnv::new("sender", &suite.factory.address)
+    ).unwrap();
+
+    // The block is advanced after the message is executed.
+    let created = suite.ensemble.block().clone();
+
+    let auction = suite.create_auction(Config {
+        reserve: Some(Uint128::new(150)),
+        min_increment: Some(Uint128::new(10)),
+        tradable_claims: Some(true),
+        dispute_blocks: Some(5),
+        early_retraction_penalty: Some(500),
+        ..config("Road 23", block)
+    }).unwrap().contract;
+
+    let config: AuctionConfig = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::Config { }
+    ).unwrap();
+
+    assert_eq!(config, AuctionConfig {
+        created_at: Some(CreatedAt {
+            height: created.height,
+            time: created.time
+        }),
+        end_time: None,
+        start_block: created.height,
+        guard_queries: false,
+        denom: Denom::new("uscrt").unwrap(),
+        reserve: Uint128::new(150),
+        min_increment: Uint128::new(10),
+        beneficiary: Addr::unchecked(ADMIN),
+        receipt_nft: None,
+        tradable_claims: true,
+        raffle: None,
+        manual_winner: false,
+        dispute_blocks: 5,
+        arbiter: None,
+        early_retraction_penalty: Some(500),
+        bid_cooldown: 0,
+        oracle: None,
+        usd_reserve: None,
+        usd_min_increment: None,
+        show_highest_bidder: false,
+        hide_current_highest: false,
+        public_active_bids: false,
+        extension_vote: None,
+        exclude_contracts: false,
+        bid_token: None,
+        seller_deposit: Uint128::zero(),
+        factory: Some(suite.factory.clone()),
+        fee: Some(fee),
+        co_sellers: None
+    });
+}
+
+#[test]
+fn auctions_link_back_to_their_factory() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    let factory: Option<ContractLink<Addr>> = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::Factory { }
+    ).unwrap();
+    assert_eq!(factory, Some(suite.factory.clone()));
+
+    let code: ContractCode = suite.ensemble.query(
+        &suite.factory.address,
+        &factory::QueryMsg::AuctionCode { }
+    ).unwrap();
+
+    let auction = suite.ensemble.instantiate(
+        code.id,
+        &AuctionInit::builder().name("Road 23").end_block(block).admin(ADMIN).build().unwrap(),
+        MockEnv::new(ADMIN, "road_23")
+    ).unwrap().instance;
+
+    let factory: Option<ContractLink<Addr>> = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::Factory { }
+    ).unwrap();
+    assert_eq!(factory, None);
+}
+
+#[test]
+fn factory_currency_policy_applies_to_new_auctions() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let token = suite.new_token("token");
+    let denom = |denom: &str| Denom::new(denom).unwrap();
+    let set_policy = |suite: &mut Suite, sender: &str, policy| suite.ensemble.execute(
+        &factory::ExecuteMsg::SetCurrencyPolicy { policy },
+        MockEnv::new(sender, &suite.factory.address)
+    );
+    let config_of = |suite: &Suite, auction: &ContractLink<Addr>| suite.ensemble.query::<_, AuctionConfig>(
+        &auction.address,
+        &auction::QueryMsg::Config { }
+    ).unwrap();
+
+    let policy = CurrencyPolicy {
+        denoms: vec![denom("uatom"), denom("uscrt")],
+        tokens: vec![ContractLink {
+            address: token.address.to_string(),
+            code_hash: token.code_hash.clone()
+        }]
+    };
+
+    let err = set_policy(&mut suite, "creator", Some(policy.clone())).unwrap_err();
+    assert_eq!(err.unwrap_contract_error().to_string(), "Generic error: Unauthorized");
+
+    let err = set_policy(&mut suite, "sender", Some(CurrencyPolicy { denoms: vec![], tokens: vec![] })).unwrap_err();
+    assert_eq!(contract_error(err), CurrencyError::Empty.into());
+
+    set_policy(&mut suite, "sender", Some(policy)).unwrap();
+
+    let stored: Option<CurrencyPolicy<Addr>> = suite.ensemble.query(
+        &suite.factory.address,
+        &factory::QueryMsg::CurrencyPolicy { }
+    ).unwrap();
+    assert_eq!(stored.unwrap().tokens, vec![token.clone()]);
+
+    // The first denom of the policy takes the place of the default one.
+    let auction = suite.new_auction(block).unwrap().contract;
+    assert_eq!(config_of(&suite, &auction).denom, denom("uatom"));
+
+    let auction = suite.create_auction(Config {
+        denom: Some(denom("uscrt")),
+        ..config("Road 24", block)
+    }).unwrap().contract;
+    assert_eq!(config_of(&suite, &auction).denom, denom("uscrt"));
+
+    let err = suite.create_auction(Config {
+        denom: Some(denom("uosmo")),
+        ..config("Road 25", block)
+    }).unwrap_err();
+    assert_eq!(contract_error(err), CurrencyError::DenomNotAllowed("uosmo".into()).into());
+
+    // The code hash of the token comes from the policy.
+    let auction = suite.create_auction(Config {
+        bid_token: Some(BidToken {
+            address: token.address.to_string(),
+            code_hash: "wrong".into()
+        }),
+        ..config("Road 26", block)
+    }).unwrap().contract;
+    assert_eq!(config_of(&suite, &auction).bid_token, Some(token.clone()));
+
+    let err = suite.create_auction(Config {
+        bid_token: Some(BidToken {
+            address: "other".into(),
+            code_hash: token.code_hash.clone()
+        }),
+        ..config("Road 27", block)
+    }).unwrap_err();
+    assert_eq!(contract_error(err), CurrencyError::TokenNotAllowed("other".into()).into());
+
+    set_policy(&mut suite, "sender", Some(CurrencyPolicy {
+        denoms: vec![],
+        tokens: vec![ContractLink {
+            address: token.address.to_string(),
+            code_hash: token.code_hash.clone()
+        }]
+    })).unwrap();
+
+    let err = suite.new_auction(block).unwrap_err();
+    assert_eq!(contract_error(err), CurrencyError::TokenRequired.into());
+
+    // Without a policy any currency can be used again.
+    set_policy(&mut suite, "sender", None).unwrap();
+
+    let auction = suite.create_auction(Config {
+        denom: Some(denom("uosmo")),
+        ..config("Road 28", block)
+    }).unwrap().contract;
+    assert_eq!(config_of(&suite, &auction).denom, denom("uosmo"));
+}
+
+#[test]
+fn auctions_without_an_admin_get_the_factory_default() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let set_default = |suite: &mut Suite, sender: &str, admin: Option<&str>| suite.ensemble.execute(
+        &factory::ExecuteMsg::SetDefaultAuctionAdmin { admin: admin.map(Into::into) },
+        MockEnv::new(sender, &suite.factory.address)
+    );
+    let admin_of = |suite: &Suite, auction: &ContractLink<Addr>| suite.ensemble.query::<_, Option<Addr>>(
+        &auction.address,
+        &auction::QueryMsg::Admin { }
+    ).unwrap();
+    let without_admin = |name: &str| Config { admin: None, ..config(name, block) };
+
+    // Otherwise the factory is the admin.
+    let auction = suite.create_auction(without_admin("Road 23")).unwrap().contract;
+    assert_eq!(admin_of(&suite, &auction), Some(suite.factory.address.clone()));
+
+    let err = set_default(&mut suite, "creator", Some("platform")).unwrap_err();
+    assert_eq!(err.unwrap_contract_error().to_string(), "Generic error: Unauthorized");
+
+    set_default(&mut suite, "sender", Some("platform")).unwrap();
+
+    let default: Option<Addr> = suite.ensemble.query(
+        &suite.factory.address,
+        &factory::QueryMsg::DefaultAuctionAdmin { }
+    ).unwrap();
+    assert_eq!(default, Some(Addr::unchecked("platform")));
+
+    let auction = suite.create_auction(without_admin("Road 24")).unwrap().contract;
+    assert_eq!(admin_of(&suite, &auction), Some(Addr::unchecked("platform")));
+
+    // Including the killswitch.
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::SetStatus {
+            status: ContractStatus::Paused { reason: "Maintenance".into() }
+        },
+        MockEnv::new("platform", &auction.address)
+    ).unwrap();
+
+    // An admin given by the creator takes precedence.
+    let auction = suite.new_auction(block).unwrap().contract;
+    assert_eq!(admin_of(&suite, &auction), Some(Addr::unchecked(ADMIN)));
+
+    set_default(&mut suite, "sender", None).unwrap();
+
+    let auction = suite.create_auction(without_admin("Road 25")).unwrap().contract;
+    assert_eq!(admin_of(&suite, &auction), Some(suite.factory.address.clone()));
+}
+#[test]
+fn bids_can_be_simulated() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    suite.ensemble.execute(
+        &factory::ExecuteMsg::SetFeeConfig {
+            fee: Some(FeeConfig {
+                rate: Decimal::percent(5),
+                recipient: "platform".into()
+            })
+        },
+        MockEnv::new("sender", &suite.factory.address)
+    ).unwrap();
+
+    let auction = suite.create_auction(Config {
+        reserve: Some(Uint128::new(150)),
+        min_increment: Some(Uint128::new(10)),
+        show_highest_bidder: Some(true),
+        ..config("Road 23", block)
+    }).unwrap().contract;
+
+    suite.ensemble.add_funds("bidder_1", vec![coin(100, "uscrt")]);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder_1", &auction.address)
+            .sent_funds(vec![coin(100, "uscrt")])
+    ).unwrap();
+
+    // Opens at the reserve price and keeps 150 to outbid others.
+    suite.ensemble.add_funds("bidder_2", vec![coin(300, "uscrt")]);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::ProxyBid { },
+        MockEnv::new("bidder_2", &auction.address)
+            .sent_funds(vec![coin(300, "uscrt")])
+    ).unwrap();
+
+    let simulate = |suite: &Suite, amount: u128| -> BidSimulation {
+        suite.ensemble.query(
+            &auction.address,
+            &auction::QueryMsg::SimulateBid {
+                permit: Permit::new(
+                    "bidder_1",
+                    PermitParams::new(auction.address.as_str())
+                        .permissions([AuctionPermission::ViewBid])
+                        .name("bids")
+                ),
+                amount: Uint128::new(amount)
+            }
+        ).unwrap()
+    };
+
+    assert_eq!(simulate(&suite, 50), BidSimulation {
+        accepted: false,
+        error: Some("Generic error: Bid must exceed the current highest by at least 10uscrt.".into()),
+        would_be_highest: false,
+        balance: Uint128::new(100),
+        reserve: Uint128::new(150),
+        min_increment: Uint128::new(10),
+        fee_rate: Decimal::percent(5)
+    });
+
+    let simulation = simulate(&suite, 250);
+    assert!(simulation.accepted);
+    assert!(simulation.would_be_highest);
+    assert_eq!(simulation.balance.u128(), 350);
+
+    // The proxy bid would be raised above it.
+    let simulation = simulate(&suite, 100);
+    assert!(simulation.accepted);
+    assert!(!simulation.would_be_highest);
+    assert_eq!(simulation.balance.u128(), 200);
+
+    let status: SaleStatus = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::SaleStatus { }
+    ).unwrap();
+    assert_eq!(status.num_bids, 2);
+
+    suite.ensemble.add_funds("bidder_1", vec![coin(100, "uscrt")]);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder_1", &auction.address)
+            .sent_funds(vec![coin(100, "uscrt")])
+    ).unwrap();
+
+    let status: SaleStatus = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::SaleStatus { }
+    ).unwrap();
+    assert_eq!(status.current_highest, Some(Uint128::new(210)));
+    assert_eq!(status.highest_bidder, Some(Addr::unchecked("bidder_2")));
+}
+
+#[test]
+fn accepted_bids_have_receipts() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.create_auction(Config {
+        min_increment: Some(Uint128::new(10)),
+        ..config("Road 23", block)
+    }).unwrap().contract;
+
+    let receipt = |suite: &Suite, receipt: u64| -> Option<BidReceipt> {
+        suite.ensemble.query(&auction.address, &auction::QueryMsg::BidReceipt { receipt }).unwrap()
+    };
+
+    let (height, time) = (suite.ensemble.block().height, suite.ensemble.block().time);
+
+    suite.ensemble.add_funds("bidder_1", vec![coin(100, "uscrt")]);
+    let resp = suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder_1", &auction.address)
+            .sent_funds(vec![coin(100, "uscrt")])
+    ).unwrap();
+
+    let attr = resp.response.attributes.iter().find(|x| x.key == events::RECEIPT).unwrap();
+    assert_eq!(attr.value, "1");
+    assert_eq!(receipt(&suite, 1), Some(BidReceipt { height, time }));
+
+    // Rejected bids don't use up an id.
+    suite.ensemble.add_funds("bidder_2", vec![coin(300, "uscrt")]);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder_2", &auction.address)
+            .sent_funds(vec![coin(105, "uscrt")])
+    ).unwrap_err();
+
+    let resp = suite.ensemble.execute(
+        &auction::ExecuteMsg::ProxyBid { },
+        MockEnv::new("bidder_2", &auction.address)
+            .sent_funds(vec![coin(300, "uscrt")])
+    ).unwrap();
+    let result: BidResult = from_binary(&resp.response.data.unwrap()).unwrap();
+    assert_eq!(result.receipt, 2);
+
+    assert!(receipt(&suite, 2).is_some());
+    assert_eq!(receipt(&suite, 3), None);
+}
+
+#[test]
+fn every_bid_is_counted() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    for (bidder, amount) in [("bidder_1", 100), ("bidder_1", 50), ("bidder_2", 200)] {
+        suite.ensemble.add_funds(bidder, vec![coin(amount, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new(bidder, &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        ).unwrap();
+    }
+
+    // Raises the bid of bidder_1 but only the deposit counts.
+    suite.ensemble.add_funds("bidder_1", vec![coin(300, "uscrt")]);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::ProxyBid { },
+        MockEnv::new("bidder_1", &auction.address)
+            .sent_funds(vec![coin(300, "uscrt")])
+    ).unwrap();
+
+    let status: SaleStatus = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::SaleStatus { }
+    ).unwrap();
+
+    assert_eq!(status.bidder_count, 2);
+    assert_eq!(status.num_bids, 4);
+}
+
+#[test]
+fn active_bids_require_a_permit_of_the_admin_by_default() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    suite.ensemble.add_funds("bidder", vec![coin(100, "uscrt")]);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder", &auction.address)
+            .sent_funds(vec![coin(100, "uscrt")])
+    ).unwrap();
+
+    let pagination = || Pagination { start: 0, limit: 30 };
+
+    let err = suite.ensemble.query::<_, PaginatedResponse<Uint128>>(
+        &auction.address,
+        &auction::QueryMsg::ActiveBids { pagination: pagination() }
+    ).unwrap_err();
+    assert_eq!(err.to_string(), AuthError::Unauthorized.to_string());
+
+    let permit = |address: &str, permission| Permit::new(
+        address,
+        PermitParams::new(auction.address.as_str())
+            .permissions([permission])
+            .name("bids")
+    );
+
+    let bids: PaginatedResponse<Uint128> = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::ActiveBidsWithPermit {
+            permit: permit(ADMIN, AuctionPermission::ViewActiveBids),
+            pagination: pagination()
+        }
+    ).unwrap();
+    assert_eq!(bids.total, 1);
+    assert_eq!(bids.entries, vec![Uint128::new(100)]);
+
+    let err = suite.ensemble.query::<_, PaginatedResponse<Uint128>>(
+        &auction.address,
+        &auction::QueryMsg::ActiveBidsWithPermit {
+            permit: permit("bidder", AuctionPermission::ViewActiveBids),
+            pagination: pagination()
+        }
+    ).unwrap_err();
+    assert_eq!(err.to_string(), AuthError::Unauthorized.to_string());
+
+    suite.ensemble.query::<_, PaginatedResponse<Uint128>>(
+        &auction.address,
+        &auction::QueryMsg::ActiveBidsWithPermit {
+            permit: permit(ADMIN, AuctionPermission::ViewBid),
+            pagination: pagination()
+        }
+    ).unwrap_err();
+}
+
+#[test]
+fn admin_can_export_bidders() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    for (bidder, amount) in [("bidder_1", 100), ("bidder_2", 150)] {
+        suite.ensemble.add_funds(bidder, vec![coin(amount, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new(bidder, &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        ).unwrap();
+    }
+
+    let export = auction::ExecuteMsg::ExportBidders {
+        pagination: Pagination { start: 1, limit: 30 }
+    };
+
+    let err = suite.ensemble.execute(
+        &export,
+        MockEnv::new("bidder_1", &auction.address)
+    ).unwrap_err();
+    assert_eq!(err.unwrap_contract_error().to_string(), "Generic error: Unauthorized");
+
+    let resp = suite.ensemble.execute(
+        &export,
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    let action = resp.response.attributes.iter()
+        .find(|x| x.key == events::ACTION)
+        .unwrap();
+    assert_eq!(action.value, events::actions::EXPORT_BIDDERS);
+
+    let page: PaginatedResponse<BidEntry> = from_binary(&resp.response.data.unwrap()).unwrap();
+    assert_eq!(page.total, 2);
+    assert_eq!(page.entries, vec![
+        BidEntry { address: Addr::unchecked("bidder_2"), amount: Uint128::new(150) }
+    ]);
+}
+
+#[test]
+fn storage_layout_version_is_recorded() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    for address in [&auction.address, &suite.factory.address] {
+        suite.ensemble.contract_storage(address, |storage| {
+            assert_eq!(layout::load(storage).unwrap(), layout::VERSION);
+        }).unwrap();
+    }
+
+    suite.ensemble.contract_storage(&auction.address, |storage| {
+        assert!(storage.get(shared::versioned_key!(b"info")).is_some());
+        assert!(storage.get(b"info").is_none());
+    }).unwrap();
+
+    // Contracts that never stored a version use the unversioned layout.
+    suite.ensemble.contract_storage_mut(&auction.address, |storage| {
+        storage.remove(b"layout_version");
+        assert_eq!(layout::load(storage).unwrap(), layout::LEGACY);
+
+        Ok(())
+    }).unwrap();
+}
+
+#[test]
+fn factory_of_the_first_release_can_be_migrated() {
+    let mut suite = Suite::new();
+    let height = suite.ensemble.block().height;
+
+    let auction = suite.ensemble.register(Box::new(Auction));
+    let factory = suite.ensemble.register(Box::new(UninitializedFactory));
+    let legacy = suite.ensemble.instantiate(
+        factory.id,
+        &Empty { },
+        MockEnv::new(ADMIN, "legacy_factory")
+    ).unwrap().instance;
+
+    // The registry as the first release wrote it, from before sales had metadata.
+    #[derive(FadromaSerialize, FadromaDeserialize)]
+    struct LegacyEntry {
+        contract: ContractLink<CanonicalAddr>,
+        info: LegacySaleInfo
+    }
+
+    #[derive(FadromaSerialize, FadromaDeserialize)]
+    struct LegacySaleInfo {
+        name: String,
+        end_block: u64
+    }
+
+    suite.ensemble.contract_storage_mut(&legacy.address, |storage| {
+        fadroma::storage::save(storage, b"contract", &auction)?;
+
+        let mut auctions = IterableStorage::new(StaticKey(b"auctions"));
+        for (name, end_block) in [
+            ("Road 23", height + 200),
+            ("Road 24", height + 100),
+            ("Road 25", height + 1000)
+        ] {
+            auctions.push(storage, &LegacyEntry {
+                contract: ContractLink {
+                    address: MockApi::default().addr_canonicalize(&name.to_lowercase().replace(' ', "_"))?,
+                    code_hash: auction.code_hash.clone()
+                },
+                info: LegacySaleInfo { name: name.into(), end_block }
+            })?;
+        }
+
+        Ok(())
+    }).unwrap();
+
+    let migrate = |suite: &mut Suite| {
+        let mut result = None;
+
+        suite.ensemble.contract_storage_mut(&legacy.address, |storage| {
+            let api = MockApi::default();
+            let querier: MockQuerier = MockQuerier::new(&[]);
+            let deps = DepsMut {
+                storage,
+                api: &api,
+                querier: QuerierWrapper::new(&querier)
+            };
+
+            result = Some(factory::migrate(deps, mock_env(), MigrateMsg { admin: Some(ADMIN.into()) }));
+
+            Ok(())
+        }).unwrap();
+
+        result.unwrap()
+    };
+
+    let resp = migrate(&mut suite).unwrap();
+    let version = resp.attributes.iter()
+        .find(|x| x.key == events::LAYOUT_VERSION)
+        .unwrap();
+    assert_eq!(version.value, layout::LEGACY.to_string());
+
+    suite.ensemble.contract_storage(&legacy.address, |storage| {
+        assert!(storage.get(b"contract").is_none());
+        assert!(IterableStorage::<LegacyEntry, _>::new(StaticKey(b"auctions")).get(storage, 0).unwrap().is_none());
+    }).unwrap();
+
+    let admin: Option<Addr> = suite.ensemble.query(&legacy.address, &factory::QueryMsg::Admin { }).unwrap();
+    assert_eq!(admin, Some(Addr::unchecked(ADMIN)));
+
+    let names = |resp: PaginatedResponse<AuctionEntry<Addr>>| {
+        (resp.total, resp.entries.into_iter().map(|x| x.info.name).collect::<Vec<_>>())
+    };
+    let query = |suite: &Suite, msg: &factory::QueryMsg| {
+        names(suite.ensemble.query(&legacy.address, msg).unwrap())
+    };
+    let pagination = || Pagination { start: 0, limit: 30 };
+
+    let auctions: PaginatedResponse<AuctionEntry<Addr>> = suite.ensemble.query(
+        &legacy.address,
+        &factory::QueryMsg::ListAuctions { pagination: pagination() }
+    ).unwrap();
+    assert_eq!(auctions.entries[0].contract.address, Addr::unchecked("road_23"));
+    assert_eq!(auctions.entries[0].info.metadata, None);
+    assert_eq!(names(auctions), (3, vec!["Road 23".into(), "Road 24".into(), "Road 25".into()]));
+
+    assert_eq!(
+        query(&suite, &factory::QueryMsg::ListEndingSoon {
+            before_block: height + 500,
+            pagination: pagination()
+        }),
+        (2, vec!["Road 24".into(), "Road 23".into()])
+    );
+
+    suite.advance_blocks(300);
+    let unsettled = factory::QueryMsg::ListUnsettled { pagination: pagination() };
+    assert_eq!(query(&suite, &unsettled), (2, vec!["Road 24".into(), "Road 23".into()]));
+
+    // Their code can't tell their state, so they are left to be settled by their admin.
+    suite.ensemble.execute(
+        &factory::ExecuteMsg::ProcessEnded { limit: 10 },
+        MockEnv::new("keeper", &legacy.address)
+    ).unwrap();
+    assert_eq!(query(&suite, &unsettled), (2, vec!["Road 24".into(), "Road 23".into()]));
+
+    // The factory keeps working.
+    suite.ensemble.execute(
+        &factory::ExecuteMsg::CreateAuction {
+            config: config("Road 26", height + 2000),
+            tags: None,
+            end: None
+        },
+        MockEnv::new("sender", &legacy.address)
+    ).unwrap();
+    assert_eq!(
+        query(&suite, &factory::QueryMsg::ListAuctions { pagination: pagination() }).1,
+        vec!["Road 23", "Road 24", "Road 25", "Road 26"]
+    );
+
+    suite.ensemble.contract_storage_mut(&legacy.address, |storage| {
+        storage.set(b"layout_version", &[layout::VERSION + 1]);
+
+        Ok(())
+    }).unwrap();
+
+    let err = migrate(&mut suite).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err(format!("Storage layout version {} can't be migrated.", layout::VERSION + 1)).into()
+    );
+}
+
+#[test]
+fn auctions_can_be_migrated() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    let migrate = |suite: &mut Suite| {
+        let mut result = None;
+
+        suite.ensemble.contract_storage_mut(&auction.address, |storage| {
+            let api = MockApi::default();
+            let querier: MockQuerier = MockQuerier::new(&[]);
+            let deps = DepsMut {
+                storage,
+                api: &api,
+                querier: QuerierWrapper::new(&querier)
+            };
+
+            result = Some(auction::Contract::migrate(deps, mock_env(), MigrateMsg { admin: None }));
+
+            Ok(())
+        }).unwrap();
+
+        result.unwrap()
+    };
+
+    let resp = migrate(&mut suite).unwrap();
+    assert_eq!(resp.attributes[0].value, events::actions::MIGRATE);
+    assert_eq!(resp.attributes[1].value, layout::VERSION.to_string());
+
+    suite.ensemble.add_funds("bidder", vec![coin(100, "uscrt")]);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder", &auction.address)
+            .sent_funds(vec![coin(100, "uscrt")])
+    ).unwrap();
+
+    // The bids of the first release can't be listed to be converted.
+    suite.ensemble.contract_storage_mut(&auction.address, |storage| {
+        storage.remove(b"layout_version");
+
+        Ok(())
+    }).unwrap();
+
+    let err = migrate(&mut suite).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err(format!("Storage layout version {} can't be migrated.", layout::LEGACY)).into()
+    );
+}
+
+#[test]
+fn contracts_only_migrate_to_newer_versions() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.new_auction(block).unwrap().contract;
+
+    let version: ContractVersion = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::Version { }
+    ).unwrap();
+    assert_eq!(version, ContractVersion {
+        contract: version::AUCTION_CONTRACT_NAME.into(),
+        version: version::CONTRACT_VERSION.into()
+    });
+
+    let version: ContractVersion = suite.ensemble.query(
+        &suite.factory.address,
+        &factory::QueryMsg::Version { }
+    ).unwrap();
+    assert_eq!(version, ContractVersion::current(version::FACTORY_CONTRACT_NAME));
+
+    let migrate = |suite: &mut Suite, stored: Option<ContractVersion>| {
+        let mut result = None;
+
+        suite.ensemble.contract_storage_mut(&suite.factory.address, |storage| {
+            if let Some(stored) = &stored {
+                fadroma::storage::save(storage, b"contract_version", stored)?;
+            }
+
+            let api = MockApi::default();
+            let querier: MockQuerier = MockQuerier::new(&[]);
+            let deps = DepsMut {
+                storage,
+                api: &api,
+                querier: QuerierWrapper::new(&querier)
+            };
+
+            result = Some(factory::migrate(deps, mock_env(), MigrateMsg { admin: None }));
+
+            Ok(())
+        }).unwrap();
+
+        result.unwrap()
+    };
+
+    let resp = migrate(&mut suite, Some(ContractVersion {
+        contract: version::FACTORY_CONTRACT_NAME.into(),
+        version: "0.0.1".into()
+    })).unwrap();
+    assert!(resp.attributes.iter().any(|x|
+        x.key == events::CONTRACT_VERSION && x.value == "0.0.1"
+    ));
+
+    let version: ContractVersion = suite.ensemble.query(
+        &suite.factory.address,
+        &factory::QueryMsg::Version { }
+    ).unwrap();
+    assert_eq!(version.version, version::CONTRACT_VERSION);
+
+    let err = migrate(&mut suite, Some(ContractVersion {
+        contract: version::FACTORY_CONTRACT_NAME.into(),
+        version: "99.0.0".into()
+    })).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err(format!(
+            "Can't migrate from version 99.0.0 to the older {}.",
+            version::CONTRACT_VERSION
+        )).into()
+    );
+
+    let err = migrate(&mut suite, Some(ContractVersion::current(version::AUCTION_CONTRACT_NAME))).unwrap_err();
+    assert_eq!(err, StdError::generic_err("Can't migrate auction to factory.").into());
+}
+
+#[test]
+fn registry_can_be_exported_by_the_admin() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    suite.create_tagged_auction(config("Road 23", block), Some(vec!["house".into()])).unwrap();
+    suite.create_auction(config("Road 24", block)).unwrap();
+
+    let export = |suite: &Suite, address: &str, start: u64| {
+        suite.ensemble.query::<_, PaginatedResponse<RegistryEntry>>(
+            &suite.factory.address,
+            &factory::QueryMsg::ExportRegistry {
+                permit: Permit::new(
+                    address,
+                    PermitParams::new(suite.factory.address.as_str())
+                        .permissions([FactoryPermission::ExportRegistry])
+                        .name("backup")
+                ),
+                pagination: Pagination { start, limit: 30 }
+            }
+        )
+    };
+
+    let err = export(&suite, "bidder", 0).unwrap_err();
+    assert_eq!(err.to_string(), AuthError::Unauthorized.to_string());
+
+    let registry = export(&suite, "sender", 0).unwrap();
+    assert_eq!(registry.total, 2);
+
+    let entry = &registry.entries[0];
+    assert_eq!(entry.index, 0);
+    assert_eq!(entry.info.name, "Road 23");
+
+    let origin = entry.origin.as_ref().unwrap();
+    assert_eq!(origin.creator, Addr::unchecked("sender"));
+    assert_eq!(origin.tags, vec!["house".to_string()]);
+    assert_eq!(from_binary::<Config>(&origin.config).unwrap().name, "Road 23");
+
+    let registry = export(&suite, "sender", 1).unwrap();
+    assert_eq!(registry.entries.len(), 1);
+    assert_eq!(registry.entries[0].index, 1);
+    assert_eq!(registry.entries[0].info.name, "Road 24");
+}
+
+#[test]
+fn sale_ends_at_the_end_block_or_time_whichever_comes_first() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+    let time = suite.ensemble.block().time;
+
+    let code = suite.ensemble.register(Box::new(Auction));
+    let err = suite.ensemble.instantiate(
+        code.id,
+        &auction::InstantiateMsg {
+            config: Config {
+                end_time: Some(time),
+                ..config("Road 23", block)
+            }
+        },
+        MockEnv::new(ADMIN, "invalid")
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::EndTimePassed.into());
+
+    let auction = suite.create_auction(Config {
+        end_time: Some(time + 600),
+        ..config("Road 23", block)
+    }).unwrap().contract;
+    let by_block = suite.create_auction(Config {
+        end_time: Some(time + 1_000_000),
+        ..config("Road 24", block)
+    }).unwrap().contract;
+
+    let remaining = |suite: &Suite, auction: &ContractLink<Addr>| -> TimeRemaining {
+        suite.ensemble.query(&auction.address, &auction::QueryMsg::TimeRemaining { }).unwrap()
+    };
+
+    let now = suite.ensemble.block();
+    assert_eq!(remaining(&suite, &auction), TimeRemaining {
+        blocks: block - now.height,
+        seconds: Some(time + 600 - now.time)
+    });
+
+    suite.ensemble.block_mut().time = time + 600;
+
+    // The last second in which bids are accepted.
+    suite.ensemble.add_funds("bidder", vec![coin(100, "uscrt")]);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder", &auction.address)
+            .sent_funds(vec![coin(100, "uscrt")])
+    ).unwrap();
+
+    suite.ensemble.block_mut().time = time + 601;
+
+    suite.ensemble.add_funds("bidder", vec![coin(100, "uscrt")]);
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder", &auction.address)
+            .sent_funds(vec![coin(100, "uscrt")])
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::Finished.into());
+
+    assert_eq!(remaining(&suite, &auction), TimeRemaining { blocks: 0, seconds: Some(0) });
+
+    let state: AuctionState = suite.ensemble.query(
+        &auction.address,
+        &auction::QueryMsg::State { }
+    ).unwrap();
+    assert_eq!(state, AuctionState::Ended);
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::ClaimProceeds { amount: None },
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap();
+
+    // The other one still runs until its end block.
+    let state: AuctionState = suite.ensemble.query(
+        &by_block.address,
+        &auction::QueryMsg::State { }
+    ).unwrap();
+    assert_eq!(state, AuctionState::Active);
+
+    suite.finish_auction(&by_block);
+    assert_eq!(remaining(&suite, &by_block), TimeRemaining { blocks: 0, seconds: Some(0) });
+}
+
+#[test]
+fn bidders_can_vote_to_extend_the_sale() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let code = suite.ensemble.register(Box::new(Auction));
+    let err = suite.ensemble.instantiate(
+        code.id,
+        &auction::InstantiateMsg {
+            config: Config {
+                extension_vote: Some(ExtensionVoteConfig { threshold: 0, max_blocks: 100 }),
+                ..config("Road 23", block)
+            }
+        },
+        MockEnv::new(ADMIN, "invalid")
+    ).unwrap_err();
+    assert_eq!(contract_error(err), ExtensionError::InvalidThreshold.into());
+
+    let auction = suite.create_auction(Config {
+        extension_vote: Some(ExtensionVoteConfig { threshold: 5000, max_blocks: 100 }),
+        ..config("Road 23", block)
+    }).unwrap().contract;
+    let without_votes = suite.new_auction(block).unwrap().contract;
+
+    for (bidder, amount) in [("alice", 100), ("bob", 200)] {
+        suite.ensemble.add_funds(bidder, vec![coin(amount, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new(bidder, &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        ).unwrap();
+    }
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::ProposeExtension { blocks: 10 },
+        MockEnv::new("alice", &without_votes.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), ExtensionError::NotEnabled.into());
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::ProposeExtension { blocks: 10 },
+        MockEnv::new("carol", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), ExtensionError::NoBid.into());
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::ProposeExtension { blocks: 150 },
+        MockEnv::new("alice", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), ExtensionError::InvalidBlocks { blocks: 150, left: 100 }.into());
+
+    // A third of the escrowed funds isn't enough.
+    let resp = suite.ensemble.execute(
+        &auction::ExecuteMsg::ProposeExtension { blocks: 60 },
+        MockEnv::new("alice", &auction.address)
+    ).unwrap();
+    let id: u64 = from_binary(&resp.response.data.unwrap()).unwrap();
+    assert_eq!(id, 0);
+
+    let proposal = |suite: &Suite, id: u64| -> ExtensionProposal {
+        suite.ensemble.query(&auction.address, &auction::QueryMsg::ExtensionProposal { proposal: id }).unwrap()
+    };
+    let remaining = |suite: &Suite| -> u64 {
+        let remaining: TimeRemaining = suite.ensemble.query(
+            &auction.address,
+            &auction::QueryMsg::TimeRemaining { }
+        ).unwrap();
+
+        remaining.blocks
+    };
+
+    assert_eq!(proposal(&suite, id), ExtensionProposal {
+        blocks: 60,
+        votes: Uint128::new(100),
+        applied: None
+    });
+    assert_eq!(remaining(&suite), block - suite.ensemble.block().height);
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::VoteExtension { proposal: id },
+        MockEnv::new("alice", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), ExtensionError::AlreadyVoted(id).into());
+
+    let resp = suite.ensemble.execute(
+        &auction::ExecuteMsg::VoteExtension { proposal: id },
+        MockEnv::new("bob", &auction.address)
+    ).unwrap();
+    let end_block = resp.response.attributes.last().unwrap();
+    assert_eq!(end_block.key, events::END_BLOCK);
+    assert_eq!(end_block.value, (block + 60).to_string());
+
+    assert_eq!(proposal(&suite, id), ExtensionProposal {
+        blocks: 60,
+        votes: Uint128::new(300),
+        applied: Some(60)
+    });
+    assert_eq!(remaining(&suite), block + 60 - suite.ensemble.block().height);
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::VoteExtension { proposal: id },
+        MockEnv::new("bob", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), ExtensionError::AlreadyApplied(id).into());
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::ProposeExtension { blocks: 30 },
+        MockEnv::new("alice", &auction.address)
+    ).unwrap();
+
+    // Passes right away.
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::ProposeExtension { blocks: 30 },
+        MockEnv::new("bob", &auction.address)
+    ).unwrap();
+    assert_eq!(proposal(&suite, 2).applied, Some(30));
+
+    // Capped at the blocks that are left.
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::VoteExtension { proposal: 1 },
+        MockEnv::new("bob", &auction.address)
+    ).unwrap();
+    assert_eq!(proposal(&suite, 1).applied, Some(10));
+    assert_eq!(remaining(&suite), block + 100 - suite.ensemble.block().height);
+
+    let err = suite.ensemble.execute(
+        &auction::ExecuteMsg::ProposeExtension { blocks: 1 },
+        MockEnv::new("bob", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), ExtensionError::InvalidBlocks { blocks: 1, left: 0 }.into());
+
+    let err = suite.ensemble.query::<_, ExtensionProposal>(
+        &auction.address,
+        &auction::QueryMsg::ExtensionProposal { proposal: 3 }
+    ).unwrap_err();
+    assert_eq!(err.to_string(), ExtensionError::NotFound(3).to_string());
+}
+
+#[test]
+fn extension_votes_count_proxy_deposits_and_are_taken_back_on_retraction() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let auction = suite.create_auction(Config {
+        extension_vote: Some(ExtensionVoteConfig { threshold: 5000, max_blocks: 100 }),
+        early_retraction_penalty: Some(0),
+        ..config("Road 23", block)
+    }).unwrap().contract;
+
+    for (bidder, amount) in [("alice", 300), ("carol", 400)] {
+        suite.ensemble.add_funds(bidder, vec![coin(amount, "uscrt")]);
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            MockEnv::new(bidder, &auction.address)
+                .sent_funds(vec![coin(amount, "uscrt")])
+        ).unwrap();
+    }
+
+    suite.ensemble.add_funds("bob", vec![coin(500, "uscrt")]);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::ProxyBid { },
+        MockEnv::new("bob", &auction.address)
+            .sent_funds(vec![coin(500, "uscrt")])
+    ).unwrap();
+
+    let proposal = |suite: &Suite| -> ExtensionProposal {
+        suite.ensemble.query(&auction.address, &auction::QueryMsg::ExtensionProposal { proposal: 0 }).unwrap()
+    };
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::ProposeExtension { blocks: 50 },
+        MockEnv::new("alice", &auction.address)
+    ).unwrap();
+    assert_eq!(proposal(&suite).votes, Uint128::new(300));
+
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::RetractBid { },
+        MockEnv::new("alice", &auction.address)
+    ).unwrap();
+    assert_eq!(proposal(&suite).votes, Uint128::zero());
+
+    // Less than half of the 900 that are left.
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::VoteExtension { proposal: 0 },
+        MockEnv::new("carol", &auction.address)
+    ).unwrap();
+    assert_eq!(proposal(&suite).applied, None);
+
+    // Weighs the whole deposit, not only the part that was bid.
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::VoteExtension { proposal: 0 },
+        MockEnv::new("bob", &auction.address)
+    ).unwrap();
+    assert_eq!(proposal(&suite), ExtensionProposal {
+        blocks: 50,
+        votes: Uint128::new(900),
+        applied: Some(50)
+    });
+}
+
+#[test]
+fn co_sellers_share_the_proceeds_and_approve_privileged_actions() {
+    let mut suite = Suite::new();
+    let block = suite.ensemble.block().height + 1000;
+
+    let code: ContractCode = suite.ensemble.query(
+        &suite.factory.address,
+        &factory::QueryMsg::AuctionCode { }
+    ).unwrap();
+
+    let init = || AuctionInit::builder().name("Road 23").end_block(block).admin(ADMIN);
+
+    for (msg, expected) in [
+        (
+            init().co_sellers(2, [("alice", 6000), ("bob", 4000), ("alice", 0)]).build().unwrap(),
+            CoSellerError::ZeroShare("alice".into())
+        ),
+        (
+            init().co_sellers(2, [("alice", 6000), ("bob", 3000), ("alice", 1000)]).build().unwrap(),
+            CoSellerError::Duplicate("alice".into())
+        ),
+        (
+            init().co_sellers(2, [("alice", 6000), ("bob", 3000)]).build().unwrap(),
+            CoSellerError::SharesMismatch(9000)
+        ),
+        (
+            init().co_sellers(3, [("alice", 6000), ("bob", 4000)]).build().unwrap(),
+            CoSellerError::InvalidThreshold(3)
+        ),
+        (
+            init().co_sellers(1, [("alice", 10000)]).beneficiary("treasury").build().unwrap(),
+            CoSellerError::WithBeneficiary
+        )
+    ] {
+        let err = suite.ensemble.instantiate(code.id, &msg, MockEnv::new(ADMIN, "road_23")).unwrap_err();
+        assert_eq!(contract_error(err), expected.into());
+    }
+
+    let auction = suite.create_auction(
+        init().co_sellers(2, [("alice", 6000), ("bob", 3000), ("carol", 1000)]).config().unwrap()
+    ).unwrap().contract;
+
+    let approve = |action: SellerAction| auction::ExecuteMsg::ApproveAction { action };
+    let approvals = |suite: &Suite| -> Vec<PendingApproval> {
+        suite.ensemble.query(&auction.address, &auction::QueryMsg::Approvals { }).unwrap()
+    };
+
+    // The admin has to go through the co-sellers.
+    for msg in [
+        auction::ExecuteMsg::ClaimProceeds { amount: None },
+        auction::ExecuteMsg::CancelSale { reason: "fraud".into() }
+    ] {
+        let err = suite.ensemble.execute(&msg, MockEnv::new(ADMIN, &auction.address)).unwrap_err();
+        assert_eq!(contract_error(err), CoSellerError::ApprovalRequired.into());
+    }
+
+    let err = suite.ensemble.execute(
+        &approve(SellerAction::Extend { blocks: 100 }),
+        MockEnv::new(ADMIN, &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), AuthError::Unauthorized.into());
+
+    let resp = suite.ensemble.execute(
+        &approve(SellerAction::Extend { blocks: 100 }),
+        MockEnv::new("alice", &auction.address)
+    ).unwrap();
+    assert!(resp.response.attributes.contains(&Attribute::new(events::APPROVALS, "1")));
+
+    let err = suite.ensemble.execute(
+        &approve(SellerAction::Extend { blocks: 100 }),
+        MockEnv::new("alice", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), CoSellerError::AlreadyApproved.into());
+
+    assert_eq!(approvals(&suite), vec![PendingApproval {
+        action: SellerAction::Extend { blocks: 100 },
+        approvers: vec![Addr::unchecked("alice")]
+    }]);
+
+    let resp = suite.ensemble.execute(
+        &approve(SellerAction::Extend { blocks: 100 }),
+        MockEnv::new("bob", &auction.address)
+    ).unwrap();
+    let end_block = resp.response.attributes.last().unwrap();
+    assert_eq!(end_block.key, events::END_BLOCK);
+    assert_eq!(end_block.value, (block + 100).to_string());
+    assert!(approvals(&suite).is_empty());
+
+    suite.ensemble.add_funds("bidder", vec![coin(1001, "uscrt")]);
+    suite.ensemble.execute(
+        &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+        MockEnv::new("bidder", &auction.address)
+            .sent_funds(vec![coin(1001, "uscrt")])
+    ).unwrap();
+
+    suite.finish_auction(&auction);
+
+    suite.ensemble.execute(
+        &approve(SellerAction::ClaimProceeds { amount: None }),
+        MockEnv::new("carol", &auction.address)
+    ).unwrap();
+    let resp = suite.ensemble.execute(
+        &approve(SellerAction::ClaimProceeds { amount: None }),
+        MockEnv::new("alice", &auction.address)
+    ).unwrap();
+
+    let result: ProceedsResult = from_binary(&resp.response.data.unwrap()).unwrap();
+    assert_eq!(result.proceeds, Uint128::new(1001));
+
+    // Alice gets what is lost to rounding.
+    for (seller, amount) in [("alice", 601), ("bob", 300), ("carol", 100)] {
+        suite.ensemble.execute(
+            &auction::ExecuteMsg::Claim { },
+            MockEnv::new(seller, &auction.address)
+        ).unwrap();
+
+        let balance = suite.ensemble.balances(seller).unwrap()["uscrt"];
+        assert_eq!(balance, Uint128::new(amount), "Balance of {}", seller);
+    }
+
+    // Checked once the threshold is reached.
+    suite.ensemble.execute(
+        &approve(SellerAction::Extend { blocks: 100 }),
+        MockEnv::new("alice", &auction.address)
+    ).unwrap();
+    let err = suite.ensemble.execute(
+        &approve(SellerAction::Extend { blocks: 100 }),
+        MockEnv::new("bob", &auction.address)
+    ).unwrap_err();
+    assert_eq!(contract_error(err), LifecycleError::Finished.into());
+}
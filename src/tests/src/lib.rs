@@ -1,2 +1,12 @@
 #[cfg(test)]
 mod auction;
+#[cfg(test)]
+mod model;
+#[cfg(test)]
+mod roundtrip;
+#[cfg(test)]
+mod scenario;
+#[cfg(test)]
+mod schema;
+#[cfg(test)]
+mod wire;
@@ -0,0 +1,260 @@
+use fadroma::{
+    core::ContractLink,
+    ensemble::MockEnv,
+    cosmwasm_std::{Addr, Uint128, coin}
+};
+use proptest::prelude::*;
+use auction::auction;
+use shared::{
+    AuctionState, Config, SaleStatus,
+    errors::{ContractError, LifecycleError}
+};
+use testing::{Suite, ADMIN, config, contract_error};
+
+const BIDDERS: [&str; 3] = ["alice", "bob", "carol"];
+const KEY: &str = "key";
+
+#[derive(Clone, Debug)]
+enum Op {
+    Bid { bidder: usize, amount: u128 },
+    Retract { bidder: usize },
+    /// Claims the proceeds as the admin.
+    Settle,
+    /// The bidders followed by the admin.
+    Claim { address: usize },
+    Advance { blocks: u64 }
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        4 => (0..BIDDERS.len(), 1..300u128).prop_map(|(bidder, amount)| Op::Bid { bidder, amount }),
+        2 => (0..BIDDERS.len()).prop_map(|bidder| Op::Retract { bidder }),
+        1 => Just(Op::Settle),
+        2 => (0..=BIDDERS.len()).prop_map(|address| Op::Claim { address }),
+        2 => (0..400u64).prop_map(|blocks| Op::Advance { blocks })
+    ]
+}
+
+// What the auction should look like with the default config, apart from
+// showing the highest bidder. Indices past the bidders refer to the admin.
+#[derive(Default, Debug)]
+struct Model {
+    end_block: u64,
+    bids: [u128; BIDDERS.len()],
+    has_bid: [bool; BIDDERS.len()],
+    num_bids: u64,
+    highest: Option<usize>,
+    settled: bool,
+    payouts: [u128; BIDDERS.len() + 1],
+    wallets: [u128; BIDDERS.len() + 1]
+}
+
+// `Err(None)` is a failure that the model doesn't predict the error of.
+type Outcome = Result<(), Option<ContractError>>;
+
+impl Model {
+    fn apply(&mut self, op: &Op, height: u64) -> Outcome {
+        let finished = height > self.end_block;
+
+        match *op {
+            Op::Bid { bidder, amount } => {
+                // The funds are added to the wallet before bidding.
+                self.wallets[bidder] += amount;
+
+                if finished {
+                    return Err(Some(LifecycleError::Finished.into()));
+                }
+
+                self.wallets[bidder] -= amount;
+                self.bids[bidder] += amount;
+                self.has_bid[bidder] = true;
+                self.num_bids += 1;
+
+                // Ties are won by whoever reached the amount first.
+                match self.highest {
+                    Some(highest) if highest == bidder || self.bids[highest] >= self.bids[bidder] => { }
+                    _ => self.highest = Some(bidder)
+                }
+
+                Ok(())
+            }
+            Op::Retract { bidder } => {
+                if !finished {
+                    return Err(Some(LifecycleError::NotFinished.into()));
+                }
+
+                let Some(highest) = self.highest else {
+                    return Err(None);
+                };
+
+                if highest == bidder {
+                    return Err(Some(LifecycleError::WinnerCannotRetract.into()));
+                }
+
+                if self.bids[bidder] == 0 {
+                    return Err(Some(LifecycleError::NothingToRefund.into()));
+                }
+
+                self.payouts[bidder] += std::mem::take(&mut self.bids[bidder]);
+
+                Ok(())
+            }
+            Op::Settle => {
+                if !finished {
+                    return Err(Some(LifecycleError::NotFinished.into()));
+                }
+
+                if self.settled {
+                    return Err(Some(LifecycleError::AlreadySettled.into()));
+                }
+
+                self.settled = true;
+
+                if let Some(highest) = self.highest {
+                    self.payouts[BIDDERS.len()] += std::mem::take(&mut self.bids[highest]);
+                }
+
+                Ok(())
+            }
+            Op::Claim { address } => {
+                if self.payouts[address] == 0 {
+                    return Err(Some(LifecycleError::NothingToClaim.into()));
+                }
+
+                self.wallets[address] += std::mem::take(&mut self.payouts[address]);
+
+                Ok(())
+            }
+            Op::Advance { .. } => Ok(())
+        }
+    }
+
+    fn state(&self, height: u64) -> AuctionState {
+        if self.settled {
+            AuctionState::Settled
+        } else if height > self.end_block {
+            AuctionState::Ended
+        } else {
+            AuctionState::Active
+        }
+    }
+}
+
+fn address(index: usize) -> &'static str {
+    BIDDERS.get(index).copied().unwrap_or(ADMIN)
+}
+
+fn execute(suite: &mut Suite, auction: &ContractLink<Addr>, op: &Op) -> Outcome {
+    let result = match *op {
+        Op::Bid { bidder, amount } => {
+            suite.ensemble.add_funds(BIDDERS[bidder], vec![coin(amount, "uscrt")]);
+            suite.ensemble.execute(
+                &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+                MockEnv::new(BIDDERS[bidder], &auction.address)
+                    .sent_funds(vec![coin(amount, "uscrt")])
+            )
+        }
+        Op::Retract { bidder } => suite.ensemble.execute(
+            &auction::ExecuteMsg::RetractBid { },
+            MockEnv::new(BIDDERS[bidder], &auction.address)
+        ),
+        Op::Settle => suite.ensemble.execute(
+            &auction::ExecuteMsg::ClaimProceeds { amount: None },
+            MockEnv::new(ADMIN, &auction.address)
+        ),
+        Op::Claim { address: index } => suite.ensemble.execute(
+            &auction::ExecuteMsg::Claim { },
+            MockEnv::new(address(index), &auction.address)
+        ),
+        Op::Advance { blocks } => {
+            suite.advance_blocks(blocks);
+
+            return Ok(());
+        }
+    };
+
+    match result {
+        Ok(_) => Ok(()),
+        Err(err) => Err(Some(contract_error(err)))
+    }
+}
+
+// Compares everything that the model keeps track of with what the auction reports.
+fn assert_matches(suite: &Suite, auction: &ContractLink<Addr>, model: &Model) -> Result<(), TestCaseError> {
+    let height = suite.ensemble.block().height;
+
+    let state: AuctionState = suite.ensemble.query(&auction.address, &auction::QueryMsg::State { }).unwrap();
+    prop_assert_eq!(state, model.state(height));
+
+    let status: SaleStatus = suite.ensemble.query(&auction.address, &auction::QueryMsg::SaleStatus { }).unwrap();
+    let highest = model.highest.map(|x| model.bids[x]).unwrap_or_default();
+
+    prop_assert_eq!(status.current_highest, Some(Uint128::new(highest)));
+    prop_assert_eq!(status.highest_bidder.as_ref().map(|x| x.as_str()), model.highest.map(address));
+    prop_assert_eq!(status.num_bids, model.num_bids);
+    prop_assert_eq!(status.bidder_count, model.has_bid.iter().filter(|x| **x).count() as u64);
+
+    for (index, bidder) in BIDDERS.iter().enumerate() {
+        let bid: Uint128 = suite.ensemble.query(
+            &auction.address,
+            &auction::QueryMsg::ViewBid { address: bidder.to_string(), key: KEY.into() }
+        ).unwrap();
+        prop_assert_eq!(bid.u128(), model.bids[index], "Bid of {}", bidder);
+    }
+
+    for index in 0..=BIDDERS.len() {
+        let address = address(index);
+
+        if index < BIDDERS.len() {
+            let payout: Uint128 = suite.ensemble.query(
+                &auction.address,
+                &auction::QueryMsg::ViewPayout { address: address.into(), key: KEY.into() }
+            ).unwrap();
+            prop_assert_eq!(payout.u128(), model.payouts[index], "Payout of {}", address);
+        }
+
+        let wallet = suite.ensemble.balances(address)
+            .and_then(|x| x.get("uscrt").copied())
+            .unwrap_or_default();
+        prop_assert_eq!(wallet.u128(), model.wallets[index], "Wallet of {}", address);
+    }
+
+    Ok(())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn auction_behaves_like_the_model(ops in proptest::collection::vec(op(), 1..40)) {
+        let mut suite = Suite::new();
+        let end_block = suite.ensemble.block().height + 1000;
+
+        let auction = suite.create_auction(Config {
+            show_highest_bidder: Some(true),
+            ..config("Road 23", end_block)
+        }).unwrap().contract;
+
+        for bidder in BIDDERS {
+            suite.ensemble.execute(
+                &auction::ExecuteMsg::SetViewingKey { key: KEY.into(), padding: None },
+                MockEnv::new(bidder, &auction.address)
+            ).unwrap();
+        }
+
+        let mut model = Model { end_block, ..Model::default() };
+
+        for (step, op) in ops.iter().enumerate() {
+            let height = suite.ensemble.block().height;
+            let expected = model.apply(op, height);
+            let actual = execute(&mut suite, &auction, op);
+
+            match expected {
+                Err(None) => prop_assert!(actual.is_err(), "Step {}: {:?} succeeded", step, op),
+                expected => prop_assert_eq!(actual, expected, "Step {}: {:?}", step, op)
+            }
+
+            assert_matches(&suite, &auction, &model)?;
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use fadroma::{
+    core::{Canonize, Humanize, ContractLink},
+    storage::SingleItem,
+    bin_serde::{FadromaSerialize, FadromaDeserialize},
+    cosmwasm_std::{
+        Addr, Decimal,
+        testing::{MockApi, MockStorage}
+    },
+    namespace
+};
+use proptest::prelude::*;
+use ::factory::factory::AuctionEntry;
+use shared::{FeeConfig, SaleInfo};
+
+namespace!(ValueNs, b"value");
+
+// Canonizes the value, stores it, loads it back and humanizes it again.
+fn round_trip<T>(value: T) -> T
+where
+    T: Canonize,
+    T::Output: FadromaSerialize + FadromaDeserialize + Humanize<Output = T>
+{
+    let api = MockApi::default();
+    let mut storage = MockStorage::default();
+
+    let item = SingleItem::<T::Output, ValueNs>::new();
+    item.save(&mut storage, &value.canonize(&api).unwrap()).unwrap();
+
+    item.load(&storage).unwrap().unwrap().humanize(&api).unwrap()
+}
+
+// Lowercase so that the mock API humanizes them back unchanged.
+fn address() -> impl Strategy<Value = Addr> {
+    "[a-z0-9]{3,40}".prop_map(Addr::unchecked)
+}
+
+fn contract_link() -> impl Strategy<Value = ContractLink<Addr>> {
+    (address(), "[0-9a-f]{64}").prop_map(|(address, code_hash)| ContractLink {
+        address,
+        code_hash
+    })
+}
+
+fn sale_info() -> impl Strategy<Value = SaleInfo> {
+    (".{0,64}", any::<u64>(), proptest::option::of(".{0,256}")).prop_map(
+        |(name, end_block, metadata)| SaleInfo { name, end_block, metadata }
+    )
+}
+
+proptest! {
+    #[test]
+    fn auction_entry_round_trips(contract in contract_link(), info in sale_info()) {
+        let entry = round_trip(AuctionEntry {
+            contract: contract.clone(),
+            info: SaleInfo {
+                name: info.name.clone(),
+                end_block: info.end_block,
+                metadata: info.metadata.clone()
+            }
+        });
+
+        prop_assert_eq!(entry.contract, contract);
+        prop_assert_eq!(entry.info, info);
+    }
+
+    #[test]
+    fn contract_link_round_trips(link in contract_link()) {
+        prop_assert_eq!(round_trip(link.clone()), link);
+    }
+
+    #[test]
+    fn fee_config_round_trips(rate in any::<u128>(), recipient in address()) {
+        let fee = FeeConfig { rate: Decimal::raw(rate), recipient };
+
+        prop_assert_eq!(round_trip(fee.clone()), fee);
+    }
+}
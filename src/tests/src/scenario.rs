@@ -0,0 +1,178 @@
+use std::fmt::Debug;
+
+use fadroma::{
+    core::ContractLink,
+    ensemble::{MockEnv, ExecuteResponse},
+    cosmwasm_std::{Addr, Uint128, coin},
+    serde::Serialize
+};
+use auction::auction;
+use shared::{
+    AuctionState, Config, SaleStatus,
+    errors::ContractError
+};
+use testing::{Suite, ADMIN, config, contract_error};
+
+/// Drives a single auction through a sequence of steps, each of which
+/// panics with the step that failed. For flows with several bidders
+/// which would otherwise repeat the same executes and queries.
+///
+/// ```ignore
+/// Scenario::new()
+///     .bid("alice", 100)
+///     .bid("bob", 150)
+///     .finish()
+///     .retract("alice")
+///     .claim("alice")
+///     .expect_balance("alice", 100);
+/// ```
+pub struct Scenario {
+    pub suite: Suite,
+    pub auction: ContractLink<Addr>,
+    // Checked against the result of the next execute.
+    expected_error: Option<ContractError>,
+    last_response: Option<ExecuteResponse>
+}
+
+impl Scenario {
+    /// An auction with the default test config, ending in 1000 blocks.
+    pub fn new() -> Self {
+        Self::with_config(|end_block| config("Road 23", end_block))
+    }
+
+    /// The config is built from the end block that [`Scenario::new`] would use.
+    pub fn with_config(config: impl FnOnce(u64) -> Config) -> Self {
+        let mut suite = Suite::new();
+        let end_block = suite.ensemble.block().height + 1000;
+        let auction = suite.create_auction(config(end_block)).unwrap().contract;
+
+        Self { suite, auction, expected_error: None, last_response: None }
+    }
+
+    /// Makes the next step fail with the given error instead of panicking.
+    pub fn fails_with(mut self, err: impl Into<ContractError>) -> Self {
+        self.expected_error = Some(err.into());
+
+        self
+    }
+
+    /// Adds the amount to the bidder's funds and bids all of it.
+    pub fn bid(mut self, bidder: &str, amount: u128) -> Self {
+        self.suite.ensemble.add_funds(bidder, vec![coin(amount, "uscrt")]);
+
+        self.execute_with_funds(
+            bidder,
+            &auction::ExecuteMsg::Bid { auto_refund: None, memo: None },
+            amount
+        )
+    }
+
+    pub fn retract(self, bidder: &str) -> Self {
+        self.execute(bidder, &auction::ExecuteMsg::RetractBid { })
+    }
+
+    pub fn claim(self, address: &str) -> Self {
+        self.execute(address, &auction::ExecuteMsg::Claim { })
+    }
+
+    /// Claims all of the proceeds as the admin, settling the sale first.
+    pub fn settle(self) -> Self {
+        self.execute(ADMIN, &auction::ExecuteMsg::ClaimProceeds { amount: None })
+    }
+
+    pub fn advance(mut self, blocks: u64) -> Self {
+        self.suite.advance_blocks(blocks);
+
+        self
+    }
+
+    /// Moves to the first block after the end of the sale.
+    pub fn finish(mut self) -> Self {
+        self.suite.finish_auction(&self.auction);
+
+        self
+    }
+
+    pub fn execute(self, sender: &str, msg: &(impl Serialize + Debug)) -> Self {
+        self.execute_with_funds(sender, msg, 0)
+    }
+
+    /// Sends the amount in uscrt along with the message.
+    pub fn execute_with_funds(
+        mut self,
+        sender: &str,
+        msg: &(impl Serialize + Debug),
+        amount: u128
+    ) -> Self {
+        let mut env = MockEnv::new(sender, &self.auction.address);
+        if amount > 0 {
+            env = env.sent_funds(vec![coin(amount, "uscrt")]);
+        }
+
+        let result = self.suite.ensemble.execute(msg, env);
+
+        match (self.expected_error.take(), result) {
+            (None, Ok(resp)) => self.last_response = Some(resp),
+            (None, Err(err)) => panic!("{:?} from {} failed: {}", msg, sender, err),
+            (Some(expected), Ok(_)) => panic!("{:?} from {} succeeded, expected: {}", msg, sender, expected),
+            (Some(expected), Err(err)) => assert_eq!(
+                contract_error(err),
+                expected,
+                "{:?} from {} failed with an unexpected error",
+                msg,
+                sender
+            )
+        }
+
+        self
+    }
+
+    /// The uscrt balance of the address, outside of the auction.
+    pub fn expect_balance(self, address: &str, amount: u128) -> Self {
+        let balance = self.suite.ensemble.balances(address)
+            .and_then(|x| x.get("uscrt").copied())
+            .unwrap_or_default();
+
+        assert_eq!(balance.u128(), amount, "Balance of {}", address);
+
+        self
+    }
+
+    pub fn expect_highest(self, amount: u128) -> Self {
+        assert_eq!(self.status().current_highest, Some(Uint128::new(amount)));
+
+        self
+    }
+
+    pub fn expect_state(self, state: AuctionState) -> Self {
+        let actual: AuctionState = self.suite.ensemble.query(
+            &self.auction.address,
+            &auction::QueryMsg::State { }
+        ).unwrap();
+
+        assert_eq!(actual, state);
+
+        self
+    }
+
+    /// Checks an attribute of the response to the last successful step.
+    pub fn expect_attribute(self, key: &str, value: &str) -> Self {
+        let resp = self.last_response.as_ref().expect("No step has succeeded yet.");
+
+        assert!(
+            resp.response.attributes.iter().any(|x| x.key == key && x.value == value),
+            "Missing attribute {} = {}",
+            key,
+            value
+        );
+
+        self
+    }
+
+    pub fn status(&self) -> SaleStatus {
+        self.suite.ensemble.query(
+            &self.auction.address,
+            &auction::QueryMsg::SaleStatus { }
+        ).unwrap()
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::BTreeMap;
+
+use fadroma::{
+    schemars::{
+        schema_for,
+        schema::{RootSchema, Schema, SchemaObject},
+        visit::{self, Visitor}
+    },
+    cosmwasm_std::{Addr, Binary, Coin, Uint128},
+    killswitch::ContractStatus,
+    core::{ContractCode, ContractLink}
+};
+use ::factory::factory::{self, AuctionEntry, RegistryEntry, TopSale, VolumeStats};
+use auction::auction;
+use shared::{
+    AuctionConfig, AuctionState, CurrencyPolicy, BidEntry, BidRank, BidReceipt, BidSimulation, Claim, Delivery, ExtensionProposal, FeeConfig, MigrateMsg, Invariants, Metrics, PaginatedResponse, PendingApproval, ProxyBid,
+    SaleStatus, SettlementHookMsg, StatusInfo, SubscriberMsg, TimeRemaining, version::ContractVersion
+};
+
+// The messages accepted by both contracts.
+fn message_schemas() -> BTreeMap<&'static str, RootSchema> {
+    BTreeMap::from([
+        ("auction_instantiate_msg", schema_for!(auction::InstantiateMsg)),
+        ("auction_execute_msg", schema_for!(auction::ExecuteMsg)),
+        ("auction_query_msg", schema_for!(auction::QueryMsg)),
+        ("factory_instantiate_msg", schema_for!(factory::InstantiateMsg)),
+        ("factory_execute_msg", schema_for!(factory::ExecuteMsg)),
+        ("factory_query_msg", schema_for!(factory::QueryMsg)),
+        ("migrate_msg", schema_for!(MigrateMsg)),
+        ("subscriber_msg", schema_for!(SubscriberMsg)),
+        ("settlement_hook_msg", schema_for!(SettlementHookMsg))
+    ])
+}
+
+// The responses of every query, keyed by the name of the query.
+fn auction_response_schemas() -> BTreeMap<&'static str, RootSchema> {
+    BTreeMap::from([
+        ("view_bid", schema_for!(Uint128)),
+        ("view_bid_with_permit", schema_for!(Uint128)),
+        ("view_bid_of_with_permit", schema_for!(Uint128)),
+        ("min_bid_to_win", schema_for!(Uint128)),
+        ("min_bid_to_win_with_permit", schema_for!(Uint128)),
+        ("simulate_bid", schema_for!(BidSimulation)),
+        ("view_payout", schema_for!(Uint128)),
+        ("view_payout_with_permit", schema_for!(Uint128)),
+        ("view_proxy_bid", schema_for!(ProxyBid)),
+        ("view_proxy_bid_with_permit", schema_for!(ProxyBid)),
+        ("my_rank", schema_for!(BidRank)),
+        ("my_rank_with_permit", schema_for!(BidRank)),
+        ("winner_payload", schema_for!(Option<Binary>)),
+        ("winner_payload_with_permit", schema_for!(Option<Binary>)),
+        ("delivery", schema_for!(Delivery)),
+        ("delivery_with_permit", schema_for!(Delivery)),
+        ("view_memo", schema_for!(Option<String>)),
+        ("view_memo_with_permit", schema_for!(Option<String>)),
+        ("view_claim", schema_for!(Claim)),
+        ("active_bids", schema_for!(PaginatedResponse<Uint128>)),
+        ("active_bids_with_permit", schema_for!(PaginatedResponse<Uint128>)),
+        ("audit_bids", schema_for!(PaginatedResponse<BidEntry>)),
+        ("audit_bids_with_permit", schema_for!(PaginatedResponse<BidEntry>)),
+        ("auditor", schema_for!(Option<Addr>)),
+        ("pauser", schema_for!(Option<Addr>)),
+        ("pending_owner", schema_for!(Option<Addr>)),
+        ("selected_winner", schema_for!(Option<Addr>)),
+        ("settlement_hooks", schema_for!(Vec<ContractLink<Addr>>)),
+        ("early_retraction_penalty", schema_for!(Option<u16>)),
+        ("sale_status", schema_for!(SaleStatus)),
+        ("config", schema_for!(AuctionConfig)),
+        ("factory", schema_for!(Option<ContractLink<Addr>>)),
+        ("approvals", schema_for!(Vec<PendingApproval>)),
+        ("bid_receipt", schema_for!(Option<BidReceipt>)),
+        ("time_remaining", schema_for!(TimeRemaining)),
+        ("extension_proposal", schema_for!(ExtensionProposal)),
+        ("state", schema_for!(AuctionState)),
+        ("invariants", schema_for!(Invariants)),
+        ("status", schema_for!(ContractStatus<Addr>)),
+        ("status_info", schema_for!(StatusInfo)),
+        ("metrics", schema_for!(Metrics)),
+        ("version", schema_for!(ContractVersion)),
+        ("admin", schema_for!(Option<Addr>))
+    ])
+}
+
+fn factory_response_schemas() -> BTreeMap<&'static str, RootSchema> {
+    BTreeMap::from([
+        ("list_auctions", schema_for!(PaginatedResponse<AuctionEntry<Addr>>)),
+        ("list_archive", schema_for!(PaginatedResponse<AuctionEntry<Addr>>)),
+        ("list_auctions_by_tag", schema_for!(PaginatedResponse<AuctionEntry<Addr>>)),
+        ("list_ending_soon", schema_for!(PaginatedResponse<AuctionEntry<Addr>>)),
+        ("list_unsettled", schema_for!(PaginatedResponse<AuctionEntry<Addr>>)),
+        ("auction_code", schema_for!(ContractCode)),
+        ("version", schema_for!(ContractVersion)),
+        ("is_closed", schema_for!(bool)),
+        ("top_sales", schema_for!(Vec<TopSale>)),
+        ("volume_stats", schema_for!(VolumeStats)),
+        ("currency_policy", schema_for!(Option<CurrencyPolicy<Addr>>)),
+        ("fee_config", schema_for!(Option<FeeConfig<Addr>>)),
+        ("keeper_reward", schema_for!(Option<Coin>)),
+        ("creator_limit", schema_for!(Option<u64>)),
+        ("default_auction_admin", schema_for!(Option<Addr>)),
+        ("active_auctions", schema_for!(u64)),
+        ("export_registry", schema_for!(PaginatedResponse<RegistryEntry>)),
+        ("subscribers", schema_for!(Vec<ContractLink<Addr>>)),
+        ("admin", schema_for!(Option<Addr>))
+    ])
+}
+
+// The names of the variants of an externally tagged enum.
+pub(crate) fn variant_names(schema: &RootSchema) -> Vec<String> {
+    schema.schema.subschemas.as_ref()
+        .and_then(|x| x.one_of.as_ref())
+        .expect("Message schema is not an enum.")
+        .iter()
+        .map(|variant| match variant {
+            Schema::Object(obj) => obj.object.as_ref()
+                .and_then(|x| x.required.iter().next().cloned())
+                .expect("Variant has no name."),
+            Schema::Bool(_) => panic!("Unexpected boolean schema.")
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct References(Vec<String>);
+
+impl Visitor for References {
+    fn visit_schema_object(&mut self, schema: &mut SchemaObject) {
+        if let Some(reference) = &schema.reference {
+            self.0.push(reference.clone());
+        }
+
+        visit::visit_schema_object(self, schema)
+    }
+}
+
+#[test]
+fn every_query_has_a_response_schema() {
+    let messages = message_schemas();
+
+    for (query_msg, responses) in [
+        ("auction_query_msg", auction_response_schemas()),
+        ("factory_query_msg", factory_response_schemas())
+    ] {
+        let mut queries = variant_names(&messages[query_msg]);
+        queries.sort();
+
+        let covered = responses.keys().map(|x| x.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(queries, covered, "Response schemas of {} are out of date.", query_msg);
+    }
+}
+
+#[test]
+fn schemas_contain_all_referenced_definitions() {
+    let schemas = message_schemas().into_iter()
+        .chain(auction_response_schemas())
+        .chain(factory_response_schemas());
+
+    for (name, mut schema) in schemas {
+        let mut references = References::default();
+        references.visit_root_schema(&mut schema);
+
+        for reference in references.0 {
+            let definition = reference.trim_start_matches("#/definitions/");
+
+            assert!(
+                schema.definitions.contains_key(definition),
+                "Schema {} references a missing definition: {}",
+                name,
+                definition
+            );
+        }
+    }
+}
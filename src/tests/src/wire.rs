@@ -0,0 +1,607 @@
+use std::collections::BTreeSet;
+
+use fadroma::{
+    schemars::schema_for,
+    cosmwasm_std::{Addr, Decimal, Uint128, coin, to_vec},
+    scrt::permit::{Permit, PermitParams},
+    killswitch::ContractStatus,
+    core::ContractLink,
+    serde::Serialize
+};
+use ::factory::factory;
+use auction::auction;
+use shared::{
+    AuctionEnd, AuctionPermission, Config, CurrencyPolicy, Denom, FactoryPermission, FeeConfig, MigrateMsg, Pagination, SellerAction
+};
+
+use crate::schema::variant_names;
+
+// Checks the encoding of each message and records the variant that it covers.
+struct Encodings {
+    covered: BTreeSet<String>
+}
+
+impl Encodings {
+    fn new() -> Self {
+        Self { covered: BTreeSet::new() }
+    }
+
+    fn assert(&mut self, msg: &impl Serialize, expected: &str) {
+        let json = String::from_utf8(to_vec(msg).unwrap()).unwrap();
+        assert_eq!(json, expected);
+
+        let variant = json.trim_start_matches("{\"").split('"').next().unwrap();
+        self.covered.insert(variant.to_string());
+    }
+
+    // Fails if any variant of the message enum wasn't checked.
+    fn assert_complete(self, all: Vec<String>) {
+        let all = all.into_iter().collect::<BTreeSet<_>>();
+        assert_eq!(self.covered, all);
+    }
+}
+
+fn pagination() -> Pagination {
+    Pagination { start: 0, limit: 10 }
+}
+
+#[test]
+fn auction_execute_msg() {
+    let mut msgs = Encodings::new();
+
+    msgs.assert(
+        &auction::ExecuteMsg::Bid { auto_refund: Some(true), memo: Some("ship to alice".into()) },
+        r#"{"bid":{"auto_refund":true,"memo":"ship to alice"}}"#
+    );
+    msgs.assert(
+        &auction::ExecuteMsg::BidWithKey { key: "key".into(), padding: None },
+        r#"{"bid_with_key":{"key":"key","padding":null}}"#
+    );
+    msgs.assert(
+        &auction::ExecuteMsg::BidFor { beneficiary: "bidder".into() },
+        r#"{"bid_for":{"beneficiary":"bidder"}}"#
+    );
+    msgs.assert(&auction::ExecuteMsg::ProxyBid { }, r#"{"proxy_bid":{}}"#);
+    msgs.assert(
+        &auction::ExecuteMsg::BidFromAllowance { amount: Uint128::new(100) },
+        r#"{"bid_from_allowance":{"amount":"100"}}"#
+    );
+    msgs.assert(
+        &auction::ExecuteMsg::SetAutoRefund { enabled: true },
+        r#"{"set_auto_refund":{"enabled":true}}"#
+    );
+    msgs.assert(
+        &auction::ExecuteMsg::AcceptBidsFor { accept: true },
+        r#"{"accept_bids_for":{"accept":true}}"#
+    );
+    msgs.assert(&auction::ExecuteMsg::RetractBid { }, r#"{"retract_bid":{}}"#);
+    msgs.assert(
+        &auction::ExecuteMsg::ClaimProceeds { amount: Some(Uint128::new(100)) },
+        r#"{"claim_proceeds":{"amount":"100"}}"#
+    );
+    msgs.assert(&auction::ExecuteMsg::Claim { }, r#"{"claim":{}}"#);
+    msgs.assert(&auction::ExecuteMsg::Settle { }, r#"{"settle":{}}"#);
+    msgs.assert(
+        &auction::ExecuteMsg::ProposeExtension { blocks: 50 },
+        r#"{"propose_extension":{"blocks":50}}"#
+    );
+    msgs.assert(
+        &auction::ExecuteMsg::VoteExtension { proposal: 2 },
+        r#"{"vote_extension":{"proposal":2}}"#
+    );
+    msgs.assert(
+        &auction::ExecuteMsg::UpdateConfig {
+            name: Some("Road 23".into()),
+            metadata: None,
+            reserve: Some(Uint128::new(100)),
+            end_block: Some(1000)
+        },
+        r#"{"update_config":{"name":"Road 23","metadata":null,"reserve":"100","end_block":1000}}"#
+    );
+    msgs.assert(
+        &auction::ExecuteMsg::SetAuditor { address: Some("auditor".into()) },
+        r#"{"set_auditor":{"address":"auditor"}}"#
+    );
+    msgs.assert(
+        &auction::ExecuteMsg::SetPauser { address: Some("monitor".into()) },
+        r#"{"set_pauser":{"address":"monitor"}}"#
+    );
+    msgs.assert(
+        &auction::ExecuteMsg::TransferOwnership { new_owner: Some("buyer".into()) },
+        r#"{"transfer_ownership":{"new_owner":"buyer"}}"#
+    );
+    msgs.assert(&auction::ExecuteMsg::AcceptOwnership { }, r#"{"accept_ownership":{}}"#);
+    msgs.assert(
+        &auction::ExecuteMsg::ExportBidders { pagination: pagination() },
+        r#"{"export_bidders":{"pagination":{"start":0,"limit":10}}}"#
+    );
+    msgs.assert(
+        &auction::ExecuteMsg::SelectWinner { address: "bidder".into() },
+        r#"{"select_winner":{"address":"bidder"}}"#
+    );
+    msgs.assert(
+        &auction::ExecuteMsg::CancelSale { reason: "fraud".into() },
+        r#"{"cancel_sale":{"reason":"fraud"}}"#
+    );
+    msgs.assert(
+        &auction::ExecuteMsg::ApproveAction {
+            action: SellerAction::ClaimProceeds { amount: Some(Uint128::new(100)) }
+        },
+        r#"{"approve_action":{"action":{"claim_proceeds":{"amount":"100"}}}}"#
+    );
+    msgs.assert(
+        &auction::ExecuteMsg::ApproveAction {
+            action: SellerAction::CancelSale { reason: "fraud".into() }
+        },
+        r#"{"approve_action":{"action":{"cancel_sale":{"reason":"fraud"}}}}"#
+    );
+    msgs.assert(
+        &auction::ExecuteMsg::ApproveAction { action: SellerAction::Extend { blocks: 100 } },
+        r#"{"approve_action":{"action":{"extend":{"blocks":100}}}}"#
+    );
+    msgs.assert(&auction::ExecuteMsg::RevokeAllKeys { }, r#"{"revoke_all_keys":{}}"#);
+    msgs.assert(
+        &auction::ExecuteMsg::RevokePermit { name: "bids".into() },
+        r#"{"revoke_permit":{"name":"bids"}}"#
+    );
+    msgs.assert(
+        &auction::ExecuteMsg::TransferClaim { claim_id: 1, recipient: "buyer".into() },
+        r#"{"transfer_claim":{"claim_id":1,"recipient":"buyer"}}"#
+    );
+    msgs.assert(
+        &auction::ExecuteMsg::RedeemClaim { claim_id: 1 },
+        r#"{"redeem_claim":{"claim_id":1}}"#
+    );
+    msgs.assert(
+        &auction::ExecuteMsg::RefundBatch { limit: 10 },
+        r#"{"refund_batch":{"limit":10}}"#
+    );
+    msgs.assert(&auction::ExecuteMsg::Close { }, r#"{"close":{}}"#);
+    msgs.assert(
+        &auction::ExecuteMsg::SendDeliveryDetails { details: "alice, road 23".into() },
+        r#"{"send_delivery_details":{"details":"alice, road 23"}}"#
+    );
+    msgs.assert(
+        &auction::ExecuteMsg::SendFulfillmentProof { proof: "tracking: 1234".into() },
+        r#"{"send_fulfillment_proof":{"proof":"tracking: 1234"}}"#
+    );
+    msgs.assert(
+        &auction::ExecuteMsg::AddSettlementHook {
+            contract: ContractLink { address: "hook".into(), code_hash: "hash".into() }
+        },
+        r#"{"add_settlement_hook":{"contract":{"address":"hook","code_hash":"hash"}}}"#
+    );
+    msgs.assert(
+        &auction::ExecuteMsg::RemoveSettlementHook { address: "hook".into() },
+        r#"{"remove_settlement_hook":{"address":"hook"}}"#
+    );
+    msgs.assert(
+        &auction::ExecuteMsg::CreateViewingKey { entropy: "entropy".into(), padding: None },
+        r#"{"create_viewing_key":{"entropy":"entropy","padding":null}}"#
+    );
+    msgs.assert(
+        &auction::ExecuteMsg::SetViewingKey { key: "key".into(), padding: None },
+        r#"{"set_viewing_key":{"key":"key","padding":null}}"#
+    );
+    // The killswitch module doesn't rename its variants.
+    msgs.assert(
+        &auction::ExecuteMsg::SetStatus {
+            status: ContractStatus::Paused { reason: "Upgrade".into() }
+        },
+        r#"{"set_status":{"status":{"Paused":{"reason":"Upgrade"}}}}"#
+    );
+    msgs.assert(
+        &auction::ExecuteMsg::ChangeAdmin { mode: None },
+        r#"{"change_admin":{"mode":null}}"#
+    );
+
+    msgs.assert_complete(variant_names(&schema_for!(auction::ExecuteMsg)));
+}
+
+#[test]
+fn auction_query_msg() {
+    let mut msgs = Encodings::new();
+
+    msgs.assert(
+        &auction::QueryMsg::ViewBid { address: "bidder".into(), key: "key".into() },
+        r#"{"view_bid":{"address":"bidder","key":"key"}}"#
+    );
+
+    let permit = Permit::new(
+        "bidder",
+        PermitParams::new("auction")
+            .permissions([AuctionPermission::ViewBid])
+            .name("bids")
+    );
+    // Permits created for the ensemble have no signature.
+    msgs.assert(
+        &auction::QueryMsg::ViewBidWithPermit { permit: permit.clone() },
+        concat!(
+            r#"{"view_bid_with_permit":{"permit":{"params":{"allowed_tokens":["auction"],"#,
+            r#""permit_name":"bids","chain_id":"fadroma-ensemble-testnet","#,
+            r#""permissions":["view_bid"]},"address":"bidder"}}}"#
+        )
+    );
+    msgs.assert(
+        &auction::QueryMsg::ViewBidOfWithPermit { bidder: "bidder".into(), permit: permit.clone() },
+        concat!(
+            r#"{"view_bid_of_with_permit":{"bidder":"bidder","permit":{"params":{"allowed_tokens":["auction"],"#,
+            r#""permit_name":"bids","chain_id":"fadroma-ensemble-testnet","#,
+            r#""permissions":["view_bid"]},"address":"bidder"}}}"#
+        )
+    );
+    msgs.assert(
+        &auction::QueryMsg::MinBidToWin { address: "bidder".into(), key: "key".into() },
+        r#"{"min_bid_to_win":{"address":"bidder","key":"key"}}"#
+    );
+    msgs.assert(
+        &auction::QueryMsg::MinBidToWinWithPermit { permit: permit.clone() },
+        concat!(
+            r#"{"min_bid_to_win_with_permit":{"permit":{"params":{"allowed_tokens":["auction"],"#,
+            r#""permit_name":"bids","chain_id":"fadroma-ensemble-testnet","#,
+            r#""permissions":["view_bid"]},"address":"bidder"}}}"#
+        )
+    );
+    msgs.assert(
+        &auction::QueryMsg::SimulateBid { permit: permit.clone(), amount: Uint128::new(100) },
+        concat!(
+            r#"{"simulate_bid":{"permit":{"params":{"allowed_tokens":["auction"],"#,
+            r#""permit_name":"bids","chain_id":"fadroma-ensemble-testnet","#,
+            r#""permissions":["view_bid"]},"address":"bidder"},"amount":"100"}}"#
+        )
+    );
+    msgs.assert(
+        &auction::QueryMsg::ViewPayout { address: "bidder".into(), key: "key".into() },
+        r#"{"view_payout":{"address":"bidder","key":"key"}}"#
+    );
+    msgs.assert(
+        &auction::QueryMsg::ViewPayoutWithPermit { permit: permit.clone() },
+        concat!(
+            r#"{"view_payout_with_permit":{"permit":{"params":{"allowed_tokens":["auction"],"#,
+            r#""permit_name":"bids","chain_id":"fadroma-ensemble-testnet","#,
+            r#""permissions":["view_bid"]},"address":"bidder"}}}"#
+        )
+    );
+    msgs.assert(
+        &auction::QueryMsg::ViewProxyBid { address: "bidder".into(), key: "key".into() },
+        r#"{"view_proxy_bid":{"address":"bidder","key":"key"}}"#
+    );
+    msgs.assert(
+        &auction::QueryMsg::ViewProxyBidWithPermit { permit: permit.clone() },
+        concat!(
+            r#"{"view_proxy_bid_with_permit":{"permit":{"params":{"allowed_tokens":["auction"],"#,
+            r#""permit_name":"bids","chain_id":"fadroma-ensemble-testnet","#,
+            r#""permissions":["view_bid"]},"address":"bidder"}}}"#
+        )
+    );
+    msgs.assert(
+        &auction::QueryMsg::MyRank { address: "bidder".into(), key: "key".into() },
+        r#"{"my_rank":{"address":"bidder","key":"key"}}"#
+    );
+    msgs.assert(
+        &auction::QueryMsg::MyRankWithPermit { permit },
+        concat!(
+            r#"{"my_rank_with_permit":{"permit":{"params":{"allowed_tokens":["auction"],"#,
+            r#""permit_name":"bids","chain_id":"fadroma-ensemble-testnet","#,
+            r#""permissions":["view_bid"]},"address":"bidder"}}}"#
+        )
+    );
+    msgs.assert(
+        &auction::QueryMsg::WinnerPayload { address: "bidder".into(), key: "key".into() },
+        r#"{"winner_payload":{"address":"bidder","key":"key"}}"#
+    );
+    msgs.assert(
+        &auction::QueryMsg::WinnerPayloadWithPermit {
+            permit: Permit::new(
+                "bidder",
+                PermitParams::new("auction")
+                    .permissions([AuctionPermission::ViewPayload])
+                    .name("payload")
+            )
+        },
+        concat!(
+            r#"{"winner_payload_with_permit":{"permit":{"params":{"allowed_tokens":["auction"],"#,
+            r#""permit_name":"payload","chain_id":"fadroma-ensemble-testnet","#,
+            r#""permissions":["view_payload"]},"address":"bidder"}}}"#
+        )
+    );
+    msgs.assert(
+        &auction::QueryMsg::Delivery { address: "bidder".into(), key: "key".into() },
+        r#"{"delivery":{"address":"bidder","key":"key"}}"#
+    );
+    msgs.assert(
+        &auction::QueryMsg::DeliveryWithPermit {
+            permit: Permit::new(
+                "bidder",
+                PermitParams::new("auction")
+                    .permissions([AuctionPermission::ViewDelivery])
+                    .name("delivery")
+            )
+        },
+        concat!(
+            r#"{"delivery_with_permit":{"permit":{"params":{"allowed_tokens":["auction"],"#,
+            r#""permit_name":"delivery","chain_id":"fadroma-ensemble-testnet","#,
+            r#""permissions":["view_delivery"]},"address":"bidder"}}}"#
+        )
+    );
+    msgs.assert(
+        &auction::QueryMsg::ViewMemo { bidder: "bidder".into(), viewer: "admin".into(), key: "key".into() },
+        r#"{"view_memo":{"bidder":"bidder","viewer":"admin","key":"key"}}"#
+    );
+    msgs.assert(
+        &auction::QueryMsg::ViewMemoWithPermit {
+            bidder: "bidder".into(),
+            permit: Permit::new(
+                "admin",
+                PermitParams::new("auction")
+                    .permissions([AuctionPermission::ViewMemo])
+                    .name("memo")
+            )
+        },
+        concat!(
+            r#"{"view_memo_with_permit":{"bidder":"bidder","permit":{"params":{"allowed_tokens":["auction"],"#,
+            r#""permit_name":"memo","chain_id":"fadroma-ensemble-testnet","#,
+            r#""permissions":["view_memo"]},"address":"admin"}}}"#
+        )
+    );
+    msgs.assert(
+        &auction::QueryMsg::ViewClaim {
+            address: "bidder".into(),
+            key: "key".into(),
+            claim_id: None
+        },
+        r#"{"view_claim":{"address":"bidder","key":"key","claim_id":null}}"#
+    );
+    msgs.assert(
+        &auction::QueryMsg::ActiveBids { pagination: pagination() },
+        r#"{"active_bids":{"pagination":{"start":0,"limit":10}}}"#
+    );
+    msgs.assert(
+        &auction::QueryMsg::ActiveBidsWithPermit {
+            permit: Permit::new(
+                "admin",
+                PermitParams::new("auction")
+                    .permissions([AuctionPermission::ViewActiveBids])
+                    .name("bids")
+            ),
+            pagination: pagination()
+        },
+        concat!(
+            r#"{"active_bids_with_permit":{"permit":{"params":{"allowed_tokens":["auction"],"#,
+            r#""permit_name":"bids","chain_id":"fadroma-ensemble-testnet","#,
+            r#""permissions":["view_active_bids"]},"address":"admin"},"#,
+            r#""pagination":{"start":0,"limit":10}}}"#
+        )
+    );
+    msgs.assert(
+        &auction::QueryMsg::AuditBidsWithPermit {
+            permit: Permit::new(
+                "auditor",
+                PermitParams::new("auction")
+                    .permissions([AuctionPermission::ViewActiveBids])
+                    .name("bids")
+            ),
+            pagination: pagination()
+        },
+        concat!(
+            r#"{"audit_bids_with_permit":{"permit":{"params":{"allowed_tokens":["auction"],"#,
+            r#""permit_name":"bids","chain_id":"fadroma-ensemble-testnet","#,
+            r#""permissions":["view_active_bids"]},"address":"auditor"},"#,
+            r#""pagination":{"start":0,"limit":10}}}"#
+        )
+    );
+    msgs.assert(
+        &auction::QueryMsg::AuditBids { key: "key".into(), pagination: pagination() },
+        r#"{"audit_bids":{"key":"key","pagination":{"start":0,"limit":10}}}"#
+    );
+    msgs.assert(&auction::QueryMsg::Auditor { }, r#"{"auditor":{}}"#);
+    msgs.assert(&auction::QueryMsg::Pauser { }, r#"{"pauser":{}}"#);
+    msgs.assert(&auction::QueryMsg::PendingOwner { }, r#"{"pending_owner":{}}"#);
+    msgs.assert(&auction::QueryMsg::SelectedWinner { }, r#"{"selected_winner":{}}"#);
+    msgs.assert(&auction::QueryMsg::SettlementHooks { }, r#"{"settlement_hooks":{}}"#);
+    msgs.assert(
+        &auction::QueryMsg::EarlyRetractionPenalty { },
+        r#"{"early_retraction_penalty":{}}"#
+    );
+    msgs.assert(&auction::QueryMsg::SaleStatus { }, r#"{"sale_status":{}}"#);
+    msgs.assert(&auction::QueryMsg::Config { }, r#"{"config":{}}"#);
+    msgs.assert(&auction::QueryMsg::Factory { }, r#"{"factory":{}}"#);
+    msgs.assert(&auction::QueryMsg::Approvals { }, r#"{"approvals":{}}"#);
+    msgs.assert(&auction::QueryMsg::BidReceipt { receipt: 3 }, r#"{"bid_receipt":{"receipt":3}}"#);
+    msgs.assert(&auction::QueryMsg::TimeRemaining { }, r#"{"time_remaining":{}}"#);
+    msgs.assert(
+        &auction::QueryMsg::ExtensionProposal { proposal: 2 },
+        r#"{"extension_proposal":{"proposal":2}}"#
+    );
+    msgs.assert(&auction::QueryMsg::State { }, r#"{"state":{}}"#);
+    msgs.assert(&auction::QueryMsg::Invariants { }, r#"{"invariants":{}}"#);
+    msgs.assert(&auction::QueryMsg::StatusInfo { }, r#"{"status_info":{}}"#);
+    msgs.assert(&auction::QueryMsg::Metrics { }, r#"{"metrics":{}}"#);
+    msgs.assert(&auction::QueryMsg::Version { }, r#"{"version":{}}"#);
+    msgs.assert(&auction::QueryMsg::Status { }, r#"{"status":{}}"#);
+    msgs.assert(&auction::QueryMsg::Admin { }, r#"{"admin":{}}"#);
+
+    msgs.assert_complete(variant_names(&schema_for!(auction::QueryMsg)));
+}
+
+#[test]
+fn factory_execute_msg() {
+    let mut msgs = Encodings::new();
+
+    msgs.assert(
+        &factory::ExecuteMsg::CreateAuction {
+            config: Config::new("Road 23", 1000),
+            tags: Some(vec!["house".into()]),
+            end: Some(AuctionEnd::Duration { blocks: 100 })
+        },
+        concat!(
+            r#"{"create_auction":{"config":{"version":1,"admin":null,"name":"Road 23","#,
+            r#""end_block":1000,"end_time":null,"start_block":null,"metadata":null,"guard_queries":null,"#,
+            r#""denom":null,"reserve":null,"min_increment":null,"beneficiary":null,"#,
+            r#""receipt_nft":null,"tradable_claims":null,"raffle":null,"manual_winner":null,"#,
+            r#""dispute_blocks":null,"arbiter":null,"early_retraction_penalty":null,"#,
+            r#""bid_cooldown":null,"oracle":null,"usd_reserve":null,"#,
+            r#""usd_min_increment":null,"winner_payload":null,"show_highest_bidder":null,"#,
+            r#""hide_current_highest":null,"public_active_bids":null,"extension_vote":null,"#,
+            r#""exclude_contracts":null,"bid_token":null,"seller_deposit":null,"factory":null,"co_sellers":null},"#,
+            r#""tags":["house"],"end":{"duration":{"blocks":100}}}}"#
+        )
+    );
+    msgs.assert(
+        &factory::ExecuteMsg::CloneAuction { index: 3, new_end_block: 2000 },
+        r#"{"clone_auction":{"index":3,"new_end_block":2000}}"#
+    );
+    msgs.assert(
+        &factory::ExecuteMsg::Subscribe { code_hash: "hash".into() },
+        r#"{"subscribe":{"code_hash":"hash"}}"#
+    );
+    msgs.assert(
+        &factory::ExecuteMsg::RemoveSubscriber { address: "subscriber".into() },
+        r#"{"remove_subscriber":{"address":"subscriber"}}"#
+    );
+    msgs.assert(
+        &factory::ExecuteMsg::SetFeeConfig {
+            fee: Some(FeeConfig { rate: Decimal::percent(5), recipient: "platform".into() })
+        },
+        r#"{"set_fee_config":{"fee":{"rate":"0.05","recipient":"platform"}}}"#
+    );
+    msgs.assert(
+        &factory::ExecuteMsg::ProcessEnded { limit: 5 },
+        r#"{"process_ended":{"limit":5}}"#
+    );
+    msgs.assert(
+        &factory::ExecuteMsg::SetKeeperReward { reward: Some(coin(10, "uscrt")) },
+        r#"{"set_keeper_reward":{"reward":{"denom":"uscrt","amount":"10"}}}"#
+    );
+    msgs.assert(
+        &factory::ExecuteMsg::SetCreatorLimit { limit: Some(3) },
+        r#"{"set_creator_limit":{"limit":3}}"#
+    );
+    msgs.assert(
+        &factory::ExecuteMsg::SetDefaultAuctionAdmin { admin: Some("platform".into()) },
+        r#"{"set_default_auction_admin":{"admin":"platform"}}"#
+    );
+    msgs.assert(
+        &factory::ExecuteMsg::ArchiveAuctions { indices: vec![0, 2] },
+        r#"{"archive_auctions":{"indices":[0,2]}}"#
+    );
+    msgs.assert(
+        &factory::ExecuteMsg::AuctionClosed { settled: true },
+        r#"{"auction_closed":{"settled":true}}"#
+    );
+    msgs.assert(
+        &factory::ExecuteMsg::EndBlockChanged { end_block: 500 },
+        r#"{"end_block_changed":{"end_block":500}}"#
+    );
+    msgs.assert(
+        &factory::ExecuteMsg::RegisterExisting {
+            contract: ContractLink { address: "auction".into(), code_hash: "hash".into() }
+        },
+        r#"{"register_existing":{"contract":{"address":"auction","code_hash":"hash"}}}"#
+    );
+    msgs.assert(
+        &factory::ExecuteMsg::SetCurrencyPolicy {
+            policy: Some(CurrencyPolicy {
+                denoms: vec![Denom::new("uscrt").unwrap()],
+                tokens: vec![ContractLink { address: "token".into(), code_hash: "hash".into() }]
+            })
+        },
+        concat!(
+            r#"{"set_currency_policy":{"policy":{"denoms":["uscrt"],"#,
+            r#""tokens":[{"address":"token","code_hash":"hash"}]}}}"#
+        )
+    );
+    // Sent by auctions as a SettlementHookMsg.
+    msgs.assert(
+        &factory::ExecuteMsg::AuctionSettled {
+            auction: ContractLink { address: Addr::unchecked("auction"), code_hash: "hash".into() },
+            winner: None,
+            price: Uint128::new(150),
+            denom: "uscrt".into()
+        },
+        concat!(
+            r#"{"auction_settled":{"auction":{"address":"auction","code_hash":"hash"},"#,
+            r#""winner":null,"price":"150","denom":"uscrt"}}"#
+        )
+    );
+    msgs.assert(
+        &factory::ExecuteMsg::ChangeAdmin { mode: None },
+        r#"{"change_admin":{"mode":null}}"#
+    );
+
+    msgs.assert_complete(variant_names(&schema_for!(factory::ExecuteMsg)));
+}
+
+#[test]
+fn factory_query_msg() {
+    let mut msgs = Encodings::new();
+
+    msgs.assert(
+        &factory::QueryMsg::ListAuctions { pagination: pagination() },
+        r#"{"list_auctions":{"pagination":{"start":0,"limit":10}}}"#
+    );
+    msgs.assert(
+        &factory::QueryMsg::ListArchive { pagination: pagination() },
+        r#"{"list_archive":{"pagination":{"start":0,"limit":10}}}"#
+    );
+    msgs.assert(
+        &factory::QueryMsg::ListEndingSoon { before_block: 1000, pagination: pagination() },
+        r#"{"list_ending_soon":{"before_block":1000,"pagination":{"start":0,"limit":10}}}"#
+    );
+    msgs.assert(
+        &factory::QueryMsg::ListUnsettled { pagination: pagination() },
+        r#"{"list_unsettled":{"pagination":{"start":0,"limit":10}}}"#
+    );
+    msgs.assert(&factory::QueryMsg::Subscribers { }, r#"{"subscribers":{}}"#);
+    msgs.assert(
+        &factory::QueryMsg::ListAuctionsByTag { tag: "house".into(), pagination: pagination() },
+        r#"{"list_auctions_by_tag":{"tag":"house","pagination":{"start":0,"limit":10}}}"#
+    );
+    msgs.assert(&factory::QueryMsg::AuctionCode { }, r#"{"auction_code":{}}"#);
+    msgs.assert(&factory::QueryMsg::Version { }, r#"{"version":{}}"#);
+    msgs.assert(&factory::QueryMsg::IsClosed { index: 0 }, r#"{"is_closed":{"index":0}}"#);
+    msgs.assert(
+        &factory::QueryMsg::TopSales { denom: "uscrt".into(), limit: 5 },
+        r#"{"top_sales":{"denom":"uscrt","limit":5}}"#
+    );
+    msgs.assert(&factory::QueryMsg::VolumeStats { }, r#"{"volume_stats":{}}"#);
+    msgs.assert(&factory::QueryMsg::CurrencyPolicy { }, r#"{"currency_policy":{}}"#);
+    msgs.assert(&factory::QueryMsg::FeeConfig { }, r#"{"fee_config":{}}"#);
+    msgs.assert(&factory::QueryMsg::KeeperReward { }, r#"{"keeper_reward":{}}"#);
+    msgs.assert(&factory::QueryMsg::CreatorLimit { }, r#"{"creator_limit":{}}"#);
+    msgs.assert(&factory::QueryMsg::DefaultAuctionAdmin { }, r#"{"default_auction_admin":{}}"#);
+    msgs.assert(
+        &factory::QueryMsg::ActiveAuctions { creator: "creator".into() },
+        r#"{"active_auctions":{"creator":"creator"}}"#
+    );
+    msgs.assert(
+        &factory::QueryMsg::ExportRegistry {
+            permit: Permit::new(
+                "admin",
+                PermitParams::new("factory")
+                    .permissions([FactoryPermission::ExportRegistry])
+                    .name("backup")
+            ),
+            pagination: pagination()
+        },
+        concat!(
+            r#"{"export_registry":{"permit":{"params":{"allowed_tokens":["factory"],"#,
+            r#""permit_name":"backup","chain_id":"fadroma-ensemble-testnet","#,
+            r#""permissions":["export_registry"]},"address":"admin"},"#,
+            r#""pagination":{"start":0,"limit":10}}}"#
+        )
+    );
+    msgs.assert(&factory::QueryMsg::Admin { }, r#"{"admin":{}}"#);
+
+    msgs.assert_complete(variant_names(&schema_for!(factory::QueryMsg)));
+}
+
+#[test]
+fn migrate_msg() {
+    // Shared by the auction and the factory.
+    assert_eq!(to_vec(&MigrateMsg { admin: None }).unwrap(), br#"{"admin":null}"#);
+    assert_eq!(
+        to_vec(&MigrateMsg { admin: Some("admin".into()) }).unwrap(),
+        br#"{"admin":"admin"}"#
+    );
+}